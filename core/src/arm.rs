@@ -0,0 +1,2121 @@
+use iced_x86::{FlowControl, FormatterTextKind};
+
+use crate::{
+    lines, relocation_offset, relocations::Reference, settings::Settings, Assembly, Instruction,
+    Object, Section, SymbolData,
+};
+
+// Which encodings the bytes of ARM code are in. Code switches between ARM and
+// Thumb with interworking branches, and assemblers mark the switches and data
+// within code with `$a`, `$t` and `$d` mapping symbols.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Arm,
+    Thumb,
+    // Literal pools and jump tables
+    Data,
+}
+
+impl Mode {
+    // The mode a mapping symbol switches to. Some assemblers add a suffix like
+    // `$t.1`.
+    pub fn of_mapping_symbol(name: &str) -> Option<Mode> {
+        let mode = match name.get(..2)? {
+            "$a" => Mode::Arm,
+            "$t" => Mode::Thumb,
+            "$d" => Mode::Data,
+            _ => return None,
+        };
+        (name.len() == 2 || name[2..].starts_with('.')).then_some(mode)
+    }
+}
+
+// The mode at `address`, from the addresses where the mode of a section
+// changes in order. Code before the first change is ARM, like objdump assumes.
+pub fn mode_at(modes: &[(u64, Mode)], address: u64) -> Mode {
+    let i = modes.partition_point(|&(start, _)| start <= address);
+    i.checked_sub(1).map_or(Mode::Arm, |i| modes[i].1)
+}
+
+fn next_change(modes: &[(u64, Mode)], address: u64) -> Option<u64> {
+    let i = modes.partition_point(|&(start, _)| start <= address);
+    modes.get(i).map(|&(start, _)| start)
+}
+
+// An instruction or data directive
+#[derive(Clone, Debug)]
+pub struct Decoded {
+    pub address: u64,
+    pub len: usize,
+    pub mode: Mode,
+    pub format: Vec<(String, FormatterTextKind)>,
+    pub flow: FlowControl,
+    // Direct branch target
+    pub target: Option<u64>,
+    // Address and size of the literal loaded relative to the PC, with a size
+    // of 0 for addresses computed by `adr`
+    pub literal: Option<(u64, usize)>,
+    pub nop: bool,
+    // Set for encodings which aren't decoded, which are shown as `.inst`
+    pub invalid: bool,
+    // Set when the end of the bytes cuts off an instruction
+    pub truncated: bool,
+}
+
+const REGISTERS: [&str; 16] = [
+    "r0", "r1", "r2", "r3", "r4", "r5", "r6", "r7", "r8", "r9", "r10", "r11", "r12", "sp", "lr",
+    "pc",
+];
+
+const CONDITIONS: [&str; 16] = [
+    "eq", "ne", "hs", "lo", "mi", "pl", "vs", "vc", "hi", "ls", "ge", "lt", "gt", "le", "", "",
+];
+
+const AL: u32 = 14;
+
+const SHIFTS: [&str; 4] = ["lsl", "lsr", "asr", "ror"];
+
+const PC: u32 = 15;
+const LR: u32 = 14;
+const SP: u32 = 13;
+
+// The mnemonic with the flag-setting and condition suffixes of UAL
+fn name(base: &str, flags: bool, cond: u32) -> String {
+    let mut name = base.to_owned();
+    if flags {
+        name.push('s');
+    }
+    name.push_str(CONDITIONS[cond as usize & 15]);
+    name
+}
+
+fn sign_extend(value: u32, bits: u32) -> i64 {
+    let shift = 32 - bits;
+    ((value << shift) as i32 >> shift) as i64
+}
+
+fn relative(pc: u64, offset: i64) -> u64 {
+    pc.wrapping_add_signed(offset) & 0xFFFF_FFFF
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Shift {
+    Imm(&'static str, u32),
+    Reg(&'static str, u32),
+    Rrx,
+}
+
+// The shift of a register operand by an immediate, which encodes shifts by 32
+// and `rrx` with an amount of 0
+fn shift_imm(kind: u32, amount: u32) -> Option<Shift> {
+    match (kind & 3, amount) {
+        (0, 0) => None,
+        (3, 0) => Some(Shift::Rrx),
+        (1 | 2, 0) => Some(Shift::Imm(SHIFTS[kind as usize & 3], 32)),
+        (kind, amount) => Some(Shift::Imm(SHIFTS[kind as usize], amount)),
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Offset {
+    None,
+    Imm(i64),
+    // Subtracted if set, with the shift of the index
+    Reg(bool, u32, Option<Shift>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Indexing {
+    Offset,
+    // Writes the address back to the base before the access
+    Pre,
+    // Accesses the base, then adds the offset to it
+    Post,
+}
+
+#[derive(Clone, Debug)]
+enum Operand {
+    Reg(u32),
+    // A base register which is written back, `rn!`
+    Writeback(u32),
+    Imm(i64),
+    Target(u64),
+    Memory(u32, Offset, Indexing),
+    // Registers by bit, and whether the user mode registers are meant
+    List(u32, bool),
+    Shift(Shift),
+    Name(String),
+}
+
+fn number(value: i64) -> String {
+    match value {
+        -9..=9 => value.to_string(),
+        _ if value < 0 => format!("-{:#x}", value.unsigned_abs()),
+        _ => format!("{:#x}", value),
+    }
+}
+
+fn render_shift(shift: Shift, parts: &mut Vec<(String, FormatterTextKind)>) {
+    match shift {
+        Shift::Imm(kind, amount) => {
+            parts.push((format!("{} ", kind), FormatterTextKind::Keyword));
+            parts.push((
+                format!("#{}", number(amount as i64)),
+                FormatterTextKind::Number,
+            ));
+        }
+        Shift::Reg(kind, register) => {
+            parts.push((format!("{} ", kind), FormatterTextKind::Keyword));
+            parts.push((
+                REGISTERS[register as usize].to_owned(),
+                FormatterTextKind::Register,
+            ));
+        }
+        Shift::Rrx => parts.push(("rrx".to_owned(), FormatterTextKind::Keyword)),
+    }
+}
+
+impl Operand {
+    fn render(&self, parts: &mut Vec<(String, FormatterTextKind)>) {
+        let register = |register: u32| {
+            (
+                REGISTERS[register as usize].to_owned(),
+                FormatterTextKind::Register,
+            )
+        };
+        let separator = || (", ".to_owned(), FormatterTextKind::Text);
+        match self {
+            Operand::Reg(r) => parts.push(register(*r)),
+            Operand::Writeback(r) => {
+                parts.push(register(*r));
+                parts.push(("!".to_owned(), FormatterTextKind::Punctuation));
+            }
+            Operand::Imm(value) => {
+                parts.push((format!("#{}", number(*value)), FormatterTextKind::Number))
+            }
+            Operand::Target(address) => {
+                parts.push((format!("{:#x}", address), FormatterTextKind::LabelAddress))
+            }
+            Operand::Memory(base, offset, indexing) => {
+                parts.push(("[".to_owned(), FormatterTextKind::Punctuation));
+                parts.push(register(*base));
+                let mut offset_parts = Vec::new();
+                match *offset {
+                    Offset::None => {}
+                    Offset::Imm(0) if *indexing == Indexing::Offset => {}
+                    Offset::Imm(value) => offset_parts
+                        .push((format!("#{}", number(value)), FormatterTextKind::Number)),
+                    Offset::Reg(subtract, index, shift) => {
+                        if subtract {
+                            offset_parts.push(("-".to_owned(), FormatterTextKind::Operator));
+                        }
+                        offset_parts.push(register(index));
+                        if let Some(shift) = shift {
+                            offset_parts.push(separator());
+                            render_shift(shift, &mut offset_parts);
+                        }
+                    }
+                }
+                if *indexing == Indexing::Post {
+                    parts.push(("]".to_owned(), FormatterTextKind::Punctuation));
+                    if !offset_parts.is_empty() {
+                        parts.push(separator());
+                        parts.extend(offset_parts);
+                    }
+                    return;
+                }
+                if !offset_parts.is_empty() {
+                    parts.push(separator());
+                    parts.extend(offset_parts);
+                }
+                parts.push(("]".to_owned(), FormatterTextKind::Punctuation));
+                if *indexing == Indexing::Pre {
+                    parts.push(("!".to_owned(), FormatterTextKind::Punctuation));
+                }
+            }
+            Operand::List(list, user) => {
+                parts.push(("{".to_owned(), FormatterTextKind::Punctuation));
+                for (i, r) in (0..16).filter(|r| list & (1 << r) != 0).enumerate() {
+                    if i > 0 {
+                        parts.push(separator());
+                    }
+                    parts.push(register(r));
+                }
+                parts.push(("}".to_owned(), FormatterTextKind::Punctuation));
+                if *user {
+                    parts.push(("^".to_owned(), FormatterTextKind::Punctuation));
+                }
+            }
+            Operand::Shift(shift) => render_shift(*shift, parts),
+            Operand::Name(name) => parts.push((name.clone(), FormatterTextKind::Keyword)),
+        }
+    }
+}
+
+// A decoded instruction before it's formatted
+struct Insn {
+    mnemonic: String,
+    operands: Vec<Operand>,
+    flow: FlowControl,
+    target: Option<u64>,
+    literal: Option<(u64, usize)>,
+    nop: bool,
+}
+
+impl Insn {
+    fn new(mnemonic: impl Into<String>) -> Insn {
+        Insn {
+            mnemonic: mnemonic.into(),
+            operands: Vec::new(),
+            flow: FlowControl::Next,
+            target: None,
+            literal: None,
+            nop: false,
+        }
+    }
+
+    fn op(mut self, operand: Operand) -> Insn {
+        self.operands.push(operand);
+        self
+    }
+
+    fn reg(self, register: u32) -> Insn {
+        self.op(Operand::Reg(register))
+    }
+
+    fn imm(self, value: impl Into<i64>) -> Insn {
+        self.op(Operand::Imm(value.into()))
+    }
+
+    fn shift(self, shift: Option<Shift>) -> Insn {
+        match shift {
+            Some(shift) => self.op(Operand::Shift(shift)),
+            None => self,
+        }
+    }
+
+    fn name(self, name: impl Into<String>) -> Insn {
+        self.op(Operand::Name(name.into()))
+    }
+
+    fn branch(mut self, target: u64, flow: FlowControl) -> Insn {
+        self.target = Some(target);
+        self.flow = flow;
+        self.op(Operand::Target(target))
+    }
+
+    fn flow(mut self, flow: FlowControl) -> Insn {
+        self.flow = flow;
+        self
+    }
+
+    fn literal(mut self, address: u64, size: usize) -> Insn {
+        self.literal = Some((address, size));
+        self
+    }
+
+    fn nop(mut self) -> Insn {
+        self.nop = true;
+        self
+    }
+
+    // Loads of the PC return when they pop it off the stack
+    fn loads_pc(self, base: u32) -> Insn {
+        self.flow(if base == SP {
+            FlowControl::Return
+        } else {
+            FlowControl::IndirectBranch
+        })
+    }
+
+    fn render(&self) -> Vec<(String, FormatterTextKind)> {
+        let mut parts = vec![(self.mnemonic.clone(), FormatterTextKind::Mnemonic)];
+        if !self.operands.is_empty() {
+            let padding = 10usize.saturating_sub(self.mnemonic.len()).max(1);
+            parts.push((" ".repeat(padding), FormatterTextKind::Text));
+        }
+        for (i, operand) in self.operands.iter().enumerate() {
+            if i > 0 {
+                parts.push((", ".to_owned(), FormatterTextKind::Text));
+            }
+            operand.render(&mut parts);
+        }
+        parts
+    }
+}
+
+// The flow of an instruction which writes `rd`, which branches if it's the PC
+fn writes(insn: Insn, rd: u32, source: Option<u32>) -> Insn {
+    match (rd, source) {
+        (PC, Some(LR)) => insn.flow(FlowControl::Return),
+        (PC, _) => insn.flow(FlowControl::IndirectBranch),
+        _ => insn,
+    }
+}
+
+fn barrier(option: u32) -> String {
+    match option {
+        15 => "sy",
+        14 => "st",
+        13 => "ld",
+        11 => "ish",
+        10 => "ishst",
+        9 => "ishld",
+        7 => "nsh",
+        6 => "nshst",
+        5 => "nshld",
+        3 => "osh",
+        2 => "oshst",
+        1 => "oshld",
+        _ => return format!("#{}", option),
+    }
+    .to_owned()
+}
+
+fn hint(hint: u32, cond: u32) -> Option<Insn> {
+    let insn = match hint {
+        0 => return Some(Insn::new(name("nop", false, cond)).nop()),
+        1 => "yield",
+        2 => "wfe",
+        3 => "wfi",
+        4 => "sev",
+        _ => return None,
+    };
+    Some(Insn::new(name(insn, false, cond)))
+}
+
+// Decodes a 32-bit ARM instruction at `address`
+fn arm(w: u32, address: u64) -> Option<Insn> {
+    let pc = address + 8;
+    let cond = w >> 28;
+    if cond == 0xF {
+        return arm_unconditional(w, pc);
+    }
+    let rn = (w >> 16) & 15;
+    let rd = (w >> 12) & 15;
+    let rm = w & 15;
+    match (w >> 25) & 7 {
+        0 if w & 0x0F90_0000 == 0x0100_0000 && w & 0x90 != 0x90 => arm_misc(w, cond),
+        0 if w & 0x90 == 0x90 && w & 0x60 == 0 => {
+            let rs = (w >> 8) & 15;
+            let flags = w & (1 << 20) != 0;
+            // `rd` and `rn` are swapped for multiplies
+            let insn = match (w >> 21) & 15 {
+                0 => Insn::new(name("mul", flags, cond)).reg(rn).reg(rm).reg(rs),
+                1 => Insn::new(name("mla", flags, cond))
+                    .reg(rn)
+                    .reg(rm)
+                    .reg(rs)
+                    .reg(rd),
+                3 => Insn::new(name("mls", false, cond))
+                    .reg(rn)
+                    .reg(rm)
+                    .reg(rs)
+                    .reg(rd),
+                op @ 4..=7 => {
+                    let base = ["umull", "umlal", "smull", "smlal"][op as usize - 4];
+                    Insn::new(name(base, flags, cond))
+                        .reg(rd)
+                        .reg(rn)
+                        .reg(rm)
+                        .reg(rs)
+                }
+                _ => return None,
+            };
+            Some(insn)
+        }
+        0 if w & 0x90 == 0x90 => {
+            let pre = w & (1 << 24) != 0;
+            let up = w & (1 << 23) != 0;
+            let immediate = w & (1 << 22) != 0;
+            let writeback = w & (1 << 21) != 0;
+            let load = w & (1 << 20) != 0;
+            let base = match (load, (w >> 5) & 3) {
+                (false, 1) => "strh",
+                (true, 1) => "ldrh",
+                (false, 2) => "ldrd",
+                (true, 2) => "ldrsb",
+                (false, 3) => "strd",
+                _ => "ldrsh",
+            };
+            let offset = if immediate {
+                let value = ((w >> 4) & 0xF0 | w & 0xF) as i64;
+                Offset::Imm(if up { value } else { -value })
+            } else {
+                Offset::Reg(!up, rm, None)
+            };
+            let mut insn = Insn::new(name(base, false, cond)).reg(rd);
+            if base.ends_with('d') {
+                insn = insn.reg((rd + 1) & 15);
+            }
+            if let (PC, true, true, false, Offset::Imm(value)) =
+                (rn, immediate, pre, writeback, offset)
+            {
+                let size = if base.ends_with('d') { 8 } else { 2 };
+                insn = insn.literal(relative(pc, value), size);
+            }
+            Some(insn.op(Operand::Memory(rn, offset, indexing(pre, writeback))))
+        }
+        0 | 1 => {
+            if w & 0x0FB0_0000 == 0x0300_0000 {
+                let imm = (w >> 4) & 0xF000 | w & 0xFFF;
+                let base = if w & (1 << 22) != 0 { "movt" } else { "movw" };
+                return Some(Insn::new(name(base, false, cond)).reg(rd).imm(imm));
+            }
+            if w & 0x0FFF_FF00 == 0x0320_F000 {
+                return hint(w & 0xFF, cond);
+            }
+            arm_data_processing(w, cond, pc)
+        }
+        2 | 3 if w & (1 << 25) != 0 && w & 0x10 != 0 => arm_media(w, cond),
+        2 | 3 => {
+            let pre = w & (1 << 24) != 0;
+            let up = w & (1 << 23) != 0;
+            let byte = w & (1 << 22) != 0;
+            let writeback = w & (1 << 21) != 0;
+            let load = w & (1 << 20) != 0;
+            let offset = if w & (1 << 25) == 0 {
+                let value = (w & 0xFFF) as i64;
+                Offset::Imm(if up { value } else { -value })
+            } else {
+                Offset::Reg(!up, rm, shift_imm((w >> 5) & 3, (w >> 7) & 31))
+            };
+            // Single registers pushed or popped
+            match (rn, load, byte, pre, writeback, offset) {
+                (SP, false, false, true, true, Offset::Imm(-4)) => {
+                    return Some(
+                        Insn::new(name("push", false, cond)).op(Operand::List(1 << rd, false)),
+                    );
+                }
+                (SP, true, false, false, false, Offset::Imm(4)) => {
+                    let insn =
+                        Insn::new(name("pop", false, cond)).op(Operand::List(1 << rd, false));
+                    return Some(if rd == PC { insn.loads_pc(SP) } else { insn });
+                }
+                _ => {}
+            }
+            let mut base = String::from(if load { "ldr" } else { "str" });
+            if byte {
+                base.push('b');
+            }
+            // Post-indexed accesses with writeback are made as if in user mode
+            if !pre && writeback {
+                base.push('t');
+            }
+            let mut insn = Insn::new(name(&base, false, cond)).reg(rd);
+            if let (PC, true, false, Offset::Imm(value)) = (rn, pre, writeback, offset) {
+                insn = insn.literal(relative(pc, value), if byte { 1 } else { 4 });
+            }
+            if load && rd == PC {
+                insn = insn.flow(FlowControl::IndirectBranch);
+            }
+            Some(insn.op(Operand::Memory(rn, offset, indexing(pre, writeback && pre))))
+        }
+        4 => {
+            let pre = w & (1 << 24) != 0;
+            let up = w & (1 << 23) != 0;
+            let user = w & (1 << 22) != 0;
+            let writeback = w & (1 << 21) != 0;
+            let load = w & (1 << 20) != 0;
+            let list = w & 0xFFFF;
+            let list_operand = Operand::List(list, user);
+            let insn = match (rn, load, pre, up, writeback) {
+                (SP, true, false, true, true) if !user => {
+                    Insn::new(name("pop", false, cond)).op(list_operand)
+                }
+                (SP, false, true, false, true) if !user => {
+                    Insn::new(name("push", false, cond)).op(list_operand)
+                }
+                _ => {
+                    let mode = match (pre, up) {
+                        (false, true) => "",
+                        (true, true) => "ib",
+                        (false, false) => "da",
+                        (true, false) => "db",
+                    };
+                    let base = format!("{}{}", if load { "ldm" } else { "stm" }, mode);
+                    let base_operand = if writeback {
+                        Operand::Writeback(rn)
+                    } else {
+                        Operand::Reg(rn)
+                    };
+                    Insn::new(name(&base, false, cond))
+                        .op(base_operand)
+                        .op(list_operand)
+                }
+            };
+            Some(if load && list & (1 << PC) != 0 {
+                insn.loads_pc(rn)
+            } else {
+                insn
+            })
+        }
+        5 => {
+            let target = relative(pc, sign_extend(w & 0xFF_FFFF, 24) << 2);
+            Some(if w & (1 << 24) != 0 {
+                Insn::new(name("bl", false, cond)).branch(target, FlowControl::Call)
+            } else if cond == AL {
+                Insn::new("b").branch(target, FlowControl::UnconditionalBranch)
+            } else {
+                Insn::new(name("b", false, cond)).branch(target, FlowControl::ConditionalBranch)
+            })
+        }
+        7 if w & (1 << 24) != 0 => Some(
+            Insn::new(name("svc", false, cond))
+                .imm(w & 0xFF_FFFF)
+                .flow(FlowControl::Interrupt),
+        ),
+        _ => None,
+    }
+}
+
+fn indexing(pre: bool, writeback: bool) -> Indexing {
+    match (pre, writeback) {
+        (true, false) => Indexing::Offset,
+        (true, true) => Indexing::Pre,
+        (false, _) => Indexing::Post,
+    }
+}
+
+// ARM instructions with the condition field set to `0b1111`
+fn arm_unconditional(w: u32, pc: u64) -> Option<Insn> {
+    if w & 0x0E00_0000 == 0x0A00_0000 {
+        // Switches to Thumb, so the target is halfword aligned
+        let offset = sign_extend(w & 0xFF_FFFF, 24) << 2 | ((w >> 23) & 2) as i64;
+        return Some(Insn::new("blx").branch(relative(pc, offset), FlowControl::Call));
+    }
+    match w & 0xFFFF_FFF0 {
+        0xF57F_F040 => return Some(Insn::new("dsb").name(barrier(w & 15))),
+        0xF57F_F050 => return Some(Insn::new("dmb").name(barrier(w & 15))),
+        0xF57F_F060 => return Some(Insn::new("isb").name(barrier(w & 15))),
+        0xF57F_F010 if w & 15 == 15 => return Some(Insn::new("clrex")),
+        _ => {}
+    }
+    if w & 0x0D70_F000 == 0x0550_F000 {
+        let value = (w & 0xFFF) as i64;
+        let value = if w & (1 << 23) != 0 { value } else { -value };
+        let offset = if w & (1 << 25) == 0 {
+            Offset::Imm(value)
+        } else {
+            Offset::Reg(
+                w & (1 << 23) == 0,
+                w & 15,
+                shift_imm((w >> 5) & 3, (w >> 7) & 31),
+            )
+        };
+        return Some(Insn::new("pld").op(Operand::Memory(
+            (w >> 16) & 15,
+            offset,
+            Indexing::Offset,
+        )));
+    }
+    None
+}
+
+// Branches to registers and other instructions in the data processing space
+// which don't set the flags of a comparison
+fn arm_misc(w: u32, cond: u32) -> Option<Insn> {
+    let rm = w & 15;
+    let rd = (w >> 12) & 15;
+    if w & 0x0FFF_FFF0 == 0x012F_FF10 {
+        let flow = if rm == LR {
+            FlowControl::Return
+        } else {
+            FlowControl::IndirectBranch
+        };
+        return Some(Insn::new(name("bx", false, cond)).reg(rm).flow(flow));
+    }
+    if w & 0x0FFF_FFF0 == 0x012F_FF30 {
+        return Some(
+            Insn::new(name("blx", false, cond))
+                .reg(rm)
+                .flow(FlowControl::IndirectCall),
+        );
+    }
+    if w & 0x0FFF_0FF0 == 0x016F_0F10 {
+        return Some(Insn::new(name("clz", false, cond)).reg(rd).reg(rm));
+    }
+    if w & 0x0FBF_0FFF == 0x010F_0000 {
+        let status = if w & (1 << 22) != 0 { "spsr" } else { "apsr" };
+        return Some(Insn::new(name("mrs", false, cond)).reg(rd).name(status));
+    }
+    if w & 0x0FB0_FFF0 == 0x0120_F000 {
+        let fields: String = ['c', 'x', 's', 'f']
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| w & (1 << (16 + i)) != 0)
+            .map(|(_, &field)| field)
+            .collect();
+        let status = if w & (1 << 22) != 0 { "spsr" } else { "cpsr" };
+        return Some(
+            Insn::new(name("msr", false, cond))
+                .name(format!("{}_{}", status, fields))
+                .reg(rm),
+        );
+    }
+    if w & 0x0FF0_00F0 == 0x0120_0070 {
+        return Some(
+            Insn::new("bkpt")
+                .imm((w >> 4) & 0xFFF0 | w & 15)
+                .flow(FlowControl::Interrupt),
+        );
+    }
+    None
+}
+
+const ARM_DATA: [&str; 16] = [
+    "and", "eor", "sub", "rsb", "add", "adc", "sbc", "rsc", "tst", "teq", "cmp", "cmn", "orr",
+    "mov", "bic", "mvn",
+];
+
+fn arm_data_processing(w: u32, cond: u32, pc: u64) -> Option<Insn> {
+    let op = (w >> 21) & 15;
+    let flags = w & (1 << 20) != 0;
+    let rn = (w >> 16) & 15;
+    let rd = (w >> 12) & 15;
+    let rm = w & 15;
+    let immediate = w & (1 << 25) != 0;
+    let (second, shift) = if immediate {
+        let value = (w & 0xFF).rotate_right(((w >> 8) & 15) * 2);
+        (Operand::Imm(value as i64), None)
+    } else if w & 0x10 == 0 {
+        (Operand::Reg(rm), shift_imm((w >> 5) & 3, (w >> 7) & 31))
+    } else {
+        let kind = SHIFTS[(w as usize >> 5) & 3];
+        (Operand::Reg(rm), Some(Shift::Reg(kind, (w >> 8) & 15)))
+    };
+    let base = ARM_DATA[op as usize];
+    let insn = match op {
+        // Comparisons without setting the flags are other instructions
+        8..=11 if !flags => return None,
+        8..=11 => {
+            return Some(
+                Insn::new(name(base, false, cond))
+                    .reg(rn)
+                    .op(second)
+                    .shift(shift),
+            )
+        }
+        // Moves of shifted registers are written as the shift
+        13 => match shift {
+            Some(Shift::Imm(kind, amount)) => Insn::new(name(kind, flags, cond))
+                .reg(rd)
+                .op(second)
+                .imm(amount),
+            Some(Shift::Reg(kind, rs)) => Insn::new(name(kind, flags, cond))
+                .reg(rd)
+                .op(second)
+                .reg(rs),
+            Some(Shift::Rrx) => Insn::new(name("rrx", flags, cond)).reg(rd).op(second),
+            None => {
+                let insn = Insn::new(name(base, flags, cond))
+                    .reg(rd)
+                    .op(second.clone());
+                match second {
+                    Operand::Reg(rm) if rm == rd && !flags && rd != PC => insn.nop(),
+                    _ => insn,
+                }
+            }
+        },
+        15 => Insn::new(name(base, flags, cond))
+            .reg(rd)
+            .op(second)
+            .shift(shift),
+        // Addresses relative to the PC
+        2 | 4 if rn == PC && immediate && !flags => {
+            let Operand::Imm(value) = second else {
+                unreachable!()
+            };
+            let value = if op == 2 { -value } else { value };
+            let target = relative(pc, value);
+            return Some(
+                Insn::new(name("adr", false, cond))
+                    .reg(rd)
+                    .op(Operand::Target(target))
+                    .literal(target, 0),
+            );
+        }
+        _ => Insn::new(name(base, flags, cond))
+            .reg(rd)
+            .reg(rn)
+            .op(second)
+            .shift(shift),
+    };
+    let source = match (op, shift) {
+        (13, None) => (!immediate).then_some(rm),
+        _ => None,
+    };
+    Some(writes(insn, rd, source))
+}
+
+// Extends, byte reversals, bit field instructions and division
+fn arm_media(w: u32, cond: u32) -> Option<Insn> {
+    let rn = (w >> 16) & 15;
+    let rd = (w >> 12) & 15;
+    let rm = w & 15;
+    if w & 0xFFF0_00F0 == 0xE7F0_00F0 {
+        return Some(
+            Insn::new("udf")
+                .imm((w >> 4) & 0xFFF0 | w & 15)
+                .flow(FlowControl::Exception),
+        );
+    }
+    let extend = match w & 0x0FF0_03F0 {
+        0x06A0_0070 => Some(("sxtb", "sxtab")),
+        0x06B0_0070 => Some(("sxth", "sxtah")),
+        0x06E0_0070 => Some(("uxtb", "uxtab")),
+        0x06F0_0070 => Some(("uxth", "uxtah")),
+        _ => None,
+    };
+    if let Some((plain, add)) = extend {
+        let rotation = ((w >> 10) & 3) * 8;
+        let insn = match rn {
+            PC => Insn::new(name(plain, false, cond)).reg(rd).reg(rm),
+            _ => Insn::new(name(add, false, cond)).reg(rd).reg(rn).reg(rm),
+        };
+        return Some(insn.shift((rotation != 0).then_some(Shift::Imm("ror", rotation))));
+    }
+    let reverse = match w & 0x0FFF_0FF0 {
+        0x06BF_0F30 => Some("rev"),
+        0x06BF_0FB0 => Some("rev16"),
+        0x06FF_0FB0 => Some("revsh"),
+        0x06FF_0F30 => Some("rbit"),
+        _ => None,
+    };
+    if let Some(base) = reverse {
+        return Some(Insn::new(name(base, false, cond)).reg(rd).reg(rm));
+    }
+    let lsb = (w >> 7) & 31;
+    match w & 0x0FE0_0070 {
+        0x07A0_0050 | 0x07E0_0050 => {
+            let base = if w & (1 << 22) != 0 { "ubfx" } else { "sbfx" };
+            let width = ((w >> 16) & 31) + 1;
+            return Some(
+                Insn::new(name(base, false, cond))
+                    .reg(rd)
+                    .reg(rm)
+                    .imm(lsb)
+                    .imm(width),
+            );
+        }
+        0x07C0_0010 => {
+            let msb = (w >> 16) & 31;
+            let width = (msb + 1).checked_sub(lsb)?;
+            let insn = match rm {
+                PC => Insn::new(name("bfc", false, cond)).reg(rd),
+                _ => Insn::new(name("bfi", false, cond)).reg(rd).reg(rm),
+            };
+            return Some(insn.imm(lsb).imm(width));
+        }
+        _ => {}
+    }
+    let divide = match w & 0x0FF0_F0F0 {
+        0x0710_F010 => Some("sdiv"),
+        0x0730_F010 => Some("udiv"),
+        _ => None,
+    };
+    divide.map(|base| {
+        Insn::new(name(base, false, cond))
+            .reg(rn)
+            .reg(rm)
+            .reg((w >> 8) & 15)
+    })
+}
+
+const THUMB_DATA: [&str; 16] = [
+    "and", "eor", "lsl", "lsr", "asr", "adc", "sbc", "ror", "tst", "rsb", "cmp", "cmn", "orr",
+    "mul", "bic", "mvn",
+];
+
+// Decodes a 16-bit Thumb instruction. Outside of `it` blocks most of them set
+// the flags, and inside they have the condition of the block.
+fn thumb16(h: u32, address: u64, cond: u32, in_it: bool) -> Option<Insn> {
+    let pc = address + 4;
+    let aligned_pc = pc & !3;
+    let flags = !in_it;
+    let low = |shift: u32| (h >> shift) & 7;
+    let insn = match h >> 11 {
+        0..=2 => {
+            let amount = (h >> 6) & 31;
+            match (h >> 11, amount) {
+                (0, 0) => Insn::new(name("mov", flags, cond)).reg(low(0)).reg(low(3)),
+                (kind, amount) => Insn::new(name(SHIFTS[kind as usize], flags, cond))
+                    .reg(low(0))
+                    .reg(low(3))
+                    .imm(if amount == 0 { 32 } else { amount }),
+            }
+        }
+        3 => {
+            let base = if h & 0x200 != 0 { "sub" } else { "add" };
+            let insn = Insn::new(name(base, flags, cond)).reg(low(0)).reg(low(3));
+            if h & 0x400 != 0 {
+                insn.imm(low(6))
+            } else {
+                insn.reg(low(6))
+            }
+        }
+        4..=7 => {
+            let op = (h >> 11) - 4;
+            let base = ["mov", "cmp", "add", "sub"][op as usize];
+            Insn::new(name(base, flags && op != 1, cond))
+                .reg(low(8))
+                .imm(h & 0xFF)
+        }
+        8 if h & 0x400 == 0 => {
+            let op = (h >> 6) & 15;
+            let (rdn, rm) = (low(0), low(3));
+            let base = THUMB_DATA[op as usize];
+            match op {
+                8 | 10 | 11 => Insn::new(name(base, false, cond)).reg(rdn).reg(rm),
+                9 => Insn::new(name(base, flags, cond)).reg(rdn).reg(rm).imm(0),
+                13 => Insn::new(name(base, flags, cond)).reg(rdn).reg(rm).reg(rdn),
+                _ => Insn::new(name(base, flags, cond)).reg(rdn).reg(rm),
+            }
+        }
+        8 => {
+            let rdn = h & 7 | (h >> 4) & 8;
+            let rm = (h >> 3) & 15;
+            match (h >> 8) & 3 {
+                0 => writes(
+                    Insn::new(name("add", false, cond)).reg(rdn).reg(rm),
+                    rdn,
+                    None,
+                ),
+                1 => Insn::new(name("cmp", false, cond)).reg(rdn).reg(rm),
+                2 => {
+                    let insn = Insn::new(name("mov", false, cond)).reg(rdn).reg(rm);
+                    let insn = if rdn == rm && rdn != PC {
+                        insn.nop()
+                    } else {
+                        insn
+                    };
+                    writes(insn, rdn, Some(rm))
+                }
+                _ if h & 0x80 != 0 => Insn::new(name("blx", false, cond))
+                    .reg(rm)
+                    .flow(FlowControl::IndirectCall),
+                _ => writes(Insn::new(name("bx", false, cond)).reg(rm), PC, Some(rm)),
+            }
+        }
+        9 => {
+            let offset = ((h & 0xFF) << 2) as i64;
+            Insn::new(name("ldr", false, cond))
+                .reg(low(8))
+                .op(Operand::Memory(PC, Offset::Imm(offset), Indexing::Offset))
+                .literal(relative(aligned_pc, offset), 4)
+        }
+        10 | 11 => {
+            let base = [
+                "str", "strh", "strb", "ldrsb", "ldr", "ldrh", "ldrb", "ldrsh",
+            ][((h >> 9) & 7) as usize];
+            Insn::new(name(base, false, cond))
+                .reg(low(0))
+                .op(Operand::Memory(
+                    low(3),
+                    Offset::Reg(false, low(6), None),
+                    Indexing::Offset,
+                ))
+        }
+        12..=17 => {
+            let (base, scale) = match (h >> 11) & 0x1F {
+                12 => ("str", 4),
+                13 => ("ldr", 4),
+                14 => ("strb", 1),
+                15 => ("ldrb", 1),
+                16 => ("strh", 2),
+                _ => ("ldrh", 2),
+            };
+            let offset = ((h >> 6) & 31) * scale;
+            Insn::new(name(base, false, cond))
+                .reg(low(0))
+                .op(Operand::Memory(
+                    low(3),
+                    Offset::Imm(offset as i64),
+                    Indexing::Offset,
+                ))
+        }
+        18 | 19 => {
+            let base = if h & 0x800 != 0 { "ldr" } else { "str" };
+            Insn::new(name(base, false, cond))
+                .reg(low(8))
+                .op(Operand::Memory(
+                    SP,
+                    Offset::Imm(((h & 0xFF) << 2) as i64),
+                    Indexing::Offset,
+                ))
+        }
+        20 => {
+            let target = relative(aligned_pc, ((h & 0xFF) << 2) as i64);
+            Insn::new(name("adr", false, cond))
+                .reg(low(8))
+                .op(Operand::Target(target))
+                .literal(target, 0)
+        }
+        21 => Insn::new(name("add", false, cond))
+            .reg(low(8))
+            .reg(SP)
+            .imm((h & 0xFF) << 2),
+        22 | 23 => return thumb16_misc(h, pc, cond),
+        24 | 25 => {
+            let rn = low(8);
+            let list = h & 0xFF;
+            if h & 0x800 == 0 {
+                Insn::new(name("stm", false, cond))
+                    .op(Operand::Writeback(rn))
+                    .op(Operand::List(list, false))
+            } else {
+                // The base is only written back if it isn't loaded
+                let base = if list & (1 << rn) != 0 {
+                    Operand::Reg(rn)
+                } else {
+                    Operand::Writeback(rn)
+                };
+                Insn::new(name("ldm", false, cond))
+                    .op(base)
+                    .op(Operand::List(list, false))
+            }
+        }
+        26 | 27 => match (h >> 8) & 15 {
+            14 => Insn::new("udf").imm(h & 0xFF).flow(FlowControl::Exception),
+            15 => Insn::new("svc").imm(h & 0xFF).flow(FlowControl::Interrupt),
+            branch => Insn::new(name("b", false, branch)).branch(
+                relative(pc, sign_extend(h & 0xFF, 8) << 1),
+                FlowControl::ConditionalBranch,
+            ),
+        },
+        28 => {
+            let flow = if cond == AL {
+                FlowControl::UnconditionalBranch
+            } else {
+                FlowControl::ConditionalBranch
+            };
+            Insn::new(name("b", false, cond))
+                .branch(relative(pc, sign_extend(h & 0x7FF, 11) << 1), flow)
+        }
+        _ => return None,
+    };
+    Some(insn)
+}
+
+fn thumb16_misc(h: u32, pc: u64, cond: u32) -> Option<Insn> {
+    let low = |shift: u32| (h >> shift) & 7;
+    let insn = match h & 0xFF00 {
+        0xB000 => {
+            let base = if h & 0x80 != 0 { "sub" } else { "add" };
+            Insn::new(name(base, false, cond))
+                .reg(SP)
+                .imm((h & 0x7F) << 2)
+        }
+        0xB100 | 0xB300 | 0xB900 | 0xBB00 => {
+            let base = if h & 0x800 != 0 { "cbnz" } else { "cbz" };
+            let offset = ((h >> 3) & 0x40 | (h >> 2) & 0x3E) as i64;
+            Insn::new(base)
+                .reg(low(0))
+                .branch(relative(pc, offset), FlowControl::ConditionalBranch)
+        }
+        0xB200 => {
+            let base = ["sxth", "sxtb", "uxth", "uxtb"][((h >> 6) & 3) as usize];
+            Insn::new(name(base, false, cond)).reg(low(0)).reg(low(3))
+        }
+        0xB400 | 0xB500 => Insn::new(name("push", false, cond))
+            .op(Operand::List(h & 0xFF | (h & 0x100) << 6, false)),
+        0xBC00 | 0xBD00 => {
+            let insn = Insn::new(name("pop", false, cond))
+                .op(Operand::List(h & 0xFF | (h & 0x100) << 7, false));
+            if h & 0x100 != 0 {
+                insn.loads_pc(SP)
+            } else {
+                insn
+            }
+        }
+        0xB600 if h & 0xFFE8 == 0xB660 => {
+            let base = if h & 0x10 != 0 { "cpsid" } else { "cpsie" };
+            let interrupts: String = [(4, 'a'), (2, 'i'), (1, 'f')]
+                .iter()
+                .filter(|(bit, _)| h & bit != 0)
+                .map(|(_, flag)| flag)
+                .collect();
+            Insn::new(base).name(interrupts)
+        }
+        0xBA00 => {
+            let base = match (h >> 6) & 3 {
+                0 => "rev",
+                1 => "rev16",
+                3 => "revsh",
+                _ => return None,
+            };
+            Insn::new(name(base, false, cond)).reg(low(0)).reg(low(3))
+        }
+        0xBE00 => Insn::new("bkpt").imm(h & 0xFF).flow(FlowControl::Interrupt),
+        0xBF00 if h & 15 != 0 => {
+            let first = (h >> 4) & 15;
+            let mask = h & 15;
+            let rest: String = (mask.trailing_zeros() + 1..4)
+                .rev()
+                .map(|bit| {
+                    if (mask >> bit) & 1 == first & 1 {
+                        't'
+                    } else {
+                        'e'
+                    }
+                })
+                .collect();
+            Insn::new(format!("it{}", rest)).name(CONDITIONS[first as usize])
+        }
+        0xBF00 => return hint((h >> 4) & 15, cond),
+        _ => return None,
+    };
+    Some(insn)
+}
+
+// The immediate of Thumb data processing instructions, a byte which is either
+// repeated or rotated
+fn thumb_expand_imm(imm: u32) -> u32 {
+    let byte = imm & 0xFF;
+    if imm >> 10 == 0 {
+        match (imm >> 8) & 3 {
+            0 => byte,
+            1 => byte << 16 | byte,
+            2 => byte << 24 | byte << 8,
+            _ => byte * 0x0101_0101,
+        }
+    } else {
+        (0x80 | imm & 0x7F).rotate_right(imm >> 7)
+    }
+}
+
+// Data processing instructions shared by the immediate and register forms.
+// Some operations are other instructions when a register is the PC.
+fn thumb_data(
+    op: u32,
+    flags: bool,
+    rn: u32,
+    rd: u32,
+    cond: u32,
+    second: Operand,
+    shift: Option<Shift>,
+) -> Option<Insn> {
+    let compare = match op {
+        0 => Some("tst"),
+        4 => Some("teq"),
+        8 => Some("cmn"),
+        13 => Some("cmp"),
+        _ => None,
+    };
+    if let (Some(base), PC, true) = (compare, rd, flags) {
+        return Some(
+            Insn::new(name(base, false, cond))
+                .reg(rn)
+                .op(second)
+                .shift(shift),
+        );
+    }
+    if rn == PC && (op == 2 || op == 3) {
+        // Moves of shifted registers are written as the shift
+        let insn = match (op, shift) {
+            (2, Some(Shift::Imm(kind, amount))) => Insn::new(name(kind, flags, cond))
+                .reg(rd)
+                .op(second)
+                .imm(amount),
+            (2, Some(Shift::Rrx)) => Insn::new(name("rrx", flags, cond)).reg(rd).op(second),
+            _ => {
+                let base = if op == 2 { "mov" } else { "mvn" };
+                Insn::new(name(base, flags, cond))
+                    .reg(rd)
+                    .op(second)
+                    .shift(shift)
+            }
+        };
+        return Some(insn);
+    }
+    let base = match op {
+        0 => "and",
+        1 => "bic",
+        2 => "orr",
+        3 => "orn",
+        4 => "eor",
+        8 => "add",
+        10 => "adc",
+        11 => "sbc",
+        13 => "sub",
+        14 => "rsb",
+        _ => return None,
+    };
+    Some(
+        Insn::new(name(base, flags, cond))
+            .reg(rd)
+            .reg(rn)
+            .op(second)
+            .shift(shift),
+    )
+}
+
+// The name of a special register of M-profile cores in `mrs` and `msr`
+fn special_register(sysm: u32, mask: Option<u32>) -> String {
+    let name = match sysm {
+        0..=3 if mask.is_some() => {
+            let base = ["apsr", "iapsr", "eapsr", "xpsr"][sysm as usize];
+            let fields = match mask {
+                Some(1) => "_g",
+                Some(3) => "_nzcvqg",
+                _ => "_nzcvq",
+            };
+            return format!("{}{}", base, fields);
+        }
+        0 => "apsr",
+        1 => "iapsr",
+        2 => "eapsr",
+        3 => "xpsr",
+        5 => "ipsr",
+        6 => "epsr",
+        7 => "iepsr",
+        8 => "msp",
+        9 => "psp",
+        16 => "primask",
+        17 => "basepri",
+        18 => "basepri_max",
+        19 => "faultmask",
+        20 => "control",
+        _ => return format!("#{}", sysm),
+    };
+    name.to_owned()
+}
+
+// Decodes a 32-bit Thumb instruction from its two halfwords
+fn thumb32(hw1: u32, hw2: u32, address: u64, cond: u32) -> Option<Insn> {
+    let pc = address + 4;
+    let aligned_pc = pc & !3;
+    let rn = hw1 & 15;
+    let rd = (hw2 >> 8) & 15;
+    let rt = hw2 >> 12;
+    let rm = hw2 & 15;
+    let flags = hw1 & 0x10 != 0;
+
+    // Load and store multiple
+    if hw1 & 0xFE40 == 0xE800 {
+        let writeback = hw1 & 0x20 != 0;
+        let load = hw1 & 0x10 != 0;
+        let list = Operand::List(hw2, false);
+        let insn = match ((hw1 >> 7) & 3, load, rn, writeback) {
+            (1, true, SP, true) => Insn::new(name("pop", false, cond)).op(list),
+            (2, false, SP, true) => Insn::new(name("push", false, cond)).op(list),
+            (op @ (1 | 2), _, _, _) => {
+                let base = match (load, op) {
+                    (true, 1) => "ldm",
+                    (true, _) => "ldmdb",
+                    (false, 1) => "stm",
+                    (false, _) => "stmdb",
+                };
+                let base_operand = if writeback {
+                    Operand::Writeback(rn)
+                } else {
+                    Operand::Reg(rn)
+                };
+                Insn::new(name(base, false, cond)).op(base_operand).op(list)
+            }
+            _ => return None,
+        };
+        return Some(if load && hw2 & (1 << PC) != 0 {
+            insn.loads_pc(rn)
+        } else {
+            insn
+        });
+    }
+
+    // Exclusive and dual loads and stores, and table branches
+    if hw1 & 0xFE40 == 0xE840 {
+        let offset = Offset::Imm(((hw2 & 0xFF) << 2) as i64);
+        return match hw1 & 0xFFF0 {
+            0xE840 => Some(
+                Insn::new(name("strex", false, cond))
+                    .reg(rd)
+                    .reg(rt)
+                    .op(Operand::Memory(rn, offset, Indexing::Offset)),
+            ),
+            0xE850 => Some(
+                Insn::new(name("ldrex", false, cond))
+                    .reg(rt)
+                    .op(Operand::Memory(rn, offset, Indexing::Offset)),
+            ),
+            0xE8D0 => match (hw2 >> 4) & 15 {
+                0 => Some(
+                    Insn::new(name("tbb", false, cond))
+                        .op(Operand::Memory(
+                            rn,
+                            Offset::Reg(false, rm, None),
+                            Indexing::Offset,
+                        ))
+                        .flow(FlowControl::IndirectBranch),
+                ),
+                1 => Some(
+                    Insn::new(name("tbh", false, cond))
+                        .op(Operand::Memory(
+                            rn,
+                            Offset::Reg(false, rm, Some(Shift::Imm("lsl", 1))),
+                            Indexing::Offset,
+                        ))
+                        .flow(FlowControl::IndirectBranch),
+                ),
+                op @ (4 | 5) => Some(
+                    Insn::new(name(["ldrexb", "ldrexh"][op as usize - 4], false, cond))
+                        .reg(rt)
+                        .op(Operand::Memory(rn, Offset::None, Indexing::Offset)),
+                ),
+                _ => None,
+            },
+            0xE8C0 => match (hw2 >> 4) & 15 {
+                op @ (4 | 5) => Some(
+                    Insn::new(name(["strexb", "strexh"][op as usize - 4], false, cond))
+                        .reg(rm)
+                        .reg(rt)
+                        .op(Operand::Memory(rn, Offset::None, Indexing::Offset)),
+                ),
+                _ => None,
+            },
+            _ if hw1 & 0x0120 != 0 => {
+                let pre = hw1 & 0x100 != 0;
+                let up = hw1 & 0x80 != 0;
+                let writeback = hw1 & 0x20 != 0;
+                let value = ((hw2 & 0xFF) << 2) as i64;
+                let value = if up { value } else { -value };
+                let base = if flags { "ldrd" } else { "strd" };
+                let mut insn = Insn::new(name(base, false, cond)).reg(rt).reg(rd);
+                if rn == PC {
+                    insn = insn.literal(relative(aligned_pc, value), 8);
+                }
+                Some(insn.op(Operand::Memory(
+                    rn,
+                    Offset::Imm(value),
+                    indexing(pre, writeback),
+                )))
+            }
+            _ => None,
+        };
+    }
+
+    // Data processing with a shifted register
+    if hw1 & 0xFE00 == 0xEA00 && hw2 & 0x8000 == 0 {
+        let amount = (hw2 >> 10) & 0x1C | (hw2 >> 6) & 3;
+        let shift = shift_imm((hw2 >> 4) & 3, amount);
+        let insn = thumb_data(
+            (hw1 >> 5) & 15,
+            flags,
+            rn,
+            rd,
+            cond,
+            Operand::Reg(rm),
+            shift,
+        )?;
+        let source = (shift.is_none() && rn == PC && (hw1 >> 5) & 15 == 2).then_some(rm);
+        return Some(writes(insn, rd, source));
+    }
+
+    // Data processing with an immediate
+    if hw1 & 0xF800 == 0xF000 && hw2 & 0x8000 == 0 {
+        let imm12 = (hw1 & 0x400) << 1 | (hw2 >> 4) & 0x700 | hw2 & 0xFF;
+        if hw1 & 0x200 == 0 {
+            let value = thumb_expand_imm(imm12) as i64;
+            return thumb_data(
+                (hw1 >> 5) & 15,
+                flags,
+                rn,
+                rd,
+                cond,
+                Operand::Imm(value),
+                None,
+            );
+        }
+        let lsb = (hw2 >> 10) & 0x1C | (hw2 >> 6) & 3;
+        return match (hw1 >> 4) & 0x1F {
+            op @ (0x00 | 0x0A) if rn == PC => {
+                let value = if op == 0 {
+                    imm12 as i64
+                } else {
+                    -(imm12 as i64)
+                };
+                let target = relative(aligned_pc, value);
+                Some(
+                    Insn::new(name("adr", false, cond))
+                        .reg(rd)
+                        .op(Operand::Target(target))
+                        .literal(target, 0),
+                )
+            }
+            0x00 => Some(
+                Insn::new(name("addw", false, cond))
+                    .reg(rd)
+                    .reg(rn)
+                    .imm(imm12),
+            ),
+            0x0A => Some(
+                Insn::new(name("subw", false, cond))
+                    .reg(rd)
+                    .reg(rn)
+                    .imm(imm12),
+            ),
+            op @ (0x04 | 0x0C) => {
+                let base = if op == 0x04 { "movw" } else { "movt" };
+                Some(
+                    Insn::new(name(base, false, cond))
+                        .reg(rd)
+                        .imm(rn << 12 | imm12),
+                )
+            }
+            op @ (0x14 | 0x1C) => {
+                let base = if op == 0x14 { "sbfx" } else { "ubfx" };
+                Some(
+                    Insn::new(name(base, false, cond))
+                        .reg(rd)
+                        .reg(rn)
+                        .imm(lsb)
+                        .imm((hw2 & 31) + 1),
+                )
+            }
+            0x16 => {
+                let width = ((hw2 & 31) + 1).checked_sub(lsb)?;
+                let insn = match rn {
+                    PC => Insn::new(name("bfc", false, cond)).reg(rd),
+                    _ => Insn::new(name("bfi", false, cond)).reg(rd).reg(rn),
+                };
+                Some(insn.imm(lsb).imm(width))
+            }
+            _ => None,
+        };
+    }
+
+    // Branches and miscellaneous control
+    if hw1 & 0xF800 == 0xF000 {
+        let sign = (hw1 >> 10) & 1;
+        let j1 = (hw2 >> 13) & 1;
+        let j2 = (hw2 >> 11) & 1;
+        let long_offset = || {
+            let i1 = !(j1 ^ sign) & 1;
+            let i2 = !(j2 ^ sign) & 1;
+            let imm = sign << 24 | i1 << 23 | i2 << 22 | (hw1 & 0x3FF) << 12 | (hw2 & 0x7FF) << 1;
+            sign_extend(imm, 25)
+        };
+        return match hw2 & 0x5000 {
+            0x0000 if (hw1 >> 6) & 0xE != 0xE => {
+                let imm =
+                    sign << 20 | j2 << 19 | j1 << 18 | (hw1 & 0x3F) << 12 | (hw2 & 0x7FF) << 1;
+                Some(Insn::new(name("b", false, (hw1 >> 6) & 15)).branch(
+                    relative(pc, sign_extend(imm, 21)),
+                    FlowControl::ConditionalBranch,
+                ))
+            }
+            0x0000 => thumb32_control(hw1, hw2, cond),
+            0x1000 => {
+                let flow = if cond == AL {
+                    FlowControl::UnconditionalBranch
+                } else {
+                    FlowControl::ConditionalBranch
+                };
+                Some(Insn::new(name("b", false, cond)).branch(relative(pc, long_offset()), flow))
+            }
+            // Switches to ARM, so the target is word aligned
+            0x4000 => Some(
+                Insn::new(name("blx", false, cond))
+                    .branch(relative(aligned_pc, long_offset()), FlowControl::Call),
+            ),
+            _ => Some(
+                Insn::new(name("bl", false, cond))
+                    .branch(relative(pc, long_offset()), FlowControl::Call),
+            ),
+        };
+    }
+
+    // Loads and stores of single registers
+    if hw1 & 0xFE00 == 0xF800 {
+        let size = (hw1 >> 5) & 3;
+        let load = hw1 & 0x10 != 0;
+        let signed = hw1 & 0x100 != 0;
+        let base = match (load, signed, size) {
+            (true, false, 0) => "ldrb",
+            (true, false, 1) => "ldrh",
+            (true, false, 2) => "ldr",
+            (true, true, 0) => "ldrsb",
+            (true, true, 1) => "ldrsh",
+            (false, false, 0) => "strb",
+            (false, false, 1) => "strh",
+            (false, false, 2) => "str",
+            _ => return None,
+        };
+        let bytes = 1 << size;
+        let (offset, indexing, user) = if rn == PC {
+            let value = (hw2 & 0xFFF) as i64;
+            let value = if hw1 & 0x80 != 0 { value } else { -value };
+            (Offset::Imm(value), Indexing::Offset, false)
+        } else if hw1 & 0x80 != 0 {
+            (Offset::Imm((hw2 & 0xFFF) as i64), Indexing::Offset, false)
+        } else if hw2 & 0x800 != 0 {
+            let pre = hw2 & 0x400 != 0;
+            let up = hw2 & 0x200 != 0;
+            let writeback = hw2 & 0x100 != 0;
+            let value = (hw2 & 0xFF) as i64;
+            let value = if up { value } else { -value };
+            if !pre && !writeback {
+                return None;
+            }
+            let user = pre && up && !writeback;
+            (Offset::Imm(value), indexing(pre, writeback), user)
+        } else if (hw2 >> 6) & 0x3F == 0 {
+            let amount = (hw2 >> 4) & 3;
+            let shift = (amount != 0).then_some(Shift::Imm("lsl", amount));
+            (Offset::Reg(false, rm, shift), Indexing::Offset, false)
+        } else {
+            return None;
+        };
+        // Single registers pushed or popped
+        match (rn, load, size, indexing, offset) {
+            (SP, false, 2, Indexing::Pre, Offset::Imm(-4)) => {
+                return Some(
+                    Insn::new(name("push", false, cond)).op(Operand::List(1 << rt, false)),
+                );
+            }
+            (SP, true, 2, Indexing::Post, Offset::Imm(4)) => {
+                let insn = Insn::new(name("pop", false, cond)).op(Operand::List(1 << rt, false));
+                return Some(if rt == PC { insn.loads_pc(SP) } else { insn });
+            }
+            _ => {}
+        }
+        let memory = Operand::Memory(rn, offset, indexing);
+        if load && rt == PC && size == 0 && !signed {
+            return Some(Insn::new(name("pld", false, cond)).op(memory));
+        }
+        let base = if user {
+            format!("{}t", base)
+        } else {
+            base.to_owned()
+        };
+        let mut insn = Insn::new(name(&base, false, cond)).reg(rt).op(memory);
+        if let (PC, Offset::Imm(value)) = (rn, offset) {
+            insn = insn.literal(relative(aligned_pc, value), bytes);
+        }
+        if load && rt == PC {
+            insn = insn.flow(FlowControl::IndirectBranch);
+        }
+        return Some(insn);
+    }
+
+    // Data processing with registers
+    if hw1 & 0xFF00 == 0xFA00 && hw2 & 0xF000 == 0xF000 {
+        if hw1 & 0x80 == 0 && hw2 & 0xF0 == 0 {
+            let base = SHIFTS[((hw1 >> 5) & 3) as usize];
+            return Some(Insn::new(name(base, flags, cond)).reg(rd).reg(rn).reg(rm));
+        }
+        if hw1 & 0x80 == 0 && hw2 & 0x80 != 0 {
+            let (plain, add) = match (hw1 >> 4) & 7 {
+                0 => ("sxth", "sxtah"),
+                1 => ("uxth", "uxtah"),
+                4 => ("sxtb", "sxtab"),
+                5 => ("uxtb", "uxtab"),
+                _ => return None,
+            };
+            let rotation = ((hw2 >> 4) & 3) * 8;
+            let insn = match rn {
+                PC => Insn::new(name(plain, false, cond)).reg(rd).reg(rm),
+                _ => Insn::new(name(add, false, cond)).reg(rd).reg(rn).reg(rm),
+            };
+            return Some(insn.shift((rotation != 0).then_some(Shift::Imm("ror", rotation))));
+        }
+        if hw2 & 0xC0 == 0x80 {
+            let base = match ((hw1 >> 4) & 15, (hw2 >> 4) & 3) {
+                (9, 0) => "rev",
+                (9, 1) => "rev16",
+                (9, 2) => "rbit",
+                (9, 3) => "revsh",
+                (11, 0) => "clz",
+                _ => return None,
+            };
+            return Some(Insn::new(name(base, false, cond)).reg(rd).reg(rm));
+        }
+        return None;
+    }
+
+    // Multiplies and divides
+    if hw1 & 0xFF80 == 0xFB00 {
+        return match ((hw1 >> 4) & 7, (hw2 >> 4) & 15) {
+            (0, 0) if rt == PC => Some(Insn::new(name("mul", false, cond)).reg(rd).reg(rn).reg(rm)),
+            (0, 0) => Some(
+                Insn::new(name("mla", false, cond))
+                    .reg(rd)
+                    .reg(rn)
+                    .reg(rm)
+                    .reg(rt),
+            ),
+            (0, 1) => Some(
+                Insn::new(name("mls", false, cond))
+                    .reg(rd)
+                    .reg(rn)
+                    .reg(rm)
+                    .reg(rt),
+            ),
+            _ => None,
+        };
+    }
+    if hw1 & 0xFF80 == 0xFB80 {
+        let base = match ((hw1 >> 4) & 7, (hw2 >> 4) & 15) {
+            (1, 15) => "sdiv",
+            (3, 15) => "udiv",
+            (0, 0) => "smull",
+            (2, 0) => "umull",
+            (4, 0) => "smlal",
+            (6, 0) => "umlal",
+            _ => return None,
+        };
+        let insn = Insn::new(name(base, false, cond));
+        return Some(if base.ends_with("div") {
+            insn.reg(rd).reg(rn).reg(rm)
+        } else {
+            insn.reg(rt).reg(rd).reg(rn).reg(rm)
+        });
+    }
+    None
+}
+
+// Hints, barriers and accesses to special registers
+fn thumb32_control(hw1: u32, hw2: u32, cond: u32) -> Option<Insn> {
+    match hw1 & 0xFFF0 {
+        0xF380 | 0xF390 if hw2 & 0xF300 == 0x8000 => Some(
+            Insn::new(name("msr", false, cond))
+                .name(special_register(hw2 & 0xFF, Some((hw2 >> 10) & 3)))
+                .reg(hw1 & 15),
+        ),
+        0xF3A0 if hw1 == 0xF3AF && hw2 & 0xFF00 == 0x8000 => hint(hw2 & 0xFF, cond),
+        0xF3B0 if hw1 == 0xF3BF && hw2 & 0xFF00 == 0x8F00 => {
+            let base = match (hw2 >> 4) & 15 {
+                2 if hw2 & 15 == 15 => return Some(Insn::new(name("clrex", false, cond))),
+                4 => "dsb",
+                5 => "dmb",
+                6 => "isb",
+                _ => return None,
+            };
+            Some(Insn::new(name(base, false, cond)).name(barrier(hw2 & 15)))
+        }
+        0xF3E0 | 0xF3F0 if hw2 & 0xF000 == 0x8000 => Some(
+            Insn::new(name("mrs", false, cond))
+                .reg((hw2 >> 8) & 15)
+                .name(special_register(hw2 & 0xFF, None)),
+        ),
+        0xF7F0 if hw2 & 0xF000 == 0xA000 => Some(
+            Insn::new("udf")
+                .imm((hw1 & 15) << 12 | hw2 & 0xFFF)
+                .flow(FlowControl::Exception),
+        ),
+        _ => None,
+    }
+}
+
+fn directive(directive: &str, value: u64, digits: usize) -> Vec<(String, FormatterTextKind)> {
+    vec![
+        (directive.to_owned(), FormatterTextKind::Directive),
+        (" ".repeat(10 - directive.len()), FormatterTextKind::Text),
+        (
+            format!("{:#0width$x}", value, width = digits + 2),
+            FormatterTextKind::Number,
+        ),
+    ]
+}
+
+// Decodes ARM and Thumb code, switching modes where the mapping symbols of
+// the section say and following the conditions of Thumb `it` blocks. Bytes
+// which don't decode are shown as `.inst` and skipped.
+pub struct Decoder<'a> {
+    bytes: &'a [u8],
+    address: u64,
+    modes: &'a [(u64, Mode)],
+    offset: usize,
+    // The condition of the next instruction and the mask of the rest of the
+    // `it` block, like the ITSTATE bits of the CPSR
+    it: u32,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(bytes: &'a [u8], address: u64, modes: &'a [(u64, Mode)]) -> Decoder<'a> {
+        Decoder {
+            bytes,
+            address,
+            modes,
+            offset: 0,
+            it: 0,
+        }
+    }
+
+    fn halfword(&self, offset: usize) -> Option<u32> {
+        let bytes = self.bytes.get(offset..offset + 2)?;
+        Some(u16::from_le_bytes([bytes[0], bytes[1]]) as u32)
+    }
+
+    fn word(&self, offset: usize) -> Option<u32> {
+        let bytes = self.bytes.get(offset..offset + 4)?;
+        Some(u32::from_le_bytes(bytes.try_into().ok()?))
+    }
+
+    // The condition of the instruction about to be decoded, and advances the
+    // `it` block past it
+    fn take_condition(&mut self) -> Option<u32> {
+        if self.it & 15 == 0 {
+            return None;
+        }
+        let cond = self.it >> 4;
+        self.it = if self.it & 7 == 0 {
+            0
+        } else {
+            self.it & 0xE0 | (self.it << 1) & 0x1F
+        };
+        Some(cond)
+    }
+
+    fn thumb(&mut self, address: u64, available: usize) -> (usize, Option<Insn>, String) {
+        let Some(h) = self.halfword(self.offset).filter(|_| available >= 2) else {
+            return (available, None, String::new());
+        };
+        if h >> 11 >= 0x1D {
+            let Some(hw2) = self.halfword(self.offset + 2).filter(|_| available >= 4) else {
+                return (available, None, String::new());
+            };
+            let cond = self.take_condition();
+            let insn = thumb32(h, hw2, address, cond.unwrap_or(AL));
+            (4, insn, format!(".inst.w 0x{:04x}{:04x}", h, hw2))
+        } else {
+            let cond = self.take_condition();
+            let insn = thumb16(h, address, cond.unwrap_or(AL), cond.is_some());
+            if h & 0xFF00 == 0xBF00 && h & 15 != 0 {
+                self.it = h & 0xFF;
+            }
+            (2, insn, format!(".inst.n 0x{:04x}", h))
+        }
+    }
+
+    // Literal pools are shown as words where they're aligned
+    fn data(&self, address: u64, available: usize) -> Decoded {
+        let (len, format) = match available {
+            4.. if address & 3 == 0 => (
+                4,
+                directive(".word", self.word(self.offset).unwrap_or(0) as u64, 8),
+            ),
+            2.. if address & 1 == 0 => (
+                2,
+                directive(".short", self.halfword(self.offset).unwrap_or(0) as u64, 4),
+            ),
+            _ => (1, directive(".byte", self.bytes[self.offset] as u64, 2)),
+        };
+        Decoded {
+            address,
+            len,
+            mode: Mode::Data,
+            format,
+            flow: FlowControl::Next,
+            target: None,
+            literal: None,
+            nop: false,
+            invalid: false,
+            truncated: false,
+        }
+    }
+}
+
+impl Iterator for Decoder<'_> {
+    type Item = Decoded;
+
+    fn next(&mut self) -> Option<Decoded> {
+        if self.offset >= self.bytes.len() {
+            return None;
+        }
+        let address = self.address + self.offset as u64;
+        let mode = mode_at(self.modes, address);
+        // Instructions don't run into the next mode
+        let end = next_change(self.modes, address).map_or(self.bytes.len(), |change| {
+            ((change - self.address) as usize).min(self.bytes.len())
+        });
+        let available = end - self.offset;
+        if mode != Mode::Thumb {
+            self.it = 0;
+        }
+        let decoded = match mode {
+            Mode::Data => self.data(address, available),
+            Mode::Arm | Mode::Thumb => {
+                let (len, insn, inst) = match mode {
+                    Mode::Arm => match self.word(self.offset).filter(|_| available >= 4) {
+                        Some(w) => (4, arm(w, address), format!(".inst 0x{:08x}", w)),
+                        None => (available, None, String::new()),
+                    },
+                    _ => self.thumb(address, available),
+                };
+                let truncated = inst.is_empty();
+                let (format, invalid) = match &insn {
+                    Some(insn) if !truncated => (insn.render(), false),
+                    _ if truncated => {
+                        let bytes = &self.bytes[self.offset..self.offset + len];
+                        let values: Vec<String> =
+                            bytes.iter().map(|byte| format!("{:#04x}", byte)).collect();
+                        let mut format = directive(".byte", bytes[0] as u64, 2);
+                        format.truncate(2);
+                        format.push((values.join(", "), FormatterTextKind::Number));
+                        (format, true)
+                    }
+                    _ => {
+                        let (directive, value) = inst.split_once(' ').unwrap_or_default();
+                        let format = vec![
+                            (directive.to_owned(), FormatterTextKind::Directive),
+                            (" ".repeat(10 - directive.len()), FormatterTextKind::Text),
+                            (value.to_owned(), FormatterTextKind::Number),
+                        ];
+                        (format, true)
+                    }
+                };
+                let insn = insn.filter(|_| !invalid);
+                Decoded {
+                    address,
+                    len,
+                    mode,
+                    format,
+                    flow: insn.as_ref().map_or(FlowControl::Next, |insn| insn.flow),
+                    target: insn.as_ref().and_then(|insn| insn.target),
+                    literal: insn.as_ref().and_then(|insn| insn.literal),
+                    nop: insn.as_ref().is_some_and(|insn| insn.nop),
+                    invalid,
+                    truncated,
+                }
+            }
+        };
+        self.offset += decoded.len;
+        Some(decoded)
+    }
+}
+
+// The rows of ARM code in `symbol`. The loop alignment and branch prediction
+// notes are left out, as they're about x86 cores.
+pub fn assembly(
+    symbol: &SymbolData,
+    object: &Object,
+    modes: &[(u64, Mode)],
+    settings: &Settings,
+) -> Assembly {
+    let bytes = symbol.data().unwrap_or_default();
+    let section = symbol.section.as_ref();
+    let mut previous_source = None;
+    let mut stripe = false;
+    let mut instructions = Vec::new();
+
+    for decoded in Decoder::new(bytes, symbol.address, modes) {
+        let offset = (decoded.address - symbol.address) as usize;
+        let relocation = section.and_then(|section| {
+            (0..decoded.len).find_map(|i| {
+                let r = section.relocations.get(&(decoded.address + i as u64))?;
+                let field = bytes.get(offset + i..).unwrap_or_default();
+                Some((
+                    r.target(),
+                    Reference::classify_arm(r),
+                    relocation_offset(r, field, (decoded.len - i) as i64),
+                ))
+            })
+        });
+        let reference = relocation.map(|(_, reference, _)| reference);
+        let (relocation, relocation_offset) = match relocation {
+            Some((target, _, offset)) => object.relocation_symbol(&target, offset),
+            None => (
+                decoded
+                    .target
+                    .and_then(|target| symbol.target_symbol(object, target)),
+                0,
+            ),
+        };
+
+        let source = object
+            .lines
+            .as_ref()
+            .and_then(|lines| lines.find(section?.index, decoded.address));
+        let group: Option<lines::SourceLocation> = source.filter(|_| source != previous_source);
+        if group.is_some() {
+            if previous_source.is_some() {
+                stripe = !stripe;
+            }
+            previous_source = source;
+        }
+
+        let mut format = decoded.format;
+        // The value of a relocated operand is a placeholder, so the target is
+        // named in its place
+        if reference.is_some() {
+            if let Some(last) = format.iter_mut().rev().find(|(_, kind)| {
+                matches!(
+                    kind,
+                    FormatterTextKind::Number | FormatterTextKind::LabelAddress
+                )
+            }) {
+                last.0.clear();
+            }
+        }
+
+        let mut notes = Vec::new();
+        if decoded.invalid {
+            notes.push(
+                if decoded.truncated {
+                    "instruction is cut off by the end of the symbol or a mode switch"
+                } else {
+                    "unknown instruction"
+                }
+                .to_owned(),
+            );
+        }
+        if let Some((address, size)) = decoded.literal {
+            notes.extend(literal_notes(symbol, object, address, size));
+        }
+
+        instructions.push(Instruction {
+            address: decoded.address,
+            bytes: bytes
+                .get(offset..offset + decoded.len)
+                .unwrap_or_default()
+                .to_vec(),
+            format,
+            registers: Vec::new(),
+            relocation,
+            relocation_offset,
+            reference,
+            padding: decoded.nop,
+            invalid: decoded.invalid,
+            prefixes: 0,
+            stripe,
+            source: group,
+            notes,
+            target: decoded
+                .target
+                .or(decoded.literal.map(|(address, _)| address)),
+            hint: None,
+            uops: None,
+            decoded: None,
+        });
+    }
+
+    if settings.collapse_padding {
+        instructions = crate::collapse_padding(instructions);
+    }
+    Assembly { instructions }
+}
+
+// The word stored at `address`, as loaded by a PC-relative `ldr`
+pub fn literal_value(section: &Section, address: u64) -> Option<u32> {
+    let offset = address.checked_sub(section.address)? as usize;
+    let word = section.data.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+}
+
+// Describes the word a PC-relative load reads, which is often the address of
+// a string or the target of a relocation in objects
+fn literal_notes(symbol: &SymbolData, object: &Object, address: u64, size: usize) -> Vec<String> {
+    let Some(section) = symbol.section.as_ref() else {
+        return Vec::new();
+    };
+    if size == 0 {
+        return symbol.literal_at(object, address).into_iter().collect();
+    }
+    if size != 4 {
+        return Vec::new();
+    }
+    if let Some(relocation) = section.relocations.get(&address) {
+        let offset = address
+            .checked_sub(section.address)
+            .and_then(|offset| section.data.get(offset as usize..))
+            .map(|field| relocation_offset(relocation, field, 4))
+            .unwrap_or(0);
+        let name = match object.relocation_symbol(&relocation.target(), offset) {
+            (Some(target), offset) => {
+                let name = target.demangled.as_ref().unwrap_or(&target.name);
+                match offset {
+                    0 => name.clone(),
+                    offset if offset < 0 => format!("{}-{:#x}", name, offset.unsigned_abs()),
+                    offset => format!("{}+{:#x}", name, offset),
+                }
+            }
+            (None, _) => return Vec::new(),
+        };
+        return vec![format!("= {}", name)];
+    }
+    let Some(value) = literal_value(section, address) else {
+        return Vec::new();
+    };
+    let mut notes = vec![format!("= {:#x}", value)];
+    notes.extend(symbol.literal_at(object, value as u64));
+    notes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics;
+
+    // The Thumb function `f` at 0 with a literal pool at 0x20, followed by
+    // the ARM function `g` at 0x24, as assembled by llvm-mc
+    const CODE: &str = "00f010f8 9847 0028 14bf 0120 0220 01f58070 11f1ff20 024b dff80420 \
+                        7047 00bf 78563412 10402de9 feffffeb fefffffa 1080bde8";
+
+    // The mapping symbols `$t`, `$d` and `$a` of the code
+    const MODES: [(u64, Mode); 3] = [(0, Mode::Thumb), (0x20, Mode::Data), (0x24, Mode::Arm)];
+
+    fn bytes(hex: &str) -> Vec<u8> {
+        let digits: Vec<u8> = hex.bytes().filter(u8::is_ascii_hexdigit).collect();
+        digits
+            .chunks(2)
+            .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap())
+            .collect()
+    }
+
+    fn text(decoded: &Decoded) -> String {
+        let text: String = decoded
+            .format
+            .iter()
+            .map(|(part, _)| part.as_str())
+            .collect();
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    fn decode(hex: &str, modes: &[(u64, Mode)]) -> Vec<Decoded> {
+        Decoder::new(&bytes(hex), 0, modes).collect()
+    }
+
+    fn decoded_at(decoded: &[Decoded], address: u64) -> &Decoded {
+        decoded
+            .iter()
+            .find(|decoded| decoded.address == address)
+            .expect("an instruction starts at the address")
+    }
+
+    #[test]
+    fn parses_mapping_symbols() {
+        assert_eq!(Mode::of_mapping_symbol("$t"), Some(Mode::Thumb));
+        assert_eq!(Mode::of_mapping_symbol("$a.2"), Some(Mode::Arm));
+        assert_eq!(Mode::of_mapping_symbol("$d.1"), Some(Mode::Data));
+        assert_eq!(Mode::of_mapping_symbol("$x"), None);
+        assert_eq!(Mode::of_mapping_symbol("$tx"), None);
+
+        assert_eq!(mode_at(&MODES[1..], 0x10), Mode::Arm);
+        assert_eq!(mode_at(&MODES, 0x1e), Mode::Thumb);
+        assert_eq!(mode_at(&MODES, 0x20), Mode::Data);
+        assert_eq!(mode_at(&MODES, 0x30), Mode::Arm);
+    }
+
+    #[test]
+    fn switches_modes_at_mapping_symbols() {
+        let decoded = decode(CODE, &MODES);
+        let rows: Vec<(u64, Mode, String)> = decoded
+            .iter()
+            .map(|decoded| (decoded.address, decoded.mode, text(decoded)))
+            .collect();
+        let expected = [
+            (0x1c, Mode::Thumb, "bx lr"),
+            (0x1e, Mode::Thumb, "nop"),
+            (0x20, Mode::Data, ".word 0x12345678"),
+            (0x24, Mode::Arm, "push {r4, lr}"),
+        ];
+        for (address, mode, text) in expected {
+            assert!(
+                rows.contains(&(address, mode, text.to_owned())),
+                "{:#x}: {:?}",
+                address,
+                rows
+            );
+        }
+        assert!(decoded.iter().all(|decoded| !decoded.invalid));
+    }
+
+    #[test]
+    fn cuts_off_instructions_at_mode_switches() {
+        // The first half of a Thumb `bl` before a literal pool
+        let decoded = decode("00f0 78563412", &[(0, Mode::Thumb), (2, Mode::Data)]);
+        assert!(decoded[0].truncated && decoded[0].invalid);
+        assert_eq!(decoded[0].len, 2);
+        assert_eq!(decoded[1].address, 2);
+        assert_eq!(decoded[1].mode, Mode::Data);
+    }
+
+    #[test]
+    fn decodes_thumb_calls() {
+        let decoded = decode(CODE, &MODES);
+
+        let bl = decoded_at(&decoded, 0);
+        assert_eq!((text(bl).as_str(), bl.len), ("bl 0x24", 4));
+        assert_eq!((bl.flow, bl.target), (FlowControl::Call, Some(0x24)));
+
+        let blx = decoded_at(&decoded, 4);
+        assert_eq!((text(blx).as_str(), blx.len), ("blx r3", 2));
+        assert_eq!((blx.flow, blx.target), (FlowControl::IndirectCall, None));
+    }
+
+    #[test]
+    fn decodes_arm_calls() {
+        let decoded = decode(CODE, &MODES);
+
+        let bl = decoded_at(&decoded, 0x28);
+        assert_eq!(text(bl), "bl 0x28");
+        assert_eq!((bl.flow, bl.target), (FlowControl::Call, Some(0x28)));
+
+        let blx = decoded_at(&decoded, 0x2c);
+        assert_eq!(text(blx), "blx 0x2c");
+        assert_eq!((blx.flow, blx.target), (FlowControl::Call, Some(0x2c)));
+
+        let pop = decoded_at(&decoded, 0x30);
+        assert_eq!(text(pop), "pop {r4, pc}");
+        assert_eq!(pop.flow, FlowControl::Return);
+    }
+
+    #[test]
+    fn follows_it_blocks() {
+        let decoded = decode(CODE, &MODES);
+        let texts: Vec<String> = [8, 0xa, 0xc, 0xe]
+            .into_iter()
+            .map(|address| text(decoded_at(&decoded, address)))
+            .collect();
+        assert_eq!(
+            texts,
+            [
+                "ite ne",
+                "movne r0, #1",
+                "moveq r0, #2",
+                "add r0, r1, #0x100",
+            ]
+        );
+    }
+
+    #[test]
+    fn ends_it_blocks_at_mode_switches() {
+        // `it eq` as the last Thumb instruction, then `mov r0, r1` in ARM
+        let decoded = decode("08bf 0100a0e1", &[(0, Mode::Thumb), (2, Mode::Arm)]);
+        assert_eq!(text(&decoded[1]), "mov r0, r1");
+    }
+
+    #[test]
+    fn decodes_thumb2_data_processing() {
+        let decoded = decode(CODE, &MODES);
+        assert_eq!(text(decoded_at(&decoded, 0xe)), "add r0, r1, #0x100");
+        assert_eq!(text(decoded_at(&decoded, 0x12)), "adds r0, r1, #0xff00ff00");
+    }
+
+    #[test]
+    fn decodes_literal_loads() {
+        let decoded = decode(CODE, &MODES);
+
+        let ldr = decoded_at(&decoded, 0x16);
+        assert_eq!(text(ldr), "ldr r3, [pc, #8]");
+        assert_eq!(ldr.literal, Some((0x20, 4)));
+
+        let wide = decoded_at(&decoded, 0x18);
+        assert_eq!(text(wide), "ldr r2, [pc, #4]");
+        assert_eq!(wide.literal, Some((0x20, 4)));
+    }
+
+    #[test]
+    fn computes_metrics() {
+        let metrics = metrics::compute_arm(&bytes(CODE), 0, &MODES);
+        assert_eq!(metrics.calls, 4);
+        assert_eq!(metrics.branches, 0);
+
+        // movs r0, #0; 1: adds r0, #1; cmp r0, #10; bne 1b; bx lr
+        let metrics = metrics::compute_arm(&bytes("0020 0130 0a28 fcd1 7047"), 0, &MODES[..1]);
+        assert_eq!(metrics.branches, 1);
+        assert_eq!(metrics.basic_blocks, 3);
+        assert_eq!(metrics.complexity, 2);
+        assert_eq!(metrics.loop_depth, 1);
+    }
+}
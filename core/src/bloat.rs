@@ -0,0 +1,178 @@
+use std::{cmp::Reverse, collections::HashMap, sync::Arc};
+
+use crate::Object;
+
+// What symbol sizes are summed by
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Grouping {
+    Crate,
+    Namespace,
+    Section,
+}
+
+impl Grouping {
+    pub const ALL: [Grouping; 3] = [Grouping::Crate, Grouping::Namespace, Grouping::Section];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Grouping::Crate => "Crate",
+            Grouping::Namespace => "Namespace",
+            Grouping::Section => "Section",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Group {
+    pub name: String,
+    pub size: u64,
+    pub symbols: usize,
+}
+
+// Symbols without a path are C functions or names which couldn't be demangled
+const UNKNOWN: &str = "[unknown]";
+
+// Splits at the first `pattern` outside of generic arguments and parameters
+fn split_outside<'a>(name: &'a str, pattern: &str) -> Option<(&'a str, &'a str)> {
+    let mut depth = 0usize;
+    for (i, c) in name.char_indices() {
+        if depth == 0 && name[i..].starts_with(pattern) {
+            return Some((&name[..i], &name[i + pattern.len()..]));
+        }
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    None
+}
+
+// The `::` separated segments of a path, up to its parameters. A return type
+// in front of C++ template functions is skipped.
+fn path(name: &str) -> Vec<&str> {
+    let name = split_outside(name, "(").map_or(name, |(path, _)| path);
+    let name = match split_outside(name, " ") {
+        Some((_, rest)) if !rest.is_empty() => rest.rsplit(' ').next().unwrap_or(rest),
+        _ => name,
+    };
+    let mut segments = Vec::new();
+    let mut rest = name;
+    while let Some((segment, next)) = split_outside(rest, "::") {
+        segments.push(segment);
+        rest = next;
+    }
+    segments.push(rest);
+    segments
+}
+
+// The `::` separated segments of a demangled name. `<Type as Trait>::f` and
+// `<impl Trait for Type>::f` are filed under the path of `Type`, or of
+// `Trait` if `Type` has no path.
+fn segments(name: &str) -> Vec<&str> {
+    let mut segments = match name.strip_prefix('<') {
+        Some(rest) => {
+            let (inner, after) = split_outside(rest, ">").unwrap_or((rest, ""));
+            let (ty, tr) =
+                match inner.strip_prefix("impl ") {
+                    Some(inner) => split_outside(inner, " for ")
+                        .map_or((inner, None), |(tr, ty)| (ty, Some(tr))),
+                    None => split_outside(inner, " as ")
+                        .map_or((inner, None), |(ty, tr)| (ty, Some(tr))),
+                };
+            let mut segments = match (path(ty), tr) {
+                (segments, Some(tr)) if segments.len() == 1 => path(tr),
+                (segments, _) => segments,
+            };
+            if let Some(after) = after.strip_prefix("::") {
+                segments.extend(path(after));
+            }
+            segments
+        }
+        None => path(name),
+    };
+    // The hash of legacy Rust mangling
+    if let [_, .., last] = segments[..] {
+        if last.len() == 17
+            && last.starts_with('h')
+            && last[1..].bytes().all(|b| b.is_ascii_hexdigit())
+        {
+            segments.pop();
+        }
+    }
+    segments
+}
+
+pub fn crate_name(name: &str) -> Option<&str> {
+    let segments = segments(name);
+    (segments.len() > 1).then(|| segments[0])
+}
+
+// The path of the enclosing namespace or type
+pub fn namespace(name: &str) -> Option<String> {
+    let segments = segments(name);
+    (segments.len() > 1).then(|| segments[..segments.len() - 1].join("::"))
+}
+
+// The path of a demangled name with the generic arguments left out, so the
+// instantiations of a generic function share it. Names without a path are
+// filed under `[unknown]`.
+pub fn generic_path(name: &str) -> Vec<String> {
+    let mut segments: Vec<String> = segments(name)
+        .into_iter()
+        .filter(|segment| !segment.starts_with('<'))
+        .map(|segment| split_outside(segment, "<").map_or(segment, |(name, _)| name))
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_owned)
+        .collect();
+    if segments.len() < 2 {
+        let name = segments.pop().unwrap_or_else(|| name.to_owned());
+        segments = vec![UNKNOWN.to_owned(), name];
+    }
+    segments
+}
+
+// The sizes of the functions and data of `objects` summed by `grouping`,
+// largest first
+pub fn groups(objects: &[Arc<Object>], grouping: Grouping) -> Vec<Group> {
+    let mut groups: HashMap<String, Group> = HashMap::new();
+    let mut add = |name: String, size: u64| {
+        let group = groups.entry(name).or_insert_with_key(|name| Group {
+            name: name.clone(),
+            size: 0,
+            symbols: 0,
+        });
+        group.size += size;
+        group.symbols += 1;
+    };
+    let key = |name: &str, section: Option<&str>| match grouping {
+        Grouping::Crate => crate_name(name).map(str::to_owned),
+        Grouping::Namespace => namespace(name),
+        Grouping::Section => section.map(str::to_owned),
+    };
+    for object in objects {
+        for symbol in &object.symbols_sorted {
+            let name = symbol.demangled.as_ref().unwrap_or(&symbol.name);
+            let section = symbol.section.as_ref().map(|section| section.name.as_str());
+            let size = symbol.extent().unwrap_or(symbol.size);
+            add(
+                key(name, section).unwrap_or_else(|| UNKNOWN.to_owned()),
+                size,
+            );
+        }
+        for symbol in &object.data_symbols {
+            let section = object
+                .sections
+                .iter()
+                .find(|section| section.index == symbol.section)
+                .map(|section| section.name.as_str());
+            add(
+                key(&symbol.name, section).unwrap_or_else(|| UNKNOWN.to_owned()),
+                symbol.size,
+            );
+        }
+    }
+    let mut groups: Vec<Group> = groups.into_values().collect();
+    groups.sort_by_key(|group| (Reverse(group.size), group.name.clone()));
+    groups
+}
@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+
+use crate::{
+    lines::{LineTable, SourceLocation},
+    overlay::ImportedSymbol,
+};
+
+// Symbols and line records read from a Breakpad `.sym` file
+pub struct BreakpadSymbols {
+    pub symbols: Vec<ImportedSymbol>,
+    pub lines: Option<LineTable>,
+}
+
+pub fn is_breakpad(text: &str) -> bool {
+    text.starts_with("MODULE ")
+}
+
+// Splits off `count` space separated fields, leaving the rest of the line,
+// which may contain spaces, as the last item.
+fn fields(line: &str, count: usize) -> Vec<&str> {
+    line.splitn(count + 1, ' ').collect()
+}
+
+fn hex(field: &str) -> Option<u64> {
+    u64::from_str_radix(field, 16).ok()
+}
+
+// Parses a `.sym` file. Addresses in it are relative to the module base,
+// which is `image_base` in the loaded binary.
+pub fn parse(text: &str, image_base: u64) -> BreakpadSymbols {
+    let mut symbols = Vec::new();
+    let mut files: Vec<String> = Vec::new();
+    let mut file_numbers = HashMap::new();
+    let mut lines = Vec::new();
+
+    for line in text.lines() {
+        let (record, rest) = line.split_once(' ').unwrap_or((line, ""));
+        // Records of multiple functions folded together are marked with `m`
+        let rest = rest.strip_prefix("m ").unwrap_or(rest);
+        match record {
+            "FILE" => {
+                if let [number, path] = fields(rest, 1)[..] {
+                    if let Ok(number) = number.parse::<u32>() {
+                        file_numbers.insert(number, files.len() as u32);
+                        files.push(path.to_owned());
+                    }
+                }
+            }
+            "FUNC" => {
+                if let [address, size, _, name] = fields(rest, 3)[..] {
+                    if let (Some(address), Some(size)) = (hex(address), hex(size)) {
+                        symbols.push(ImportedSymbol {
+                            name: name.to_owned(),
+                            section: None,
+                            address: image_base + address,
+                            size: Some(size),
+                        });
+                    }
+                }
+            }
+            "PUBLIC" => {
+                if let [address, _, name] = fields(rest, 2)[..] {
+                    if let Some(address) = hex(address) {
+                        symbols.push(ImportedSymbol {
+                            name: name.to_owned(),
+                            section: None,
+                            address: image_base + address,
+                            size: None,
+                        });
+                    }
+                }
+            }
+            "MODULE" | "INFO" | "STACK" | "INLINE" | "INLINE_ORIGIN" => (),
+            // Line records follow their function: `address size line file`
+            _ => {
+                let [address, size, line, file] = fields(line, 3)[..] else {
+                    continue;
+                };
+                let (Some(address), Some(size), Ok(line), Ok(file)) =
+                    (hex(address), hex(size), line.parse(), file.parse::<u32>())
+                else {
+                    continue;
+                };
+                let Some(&file) = file_numbers.get(&file) else {
+                    continue;
+                };
+                lines.push((image_base + address, size, SourceLocation { file, line }));
+            }
+        }
+    }
+
+    BreakpadSymbols {
+        symbols,
+        lines: (!lines.is_empty()).then(|| LineTable::new(files, lines)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use object::SectionIndex;
+
+    use super::*;
+
+    const SYMBOLS: &str = "MODULE Linux x86_64 0123456789ABCDEF0 app\n\
+                           INFO CODE_ID 0123456789ABCDEF\n\
+                           FILE 0 src/main.c\n\
+                           FILE 7 src/util file.c\n\
+                           FUNC 1000 20 0 main(int, char**)\n\
+                           1000 10 5 0\n\
+                           1010 10 6 7\n\
+                           FUNC m 1040 8 0 folded\n\
+                           1040 8 9 3\n\
+                           PUBLIC 2000 0 helper\n\
+                           STACK CFI INIT 1000 20 .cfa: $rsp 8 +\n";
+
+    #[test]
+    fn parses_symbols() {
+        let parsed = parse(SYMBOLS, 0x400000);
+        let symbols: Vec<_> = parsed
+            .symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.address, s.size))
+            .collect();
+        assert_eq!(
+            symbols,
+            [
+                ("main(int, char**)", 0x401000, Some(0x20)),
+                ("folded", 0x401040, Some(8)),
+                ("helper", 0x402000, None)
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_lines() {
+        let lines = parse(SYMBOLS, 0x400000)
+            .lines
+            .expect("the file has line records");
+        let at = |address| lines.find(SectionIndex(0), address);
+
+        let first = at(0x401004).expect("the first line covers 0x401004");
+        assert_eq!((lines.file(first), first.line), ("src/main.c", 5));
+        let second = at(0x401010).expect("the second line covers 0x401010");
+        assert_eq!((lines.file(second), second.line), ("src/util file.c", 6));
+        // Past the end of the last line of `main`
+        assert_eq!(at(0x401020), None);
+        // Lines of unknown files are dropped
+        assert_eq!(at(0x401040), None);
+
+        assert!(parse("MODULE Linux x86_64 0 app\n", 0).lines.is_none());
+    }
+}
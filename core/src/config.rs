@@ -0,0 +1,225 @@
+use std::{fs, path::PathBuf};
+
+use toml_edit::{Array, Document, Item};
+
+use crate::settings::config_dir;
+
+// A value of `config.toml`. Only top level keys with the kinds of values the
+// settings need are read.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Value {
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+    Strings(Vec<String>),
+}
+
+impl Value {
+    fn from_toml(value: &toml_edit::Value) -> Option<Value> {
+        Some(match value {
+            toml_edit::Value::Boolean(value) => Value::Bool(*value.value()),
+            toml_edit::Value::Integer(value) => Value::Integer(*value.value()),
+            toml_edit::Value::Float(value) => Value::Float(*value.value()),
+            toml_edit::Value::String(value) => Value::String(value.value().clone()),
+            toml_edit::Value::Array(values) => Value::Strings(
+                values
+                    .iter()
+                    .map(|value| value.as_str().map(str::to_owned))
+                    .collect::<Option<_>>()?,
+            ),
+            _ => return None,
+        })
+    }
+
+    fn to_toml(&self) -> toml_edit::Value {
+        match self {
+            Value::Bool(value) => (*value).into(),
+            Value::Integer(value) => (*value).into(),
+            Value::Float(value) => (*value).into(),
+            Value::String(value) => value.as_str().into(),
+            Value::Strings(values) => values.iter().collect::<Array>().into(),
+        }
+    }
+}
+
+// The config file, kept as written so saving leaves comments, formatting and
+// keys of other tools in place
+#[derive(Clone, Default)]
+pub struct Config {
+    document: Document,
+    // Why the text didn't parse. Such files aren't overwritten.
+    error: Option<String>,
+}
+
+pub fn config_file() -> Option<PathBuf> {
+    Some(config_dir()?.join("config.toml"))
+}
+
+impl Config {
+    // A file which doesn't parse still provides the top level keys on lines
+    // which parse by themselves
+    pub fn parse(text: &str) -> Config {
+        let error = match text.parse::<Document>() {
+            Ok(document) => {
+                return Config {
+                    document,
+                    error: None,
+                }
+            }
+            Err(error) => error.to_string(),
+        };
+        let mut document = Document::new();
+        for line in text.lines() {
+            // Keys after a table header belong to the table
+            if line.trim_start().starts_with('[') {
+                break;
+            }
+            if let Ok(line) = line.parse::<Document>() {
+                for (key, item) in line.iter() {
+                    document[key] = item.clone();
+                }
+            }
+        }
+        Config {
+            document,
+            error: Some(error),
+        }
+    }
+
+    pub fn load() -> Config {
+        config_file()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|text| Config::parse(&text))
+            .unwrap_or_default()
+    }
+
+    pub fn text(&self) -> String {
+        self.document.to_string()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let Some(path) = config_file() else {
+            return Ok(());
+        };
+        if let Some(error) = &self.error {
+            return Err(format!(
+                "Not saving `{}` as it has errors: {}",
+                path.display(),
+                error.trim()
+            ));
+        }
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        fs::write(&path, self.text())
+            .map_err(|error| format!("Saving `{}`: {}", path.display(), error))
+    }
+
+    pub fn get(&self, key: &str) -> Option<Value> {
+        Value::from_toml(self.document.get(key)?.as_value()?)
+    }
+
+    // Sets a top level key, keeping the comments around an earlier value
+    pub fn set(&mut self, key: &str, value: Value) {
+        let mut value = value.to_toml();
+        if let Some(old) = self.document.get(key).and_then(Item::as_value) {
+            *value.decor_mut() = old.decor().clone();
+        }
+        self.document[key] = Item::Value(value);
+    }
+
+    pub fn bool(&self, key: &str) -> Option<bool> {
+        self.document.get(key)?.as_bool()
+    }
+
+    pub fn integer(&self, key: &str) -> Option<i64> {
+        self.document.get(key)?.as_integer()
+    }
+
+    pub fn float(&self, key: &str) -> Option<f64> {
+        match self.get(key)? {
+            Value::Float(value) => Some(value),
+            Value::Integer(value) => Some(value as f64),
+            _ => None,
+        }
+    }
+
+    pub fn string(&self, key: &str) -> Option<&str> {
+        self.document.get(key)?.as_str()
+    }
+
+    pub fn strings(&self, key: &str) -> Option<Vec<String>> {
+        match self.get(key)? {
+            Value::Strings(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+// Changes some keys of the config file, leaving the keys written by other
+// parts of the viewer as they are
+pub fn update(change: impl FnOnce(&mut Config)) -> Result<(), String> {
+    let mut config = Config::load();
+    let before = config.text();
+    change(&mut config);
+    if config.text() == before {
+        return Ok(());
+    }
+    config.save()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "# Written by hand
+show_bytes = true # keep bytes
+syntax = \"att\"
+recent = [\"a\", \"b\\\\c\"]
+
+[window]
+width = 800
+";
+
+    #[test]
+    fn parse() {
+        let config = Config::parse(TEXT);
+        assert_eq!(config.bool("show_bytes"), Some(true));
+        assert_eq!(config.string("syntax"), Some("att"));
+        assert_eq!(
+            config.strings("recent"),
+            Some(vec!["a".to_owned(), "b\\c".to_owned()])
+        );
+        // Keys of tables aren't settings
+        assert_eq!(config.get("width"), None);
+        assert_eq!(config.float("missing"), None);
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut config = Config::parse(TEXT);
+        assert_eq!(config.text(), TEXT);
+
+        config.set("show_bytes", Value::Bool(false));
+        config.set("threads", Value::Integer(4));
+        config.set("font_size", Value::Float(14.0));
+        let text = config.text();
+        assert!(text.starts_with("# Written by hand\nshow_bytes = false # keep bytes\n"));
+        assert!(text.ends_with("[window]\nwidth = 800\n"));
+
+        let config = Config::parse(&text);
+        assert_eq!(config.bool("show_bytes"), Some(false));
+        assert_eq!(config.integer("threads"), Some(4));
+        assert_eq!(config.float("font_size"), Some(14.0));
+        assert_eq!(config.get("width"), None);
+    }
+
+    #[test]
+    fn invalid() {
+        let config = Config::parse("threads = 4\nbroken\n[window]\nshow_bytes = true\n");
+        assert!(config.error.is_some());
+        assert_eq!(config.integer("threads"), Some(4));
+        assert_eq!(config.bool("show_bytes"), None);
+    }
+}
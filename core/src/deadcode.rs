@@ -0,0 +1,186 @@
+use std::{cmp::Reverse, collections::HashSet, sync::Arc};
+
+use object::{
+    elf, Architecture, Object as _, ObjectKind, ObjectSection, ObjectSymbol, RelocationTarget,
+    SectionKind,
+};
+
+use crate::{Object, Symbol};
+
+// Functions which are called by the runtime rather than by other code
+const ROOT_NAMES: [&str; 7] = [
+    "main", "_start", "_init", "_fini", "DllMain", "WinMain", "wmain",
+];
+
+// References out of an object which aren't visible in its code
+#[derive(Clone, Debug, Default)]
+pub struct References {
+    // Symbols named by relocations, which may be defined in other objects
+    pub names: HashSet<String>,
+    // Addresses stored in data, like function pointers in vtables of
+    // position independent images
+    pub addresses: HashSet<u64>,
+    pub exports: HashSet<String>,
+}
+
+impl References {
+    pub fn collect(file: &object::File) -> References {
+        let mut references = References::default();
+        // Addresses of Thumb functions have the lowest bit set
+        let mask = match file.architecture() {
+            Architecture::Arm => !1,
+            _ => !0,
+        };
+
+        // Debug info and unwind tables refer to every function
+        let sources = file.sections().filter(|section| {
+            section.kind() != SectionKind::Debug && section.name() != Ok(".eh_frame")
+        });
+        for section in sources {
+            for (_, relocation) in section.relocations() {
+                if let RelocationTarget::Symbol(index) = relocation.target() {
+                    if let Ok(symbol) = file.symbol_by_index(index) {
+                        if let Ok(name) = symbol.name() {
+                            references.names.insert(name.to_owned());
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(relocations) = file.dynamic_relocations() {
+            for (_, relocation) in relocations {
+                match relocation.target() {
+                    RelocationTarget::Symbol(index) => {
+                        if let Ok(name) = file.symbol_by_index(index).and_then(|s| s.name()) {
+                            references.names.insert(name.to_owned());
+                        }
+                    }
+                    _ => {
+                        references
+                            .addresses
+                            .insert(relocation.addend() as u64 & mask);
+                    }
+                }
+            }
+        }
+
+        if file.kind() != ObjectKind::Relocatable {
+            references.addresses.insert(file.entry() & mask);
+            references.addresses.extend(code_pointers(file, mask));
+        }
+
+        references.exports = file
+            .exports()
+            .unwrap_or_default()
+            .iter()
+            .map(|export| String::from_utf8_lossy(export.name()).into_owned())
+            .collect();
+
+        references
+    }
+}
+
+// Pointer sized words in the data of a linked image which point into code,
+// like the entries of vtables, vector tables and constructor lists
+fn code_pointers(file: &object::File, mask: u64) -> Vec<u64> {
+    let code: Vec<(u64, u64)> = file
+        .sections()
+        .filter(|section| section.kind() == SectionKind::Text)
+        .map(|section| (section.address(), section.address() + section.size()))
+        .collect();
+    let data = file.sections().filter(|section| {
+        matches!(
+            section.kind(),
+            SectionKind::Data
+                | SectionKind::ReadOnlyData
+                | SectionKind::ReadOnlyDataWithRel
+                | SectionKind::Elf(
+                    elf::SHT_INIT_ARRAY | elf::SHT_FINI_ARRAY | elf::SHT_PREINIT_ARRAY
+                )
+        ) && section.name() != Ok(".eh_frame")
+    });
+
+    let size = if file.is_64() { 8 } else { 4 };
+    let mut pointers = Vec::new();
+    for section in data {
+        let Ok(bytes) = section.data() else {
+            continue;
+        };
+        for word in bytes.chunks_exact(size) {
+            let mut value = [0; 8];
+            let value = if file.is_little_endian() {
+                value[..size].copy_from_slice(word);
+                u64::from_le_bytes(value)
+            } else {
+                value[8 - size..].copy_from_slice(word);
+                u64::from_be_bytes(value)
+            } & mask;
+            if code
+                .iter()
+                .any(|&(start, end)| (start..end).contains(&value))
+            {
+                pointers.push(value);
+            }
+        }
+    }
+    pointers
+}
+
+#[derive(Clone)]
+pub struct Candidate {
+    pub symbol: Symbol,
+    pub size: u64,
+}
+
+// Functions with no detected references from any loaded object, largest first
+pub fn candidates(objects: &[Arc<Object>]) -> Vec<Candidate> {
+    let mut names: HashSet<&str> = objects
+        .iter()
+        .flat_map(|object| {
+            object
+                .references
+                .names
+                .iter()
+                .chain(&object.references.exports)
+        })
+        .map(|name| name.as_str())
+        .collect();
+    // Calls from code, leaving out recursion
+    for object in objects {
+        for (target, xrefs) in object.callers.iter() {
+            if xrefs.iter().any(|xref| xref.from != target) {
+                names.insert(target);
+            }
+        }
+    }
+
+    let mut candidates = Vec::new();
+    for object in objects {
+        for symbol in &object.symbols_sorted {
+            let root = ROOT_NAMES.contains(&symbol.name.as_str())
+                || symbol
+                    .section
+                    .as_ref()
+                    .map(|section| section.name == ".init" || section.name == ".fini")
+                    .unwrap_or(false);
+            if root
+                || symbol.section.is_none()
+                || names.contains(symbol.name.as_str())
+                || object.callers.address_taken(&symbol.name)
+                || object.references.addresses.contains(&symbol.address)
+            {
+                continue;
+            }
+            candidates.push(Candidate {
+                size: symbol.data().map(|data| data.len()).unwrap_or_default() as u64,
+                symbol: Symbol {
+                    object: object.clone(),
+                    data: symbol.clone(),
+                },
+            });
+        }
+    }
+    candidates.sort_by_key(|candidate| Reverse(candidate.size));
+    candidates
+}
@@ -0,0 +1,144 @@
+use std::collections::BTreeMap;
+
+use gimli::{BaseAddresses, CieOrFde, EhFrame, LittleEndian, UnwindSection};
+use iced_x86::FlowControl;
+use object::SectionKind;
+
+use crate::{arm, overlay::ImportedSymbol, raw, Isa, Object, Section};
+
+fn is_code(section: &Section) -> bool {
+    section.kind == SectionKind::Text
+}
+
+// Whether the symbol table names any code, which stripped images don't
+pub fn has_functions(object: &Object) -> bool {
+    object
+        .symbols_sorted
+        .iter()
+        .any(|symbol| symbol.section.as_deref().is_some_and(is_code))
+}
+
+// Decoder mode of the code
+fn bitness(object: &Object) -> u32 {
+    object
+        .sections
+        .iter()
+        .find(|section| is_code(section))
+        .map_or(64, |section| section.bitness)
+}
+
+// Function starts described by `.eh_frame`, with their sizes
+fn unwind_entries(object: &Object, section: &Section, starts: &mut BTreeMap<u64, Option<u64>>) {
+    let mut eh_frame = EhFrame::new(&section.data[..], LittleEndian);
+    eh_frame.set_address_size(if bitness(object) == 64 { 8 } else { 4 });
+    let mut bases = BaseAddresses::default().set_eh_frame(section.address);
+    if let Some(text) = object
+        .sections
+        .iter()
+        .find(|section| section.name == ".text")
+    {
+        bases = bases.set_text(text.address);
+    }
+    let mut entries = eh_frame.entries(&bases);
+    while let Ok(Some(entry)) = entries.next() {
+        let CieOrFde::Fde(partial) = entry else {
+            continue;
+        };
+        if let Ok(fde) = partial.parse(|_, bases, offset| eh_frame.cie_from_offset(bases, offset)) {
+            starts.insert(fde.initial_address(), Some(fde.len()));
+        }
+    }
+}
+
+// Function starts from the `RUNTIME_FUNCTION` records of x64 images, with
+// their sizes
+fn pdata_entries(object: &Object, section: &Section, starts: &mut BTreeMap<u64, Option<u64>>) {
+    // Records of other architectures have other layouts
+    if bitness(object) != 64
+        || object
+            .sections
+            .iter()
+            .any(|section| section.isa != Isa::X86)
+    {
+        return;
+    }
+    let word = |bytes: &[u8]| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as u64;
+    for entry in section.data.chunks_exact(12) {
+        let (begin, end) = (word(&entry[0..4]), word(&entry[4..8]));
+        if begin != 0 && end > begin {
+            starts.insert(object.image_base + begin, Some(end - begin));
+        }
+    }
+}
+
+// Targets of direct calls in a code section
+fn call_targets(section: &Section) -> Vec<u64> {
+    match &section.isa {
+        Isa::Arm(modes) => arm::Decoder::new(&section.data[..], section.address, modes)
+            .filter(|decoded| decoded.flow == FlowControl::Call)
+            .filter_map(|decoded| decoded.target)
+            .collect(),
+        Isa::X86 => {
+            let mut decoder = iced_x86::Decoder::with_ip(
+                section.bitness,
+                &section.data[..],
+                section.address,
+                iced_x86::DecoderOptions::NONE,
+            );
+            let mut instruction = iced_x86::Instruction::default();
+            let mut targets = Vec::new();
+            while decoder.can_decode() {
+                decoder.decode_out(&mut instruction);
+                if instruction.flow_control() == FlowControl::Call
+                    && matches!(
+                        instruction.op0_kind(),
+                        iced_x86::OpKind::NearBranch16
+                            | iced_x86::OpKind::NearBranch32
+                            | iced_x86::OpKind::NearBranch64
+                    )
+                {
+                    targets.push(instruction.near_branch_target());
+                }
+            }
+            targets
+        }
+    }
+}
+
+// Functions of an image without symbols, found from the entry point, code
+// pointers in data, unwind tables and the targets of direct calls. They're
+// named like functions added by hand.
+pub fn functions(object: &Object) -> Vec<ImportedSymbol> {
+    let mut starts: BTreeMap<u64, Option<u64>> = BTreeMap::new();
+    for section in &object.sections {
+        match section.name.as_str() {
+            ".eh_frame" => unwind_entries(object, section, &mut starts),
+            ".pdata" => pdata_entries(object, section, &mut starts),
+            _ if is_code(section) => {
+                for target in call_targets(section) {
+                    starts.entry(target).or_insert(None);
+                }
+            }
+            _ => (),
+        }
+    }
+    for &address in &object.references.addresses {
+        starts.entry(address).or_insert(None);
+    }
+
+    let in_code = |address: u64| {
+        object.sections.iter().any(|section| {
+            is_code(section) && (section.address..section.address + section.size).contains(&address)
+        })
+    };
+    starts
+        .into_iter()
+        .filter(|&(address, _)| in_code(address))
+        .map(|(address, size)| ImportedSymbol {
+            name: raw::function_name(address),
+            section: None,
+            address,
+            size,
+        })
+        .collect()
+}
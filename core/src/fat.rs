@@ -0,0 +1,62 @@
+use object::{
+    macho::{CPU_SUBTYPE_ARM64E, CPU_SUBTYPE_MASK, CPU_SUBTYPE_X86_64_H},
+    read::macho::{FatArch, FatHeader},
+    Architecture, FileKind,
+};
+
+// An architecture in a Mach-O universal binary
+pub struct Slice<'data> {
+    // Named as by `lipo`, like `arm64` or `x86_64`
+    pub architecture: String,
+    pub data: &'data [u8],
+}
+
+fn name(arch: &impl FatArch) -> String {
+    let subtype = arch.cpusubtype() & !CPU_SUBTYPE_MASK;
+    match arch.architecture() {
+        Architecture::X86_64 if subtype == CPU_SUBTYPE_X86_64_H => "x86_64h".to_owned(),
+        Architecture::X86_64 => "x86_64".to_owned(),
+        Architecture::I386 => "i386".to_owned(),
+        Architecture::Aarch64 if subtype == CPU_SUBTYPE_ARM64E => "arm64e".to_owned(),
+        Architecture::Aarch64 => "arm64".to_owned(),
+        Architecture::Arm => "arm".to_owned(),
+        Architecture::PowerPc => "ppc".to_owned(),
+        Architecture::PowerPc64 => "ppc64".to_owned(),
+        _ => format!("cpu {:#x}", arch.cputype()),
+    }
+}
+
+fn collect<'data>(file: &'data [u8], arches: &[impl FatArch]) -> object::Result<Vec<Slice<'data>>> {
+    arches
+        .iter()
+        .map(|arch| {
+            Ok(Slice {
+                architecture: name(arch),
+                data: arch.data(file)?,
+            })
+        })
+        .collect()
+}
+
+// The slices of a universal binary, or `None` if `file` isn't one. Each slice
+// is an object or an archive of its own.
+pub fn slices(file: &[u8]) -> Option<object::Result<Vec<Slice<'_>>>> {
+    match FileKind::parse(file) {
+        Ok(FileKind::MachOFat32) => {
+            Some(FatHeader::parse_arch32(file).and_then(|arches| collect(file, arches)))
+        }
+        Ok(FileKind::MachOFat64) => {
+            Some(FatHeader::parse_arch64(file).and_then(|arches| collect(file, arches)))
+        }
+        _ => None,
+    }
+}
+
+// The data of the slice for `architecture`
+pub fn find<'data>(file: &'data [u8], architecture: &str) -> Option<&'data [u8]> {
+    slices(file)?
+        .ok()?
+        .into_iter()
+        .find(|slice| slice.architecture == architecture)
+        .map(|slice| slice.data)
+}
@@ -0,0 +1,70 @@
+use std::{
+    path::PathBuf,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use object::{Object as _, SymbolIndex};
+
+// Identifies an object by the file and build it was loaded from, so it can be
+// recognized again after a reload and across sessions
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ObjectId {
+    pub path: PathBuf,
+    // Name and offset of the archive member
+    pub member: Option<(String, u64)>,
+    // Architecture of the slice of a universal binary
+    pub slice: Option<String>,
+    // GNU build ID, Mach-O UUID or PDB GUID and age, if the object has one
+    pub build: Option<Vec<u8>>,
+}
+
+impl ObjectId {
+    pub fn new(
+        path: PathBuf,
+        member: Option<(String, u64)>,
+        slice: Option<String>,
+        file: &object::File,
+    ) -> ObjectId {
+        let build = if let Ok(Some(id)) = file.build_id() {
+            Some(id.to_vec())
+        } else if let Ok(Some(uuid)) = file.mach_uuid() {
+            Some(uuid.to_vec())
+        } else if let Ok(Some(pdb)) = file.pdb_info() {
+            let mut id = pdb.guid().to_vec();
+            id.extend_from_slice(&pdb.age().to_le_bytes());
+            Some(id)
+        } else {
+            None
+        };
+        ObjectId {
+            path,
+            member,
+            slice,
+            build,
+        }
+    }
+
+    // The build ID in hex
+    pub fn build_hex(&self) -> Option<String> {
+        let build = self.build.as_ref()?;
+        Some(build.iter().map(|byte| format!("{:02x}", byte)).collect())
+    }
+}
+
+// Identifies a symbol by its index in the symbol table. Imported symbols
+// which aren't in the table are told apart by name and address.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SymbolId {
+    pub object: ObjectId,
+    pub index: Option<SymbolIndex>,
+    pub name: String,
+    pub address: u64,
+}
+
+// Numbers the objects in the order they're made, including the copies made
+// when reloading or changing how they're decoded. List keys pair it with the
+// id, so rows are rebuilt when the contents of an object change.
+pub fn next_generation() -> u64 {
+    static GENERATION: AtomicU64 = AtomicU64::new(0);
+    GENERATION.fetch_add(1, Ordering::Relaxed)
+}
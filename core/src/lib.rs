@@ -0,0 +1,1767 @@
+//! Loading, symbol indexing and disassembly of object files, shared by the
+//! viewer and anything else which wants to inspect them without a GUI.
+
+use std::{
+    borrow::Cow,
+    collections::{BTreeSet, HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc, OnceLock},
+};
+
+use iced_x86::Formatter;
+use lines::LineTable;
+use mapping::{Mapping, SectionData};
+use metrics::Metrics;
+use object::{
+    read::archive::ArchiveFile, Architecture, BinaryFormat, CompressionFormat, Object as _,
+    ObjectSection, ObjectSymbol, Relocation, RelocationKind, RelocationTarget, SectionFlags,
+    SectionIndex, SectionKind, SymbolIndex, SymbolKind,
+};
+use settings::{Settings, Syntax};
+
+pub mod arm;
+pub mod bloat;
+pub mod breakpad;
+pub mod config;
+pub mod deadcode;
+pub mod discover;
+pub mod fat;
+pub mod identity;
+pub mod lines;
+pub mod linkage;
+pub mod literals;
+pub mod mapping;
+pub mod metrics;
+pub mod names;
+pub mod overlay;
+pub mod plt;
+pub mod pool;
+pub mod prediction;
+pub mod raw;
+pub mod relocations;
+pub mod sections;
+pub mod settings;
+pub mod symsrv;
+pub mod uops;
+pub mod xrefs;
+
+// Location of an object extracted from an archive
+#[derive(Clone, Debug)]
+pub struct ArchiveMember {
+    pub name: String,
+    pub offset: u64,
+    pub size: u64,
+}
+
+pub struct Object {
+    pub id: identity::ObjectId,
+    pub generation: u64,
+    pub path: PathBuf,
+    pub name: String,
+    pub member: Option<ArchiveMember>,
+    // Architecture of the slice of a universal binary the object is from
+    pub slice: Option<String>,
+    // `None` for raw binaries
+    pub format: Option<BinaryFormat>,
+    // Base address which relative addresses in debug info are relative to
+    pub image_base: u64,
+    pub symbols: HashMap<SymbolIndex, Arc<SymbolData>>,
+    // Symbols standing for the start of a section, which relocations in
+    // relocatable objects often refer to with an addend instead of a function
+    pub section_symbols: HashMap<SymbolIndex, SectionIndex>,
+    // Where other non-text symbols are, for previewing the data relocations
+    // refer to
+    pub data_addresses: HashMap<SymbolIndex, (SectionIndex, u64)>,
+    // Functions of shared libraries by the address of their GOT slot
+    pub got: HashMap<u64, String>,
+    pub symbols_sorted: Vec<Arc<SymbolData>>,
+    // Non-text symbols sorted by name
+    pub data_symbols: Vec<DataSymbol>,
+    pub sections: Vec<Arc<Section>>,
+    pub lines: Option<LineTable>,
+    // Number of symbols of each kind, including non-text symbols
+    pub symbol_kinds: Vec<(SymbolKind, usize)>,
+    // Sections of objects built with `-ffunction-sections`
+    pub text_sections: Vec<sections::TextSection>,
+    pub linkage: linkage::Linkage,
+    pub references: deadcode::References,
+    // Parts of a malformed or truncated file which were skipped
+    pub warnings: Vec<String>,
+    // Defined symbols sorted by address, built on first use
+    pub by_address: OnceLock<Vec<Arc<SymbolData>>>,
+    pub callers: xrefs::Callers,
+}
+
+impl Object {
+    // Key for lists of objects, which changes when the object is replaced
+    pub fn key(&self) -> (identity::ObjectId, u64) {
+        (self.id.clone(), self.generation)
+    }
+
+    // Name shown in the object list, `archive.a(member.o)` for archive members
+    // and `libfoo.dylib (arm64)` for slices of universal binaries
+    pub fn title(&self) -> String {
+        let file = || {
+            self.path
+                .file_name()
+                .map(|name| name.to_string_lossy())
+                .unwrap_or_default()
+        };
+        match (&self.member, &self.slice) {
+            (Some(member), None) => format!("{}({})", file(), member.name),
+            (Some(member), Some(slice)) => format!("{} ({})({})", file(), slice, member.name),
+            (None, Some(slice)) => format!("{} ({})", self.name, slice),
+            (None, None) => self.name.clone(),
+        }
+    }
+
+    // The parse warnings and the sections which failed to decompress so far
+    pub fn all_warnings(&self) -> Vec<String> {
+        let sections = self.sections.iter().filter_map(|section| {
+            let error = section.data.error()?;
+            Some(format!(
+                "Section `{}`: failed to decompress: {}",
+                section.name, error
+            ))
+        });
+        self.warnings.iter().cloned().chain(sections).collect()
+    }
+
+    pub fn bitness(&self) -> u32 {
+        self.sections
+            .first()
+            .map(|section| section.bitness)
+            .unwrap_or(64)
+    }
+
+    fn by_address(&self) -> &[Arc<SymbolData>] {
+        self.by_address.get_or_init(|| {
+            let mut symbols: Vec<_> = self
+                .symbols_sorted
+                .iter()
+                .filter(|symbol| symbol.section.is_some())
+                .cloned()
+                .collect();
+            symbols
+                .sort_by_key(|symbol| (symbol.section.as_ref().map(|s| s.index.0), symbol.address));
+            symbols
+        })
+    }
+
+    // The defined symbol starting at `address` in `section`, for naming branch
+    // targets in images without relocations
+    pub fn symbol_at(&self, section: SectionIndex, address: u64) -> Option<Arc<SymbolData>> {
+        let by_address = self.by_address();
+        let key = (Some(section.0), address);
+        let i = by_address.partition_point(|symbol| {
+            (symbol.section.as_ref().map(|s| s.index.0), symbol.address) < key
+        });
+        by_address
+            .get(i)
+            .filter(|symbol| {
+                symbol.address == address
+                    && symbol.section.as_ref().map(|s| s.index) == Some(section)
+            })
+            .cloned()
+    }
+
+    // The defined symbol in `section` which `address` is within
+    pub fn symbol_containing(
+        &self,
+        section: SectionIndex,
+        address: u64,
+    ) -> Option<Arc<SymbolData>> {
+        let by_address = self.by_address();
+        let key = (Some(section.0), address);
+        let i = by_address.partition_point(|symbol| {
+            (symbol.section.as_ref().map(|s| s.index.0), symbol.address) <= key
+        });
+        by_address
+            .get(i.checked_sub(1)?)
+            .filter(|symbol| {
+                let size = symbol.extent().unwrap_or(symbol.size).max(1);
+                symbol.section.as_ref().map(|s| s.index) == Some(section)
+                    && address - symbol.address < size
+            })
+            .cloned()
+    }
+
+    // A preview of the string at `address` in `section`, if it's read-only data
+    pub fn literal(&self, section: SectionIndex, address: u64) -> Option<String> {
+        let section = self.sections.iter().find(|s| s.index == section)?;
+        if !matches!(
+            section.kind,
+            SectionKind::ReadOnlyData | SectionKind::ReadOnlyString
+        ) {
+            return None;
+        }
+        let offset: usize = address.checked_sub(section.address)?.try_into().ok()?;
+        literals::preview(section.data.get(offset..)?)
+    }
+
+    // The symbol which `offset` from the start of `section` is within, with
+    // the offset into the symbol
+    fn section_offset(&self, section: SectionIndex, offset: i64) -> Option<(Arc<SymbolData>, i64)> {
+        let start = self.sections.iter().find(|s| s.index == section)?.address;
+        let address = start.checked_add_signed(offset)?;
+        let symbol = self.symbol_containing(section, address)?;
+        let offset = (address - symbol.address) as i64;
+        Some((symbol, offset))
+    }
+
+    // The symbol a relocation refers to, with the offset into it. References
+    // to sections are resolved to the symbol at the offset.
+    fn relocation_symbol(
+        &self,
+        target: &RelocationTarget,
+        offset: i64,
+    ) -> (Option<Arc<SymbolData>>, i64) {
+        let within = |section, offset| {
+            self.section_offset(section, offset)
+                .map_or((None, 0), |(symbol, offset)| (Some(symbol), offset))
+        };
+        match target {
+            RelocationTarget::Symbol(i) => {
+                match (self.symbols.get(i), self.section_symbols.get(i)) {
+                    (Some(symbol), _) => (Some(symbol.clone()), offset),
+                    (None, Some(&section)) => within(section, offset),
+                    (None, None) => (None, 0),
+                }
+            }
+            RelocationTarget::Section(section) => within(*section, offset),
+            _ => (None, 0),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Section {
+    pub index: SectionIndex,
+    pub name: String,
+    // Set if the name isn't valid UTF-8
+    pub raw_name: Option<Arc<[u8]>>,
+    pub data: SectionData,
+    // Uncompressed size
+    pub size: u64,
+    pub address: u64,
+    // Decoder mode, 16, 32 or 64
+    pub bitness: u32,
+    pub isa: Isa,
+    pub kind: SectionKind,
+    pub flags: SectionFlags,
+
+    // Shared with copies of the section made when importing symbols
+    pub relocations: Arc<HashMap<u64, Relocation>>,
+    // Names of the symbols the relocations refer to, so references can be
+    // indexed again without the file
+    pub relocation_targets: Arc<HashMap<u64, String>>,
+
+    // A sorted list of symbol positions
+    pub symbols: Vec<u64>,
+}
+
+// Instruction set of the code in a section
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Isa {
+    // Decoded in the mode of `bitness`
+    X86,
+    // ARM and Thumb code, with the addresses where the mode changes in order
+    Arm(Vec<(u64, arm::Mode)>),
+}
+
+#[derive(Debug)]
+pub struct SymbolData {
+    // Index in the symbol table, unless the symbol was imported
+    pub index: Option<SymbolIndex>,
+    pub name: String,
+    // Set if the name isn't valid UTF-8
+    pub raw_name: Option<Arc<[u8]>>,
+    pub demangled: Option<String>,
+    pub address: u64,
+    pub section: Option<Arc<Section>>,
+    pub size: u64,
+    pub metrics: OnceLock<Metrics>,
+}
+
+impl SymbolData {
+    // The name as stored in the file
+    pub fn name_bytes(&self) -> &[u8] {
+        self.raw_name.as_deref().unwrap_or(self.name.as_bytes())
+    }
+
+    // The name with the bytes which aren't UTF-8 escaped, for exact matching
+    pub fn exact_name(&self) -> Cow<'_, str> {
+        match &self.raw_name {
+            Some(raw) => Cow::Owned(names::escape(raw)),
+            None => Cow::Borrowed(&self.name),
+        }
+    }
+
+    // The distance to the next symbol at a higher address, or to the end of
+    // the section. Aliases at the same address are skipped over.
+    pub fn estimate_size(&self) -> Option<u64> {
+        let section = self.section.as_ref()?;
+        let end = section.address.checked_add(section.size)?;
+        let i = section
+            .symbols
+            .partition_point(|&address| address <= self.address);
+        section
+            .symbols
+            .get(i)
+            .copied()
+            .unwrap_or(end)
+            .checked_sub(self.address)
+    }
+
+    // The bytes the symbol covers. The size recorded in the symbol table is
+    // used when there is one, as the estimate includes padding and alignment
+    // up to the next symbol.
+    pub fn extent(&self) -> Option<u64> {
+        if self.size == 0 {
+            return self.estimate_size();
+        }
+        let section = self.section.as_ref()?;
+        let end = section.address.checked_add(section.size)?;
+        Some(self.size.min(end.checked_sub(self.address)?))
+    }
+
+    pub fn metrics(&self) -> Metrics {
+        *self.metrics.get_or_init(|| {
+            self.data()
+                .map(|data| match self.arm_modes() {
+                    Some(modes) => metrics::compute_arm(data, self.address, modes),
+                    None => metrics::compute(data, self.address, self.bitness()),
+                })
+                .unwrap_or_default()
+        })
+    }
+
+    // Where the section changes between ARM and Thumb code, if it's ARM code.
+    // The analyses built on iced only apply to other symbols.
+    pub fn arm_modes(&self) -> Option<&[(u64, arm::Mode)]> {
+        match &self.section.as_ref()?.isa {
+            Isa::Arm(modes) => Some(modes),
+            Isa::X86 => None,
+        }
+    }
+
+    pub fn bitness(&self) -> u32 {
+        self.section
+            .as_ref()
+            .map(|section| section.bitness)
+            .unwrap_or(64)
+    }
+
+    pub fn data(&self) -> Option<&[u8]> {
+        let section = self.section.as_ref()?;
+        let size: usize = self.extent()?.try_into().ok()?;
+        let offset: usize = self.address.checked_sub(section.address)?.try_into().ok()?;
+        let end = offset.checked_add(size)?;
+        section.data.get(offset..end)
+    }
+
+    // The symbol a direct branch goes to, for instructions without relocations
+    pub fn branch_target(
+        &self,
+        object: &Object,
+        instruction: &iced_x86::Instruction,
+    ) -> Option<Arc<SymbolData>> {
+        if !matches!(
+            instruction.op0_kind(),
+            iced_x86::OpKind::NearBranch16
+                | iced_x86::OpKind::NearBranch32
+                | iced_x86::OpKind::NearBranch64
+        ) {
+            return None;
+        }
+        self.target_symbol(object, instruction.near_branch_target())
+    }
+
+    // The symbol a direct branch to `target` goes to, unless it's within this
+    // one
+    pub fn target_symbol(&self, object: &Object, target: u64) -> Option<Arc<SymbolData>> {
+        if target != self.address
+            && (self.address
+                ..self
+                    .address
+                    .saturating_add(self.extent().unwrap_or(self.size)))
+                .contains(&target)
+        {
+            return None;
+        }
+        // Sections of relocatable objects all start at 0, so prefer the
+        // current one
+        let contains = |section: &&Arc<Section>| {
+            (section.address..section.address.saturating_add(section.size)).contains(&target)
+        };
+        let section = self
+            .section
+            .as_ref()
+            .filter(contains)
+            .or_else(|| object.sections.iter().find(contains))?;
+        object.symbol_at(section.index, target)
+    }
+
+    // A preview of the string at `address` in a linked image. Sections of
+    // relocatable objects all start at 0, so the current one is preferred
+    // like for branch targets.
+    fn literal_at(&self, object: &Object, address: u64) -> Option<String> {
+        let contains = |section: &&Arc<Section>| {
+            (section.address..section.address.saturating_add(section.size)).contains(&address)
+        };
+        let section = self
+            .section
+            .as_ref()
+            .filter(contains)
+            .or_else(|| object.sections.iter().find(contains))?;
+        object.literal(section.index, address)
+    }
+
+    pub fn assembly(&self, object: &Object, settings: &Settings) -> Option<Arc<Assembly>> {
+        let bytes = self.data()?;
+        if let Some(modes) = self.arm_modes() {
+            return Some(Arc::new(arm::assembly(self, object, modes, settings)));
+        }
+        let bitness = self.bitness();
+        let mut decoder = iced_x86::Decoder::with_ip(
+            bitness,
+            bytes,
+            self.address,
+            iced_x86::DecoderOptions::NONE,
+        );
+
+        let mut formatter: Box<dyn Formatter> = match settings.syntax {
+            Syntax::Intel => Box::new(iced_x86::IntelFormatter::new()),
+            Syntax::Gas => Box::new(iced_x86::GasFormatter::new()),
+            Syntax::Nasm => Box::new(iced_x86::NasmFormatter::new()),
+            Syntax::Masm => Box::new(iced_x86::MasmFormatter::new()),
+        };
+
+        formatter.options_mut().set_first_operand_char_index(10);
+        formatter
+            .options_mut()
+            .set_space_after_operand_separator(true);
+        if settings.operand_sizes {
+            formatter
+                .options_mut()
+                .set_memory_size_options(iced_x86::MemorySizeOptions::Always);
+        }
+
+        let mut info_factory = iced_x86::InstructionInfoFactory::new();
+
+        let mut instruction = iced_x86::Instruction::default();
+
+        // Control flow of the instructions ending basic blocks
+        let mut flow = HashMap::new();
+
+        let mut previous_source = None;
+        let mut stripe = false;
+
+        // Checked for macro-fusion with the next instruction
+        let mut previous = None;
+
+        let mut assembly = Assembly {
+            instructions: Vec::new(),
+        };
+
+        while decoder.can_decode() {
+            let start_index = decoder.position();
+            decoder.decode_out(&mut instruction);
+
+            if instruction.is_invalid() {
+                // Decoding resumes at the next byte, which finds the
+                // instructions again after data or a truncated instruction
+                let address = self.address + start_index as u64;
+                let truncated = decoder.last_error() == iced_x86::DecoderError::NoMoreBytes;
+                assembly.instructions.push(bad_byte(
+                    address,
+                    bytes[start_index],
+                    truncated,
+                    stripe,
+                ));
+                let _ = decoder.set_position(start_index + 1);
+                decoder.set_ip(address + 1);
+                previous = None;
+                continue;
+            }
+
+            let mut relocation = None;
+
+            if let Some(section) = &self.section {
+                for i in 0..instruction.len() {
+                    if let Some(r) = section.relocations.get(&(instruction.ip() + i as u64)) {
+                        let field = bytes.get(start_index + i..).unwrap_or_default();
+                        relocation = Some((
+                            r.target(),
+                            relocations::Reference::classify(r, instruction.flow_control()),
+                            relocation_offset(r, field, (instruction.len() - i) as i64),
+                        ));
+                    }
+                }
+            }
+
+            let literal = match &relocation {
+                Some((RelocationTarget::Symbol(i), _, offset)) => {
+                    let start = match object.section_symbols.get(i) {
+                        Some(&section) => object
+                            .sections
+                            .iter()
+                            .find(|s| s.index == section)
+                            .map(|s| (section, s.address)),
+                        None => object.data_addresses.get(i).copied(),
+                    };
+                    start.and_then(|(section, address)| {
+                        object.literal(section, address.checked_add_signed(*offset)?)
+                    })
+                }
+                Some((RelocationTarget::Section(section), _, offset)) => object
+                    .sections
+                    .iter()
+                    .find(|s| s.index == *section)
+                    .and_then(|s| object.literal(*section, s.address.checked_add_signed(*offset)?)),
+                Some(_) => None,
+                None if instruction.is_ip_rel_memory_operand() => {
+                    self.literal_at(object, instruction.ip_rel_memory_address())
+                }
+                None => None,
+            };
+
+            let reference = relocation.map(|(_, reference, _)| reference);
+            let (relocation, relocation_offset) = match relocation {
+                Some((target, _, offset)) => object.relocation_symbol(&target, offset),
+                // Linked images have no relocations, so name direct branches to
+                // other symbols by their target address
+                None => (self.branch_target(object, &instruction), 0),
+            };
+
+            let source = object
+                .lines
+                .as_ref()
+                .and_then(|lines| lines.find(self.section.as_ref()?.index, instruction.ip()));
+            let group = source.filter(|_| source != previous_source);
+            if group.is_some() {
+                if previous_source.is_some() {
+                    stripe = !stripe;
+                }
+                previous_source = source;
+            }
+
+            let mut inst = Instruction {
+                address: instruction.ip(),
+                bytes: bytes
+                    .get(start_index..start_index + instruction.len())
+                    .unwrap_or_default()
+                    .to_vec(),
+                format: Vec::new(),
+                registers: Vec::new(),
+                relocation,
+                relocation_offset,
+                reference,
+                padding: instruction.mnemonic() == iced_x86::Mnemonic::Nop,
+                invalid: false,
+                prefixes: prefix_bytes(
+                    &instruction,
+                    bytes.get(start_index..).unwrap_or_default(),
+                    bitness,
+                ),
+                stripe,
+                source: group,
+                notes: if settings.operand_sizes {
+                    operand_width_notes(&instruction, bitness, &mut info_factory)
+                } else {
+                    Vec::new()
+                },
+                target: if instruction.is_ip_rel_memory_operand() {
+                    Some(instruction.ip_rel_memory_address())
+                } else if matches!(
+                    instruction.op0_kind(),
+                    iced_x86::OpKind::NearBranch16
+                        | iced_x86::OpKind::NearBranch32
+                        | iced_x86::OpKind::NearBranch64
+                ) {
+                    Some(instruction.near_branch_target())
+                } else {
+                    None
+                },
+                hint: None,
+                uops: if settings.show_uops {
+                    uops::count(settings.target, &instruction, &mut info_factory)
+                } else {
+                    None
+                },
+                decoded: Some(instruction),
+            };
+            formatter.format(&instruction, &mut inst);
+            inst.notes.extend(literal);
+            // Calls compiled with `-fno-plt` go through the GOT directly
+            if inst.relocation.is_none() && instruction.is_ip_rel_memory_operand() {
+                if let Some(name) = object.got.get(&instruction.ip_rel_memory_address()) {
+                    inst.notes.push(format!("{}@got", name));
+                }
+            }
+
+            if let Some(feature) = uops::unsupported(settings.target, &instruction) {
+                inst.notes.push(format!(
+                    "requires {:?}, which {} lacks",
+                    feature,
+                    settings.target.label()
+                ));
+            }
+
+            if let (true, Some(previous)) = (settings.show_uops, &previous) {
+                if uops::fuses(settings.target, previous, &instruction) {
+                    inst.uops = Some(0);
+                    if let Some(first) = assembly.instructions.last_mut() {
+                        let branch = format!("{:?}", instruction.mnemonic()).to_lowercase();
+                        first
+                            .notes
+                            .push(format!("macro-fuses with the following {}", branch));
+                    }
+                }
+            }
+            previous = Some(instruction);
+
+            // Relocated operands hold placeholder values, which an assembler
+            // could encode in a shorter form
+            if !inst.padding && inst.reference.is_none() {
+                if let Some(note) = reencoding_note(&instruction, bitness, &inst.bytes) {
+                    inst.notes.push(note);
+                }
+            }
+
+            if instruction.flow_control() != iced_x86::FlowControl::Next {
+                flow.insert(inst.address, instruction.flow_control());
+            }
+            assembly.instructions.push(inst);
+        }
+
+        loop_header_notes(&mut assembly.instructions, &flow);
+        prediction::hints(&mut assembly.instructions, &flow);
+
+        if settings.collapse_padding {
+            assembly.instructions = collapse_padding(assembly.instructions);
+        }
+
+        Some(Arc::new(assembly))
+    }
+}
+
+// Notes loop headers whose basic block crosses a cache line or a 32-byte fetch
+// block, which costs an extra fetch on every iteration
+fn loop_header_notes(instructions: &mut [Instruction], flow: &HashMap<u64, iced_x86::FlowControl>) {
+    let starts: HashSet<u64> = instructions
+        .iter()
+        .filter_map(Instruction::local_target)
+        .collect();
+    let headers: HashSet<u64> = instructions
+        .iter()
+        .filter_map(|i| i.local_target().filter(|&target| target <= i.address))
+        .collect();
+    for index in 0..instructions.len() {
+        let header = instructions[index].address;
+        if !headers.contains(&header) {
+            continue;
+        }
+        let mut end = header;
+        for (n, i) in instructions[index..].iter().enumerate() {
+            if n > 0 && starts.contains(&i.address) {
+                break;
+            }
+            end = i.address + i.bytes.len() as u64;
+            if flow.contains_key(&i.address) {
+                break;
+            }
+        }
+        let last = end.saturating_sub(1).max(header);
+        let note = if header / 64 != last / 64 {
+            "loop header block crosses a 64-byte cache line"
+        } else if header / 32 != last / 32 {
+            "loop header block crosses a 32-byte fetch boundary"
+        } else {
+            continue;
+        };
+        instructions[index].notes.push(note.to_owned());
+    }
+}
+
+fn prefix_bytes(instruction: &iced_x86::Instruction, bytes: &[u8], bitness: u32) -> usize {
+    let legacy = bytes
+        .iter()
+        .take_while(|byte| {
+            matches!(
+                byte,
+                0xF0 | 0xF2 | 0xF3 | 0x2E | 0x36 | 0x3E | 0x26 | 0x64 | 0x65 | 0x66 | 0x67
+            )
+        })
+        .count();
+    let first = bytes.get(legacy).copied();
+    let extended = match instruction.encoding() {
+        iced_x86::EncodingKind::VEX if first == Some(0xC5) => 2,
+        iced_x86::EncodingKind::VEX | iced_x86::EncodingKind::XOP => 3,
+        iced_x86::EncodingKind::EVEX => 4,
+        _ => usize::from(bitness == 64 && first.is_some_and(|byte| byte & 0xF0 == 0x40)),
+    };
+    legacy + extended
+}
+
+// A `(bad)` row for a byte which isn't the start of a valid instruction
+fn bad_byte(address: u64, byte: u8, truncated: bool, stripe: bool) -> Instruction {
+    use iced_x86::FormatterTextKind;
+
+    let note = if truncated {
+        "instruction is cut off by the end of the symbol"
+    } else {
+        "invalid instruction"
+    };
+    Instruction {
+        address,
+        bytes: vec![byte],
+        format: vec![
+            ("(bad)".to_owned(), FormatterTextKind::Mnemonic),
+            ("     ".to_owned(), FormatterTextKind::Text),
+            ("db".to_owned(), FormatterTextKind::Directive),
+            (" ".to_owned(), FormatterTextKind::Text),
+            (format!("0x{:02x}", byte), FormatterTextKind::Number),
+        ],
+        registers: Vec::new(),
+        relocation: None,
+        relocation_offset: 0,
+        reference: None,
+        padding: false,
+        invalid: true,
+        prefixes: 0,
+        stripe,
+        source: None,
+        notes: vec![note.to_owned()],
+        target: None,
+        hint: None,
+        uops: None,
+        decoded: None,
+    }
+}
+
+// Replaces runs of padding NOPs with a single `align N` pseudo-instruction
+// which keeps the bytes of the whole run.
+fn collapse_padding(instructions: Vec<Instruction>) -> Vec<Instruction> {
+    fn flush(run: &mut Vec<Instruction>, result: &mut Vec<Instruction>) {
+        let Some(first) = run.first() else {
+            return;
+        };
+        let bytes: Vec<u8> = run.iter().flat_map(|i| i.bytes.iter().copied()).collect();
+        let end = first.address + bytes.len() as u64;
+        let directive = [64, 32, 16, 8, 4]
+            .into_iter()
+            .find(|&align| end.is_multiple_of(align) && (bytes.len() as u64) < align)
+            .map(|align| format!("align {}", align))
+            .unwrap_or_else(|| "padding".to_owned());
+        let notes = vec![format!(
+            "{} bytes in {} nop{}",
+            bytes.len(),
+            run.len(),
+            if run.len() == 1 { "" } else { "s" }
+        )];
+        result.push(Instruction {
+            address: first.address,
+            bytes,
+            format: vec![(directive, iced_x86::FormatterTextKind::Directive)],
+            registers: Vec::new(),
+            relocation: None,
+            relocation_offset: 0,
+            reference: None,
+            padding: true,
+            invalid: false,
+            prefixes: 0,
+            stripe: first.stripe,
+            source: first.source,
+            notes,
+            target: None,
+            hint: None,
+            uops: run.iter().map(|i| i.uops).sum(),
+            decoded: None,
+        });
+        run.clear();
+    }
+
+    let mut result = Vec::with_capacity(instructions.len());
+    let mut run = Vec::new();
+    for instruction in instructions {
+        if instruction.padding {
+            run.push(instruction);
+        } else {
+            flush(&mut run, &mut result);
+            result.push(instruction);
+        }
+    }
+    flush(&mut run, &mut result);
+    result
+}
+
+fn register_name(register: iced_x86::Register) -> String {
+    format!("{:?}", register).to_lowercase()
+}
+
+// Notes when encoding the instruction again gives different bytes, so the
+// formatted text wouldn't reproduce it when assembled
+fn reencoding_note(
+    instruction: &iced_x86::Instruction,
+    bitness: u32,
+    bytes: &[u8],
+) -> Option<String> {
+    use iced_x86::{OpKind, Register};
+
+    let mut instruction = *instruction;
+    // Assemblers pick the shortest displacement, while the encoder keeps the
+    // decoded size
+    let memory = (0..instruction.op_count()).any(|i| instruction.op_kind(i) == OpKind::Memory);
+    if memory
+        && !matches!(
+            instruction.memory_base(),
+            Register::None | Register::RIP | Register::EIP
+        )
+    {
+        instruction.set_memory_displ_size(if instruction.memory_displacement64() == 0 {
+            0
+        } else {
+            1
+        });
+    }
+    let mut encoder = iced_x86::Encoder::new(bitness);
+    encoder.encode(&instruction, instruction.ip()).ok()?;
+    let encoded = encoder.take_buffer();
+    if encoded == bytes {
+        return None;
+    }
+    let encoded: Vec<String> = encoded.iter().map(|b| format!("{:02X}", b)).collect();
+    Some(format!("assembles as {}", encoded.join(" ")))
+}
+
+// Describes width effects that aren't visible in the operand text, such as
+// 32-bit writes clearing the upper half of a 64-bit register, partial register
+// writes and registers accessed implicitly by the instruction.
+fn operand_width_notes(
+    instruction: &iced_x86::Instruction,
+    bitness: u32,
+    info_factory: &mut iced_x86::InstructionInfoFactory,
+) -> Vec<String> {
+    use iced_x86::{OpAccess, OpKind, Register};
+
+    let explicit: Vec<Register> = (0..instruction.op_count())
+        .filter(|&i| instruction.op_kind(i) == OpKind::Register)
+        .map(|i| instruction.op_register(i))
+        .chain([instruction.memory_base(), instruction.memory_index()])
+        .filter(|&r| r != Register::None)
+        .collect();
+
+    let mut notes = Vec::new();
+    let mut implicit = Vec::new();
+
+    for used in info_factory.info(instruction).used_registers() {
+        let register = used.register();
+        if !register.is_gpr() {
+            continue;
+        }
+
+        let access = match used.access() {
+            OpAccess::Read => "r",
+            OpAccess::CondRead => "r?",
+            OpAccess::Write => "w",
+            OpAccess::CondWrite => "w?",
+            OpAccess::ReadWrite => "rw",
+            OpAccess::ReadCondWrite => "rw?",
+            _ => continue,
+        };
+        let writes = access.contains('w');
+
+        if explicit.contains(&register) {
+            if writes && bitness == 64 && register.is_gpr32() {
+                notes.push(format!(
+                    "zero-extends to {}",
+                    register_name(register.full_register())
+                ));
+            } else if writes && (register.is_gpr8() || register.is_gpr16()) {
+                notes.push(format!(
+                    "preserves upper bits of {}",
+                    register_name(register.full_register())
+                ));
+            }
+        } else if register.full_register() != Register::RSP {
+            let entry = format!("{} ({})", register_name(register), access);
+            if !implicit.contains(&entry) {
+                implicit.push(entry);
+            }
+        }
+    }
+
+    if !implicit.is_empty() {
+        notes.push(format!("implicit: {}", implicit.join(", ")));
+    }
+
+    notes
+}
+
+// A non-text symbol, which can only be shown as a dump
+#[derive(Clone)]
+pub struct DataSymbol {
+    // Demangled if possible
+    pub name: String,
+    pub mangled: String,
+    pub section: SectionIndex,
+    pub address: u64,
+    pub size: u64,
+}
+
+#[derive(Clone)]
+pub struct Symbol {
+    pub object: Arc<Object>,
+    pub data: Arc<SymbolData>,
+}
+
+impl Symbol {
+    pub fn id(&self) -> identity::SymbolId {
+        identity::SymbolId {
+            object: self.object.id.clone(),
+            index: self.data.index,
+            name: self.data.name.clone(),
+            address: self.data.address,
+        }
+    }
+
+    // Key for lists of symbols, which changes when their object is replaced
+    pub fn key(&self) -> (identity::SymbolId, u64) {
+        (self.id(), self.object.generation)
+    }
+}
+
+#[derive(Clone)]
+pub struct Instruction {
+    pub address: u64,
+    pub bytes: Vec<u8>,
+    pub format: Vec<(String, iced_x86::FormatterTextKind)>,
+    // Parts of `format` which name a register, with the full register so
+    // sub-registers like `edi` are found with `rdi`
+    pub registers: Vec<(usize, iced_x86::Register)>,
+    pub relocation: Option<Arc<SymbolData>>,
+    // Offset from the start of `relocation` to the referenced address, from
+    // the addend of the relocation
+    pub relocation_offset: i64,
+    // Kind of reference made by the relocation, also for unnamed targets
+    pub reference: Option<relocations::Reference>,
+    // Set for NOPs used as alignment padding
+    pub padding: bool,
+    // Set for a byte which doesn't decode as an instruction
+    pub invalid: bool,
+    // Alternates between consecutive source statements
+    pub stripe: bool,
+    // Set on the first instruction of a source statement
+    pub source: Option<lines::SourceLocation>,
+    pub notes: Vec<String>,
+    // Branch target or RIP-relative address
+    pub target: Option<u64>,
+    // Why the branch may be mispredicted
+    pub hint: Option<&'static str>,
+    // Bytes of legacy, REX, VEX, EVEX and XOP prefixes
+    pub prefixes: usize,
+    // Estimated uops on the target CPU, zero for branches
+    // fused into the previous instruction
+    pub uops: Option<u32>,
+    // The decoded instruction, `None` for directives
+    pub decoded: Option<iced_x86::Instruction>,
+}
+
+impl Instruction {
+    // The instruction text without its address, with relocation targets named
+    pub fn text(&self) -> String {
+        let mut text: String = self.format.iter().map(|(s, _)| &**s).collect();
+        if let Some(name) = self.relocation_name() {
+            text.push_str(&name);
+        }
+        text
+    }
+
+    // The relocation target as `symbol+offset`
+    pub fn relocation_name(&self) -> Option<String> {
+        let relocation = self.relocation.as_ref()?;
+        let name = relocation.demangled.as_ref().unwrap_or(&relocation.name);
+        Some(format!("{}{}", name, self.offset_suffix()))
+    }
+
+    // The `+offset` following the name of the relocation target, if any
+    pub fn offset_suffix(&self) -> String {
+        match self.relocation_offset {
+            0 => String::new(),
+            offset if offset < 0 => format!("-{:#x}", offset.unsigned_abs()),
+            offset => format!("+{:#x}", offset),
+        }
+    }
+
+    // The address the relocation refers to, which may be past the start of
+    // the symbol it's relative to
+    pub fn relocation_address(&self) -> Option<u64> {
+        let relocation = self.relocation.as_ref()?;
+        relocation
+            .address
+            .checked_add_signed(self.relocation_offset)
+    }
+
+    // Targets of operands without relocations, which may be within the function
+    pub fn local_target(&self) -> Option<u64> {
+        self.target
+            .filter(|_| self.relocation.is_none() && self.reference.is_none())
+    }
+
+    // The register named at byte `offset` of the formatted instruction
+    pub fn register_at(&self, offset: usize) -> Option<iced_x86::Register> {
+        let mut start = 0;
+        let part = self.format.iter().position(|(part, _)| {
+            start += part.len();
+            offset < start
+        })?;
+        self.registers
+            .iter()
+            .find(|(index, _)| *index == part)
+            .map(|(_, register)| *register)
+    }
+
+    // Parts of `format` which name `register` or one of its sub-registers
+    pub fn register_parts(&self, register: Option<iced_x86::Register>) -> Vec<usize> {
+        self.registers
+            .iter()
+            .filter(|(_, r)| Some(*r) == register)
+            .map(|(index, _)| *index)
+            .collect()
+    }
+}
+
+impl iced_x86::FormatterOutput for Instruction {
+    fn write(&mut self, text: &str, kind: iced_x86::FormatterTextKind) {
+        self.format.push((text.to_owned(), kind));
+    }
+
+    fn write_register(
+        &mut self,
+        _instruction: &iced_x86::Instruction,
+        _operand: u32,
+        _instruction_operand: Option<u32>,
+        text: &str,
+        register: iced_x86::Register,
+    ) {
+        self.registers
+            .push((self.format.len(), register.full_register()));
+        self.write(text, iced_x86::FormatterTextKind::Register);
+    }
+
+    fn write_number(
+        &mut self,
+        _instruction: &iced_x86::Instruction,
+        _operand: u32,
+        _instruction_operand: Option<u32>,
+        text: &str,
+        _value: u64,
+        _number_kind: iced_x86::NumberKind,
+        kind: iced_x86::FormatterTextKind,
+    ) {
+        // Relocated operands are left empty so their target can be named in place
+        if self.relocation.is_none() {
+            self.write(text, kind);
+        } else {
+            self.write("", kind);
+        }
+    }
+}
+
+// The addend stored in the relocated field of the instruction, for formats
+// without an explicit addend
+fn implicit_addend(field: &[u8], bits: u8) -> i64 {
+    let addend = match bits {
+        8 => field.first().map(|&byte| byte as i8 as i64),
+        16 => field
+            .get(..2)
+            .and_then(|field| field.try_into().ok())
+            .map(|field| i16::from_le_bytes(field) as i64),
+        32 => field
+            .get(..4)
+            .and_then(|field| field.try_into().ok())
+            .map(|field| i32::from_le_bytes(field) as i64),
+        64 => field
+            .get(..8)
+            .and_then(|field| field.try_into().ok())
+            .map(i64::from_le_bytes),
+        _ => None,
+    };
+    addend.unwrap_or(0)
+}
+
+// How far past the relocation target the operand refers to. `field` starts at
+// the relocated field and `field_to_end` is its distance to the end of the
+// instruction, which PC-relative operands are relative to, while their
+// addends are relative to the field.
+fn relocation_offset(relocation: &Relocation, field: &[u8], field_to_end: i64) -> i64 {
+    let mut addend = relocation.addend();
+    if relocation.has_implicit_addend() {
+        addend = addend.wrapping_add(implicit_addend(field, relocation.size()));
+    }
+    match relocation.kind() {
+        RelocationKind::Absolute | RelocationKind::ImageOffset => addend,
+        RelocationKind::Relative | RelocationKind::PltRelative => addend.wrapping_add(field_to_end),
+        // GOT entries hold the address of the symbol itself
+        _ => 0,
+    }
+}
+
+pub struct Assembly {
+    pub instructions: Vec<Instruction>,
+}
+
+impl Assembly {
+    // Labels for the instructions targeted from within the function, numbered
+    // in address order
+    pub fn local_labels(&self) -> HashMap<u64, String> {
+        let addresses: HashSet<u64> = self.instructions.iter().map(|i| i.address).collect();
+        let targets: BTreeSet<u64> = self
+            .instructions
+            .iter()
+            .filter_map(Instruction::local_target)
+            .filter(|target| addresses.contains(target))
+            .collect();
+        targets
+            .into_iter()
+            .enumerate()
+            .map(|(n, target)| (target, format!(".L{}", n)))
+            .collect()
+    }
+}
+
+// A message from a worker thread parsing a file
+pub enum Loaded {
+    // The number of members in an archive
+    Members(PathBuf, usize),
+    Object(Box<Object>, Option<symsrv::PdbId>),
+    Errors(Vec<String>),
+    Done(PathBuf),
+}
+
+// The address of a function symbol, without the bit which marks Thumb code
+// in ARM objects
+fn code_address<'data>(symbol: &impl ObjectSymbol<'data>, arm: bool) -> u64 {
+    if arm {
+        symbol.address() & !1
+    } else {
+        symbol.address()
+    }
+}
+
+pub fn parse_object(
+    mapping: &Mapping,
+    data: &[u8],
+    name: String,
+    path: PathBuf,
+    member: Option<ArchiveMember>,
+    slice: Option<String>,
+) -> object::Result<Object> {
+    object::File::parse(data).map(|file| {
+        let bitness = match file.architecture() {
+            Architecture::I386 | Architecture::Arm => 32,
+            _ => 64,
+        };
+        let arm = file.architecture() == Architecture::Arm;
+        let mut warnings = Vec::new();
+        let mut sections: HashMap<SectionIndex, Section> = file
+            .sections()
+            .filter_map(|section| {
+                let (name, raw_name) = match section.name_bytes() {
+                    Ok(name) => names::decode(name),
+                    Err(error) => {
+                        warnings.push(format!(
+                            "Section {}: unreadable name: {}",
+                            section.index().0,
+                            error
+                        ));
+                        return None;
+                    }
+                };
+                let (data, size) = match section.compressed_data() {
+                    Ok(data) => {
+                        let size = match data.format {
+                            CompressionFormat::None => data.data.len() as u64,
+                            _ => data.uncompressed_size,
+                        };
+                        (SectionData::new(mapping, data), size)
+                    }
+                    Err(error) => {
+                        warnings.push(format!("Section `{}`: skipped: {}", name, error));
+                        return None;
+                    }
+                };
+                if section.address().checked_add(size).is_none() {
+                    warnings.push(format!(
+                        "Section `{}`: skipped: address range overflows",
+                        name
+                    ));
+                    return None;
+                }
+                let relocations: HashMap<u64, Relocation> = section.relocations().collect();
+                let relocation_targets = relocations
+                    .iter()
+                    .filter_map(|(&offset, relocation)| {
+                        let RelocationTarget::Symbol(index) = relocation.target() else {
+                            return None;
+                        };
+                        let target = file
+                            .symbol_by_index(index)
+                            .ok()
+                            .filter(|target| target.kind() != SymbolKind::Section)?;
+                        let name = target.name().ok().filter(|name| !name.is_empty())?;
+                        Some((offset, name.to_owned()))
+                    })
+                    .collect();
+                Some((
+                    section.index(),
+                    Section {
+                        index: section.index(),
+                        name,
+                        raw_name,
+                        address: section.address(),
+                        bitness,
+                        isa: if arm { Isa::Arm(Vec::new()) } else { Isa::X86 },
+                        kind: section.kind(),
+                        flags: section.flags(),
+                        data,
+                        size,
+                        symbols: Vec::new(),
+                        relocations: Arc::new(relocations),
+                        relocation_targets: Arc::new(relocation_targets),
+                    },
+                ))
+            })
+            .collect();
+
+        // Insert symbol addresses into sections. ARM code also gets the
+        // switches between ARM and Thumb from mapping symbols, or from the
+        // functions if there are none.
+        let mut mapping: HashMap<SectionIndex, Vec<(u64, arm::Mode)>> = HashMap::new();
+        let mut functions: HashMap<SectionIndex, Vec<(u64, arm::Mode)>> = HashMap::new();
+        file.symbols().for_each(|symbol| {
+            let Some(index) = symbol.section().index() else {
+                return;
+            };
+            if arm {
+                if let Some(mode) = symbol.name().ok().and_then(arm::Mode::of_mapping_symbol) {
+                    mapping
+                        .entry(index)
+                        .or_default()
+                        .push((symbol.address(), mode));
+                    return;
+                }
+            }
+            if symbol.kind() != SymbolKind::Text {
+                return;
+            }
+            let address = code_address(&symbol, arm);
+            if arm {
+                let mode = if symbol.address() & 1 != 0 {
+                    arm::Mode::Thumb
+                } else {
+                    arm::Mode::Arm
+                };
+                functions.entry(index).or_default().push((address, mode));
+            }
+
+            if let Some(section) = sections.get_mut(&index) {
+                section.symbols.push(address);
+            }
+        });
+        for (index, section) in &mut sections {
+            if let Isa::Arm(modes) = &mut section.isa {
+                *modes = mapping
+                    .remove(index)
+                    .or_else(|| functions.remove(index))
+                    .unwrap_or_default();
+                modes.sort_by_key(|&(address, _)| address);
+                modes.dedup_by_key(|&mut (address, _)| address);
+            }
+        }
+
+        // Entries of the PLT get a `function@plt` symbol unless the file has
+        // one there already
+        let got = plt::slots(&file);
+        let mut plt = plt::entries(&file, &sections, &got);
+        plt.retain(|entry| {
+            sections.get_mut(&entry.section).is_some_and(|section| {
+                let new = !section.symbols.contains(&entry.address);
+                if new {
+                    section.symbols.push(entry.address);
+                }
+                new
+            })
+        });
+
+        let section_map: HashMap<SectionIndex, Arc<Section>> = sections
+            .into_iter()
+            .map(|(index, mut section)| {
+                section.symbols.sort_unstable();
+                section.symbols.dedup();
+                (index, Arc::new(section))
+            })
+            .collect();
+
+        let sections = section_map.values().cloned().collect();
+
+        let lines = LineTable::load(&file, &mut warnings)
+            .or_else(|| LineTable::load_separate(&path, &file, &mut warnings));
+
+        let mut unnamed = 0;
+
+        let symbols: HashMap<_, _> = file
+            .symbols()
+            .filter_map(|symbol| {
+                // Filter out non-text symbols
+                (symbol.kind() == SymbolKind::Text).then_some(())?;
+
+                let Ok(name) = symbol.name_bytes() else {
+                    unnamed += 1;
+                    return None;
+                };
+                let (name, raw_name) = names::decode(name);
+                let demangled = settings::demangling().demangle(&name);
+
+                let section = symbol
+                    .section()
+                    .index()
+                    .and_then(|index| section_map.get(&index).cloned());
+
+                Some((
+                    symbol.index(),
+                    Arc::new(SymbolData {
+                        index: Some(symbol.index()),
+                        name,
+                        raw_name,
+                        demangled,
+                        section,
+                        address: code_address(&symbol, arm),
+                        size: symbol.size(),
+                        metrics: OnceLock::new(),
+                    }),
+                ))
+            })
+            .collect();
+
+        let section_symbols = file
+            .symbols()
+            .filter(|symbol| symbol.kind() == SymbolKind::Section)
+            .filter_map(|symbol| Some((symbol.index(), symbol.section().index()?)))
+            .collect();
+
+        let data_addresses = file
+            .symbols()
+            .filter(|symbol| !matches!(symbol.kind(), SymbolKind::Text | SymbolKind::Section))
+            .filter_map(|symbol| {
+                Some((
+                    symbol.index(),
+                    (symbol.section().index()?, symbol.address()),
+                ))
+            })
+            .collect();
+
+        let mut symbols_sorted: Vec<_> = symbols.values().cloned().collect();
+        symbols_sorted.extend(plt.into_iter().map(|entry| {
+            let demangled = entry
+                .name
+                .strip_suffix("@plt")
+                .and_then(|name| settings::demangling().demangle(name))
+                .map(|name| format!("{}@plt", name));
+            Arc::new(SymbolData {
+                index: None,
+                name: entry.name,
+                raw_name: None,
+                demangled,
+                address: entry.address,
+                section: section_map.get(&entry.section).cloned(),
+                size: 0,
+                metrics: OnceLock::new(),
+            })
+        }));
+        symbols_sorted.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+        if unnamed > 0 {
+            warnings.push(format!(
+                "{} function symbols with unreadable names",
+                unnamed
+            ));
+        }
+        let outside = symbols_sorted
+            .iter()
+            .filter(|symbol| symbol.section.is_some() && symbol.data().is_none())
+            .count();
+        if outside > 0 {
+            warnings.push(format!(
+                "{} function symbols outside the data of their section",
+                outside
+            ));
+        }
+
+        let mut data_symbols: Vec<_> = file
+            .symbols()
+            .filter(|symbol| symbol.kind() == SymbolKind::Data)
+            .filter_map(|symbol| {
+                let name = symbol.name().ok()?;
+                Some(DataSymbol {
+                    name: settings::demangling()
+                        .demangle(name)
+                        .unwrap_or_else(|| name.to_owned()),
+                    mangled: name.to_owned(),
+                    section: symbol.section().index()?,
+                    address: symbol.address(),
+                    size: symbol.size(),
+                })
+            })
+            .collect();
+        data_symbols.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+        let mut symbol_kinds: HashMap<SymbolKind, usize> = HashMap::new();
+        file.symbols()
+            .for_each(|symbol| *symbol_kinds.entry(symbol.kind()).or_default() += 1);
+        let symbol_kinds = symbol_kinds.into_iter().collect();
+
+        let id = identity::ObjectId::new(
+            path.clone(),
+            member
+                .as_ref()
+                .map(|member| (member.name.clone(), member.offset)),
+            slice.clone(),
+            &file,
+        );
+        let mut object = Object {
+            id,
+            generation: identity::next_generation(),
+            name,
+            path,
+            member,
+            slice,
+            format: Some(file.format()),
+            image_base: file.relative_address_base(),
+            symbols,
+            section_symbols,
+            data_addresses,
+            got,
+            symbols_sorted,
+            data_symbols,
+            sections,
+            lines,
+            symbol_kinds,
+            text_sections: sections::analyze(&file),
+            linkage: linkage::read(&file),
+            references: deadcode::References::collect(&file),
+            warnings,
+            by_address: OnceLock::new(),
+            callers: Default::default(),
+        };
+        object.callers = xrefs::Callers::collect(&object);
+        object
+    })
+}
+
+// Calls `load` with each member of an archive or with the file itself, and
+// does so for each slice of a universal binary. `load` returns why the data
+// isn't an object it could parse. Returns the errors for the parts which
+// couldn't be loaded.
+pub fn each_object(
+    path: &Path,
+    file: &[u8],
+    mut load: impl FnMut(&[u8], String, Option<ArchiveMember>, Option<String>) -> object::Result<()>,
+) -> Vec<String> {
+    match fat::slices(file) {
+        Some(Ok(slices)) => {
+            let mut errors = Vec::new();
+            for slice in slices {
+                errors.extend(each_member(
+                    path,
+                    slice.data,
+                    Some(slice.architecture),
+                    &mut load,
+                ));
+            }
+            errors
+        }
+        Some(Err(error)) => vec![format!(
+            "`{}`: bad universal binary: {}",
+            path.display(),
+            error
+        )],
+        None => each_member(path, file, None, &mut load),
+    }
+}
+
+fn each_member(
+    path: &Path,
+    file: &[u8],
+    slice: Option<String>,
+    load: &mut impl FnMut(&[u8], String, Option<ArchiveMember>, Option<String>) -> object::Result<()>,
+) -> Vec<String> {
+    let mut errors = Vec::new();
+    let location = match &slice {
+        Some(slice) => format!("{} ({})", path.display(), slice),
+        None => path.display().to_string(),
+    };
+
+    if let Ok(archive) = ArchiveFile::parse(file) {
+        for member in archive.members() {
+            let member = match member {
+                Ok(member) => member,
+                Err(error) => {
+                    errors.push(format!("`{}`: bad archive member: {}", location, error));
+                    // The member table can't be walked past a broken header
+                    break;
+                }
+            };
+            let name = String::from_utf8_lossy(member.name()).into_owned();
+            let (offset, size) = member.file_range();
+            let provenance = ArchiveMember {
+                name: name.clone(),
+                offset,
+                size,
+            };
+            match member.data(file) {
+                Ok(data) => {
+                    if let Err(error) = load(data, name.clone(), Some(provenance), slice.clone()) {
+                        errors.push(format!(
+                            "`{}({})`: not a supported object file: {}",
+                            location, name, error
+                        ));
+                    }
+                }
+                Err(error) => errors.push(format!(
+                    "`{}({})`: truncated member: {}",
+                    location, name, error
+                )),
+            }
+        }
+        return errors;
+    }
+
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default()
+        .into_owned();
+    if let Err(error) = load(file, name, None, slice) {
+        errors.push(format!(
+            "`{}`: not a supported object file: {}",
+            location, error
+        ));
+    }
+    errors
+}
+
+// The number of objects in an archive or universal binary
+pub fn member_count(file: &[u8]) -> Option<usize> {
+    let members = |data: &[u8]| {
+        ArchiveFile::parse(data)
+            .ok()
+            .map(|archive| archive.members().filter(|member| member.is_ok()).count())
+    };
+    match fat::slices(file) {
+        Some(slices) => Some(
+            slices
+                .ok()?
+                .iter()
+                .map(|slice| members(slice.data).unwrap_or(1))
+                .sum(),
+        ),
+        None => members(file),
+    }
+}
+
+// Sends `Done` for a file once the last task parsing its objects is dropped
+struct Pending {
+    path: PathBuf,
+    sender: mpsc::Sender<Loaded>,
+}
+
+impl Drop for Pending {
+    fn drop(&mut self) {
+        let _ = self.sender.send(Loaded::Done(self.path.clone()));
+    }
+}
+
+fn parse_member(
+    file: &Mapping,
+    data: &[u8],
+    name: String,
+    path: PathBuf,
+    member: Option<ArchiveMember>,
+    slice: Option<String>,
+    sender: &mpsc::Sender<Loaded>,
+) {
+    let location = format!("{}({})", path.display(), name);
+    match parse_object(file, data, name, path, member, slice) {
+        Ok(object) => {
+            let id = object::File::parse(data)
+                .ok()
+                .and_then(|file| symsrv::PdbId::from_file(&file));
+            let _ = sender.send(Loaded::Object(Box::new(object), id));
+        }
+        Err(error) => {
+            let error = format!("`{}`: not a supported object file: {}", location, error);
+            let _ = sender.send(Loaded::Errors(vec![error]));
+        }
+    }
+}
+
+// Parses the objects of `path` on the thread pool, sending each object as it
+// completes. Members of archives are parsed in parallel.
+pub fn parse_file(path: PathBuf, sender: mpsc::Sender<Loaded>) {
+    let pending = Arc::new(Pending {
+        path: path.clone(),
+        sender: sender.clone(),
+    });
+    match mapping::map(&path) {
+        Ok(file) => {
+            if let Some(members) = member_count(&file) {
+                let _ = sender.send(Loaded::Members(path.clone(), members));
+            }
+            let errors = each_object(&path, &file, |data, name, member, slice| {
+                // Checked here so files which aren't objects fail like before
+                object::File::parse(data)?;
+                let Some(range) = mapping::range(&file, data) else {
+                    parse_member(&file, data, name, path.clone(), member, slice, &sender);
+                    return Ok(());
+                };
+                let (file, path, sender) = (file.clone(), path.clone(), sender.clone());
+                let pending = pending.clone();
+                pool::spawn(pool::Priority::Batch, move || {
+                    let _pending = pending;
+                    let data = &file[range];
+                    parse_member(&file, data, name, path, member, slice, &sender);
+                });
+                Ok(())
+            });
+            let _ = sender.send(Loaded::Errors(errors));
+        }
+        Err(error) => {
+            let _ = sender.send(Loaded::Errors(vec![format!(
+                "`{}`: {}",
+                path.display(),
+                error
+            )]));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    // A raw 64-bit binary with `code` at 0x1000 and a function at its start
+    fn assemble(code: &[u8], collapse_padding: bool) -> Arc<Assembly> {
+        let layout = raw::Layout {
+            bitness: 64,
+            base: 0x1000,
+        };
+        let object = raw::from_data(
+            PathBuf::from("test.bin"),
+            SectionData::Owned(code.into()),
+            layout,
+        );
+        let settings = Settings {
+            collapse_padding,
+            ..Settings::default()
+        };
+        object.symbols_sorted[0]
+            .assembly(&object, &settings)
+            .expect("the function has code")
+    }
+
+    fn texts(assembly: &Assembly) -> Vec<(u64, String)> {
+        assembly
+            .instructions
+            .iter()
+            .map(|i| {
+                (
+                    i.address,
+                    i.text().split_whitespace().collect::<Vec<_>>().join(" "),
+                )
+            })
+            .collect()
+    }
+
+    // `ret` followed by a 10 and a 5 byte NOP, ending at 0x1010
+    const PADDED: [u8; 16] = [
+        0xc3, 0x66, 0x2e, 0x0f, 0x1f, 0x84, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0f, 0x1f, 0x44, 0x00,
+        0x00,
+    ];
+
+    #[test]
+    fn decodes_instructions() {
+        // push rbp; mov rbp, rsp; pop rbp; ret
+        let assembly = assemble(&[0x55, 0x48, 0x89, 0xe5, 0x5d, 0xc3], false);
+        assert_eq!(
+            texts(&assembly),
+            [
+                (0x1000, "push rbp".to_owned()),
+                (0x1001, "mov rbp, rsp".to_owned()),
+                (0x1004, "pop rbp".to_owned()),
+                (0x1005, "ret".to_owned()),
+            ]
+        );
+        let bytes: Vec<usize> = assembly
+            .instructions
+            .iter()
+            .map(|i| i.bytes.len())
+            .collect();
+        assert_eq!(bytes, [1, 3, 1, 1]);
+    }
+
+    #[test]
+    fn marks_invalid_bytes() {
+        // 0x06 is `push es`, which doesn't exist in 64-bit mode
+        let assembly = assemble(&[0x06, 0xc3], false);
+        let invalid: Vec<(u64, bool)> = assembly
+            .instructions
+            .iter()
+            .map(|i| (i.address, i.invalid))
+            .collect();
+        assert_eq!(invalid, [(0x1000, true), (0x1001, false)]);
+    }
+
+    #[test]
+    fn keeps_padding_without_collapsing() {
+        let assembly = assemble(&PADDED, false);
+        let padding: Vec<(u64, bool)> = assembly
+            .instructions
+            .iter()
+            .map(|i| (i.address, i.padding))
+            .collect();
+        assert_eq!(padding, [(0x1000, false), (0x1001, true), (0x100b, true)]);
+    }
+
+    #[test]
+    fn collapses_padding_to_alignment() {
+        let assembly = assemble(&PADDED, true);
+        let [ret, align] = &assembly.instructions[..] else {
+            panic!("expected 2 rows, got {}", assembly.instructions.len());
+        };
+        assert_eq!(ret.text(), "ret");
+        assert_eq!(align.address, 0x1001);
+        assert_eq!(align.text(), "align 16");
+        assert_eq!(align.bytes, PADDED[1..]);
+        assert!(align.padding);
+        assert_eq!(align.notes, ["15 bytes in 2 nops"]);
+    }
+
+    #[test]
+    fn collapses_unaligned_padding() {
+        // `ret` followed by two NOPs ending at 0x1003, which isn't aligned
+        let assembly = assemble(&[0xc3, 0x90, 0x90, 0xc3], true);
+        assert_eq!(
+            texts(&assembly),
+            [
+                (0x1000, "ret".to_owned()),
+                (0x1001, "padding".to_owned()),
+                (0x1003, "ret".to_owned()),
+            ]
+        );
+        assert_eq!(assembly.instructions[1].notes, ["2 bytes in 2 nops"]);
+    }
+}
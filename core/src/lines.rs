@@ -0,0 +1,352 @@
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use object::{
+    Object as _, ObjectKind, ObjectSection, ObjectSymbol, RelocationKind, RelocationTarget,
+    SectionIndex, SectionKind,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub file: u32,
+    pub line: u32,
+}
+
+#[derive(Clone, Debug)]
+struct Row {
+    address: u64,
+    // `None` marks the end of a sequence
+    location: Option<SourceLocation>,
+}
+
+// Address to source line mapping built from `.debug_line`.
+#[derive(Clone, Debug)]
+pub struct LineTable {
+    files: Vec<String>,
+    rows: Vec<Row>,
+    relocatable: bool,
+}
+
+// Every section of a relocatable object starts at address 0, so addresses
+// relocated against code sections get the section index stored in the upper
+// bits to keep sequences from different sections apart.
+fn section_tag(index: SectionIndex) -> u64 {
+    (index.0 as u64 + 1) << 48
+}
+
+fn load_section<'a>(file: &object::File<'a>, name: &str) -> Option<Cow<'a, [u8]>> {
+    let section = file.section_by_name(name)?;
+    let mut data = section.uncompressed_data().ok()?;
+
+    if file.kind() != ObjectKind::Relocatable {
+        return Some(data);
+    }
+
+    for (offset, relocation) in section.relocations() {
+        if relocation.kind() != RelocationKind::Absolute {
+            continue;
+        }
+        let RelocationTarget::Symbol(index) = relocation.target() else {
+            continue;
+        };
+        let Ok(symbol) = file.symbol_by_index(index) else {
+            continue;
+        };
+
+        let size = match relocation.size() {
+            32 => 4,
+            64 => 8,
+            _ => continue,
+        };
+        let Some(bytes) = usize::try_from(offset)
+            .ok()
+            .and_then(|offset| data.to_mut().get_mut(offset..offset.checked_add(size)?))
+        else {
+            continue;
+        };
+
+        let mut raw = [0; 8];
+        let existing = if file.is_little_endian() {
+            raw[..size].copy_from_slice(bytes);
+            u64::from_le_bytes(raw)
+        } else {
+            raw[8 - size..].copy_from_slice(bytes);
+            u64::from_be_bytes(raw)
+        };
+
+        let addend = if relocation.has_implicit_addend() {
+            existing as i64
+        } else {
+            relocation.addend()
+        };
+        let mut value = symbol.address().wrapping_add(addend as u64);
+
+        let code = symbol.section_index().filter(|&index| {
+            file.section_by_index(index)
+                .map(|section| section.kind() == SectionKind::Text)
+                .unwrap_or(false)
+        });
+        if let Some(index) = code {
+            value |= section_tag(index);
+        }
+
+        if file.is_little_endian() {
+            bytes.copy_from_slice(&value.to_le_bytes()[..size]);
+        } else {
+            bytes.copy_from_slice(&value.to_be_bytes()[8 - size..]);
+        }
+    }
+
+    Some(data)
+}
+
+impl LineTable {
+    pub fn load(file: &object::File, warnings: &mut Vec<String>) -> Option<LineTable> {
+        file.section_by_name(".debug_line")?;
+
+        let endian = if file.is_little_endian() {
+            gimli::RunTimeEndian::Little
+        } else {
+            gimli::RunTimeEndian::Big
+        };
+
+        let sections = gimli::Dwarf::load(|id| -> Result<_, gimli::Error> {
+            Ok(load_section(file, id.name()).unwrap_or(Cow::Borrowed(&[])))
+        })
+        .ok()?;
+        let dwarf = sections.borrow(|section| gimli::EndianSlice::new(section, endian));
+
+        let mut table = LineTable {
+            files: Vec::new(),
+            rows: Vec::new(),
+            relocatable: file.kind() == ObjectKind::Relocatable,
+        };
+        let mut file_indices: HashMap<String, u32> = HashMap::new();
+
+        let mut units = dwarf.units();
+        loop {
+            let header = match units.next() {
+                Ok(Some(header)) => header,
+                Ok(None) => break,
+                Err(error) => {
+                    warnings.push(format!("Line table: stopped at a bad unit: {}", error));
+                    break;
+                }
+            };
+            let unit = match dwarf.unit(header) {
+                Ok(unit) => unit,
+                Err(error) => {
+                    warnings.push(format!("Line table: skipped a unit: {}", error));
+                    continue;
+                }
+            };
+            let Some(program) = unit.line_program.clone() else {
+                continue;
+            };
+
+            let mut rows = program.rows();
+            loop {
+                let (header, row) = match rows.next_row() {
+                    Ok(Some(row)) => row,
+                    Ok(None) => break,
+                    Err(error) => {
+                        warnings.push(format!("Line table: truncated line program: {}", error));
+                        break;
+                    }
+                };
+                if row.end_sequence() {
+                    table.rows.push(Row {
+                        address: row.address(),
+                        location: None,
+                    });
+                    continue;
+                }
+
+                let Some(line) = row.line() else {
+                    continue;
+                };
+
+                let path = row
+                    .file(header)
+                    .map(|entry| {
+                        let mut path = String::new();
+                        if let Some(directory) = entry.directory(header) {
+                            if let Ok(directory) = dwarf.attr_string(&unit, directory) {
+                                path.push_str(&directory.to_string_lossy());
+                            }
+                        }
+                        if let Ok(name) = dwarf.attr_string(&unit, entry.path_name()) {
+                            let name = name.to_string_lossy();
+                            if path.is_empty() || name.starts_with('/') {
+                                path = name.into_owned();
+                            } else {
+                                path.push('/');
+                                path.push_str(&name);
+                            }
+                        }
+                        path
+                    })
+                    .unwrap_or_default();
+
+                let next = table.files.len() as u32;
+                let file = *file_indices.entry(path.clone()).or_insert_with(|| {
+                    table.files.push(path);
+                    next
+                });
+
+                table.rows.push(Row {
+                    address: row.address(),
+                    location: Some(SourceLocation {
+                        file,
+                        line: line.get().try_into().unwrap_or(u32::MAX),
+                    }),
+                });
+            }
+        }
+
+        table.sort();
+
+        (!table.rows.is_empty()).then_some(table)
+    }
+
+    // Loads the table from the separate debug file of a stripped image, found
+    // through `.gnu_debuglink`, the build ID or a `.dSYM` bundle
+    pub fn load_separate(
+        path: &Path,
+        file: &object::File,
+        warnings: &mut Vec<String>,
+    ) -> Option<LineTable> {
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let mut candidates: Vec<PathBuf> = Vec::new();
+        if let Ok(Some((name, _))) = file.gnu_debuglink() {
+            let name = String::from_utf8_lossy(name).into_owned();
+            candidates.push(dir.join(&name));
+            candidates.push(dir.join(".debug").join(&name));
+            if let Ok(dir) = dir.canonicalize() {
+                let relative = dir.strip_prefix("/").unwrap_or(&dir);
+                candidates.push(Path::new("/usr/lib/debug").join(relative).join(&name));
+            }
+        }
+        if let Ok(Some([first, rest @ ..])) = file.build_id() {
+            let rest: String = rest.iter().map(|byte| format!("{:02x}", byte)).collect();
+            candidates.push(
+                Path::new("/usr/lib/debug/.build-id")
+                    .join(format!("{:02x}", first))
+                    .join(format!("{}.debug", rest)),
+            );
+        }
+        if let Some(name) = path.file_name() {
+            let mut bundle = path.as_os_str().to_owned();
+            bundle.push(".dSYM");
+            candidates.push(
+                PathBuf::from(bundle)
+                    .join("Contents/Resources/DWARF")
+                    .join(name),
+            );
+        }
+
+        for candidate in candidates {
+            let Ok(data) = fs::read(&candidate) else {
+                continue;
+            };
+            let Ok(debug) = object::File::parse(data.as_slice()) else {
+                warnings.push(format!(
+                    "Line table: `{}` is not an object file",
+                    candidate.display()
+                ));
+                continue;
+            };
+            if let Some(table) = LineTable::load(&debug, warnings) {
+                return Some(table);
+            }
+        }
+        None
+    }
+
+    // Builds a table from `(address, size, location)` ranges in a linked image
+    pub fn new(files: Vec<String>, ranges: Vec<(u64, u64, SourceLocation)>) -> LineTable {
+        let mut table = LineTable {
+            files,
+            rows: Vec::new(),
+            relocatable: false,
+        };
+        let starts: HashSet<u64> = ranges.iter().map(|range| range.0).collect();
+        for (address, size, location) in ranges {
+            table.rows.push(Row {
+                address,
+                location: Some(location),
+            });
+            let end = address.saturating_add(size);
+            if !starts.contains(&end) {
+                table.rows.push(Row {
+                    address: end,
+                    location: None,
+                });
+            }
+        }
+        table.sort();
+        table
+    }
+
+    fn sort(&mut self) {
+        // Sequence ends sort before rows starting at the same address
+        self.rows
+            .sort_by_key(|row| (row.address, row.location.is_some()));
+    }
+
+    pub fn find(&self, section: SectionIndex, address: u64) -> Option<SourceLocation> {
+        let address = if self.relocatable {
+            address | section_tag(section)
+        } else {
+            address
+        };
+        let i = self.rows.partition_point(|row| row.address <= address);
+        self.rows.get(i.checked_sub(1)?)?.location
+    }
+
+    pub fn file(&self, location: SourceLocation) -> &str {
+        self.files
+            .get(location.file as usize)
+            .map(|file| file.as_str())
+            .unwrap_or("<unknown>")
+    }
+
+    // The location as `path:line`, followed by the line if the file can be read
+    pub fn describe(&self, location: SourceLocation) -> String {
+        let path = self.file(location);
+        match source_line(path, location.line) {
+            Some(text) => format!("{}:{}  {}", path, location.line, text.trim_end()),
+            None => format!("{}:{}", path, location.line),
+        }
+    }
+}
+
+// Lines of source files read so far. Files which can't be read are cached as
+// `None`.
+type Sources = Mutex<HashMap<String, Option<Arc<Vec<String>>>>>;
+
+fn sources() -> &'static Sources {
+    static SOURCES: OnceLock<Sources> = OnceLock::new();
+    SOURCES.get_or_init(Default::default)
+}
+
+// The text of a 1-based line of a source file
+pub fn source_line(path: &str, line: u32) -> Option<String> {
+    let file = {
+        let mut sources = sources().lock().unwrap();
+        sources
+            .entry(path.to_owned())
+            .or_insert_with(|| {
+                let text = fs::read(path).ok()?;
+                let text = String::from_utf8_lossy(&text);
+                Some(Arc::new(text.lines().map(str::to_owned).collect()))
+            })
+            .clone()?
+    };
+    file.get(line.checked_sub(1)? as usize).cloned()
+}
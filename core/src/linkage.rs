@@ -0,0 +1,101 @@
+use object::{elf, BinaryFormat, Object as _, ObjectSection};
+
+// A function or variable the object expects a shared library to provide
+#[derive(Clone, Debug)]
+pub struct Import {
+    // Empty for ELF, where imports aren't bound to a library
+    pub library: String,
+    pub name: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct Export {
+    pub name: String,
+    pub address: u64,
+}
+
+// The import and export tables of a PE image or the dynamic symbols of an
+// ELF shared object or executable
+#[derive(Clone, Debug, Default)]
+pub struct Linkage {
+    // `DT_NEEDED` entries of ELF files
+    pub needed: Vec<String>,
+    pub imports: Vec<Import>,
+    pub exports: Vec<Export>,
+}
+
+fn string(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+// The shared libraries named by the dynamic section, in load order
+fn needed(file: &object::File) -> Vec<String> {
+    let (Some(dynamic), Some(strings)) = (
+        file.section_by_name(".dynamic"),
+        file.section_by_name(".dynstr"),
+    ) else {
+        return Vec::new();
+    };
+    let (Ok(dynamic), Ok(strings)) = (dynamic.data(), strings.data()) else {
+        return Vec::new();
+    };
+    let word = if file.is_64() { 8 } else { 4 };
+    let read = |bytes: &[u8]| {
+        let mut value = [0; 8];
+        if file.is_little_endian() {
+            value[..word].copy_from_slice(bytes);
+            u64::from_le_bytes(value)
+        } else {
+            value[8 - word..].copy_from_slice(bytes);
+            u64::from_be_bytes(value)
+        }
+    };
+    dynamic
+        .chunks_exact(word * 2)
+        .map(|entry| (read(&entry[..word]), read(&entry[word..])))
+        .take_while(|&(tag, _)| tag != elf::DT_NULL as u64)
+        .filter(|&(tag, _)| tag == elf::DT_NEEDED as u64)
+        .filter_map(|(_, offset)| {
+            let rest = strings.get(usize::try_from(offset).ok()?..)?;
+            let end = rest.iter().position(|&b| b == 0)?;
+            Some(string(&rest[..end]))
+        })
+        .collect()
+}
+
+pub fn read(file: &object::File) -> Linkage {
+    let mut imports: Vec<Import> = file
+        .imports()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|import| Import {
+            library: string(import.library()),
+            name: string(import.name()),
+        })
+        .filter(|import| !import.name.is_empty())
+        .collect();
+    imports.sort_by(|a, b| (&a.library, &a.name).cmp(&(&b.library, &b.name)));
+    imports.dedup_by(|a, b| a.library == b.library && a.name == b.name);
+
+    let mut exports: Vec<Export> = file
+        .exports()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|export| Export {
+            name: string(export.name()),
+            address: export.address(),
+        })
+        .filter(|export| !export.name.is_empty())
+        .collect();
+    exports.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Linkage {
+        needed: if file.format() == BinaryFormat::Elf {
+            needed(file)
+        } else {
+            Vec::new()
+        },
+        imports,
+        exports,
+    }
+}
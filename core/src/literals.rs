@@ -0,0 +1,28 @@
+// Previews of the string literals instructions refer to, like the panic
+// messages and format strings of Rust code
+
+const MIN_CHARS: usize = 4;
+
+const MAX_CHARS: usize = 60;
+
+// The text at the start of `bytes`, quoted, if it looks like a string. Rust
+// strings aren't NUL terminated, so the text ends at the first character
+// which isn't printable.
+pub fn preview(bytes: &[u8]) -> Option<String> {
+    let bytes = &bytes[..bytes.len().min(MAX_CHARS * 4)];
+    let text = match std::str::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(error) => std::str::from_utf8(&bytes[..error.valid_up_to()]).ok()?,
+    };
+    let mut chars = text
+        .chars()
+        .take_while(|&c| !c.is_control() || c == '\n' || c == '\t');
+    let mut string: String = chars.by_ref().take(MAX_CHARS).collect();
+    if string.chars().count() < MIN_CHARS || !string.chars().any(char::is_alphanumeric) {
+        return None;
+    }
+    if chars.next().is_some() {
+        string.push('…');
+    }
+    Some(format!("{:?}", string))
+}
@@ -0,0 +1,141 @@
+use std::{
+    borrow::Cow,
+    fmt,
+    fs::File,
+    io::{self, Read},
+    ops::{Deref, Range},
+    path::Path,
+    sync::{Arc, OnceLock},
+};
+
+use memmap2::{Mmap, MmapMut};
+use object::{CompressedData, CompressionFormat};
+
+// An input file mapped or copied into memory, kept alive by the sections
+// referring to it
+pub type Mapping = Arc<Mmap>;
+
+// Files up to this size are copied into memory instead of mapped, so they can
+// be rewritten while loaded
+const COPY_LIMIT: u64 = 64 << 20;
+
+pub fn map(path: &Path) -> io::Result<Mapping> {
+    let mut file = File::open(path)?;
+    let size = file.metadata()?.len();
+    if size <= COPY_LIMIT {
+        let mut copy = MmapMut::map_anon(size as usize)?;
+        file.read_exact(&mut copy)?;
+        return Ok(Arc::new(copy.make_read_only()?));
+    }
+    // Safety: this is unsound if the file is changed in place while it's
+    // mapped. Writes show through the mapping, and reading pages past the end
+    // of a file truncated in place raises SIGBUS, which kills the viewer. That
+    // happens when a build rewrites a loaded output instead of replacing it,
+    // including while the object is reloaded from the new contents. Only large
+    // files are mapped to keep their memory use down, smaller ones are copied.
+    let mmap = unsafe { Mmap::map(&file)? };
+    Ok(Arc::new(mmap))
+}
+
+// The position of `data` in `mapping`, if it was borrowed from it
+pub fn range(mapping: &Mapping, data: &[u8]) -> Option<Range<usize>> {
+    let start = (data.as_ptr() as usize).checked_sub(mapping.as_ptr() as usize)?;
+    let end = start.checked_add(data.len())?;
+    (end <= mapping.len()).then_some(start..end)
+}
+
+pub struct Compressed {
+    mapping: Mapping,
+    format: CompressionFormat,
+    range: Range<usize>,
+    size: u64,
+    data: OnceLock<Result<Vec<u8>, String>>,
+}
+
+impl Compressed {
+    fn decompress(&self) -> Result<Vec<u8>, String> {
+        let compressed = CompressedData {
+            format: self.format,
+            data: &self.mapping[self.range.clone()],
+            uncompressed_size: self.size,
+        };
+        compressed
+            .decompress()
+            .map(Cow::into_owned)
+            .map_err(|error| error.to_string())
+    }
+
+    fn data(&self) -> &Result<Vec<u8>, String> {
+        self.data.get_or_init(|| self.decompress())
+    }
+}
+
+// The contents of a section. Uncompressed sections refer to the mapped file
+// and compressed ones are only decompressed once read.
+#[derive(Clone)]
+pub enum SectionData {
+    Mapped(Mapping, Range<usize>),
+    Compressed(Arc<Compressed>),
+    // Data which isn't in the file, like zero sized sections
+    Owned(Arc<[u8]>),
+}
+
+impl SectionData {
+    pub fn new(mapping: &Mapping, data: CompressedData<'_>) -> Self {
+        let Some(range) = range(mapping, data.data) else {
+            return SectionData::Owned(data.data.into());
+        };
+        match data.format {
+            CompressionFormat::None => SectionData::Mapped(mapping.clone(), range),
+            format => SectionData::Compressed(Arc::new(Compressed {
+                mapping: mapping.clone(),
+                format,
+                range,
+                size: data.uncompressed_size,
+                data: OnceLock::new(),
+            })),
+        }
+    }
+
+    // Why the section couldn't be decompressed, once it was read. Its data
+    // is empty then.
+    pub fn error(&self) -> Option<&str> {
+        match self {
+            SectionData::Compressed(compressed) => {
+                compressed.data.get()?.as_ref().err().map(String::as_str)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Deref for SectionData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            SectionData::Mapped(mapping, range) => &mapping[range.clone()],
+            SectionData::Compressed(compressed) => match compressed.data() {
+                Ok(data) => data,
+                Err(_) => &[],
+            },
+            SectionData::Owned(data) => data,
+        }
+    }
+}
+
+impl fmt::Debug for SectionData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SectionData::Mapped(_, range) => write!(f, "Mapped({:?})", range),
+            SectionData::Compressed(compressed) => {
+                write!(
+                    f,
+                    "Compressed({:?}, {} bytes)",
+                    compressed.range, compressed.size
+                )
+            }
+            SectionData::Owned(data) => write!(f, "Owned({} bytes)", data.len()),
+        }
+    }
+}
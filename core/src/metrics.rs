@@ -0,0 +1,162 @@
+use std::collections::BTreeSet;
+
+use iced_x86::FlowControl;
+
+use crate::arm;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Metrics {
+    pub basic_blocks: usize,
+    pub branches: usize,
+    pub complexity: usize,
+    pub loop_depth: usize,
+    pub calls: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Metric {
+    BasicBlocks,
+    Branches,
+    Complexity,
+    LoopDepth,
+    Calls,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 5] = [
+        Metric::BasicBlocks,
+        Metric::Branches,
+        Metric::Complexity,
+        Metric::LoopDepth,
+        Metric::Calls,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Metric::BasicBlocks => "BB",
+            Metric::Branches => "Br",
+            Metric::Complexity => "CC",
+            Metric::LoopDepth => "Loop",
+            Metric::Calls => "Calls",
+        }
+    }
+
+    pub fn value(self, metrics: &Metrics) -> usize {
+        match self {
+            Metric::BasicBlocks => metrics.basic_blocks,
+            Metric::Branches => metrics.branches,
+            Metric::Complexity => metrics.complexity,
+            Metric::LoopDepth => metrics.loop_depth,
+            Metric::Calls => metrics.calls,
+        }
+    }
+}
+
+// The control flow of an instruction, from whichever decoder
+struct Step {
+    ip: u64,
+    next_ip: u64,
+    flow: FlowControl,
+    target: u64,
+}
+
+pub fn compute(bytes: &[u8], address: u64, bitness: u32) -> Metrics {
+    let mut decoder =
+        iced_x86::Decoder::with_ip(bitness, bytes, address, iced_x86::DecoderOptions::NONE);
+    let steps: Vec<_> = decoder
+        .iter()
+        .map(|instruction| Step {
+            ip: instruction.ip(),
+            next_ip: instruction.next_ip(),
+            flow: instruction.flow_control(),
+            target: instruction.near_branch_target(),
+        })
+        .collect();
+    from_steps(&steps, address, address + bytes.len() as u64)
+}
+
+pub fn compute_arm(bytes: &[u8], address: u64, modes: &[(u64, arm::Mode)]) -> Metrics {
+    let steps: Vec<_> = arm::Decoder::new(bytes, address, modes)
+        .map(|decoded| Step {
+            ip: decoded.address,
+            next_ip: decoded.address + decoded.len as u64,
+            flow: decoded.flow,
+            target: decoded.target.unwrap_or(u64::MAX),
+        })
+        .collect();
+    from_steps(&steps, address, address + bytes.len() as u64)
+}
+
+fn from_steps(instructions: &[Step], address: u64, end: u64) -> Metrics {
+    let inside = |target: u64| (address..end).contains(&target);
+
+    let mut metrics = Metrics::default();
+
+    // Block leaders, and (source, target) for branches within the function
+    let mut leaders = BTreeSet::from([address]);
+    let mut branches = Vec::new();
+
+    for instruction in instructions {
+        match instruction.flow {
+            FlowControl::Call | FlowControl::IndirectCall => metrics.calls += 1,
+            FlowControl::ConditionalBranch | FlowControl::UnconditionalBranch => {
+                metrics.branches += 1;
+                leaders.insert(instruction.next_ip);
+                let target = instruction.target;
+                if inside(target) {
+                    leaders.insert(target);
+                    branches.push((instruction.ip, target));
+                }
+            }
+            FlowControl::IndirectBranch => {
+                metrics.branches += 1;
+                leaders.insert(instruction.next_ip);
+            }
+            FlowControl::Return | FlowControl::Interrupt | FlowControl::Exception => {
+                leaders.insert(instruction.next_ip);
+            }
+            _ => (),
+        }
+    }
+    leaders.retain(|&leader| inside(leader));
+    metrics.basic_blocks = leaders.len();
+
+    // Count control flow edges between blocks
+    let mut edges = 0;
+    for (i, instruction) in instructions.iter().enumerate() {
+        let falls_through = match instruction.flow {
+            FlowControl::ConditionalBranch => true,
+            FlowControl::UnconditionalBranch
+            | FlowControl::IndirectBranch
+            | FlowControl::Return
+            | FlowControl::Interrupt
+            | FlowControl::Exception => false,
+            _ => i + 1 < instructions.len() && leaders.contains(&instruction.next_ip),
+        };
+        if falls_through && inside(instruction.next_ip) {
+            edges += 1;
+        }
+    }
+    edges += branches.len();
+
+    metrics.complexity = (edges + 2).saturating_sub(metrics.basic_blocks).max(1);
+
+    // Each backward branch forms a loop spanning from its target to the branch
+    let loops: Vec<(u64, u64)> = branches
+        .iter()
+        .filter(|&&(source, target)| target <= source)
+        .map(|&(source, target)| (target, source))
+        .collect();
+    metrics.loop_depth = loops
+        .iter()
+        .map(|&(start, end)| {
+            loops
+                .iter()
+                .filter(|&&(outer_start, outer_end)| outer_start <= start && end <= outer_end)
+                .count()
+        })
+        .max()
+        .unwrap_or(0);
+
+    metrics
+}
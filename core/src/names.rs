@@ -0,0 +1,46 @@
+use std::{borrow::Cow, ffi::OsString, sync::Arc};
+
+// A name from the file as shown, and its bytes if they aren't valid UTF-8 and
+// some were replaced in the shown name
+pub fn decode(bytes: &[u8]) -> (String, Option<Arc<[u8]>>) {
+    match String::from_utf8_lossy(bytes) {
+        Cow::Borrowed(name) => (name.to_owned(), None),
+        Cow::Owned(name) => (name, Some(bytes.into())),
+    }
+}
+
+// The name with bytes which aren't UTF-8 written as `\xNN`, so names which
+// differ only in those bytes can be told apart and searched for
+pub fn escape(mut bytes: &[u8]) -> String {
+    let mut out = String::new();
+    loop {
+        match std::str::from_utf8(bytes) {
+            Ok(valid) => {
+                out.push_str(valid);
+                return out;
+            }
+            Err(error) => {
+                let (valid, rest) = bytes.split_at(error.valid_up_to());
+                out.push_str(std::str::from_utf8(valid).unwrap_or_default());
+                let invalid = error.error_len().unwrap_or(rest.len());
+                for byte in &rest[..invalid] {
+                    out.push_str(&format!("\\x{:02X}", byte));
+                }
+                bytes = &rest[invalid..];
+            }
+        }
+    }
+}
+
+// The exact bytes as a command line argument. Other platforms than Unix only
+// take Unicode arguments, so the name is converted there.
+#[cfg(unix)]
+pub fn os_string(bytes: &[u8]) -> OsString {
+    use std::os::unix::ffi::OsStringExt;
+    OsString::from_vec(bytes.to_vec())
+}
+
+#[cfg(not(unix))]
+pub fn os_string(bytes: &[u8]) -> OsString {
+    OsString::from(String::from_utf8_lossy(bytes).into_owned())
+}
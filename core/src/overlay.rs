@@ -0,0 +1,428 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use object::{BinaryFormat, SectionIndex};
+
+use crate::{
+    identity,
+    settings::{self, Demangling},
+    xrefs, Object, Section, SymbolData,
+};
+
+// A function symbol parsed from the text output of a symbol listing tool
+#[derive(Clone, Debug)]
+pub struct ImportedSymbol {
+    pub name: String,
+    // The 1-based COFF section number from `dumpbin`, which addresses are
+    // relative to, or the ELF section header index from `readelf`
+    pub section: Option<usize>,
+    pub address: u64,
+    pub size: Option<u64>,
+}
+
+fn hex(field: &str) -> Option<u64> {
+    u64::from_str_radix(field.trim_start_matches("0x"), 16).ok()
+}
+
+fn number(field: &str) -> Option<u64> {
+    match field.strip_prefix("0x") {
+        Some(field) => u64::from_str_radix(field, 16).ok(),
+        None => field.parse().ok(),
+    }
+}
+
+// `nm` and `nm -S`: `address [size] type name`
+fn parse_nm(line: &str) -> Option<ImportedSymbol> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let (address, size, kind, name) = match fields[..] {
+        [address, size, kind, name] => (address, Some(hex(size)?), kind, name),
+        [address, kind, name] => (address, None, kind, name),
+        _ => return None,
+    };
+    if !matches!(kind, "T" | "t" | "W" | "w") {
+        return None;
+    }
+    Some(ImportedSymbol {
+        name: name.to_owned(),
+        section: None,
+        address: hex(address)?,
+        size,
+    })
+}
+
+// `readelf -sW`: `num: value size type bind vis ndx name`
+fn parse_readelf(line: &str) -> Option<ImportedSymbol> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let [num, value, size, "FUNC", _, _, ndx, name, ..] = fields[..] else {
+        return None;
+    };
+    if !num.ends_with(':') || ndx == "UND" {
+        return None;
+    }
+    // Drop symbol versions like `memcpy@GLIBC_2.14`
+    let name = name.split('@').next().unwrap_or(name);
+    Some(ImportedSymbol {
+        name: name.to_owned(),
+        // `ABS` and `COM` symbols aren't in a section
+        section: ndx.parse().ok(),
+        address: hex(value)?,
+        size: number(size).filter(|&size| size > 0),
+    })
+}
+
+// `dumpbin /symbols`: `index value SECTn type () class | name`
+fn parse_dumpbin(line: &str) -> Option<ImportedSymbol> {
+    let (fields, name) = line.split_once('|')?;
+    let fields: Vec<&str> = fields.split_whitespace().collect();
+    let [_, value, section, _, "()", ..] = fields[..] else {
+        return None;
+    };
+    let section = section.strip_prefix("SECT")?;
+    let name = name.split_whitespace().next()?;
+    Some(ImportedSymbol {
+        name: name.to_owned(),
+        section: Some(usize::from_str_radix(section, 16).ok()?),
+        address: hex(value)?,
+        size: None,
+    })
+}
+
+// Parses function symbols from `nm -S`, `readelf -sW` or `dumpbin /symbols`
+// output. Lines which don't describe function symbols are ignored.
+pub fn parse(text: &str) -> Vec<ImportedSymbol> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.contains('|') {
+                parse_dumpbin(line)
+            } else if line.split_whitespace().next()?.ends_with(':') {
+                parse_readelf(line)
+            } else {
+                parse_nm(line)
+            }
+        })
+        .collect()
+}
+
+// Returns a copy of `object` with the imported symbols added. Symbols which
+// are already present by name or address are skipped.
+pub fn merge(object: &Object, imported: &[ImportedSymbol]) -> Object {
+    let indices: HashMap<usize, SectionIndex> = object
+        .sections
+        .iter()
+        .map(|section| (section.index.0, section.index))
+        .collect();
+
+    let existing_names: HashSet<&str> = object
+        .symbols_sorted
+        .iter()
+        .map(|symbol| symbol.name.as_str())
+        .collect();
+
+    let mut sections: HashMap<SectionIndex, Section> = object
+        .sections
+        .iter()
+        .map(|section| (section.index, Section::clone(section)))
+        .collect();
+
+    let mut existing_addresses: HashMap<SectionIndex, HashSet<u64>> = sections
+        .values()
+        .map(|section| (section.index, section.symbols.iter().copied().collect()))
+        .collect();
+
+    // ELF symbol values are addresses, even with a section index
+    let relative = object.format != Some(BinaryFormat::Elf);
+    let contains = |section: &Section, address| {
+        (section.address..section.address + section.size).contains(&address)
+    };
+
+    let mut added = Vec::new();
+    for symbol in imported {
+        if existing_names.contains(symbol.name.as_str()) {
+            continue;
+        }
+        let (address, section) = match symbol.section {
+            Some(number) => {
+                let Some(section) = indices.get(&number).and_then(|i| sections.get_mut(i)) else {
+                    continue;
+                };
+                let address = if relative {
+                    section.address.checked_add(symbol.address)
+                } else {
+                    Some(symbol.address)
+                };
+                match address {
+                    Some(address) if contains(section, address) => (address, section),
+                    _ => continue,
+                }
+            }
+            None => {
+                let address = symbol.address;
+                let Some(section) = sections
+                    .values_mut()
+                    .find(|section| contains(section, address))
+                else {
+                    continue;
+                };
+                (address, section)
+            }
+        };
+        if !existing_addresses
+            .entry(section.index)
+            .or_default()
+            .insert(address)
+        {
+            continue;
+        }
+        section.symbols.push(address);
+        added.push((symbol, address, section.index));
+    }
+
+    rebuild(object, sections, added)
+}
+
+// A copy of the object which decodes all sections with the given bitness
+pub fn with_bitness(object: &Object, bitness: u32) -> Object {
+    let sections = object
+        .sections
+        .iter()
+        .map(|section| {
+            let mut section = Section::clone(section);
+            section.bitness = bitness;
+            (section.index, section)
+        })
+        .collect();
+    rebuild(object, sections, Vec::new())
+}
+
+// Rebuilds the symbols of `object` to refer to the new sections
+fn rebuild(
+    object: &Object,
+    sections: HashMap<SectionIndex, Section>,
+    added: Vec<(&ImportedSymbol, u64, SectionIndex)>,
+) -> Object {
+    let sections: HashMap<SectionIndex, Arc<Section>> = sections
+        .into_iter()
+        .map(|(index, mut section)| {
+            section.symbols.sort_unstable();
+            (index, Arc::new(section))
+        })
+        .collect();
+
+    let rebuild = |symbol: &SymbolData| {
+        Arc::new(SymbolData {
+            index: symbol.index,
+            name: symbol.name.clone(),
+            raw_name: symbol.raw_name.clone(),
+            demangled: symbol.demangled.clone(),
+            address: symbol.address,
+            section: symbol
+                .section
+                .as_ref()
+                .and_then(|section| sections.get(&section.index).cloned()),
+            size: symbol.size,
+            metrics: Default::default(),
+        })
+    };
+
+    // Symbols imported earlier are only in `symbols_sorted`
+    let mut rebuilt: HashMap<*const SymbolData, Arc<SymbolData>> = HashMap::new();
+    let mut symbols_sorted: Vec<_> = object
+        .symbols_sorted
+        .iter()
+        .map(|symbol| {
+            rebuilt
+                .entry(Arc::as_ptr(symbol))
+                .or_insert_with(|| rebuild(symbol))
+                .clone()
+        })
+        .collect();
+    let symbols: HashMap<_, _> = object
+        .symbols
+        .iter()
+        .map(|(&index, symbol)| {
+            let symbol = rebuilt
+                .get(&Arc::as_ptr(symbol))
+                .cloned()
+                .unwrap_or_else(|| rebuild(symbol));
+            (index, symbol)
+        })
+        .collect();
+
+    symbols_sorted.extend(added.into_iter().map(|(symbol, address, section)| {
+        Arc::new(SymbolData {
+            demangled: settings::demangling().demangle(&symbol.name),
+            index: None,
+            name: symbol.name.clone(),
+            raw_name: None,
+            address,
+            section: sections.get(&section).cloned(),
+            size: symbol.size.unwrap_or_default(),
+            metrics: Default::default(),
+        })
+    }));
+    symbols_sorted.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+    let mut rebuilt = Object {
+        id: object.id.clone(),
+        generation: identity::next_generation(),
+        path: object.path.clone(),
+        name: object.name.clone(),
+        member: object.member.clone(),
+        slice: object.slice.clone(),
+        format: object.format,
+        image_base: object.image_base,
+        symbols,
+        section_symbols: object.section_symbols.clone(),
+        data_addresses: object.data_addresses.clone(),
+        got: object.got.clone(),
+        symbols_sorted,
+        data_symbols: object.data_symbols.clone(),
+        sections: sections.into_values().collect(),
+        lines: object.lines.clone(),
+        symbol_kinds: object.symbol_kinds.clone(),
+        text_sections: object.text_sections.clone(),
+        linkage: object.linkage.clone(),
+        references: object.references.clone(),
+        warnings: object.warnings.clone(),
+        by_address: Default::default(),
+        callers: Default::default(),
+    };
+    // Added symbols make references, and other bitnesses decode other ones
+    rebuilt.callers = xrefs::Callers::collect(&rebuilt);
+    rebuilt
+}
+
+// A copy of `object` with its names demangled again, or `None` if none of them
+// change. Symbols keeping their names are shared with `object`.
+pub fn with_demangling(object: &Object, demangling: Demangling) -> Option<Object> {
+    let mut renamed: HashMap<*const SymbolData, Arc<SymbolData>> = HashMap::new();
+    for symbol in &object.symbols_sorted {
+        let demangled = demangling.demangle(&symbol.name);
+        if demangled != symbol.demangled {
+            let symbol_data = SymbolData {
+                index: symbol.index,
+                name: symbol.name.clone(),
+                raw_name: symbol.raw_name.clone(),
+                demangled,
+                address: symbol.address,
+                section: symbol.section.clone(),
+                size: symbol.size,
+                metrics: symbol.metrics.clone(),
+            };
+            renamed.insert(Arc::as_ptr(symbol), Arc::new(symbol_data));
+        }
+    }
+    let mut data_symbols = object.data_symbols.clone();
+    let mut data_renamed = false;
+    for symbol in &mut data_symbols {
+        let name = demangling
+            .demangle(&symbol.mangled)
+            .unwrap_or_else(|| symbol.mangled.clone());
+        if name != symbol.name {
+            symbol.name = name;
+            data_renamed = true;
+        }
+    }
+    if renamed.is_empty() && !data_renamed {
+        return None;
+    }
+    data_symbols.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+    let rename = |symbol: &Arc<SymbolData>| {
+        renamed
+            .get(&Arc::as_ptr(symbol))
+            .cloned()
+            .unwrap_or_else(|| symbol.clone())
+    };
+    Some(Object {
+        id: object.id.clone(),
+        generation: identity::next_generation(),
+        path: object.path.clone(),
+        name: object.name.clone(),
+        member: object.member.clone(),
+        slice: object.slice.clone(),
+        format: object.format,
+        image_base: object.image_base,
+        symbols: object
+            .symbols
+            .iter()
+            .map(|(&index, symbol)| (index, rename(symbol)))
+            .collect(),
+        section_symbols: object.section_symbols.clone(),
+        data_addresses: object.data_addresses.clone(),
+        got: object.got.clone(),
+        symbols_sorted: object.symbols_sorted.iter().map(rename).collect(),
+        data_symbols,
+        sections: object.sections.clone(),
+        lines: object.lines.clone(),
+        symbol_kinds: object.symbol_kinds.clone(),
+        text_sections: object.text_sections.clone(),
+        linkage: object.linkage.clone(),
+        references: object.references.clone(),
+        warnings: object.warnings.clone(),
+        by_address: Default::default(),
+        // References are by mangled name, which doesn't change
+        callers: object.callers.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(symbols: &[ImportedSymbol]) -> Vec<(&str, Option<usize>, u64, Option<u64>)> {
+        symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.section, s.address, s.size))
+            .collect()
+    }
+
+    #[test]
+    fn parses_nm() {
+        let text = "0000000000401000 0000000000000020 T _start\n\
+                    0000000000401020 t helper\n\
+                    0000000000404000 0000000000000008 D data\n\
+                    \x20                U puts\n";
+        assert_eq!(
+            fields(&parse(text)),
+            [
+                ("_start", None, 0x401000, Some(0x20)),
+                ("helper", None, 0x401020, None)
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_readelf() {
+        let text = "Symbol table '.symtab' contains 4 entries:\n\
+                    \x20  Num:    Value          Size Type    Bind   Vis      Ndx Name\n\
+                    \x20    1: 0000000000000000     0 FUNC    GLOBAL DEFAULT  UND puts@GLIBC_2.2.5\n\
+                    \x20    2: 0000000000001130    35 FUNC    GLOBAL DEFAULT   14 main\n\
+                    \x20    3: 0000000000001160 0x10 FUNC    GLOBAL DEFAULT   14 memcpy@GLIBC_2.14\n\
+                    \x20    4: 0000000000002000     0 FUNC    LOCAL  DEFAULT  ABS absolute\n\
+                    \x20    5: 0000000000004010     8 OBJECT  GLOBAL DEFAULT   24 data\n";
+        assert_eq!(
+            fields(&parse(text)),
+            [
+                ("main", Some(14), 0x1130, Some(35)),
+                ("memcpy", Some(14), 0x1160, Some(0x10)),
+                ("absolute", None, 0x2000, None)
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_dumpbin() {
+        let text = "008 00000000 SECT3  notype ()    External     | main\n\
+                    009 00000040 SECT3  notype ()    Static       | helper (int)\n\
+                    00A 00000000 SECT2  notype       Static       | .data\n\
+                    00B 00000000 UNDEF  notype ()    External     | puts\n";
+        assert_eq!(
+            fields(&parse(text)),
+            [("main", Some(3), 0, None), ("helper", Some(3), 0x40, None)]
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+use object::{
+    Architecture, BinaryFormat, Object as _, ObjectSymbol, ObjectSymbolTable, RelocationTarget,
+    SectionIndex,
+};
+
+use crate::Section;
+
+// An entry of the procedure linkage table of a linked ELF file, which calls
+// to functions of shared libraries go through
+pub struct Entry {
+    pub section: SectionIndex,
+    pub address: u64,
+    // `function@plt`
+    pub name: String,
+}
+
+// The functions the dynamic relocations of the GOT slots bind to, by the
+// address of the slot
+pub fn slots(file: &object::File) -> HashMap<u64, String> {
+    let (Some(relocations), Some(symbols)) =
+        (file.dynamic_relocations(), file.dynamic_symbol_table())
+    else {
+        return HashMap::new();
+    };
+    relocations
+        .filter_map(|(slot, relocation)| {
+            let RelocationTarget::Symbol(index) = relocation.target() else {
+                return None;
+            };
+            let name = symbols.symbol_by_index(index).ok()?.name().ok()?;
+            (!name.is_empty()).then(|| (slot, name.to_owned()))
+        })
+        .collect()
+}
+
+// Finds the indirect jumps through GOT slots in `.plt`, `.plt.sec` and
+// `.plt.got`. An entry starts after the jump ending the previous one and any
+// padding following it.
+pub fn entries(
+    file: &object::File,
+    sections: &HashMap<SectionIndex, Section>,
+    slots: &HashMap<u64, String>,
+) -> Vec<Entry> {
+    let bitness = match file.architecture() {
+        Architecture::X86_64 | Architecture::X86_64_X32 => 64,
+        Architecture::I386 => 32,
+        _ => return Vec::new(),
+    };
+    if file.format() != BinaryFormat::Elf {
+        return Vec::new();
+    }
+    let plt: Vec<&Section> = sections
+        .values()
+        .filter(|section| matches!(section.name.as_str(), ".plt" | ".plt.sec" | ".plt.got"))
+        .collect();
+    if plt.is_empty() {
+        return Vec::new();
+    }
+
+    let mut entries = Vec::new();
+    let mut instruction = iced_x86::Instruction::default();
+    for section in plt {
+        let mut decoder = iced_x86::Decoder::with_ip(
+            bitness,
+            &section.data,
+            section.address,
+            iced_x86::DecoderOptions::NONE,
+        );
+        let mut start = None;
+        while decoder.can_decode() {
+            decoder.decode_out(&mut instruction);
+            if instruction.is_invalid() {
+                start = None;
+                continue;
+            }
+            if start.is_none()
+                && !matches!(
+                    instruction.mnemonic(),
+                    iced_x86::Mnemonic::Nop | iced_x86::Mnemonic::Int3
+                )
+            {
+                start = Some(instruction.ip());
+            }
+            if instruction.mnemonic() != iced_x86::Mnemonic::Jmp {
+                continue;
+            }
+            let slot = if instruction.is_ip_rel_memory_operand() {
+                Some(instruction.ip_rel_memory_address())
+            } else if instruction.op0_kind() == iced_x86::OpKind::Memory
+                && instruction.memory_base() == iced_x86::Register::None
+            {
+                Some(instruction.memory_displacement64())
+            } else {
+                None
+            };
+            if let (Some(name), Some(address)) = (slot.and_then(|slot| slots.get(&slot)), start) {
+                entries.push(Entry {
+                    section: section.index,
+                    address,
+                    name: format!("{}@plt", name),
+                });
+            }
+            start = None;
+        }
+    }
+    entries
+}
@@ -0,0 +1,123 @@
+use std::{
+    collections::VecDeque,
+    panic::{self, AssertUnwindSafe},
+    sync::{Condvar, Mutex, MutexGuard, OnceLock},
+    thread,
+};
+
+// Interactive tasks are ones the user is waiting on, which run before any
+// queued batch work
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    Interactive,
+    Batch,
+}
+
+type Task = Box<dyn FnOnce() + Send>;
+
+struct Queue {
+    interactive: VecDeque<Task>,
+    batch: VecDeque<Task>,
+    // The number of workers allowed, which running workers may exceed until
+    // they finish their task after the limit is lowered
+    threads: usize,
+    workers: usize,
+    idle: usize,
+}
+
+struct Pool {
+    queue: Mutex<Queue>,
+    ready: Condvar,
+}
+
+fn pool() -> &'static Pool {
+    static POOL: OnceLock<Pool> = OnceLock::new();
+    POOL.get_or_init(|| Pool {
+        queue: Mutex::new(Queue {
+            interactive: VecDeque::new(),
+            batch: VecDeque::new(),
+            threads: default_threads(),
+            workers: 0,
+            idle: 0,
+        }),
+        ready: Condvar::new(),
+    })
+}
+
+fn lock() -> MutexGuard<'static, Queue> {
+    // Tasks run outside of the lock, so a poisoned queue is still consistent
+    pool()
+        .queue
+        .lock()
+        .unwrap_or_else(|error| error.into_inner())
+}
+
+pub fn default_threads() -> usize {
+    thread::available_parallelism().map_or(1, |threads| threads.get())
+}
+
+pub fn threads() -> usize {
+    lock().threads
+}
+
+// Changes the number of workers. Extra workers exit once they're done with
+// their current task.
+pub fn set_threads(threads: usize) {
+    let mut queue = lock();
+    queue.threads = threads.max(1);
+    while queue.workers < queue.threads && !queue.batch.is_empty() {
+        queue.workers += 1;
+        thread::spawn(|| work(None));
+    }
+    pool().ready.notify_all();
+}
+
+// Queues `task` on the pool. Interactive tasks get a worker of their own when
+// all of them are busy, so they don't wait for batch work to finish.
+pub fn spawn(priority: Priority, task: impl FnOnce() + Send + 'static) {
+    let task: Task = Box::new(task);
+    let mut queue = lock();
+    if queue.idle > 0 {
+        match priority {
+            Priority::Interactive => queue.interactive.push_back(task),
+            Priority::Batch => queue.batch.push_back(task),
+        }
+        pool().ready.notify_one();
+    } else if queue.workers < queue.threads || priority == Priority::Interactive {
+        queue.workers += 1;
+        thread::spawn(move || work(Some(task)));
+    } else {
+        queue.batch.push_back(task);
+    }
+}
+
+fn work(mut task: Option<Task>) {
+    loop {
+        if let Some(task) = task.take() {
+            // A panicking task only loses its own results
+            if panic::catch_unwind(AssertUnwindSafe(task)).is_err() {
+                eprintln!("a background task panicked");
+            }
+        }
+        let mut queue = lock();
+        loop {
+            if queue.workers > queue.threads {
+                queue.workers -= 1;
+                return;
+            }
+            task = queue
+                .interactive
+                .pop_front()
+                .or_else(|| queue.batch.pop_front());
+            if task.is_some() {
+                break;
+            }
+            queue.idle += 1;
+            queue = pool()
+                .ready
+                .wait(queue)
+                .unwrap_or_else(|error| error.into_inner());
+            queue.idle -= 1;
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+
+use iced_x86::FlowControl;
+
+use crate::Instruction;
+
+// Indirect branches closer than this are likely to alias in the branch target
+// buffer
+const INDIRECT_DISTANCE: u64 = 16;
+
+const DENSE_INDIRECT: &str = "Indirect branches this close together may share branch target \
+    buffer entries and mispredict each other's targets";
+
+const LOOP_CONDITIONAL: &str = "Conditional branch inside a loop. If its direction depends on \
+    the data it's often mispredicted, and a branchless form like `cmov` may be faster";
+
+// Flags branches which are prone to misprediction. Without profile data these
+// are guesses from the shape of the code. `flow` holds the control flow of
+// instructions which don't fall through.
+pub fn hints(instructions: &mut [Instruction], flow: &HashMap<u64, FlowControl>) {
+    let indirect: Vec<u64> = flow
+        .iter()
+        .filter(|(_, flow)| {
+            matches!(
+                flow,
+                FlowControl::IndirectBranch | FlowControl::IndirectCall
+            )
+        })
+        .map(|(address, _)| *address)
+        .collect();
+
+    // Loop bodies, from the target of a backward branch to the branch
+    let loops: Vec<(u64, u64)> = instructions
+        .iter()
+        .filter_map(|i| {
+            i.local_target()
+                .filter(|&target| target <= i.address)
+                .map(|target| (target, i.address))
+        })
+        .collect();
+
+    for i in instructions {
+        i.hint = match flow.get(&i.address) {
+            Some(FlowControl::IndirectBranch | FlowControl::IndirectCall)
+                if indirect.iter().any(|&other| {
+                    other != i.address && other.abs_diff(i.address) < INDIRECT_DISTANCE
+                }) =>
+            {
+                Some(DENSE_INDIRECT)
+            }
+            Some(FlowControl::ConditionalBranch)
+                if loops
+                    .iter()
+                    .any(|&(start, end)| start <= i.address && i.address < end) =>
+            {
+                Some(LOOP_CONDITIONAL)
+            }
+            _ => None,
+        };
+    }
+}
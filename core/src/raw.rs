@@ -0,0 +1,149 @@
+use std::{
+    collections::HashMap,
+    io,
+    path::PathBuf,
+    sync::{Arc, OnceLock},
+};
+
+use object::{SectionFlags, SectionIndex, SectionKind, SymbolKind};
+
+use crate::{
+    identity,
+    mapping::{self, SectionData},
+    overlay::ImportedSymbol,
+    Isa, Object, Section, SymbolData,
+};
+
+// How to decode a file which isn't an object, like a firmware image or a
+// memory dump, as picked when opening it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Layout {
+    // Decoder mode, 16, 32 or 64
+    pub bitness: u32,
+    // Address the first byte is loaded at
+    pub base: u64,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Layout {
+            bitness: 64,
+            base: 0,
+        }
+    }
+}
+
+// The name of a function found by hand at `address`
+pub fn function_name(address: u64) -> String {
+    format!("sub_{:X}", address)
+}
+
+// The layout `object` was loaded with, if it's a raw binary
+pub fn layout(object: &Object) -> Option<Layout> {
+    if object.format.is_some() {
+        return None;
+    }
+    let section = object.sections.first()?;
+    Some(Layout {
+        bitness: section.bitness,
+        base: section.address,
+    })
+}
+
+fn invalid(error: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+// Loads all of `path` as a single code section at the base address, with a
+// function starting at its first byte
+pub fn load(path: PathBuf, layout: Layout) -> io::Result<Object> {
+    let mapping = mapping::map(&path)?;
+    if mapping.is_empty() {
+        return Err(invalid("the file is empty"));
+    }
+    if layout.base.checked_add(mapping.len() as u64).is_none() {
+        return Err(invalid("the file doesn't fit above the load address"));
+    }
+    let data = SectionData::Mapped(mapping.clone(), 0..mapping.len());
+    Ok(from_data(path, data, layout))
+}
+
+// An object with `data` as its code section and a function at its start
+pub(crate) fn from_data(path: PathBuf, data: SectionData, layout: Layout) -> Object {
+    let section = Arc::new(Section {
+        index: SectionIndex(1),
+        name: ".raw".to_owned(),
+        raw_name: None,
+        size: data.len() as u64,
+        data,
+        address: layout.base,
+        bitness: layout.bitness,
+        isa: Isa::X86,
+        kind: SectionKind::Text,
+        flags: SectionFlags::None,
+        relocations: Arc::new(HashMap::new()),
+        relocation_targets: Arc::new(HashMap::new()),
+        symbols: vec![layout.base],
+    });
+    let entry = Arc::new(SymbolData {
+        index: None,
+        name: function_name(layout.base),
+        raw_name: None,
+        demangled: None,
+        section: Some(section.clone()),
+        address: layout.base,
+        size: 0,
+        metrics: OnceLock::new(),
+    });
+
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    Object {
+        id: identity::ObjectId {
+            path: path.clone(),
+            member: None,
+            slice: None,
+            build: None,
+        },
+        generation: identity::next_generation(),
+        path,
+        name,
+        member: None,
+        slice: None,
+        format: None,
+        image_base: layout.base,
+        symbols: HashMap::new(),
+        section_symbols: HashMap::new(),
+        data_addresses: HashMap::new(),
+        got: HashMap::new(),
+        symbols_sorted: vec![entry],
+        data_symbols: Vec::new(),
+        sections: vec![section],
+        lines: None,
+        symbol_kinds: vec![(SymbolKind::Text, 1)],
+        text_sections: Vec::new(),
+        linkage: Default::default(),
+        references: Default::default(),
+        warnings: Vec::new(),
+        by_address: OnceLock::new(),
+        callers: Default::default(),
+    }
+}
+
+// The function to add to a raw binary to disassemble from `offset` into the
+// file, if that's within it
+pub fn function_at(object: &Object, offset: u64) -> Option<ImportedSymbol> {
+    let section = object.sections.first()?;
+    if offset >= section.size {
+        return None;
+    }
+    let address = section.address + offset;
+    Some(ImportedSymbol {
+        name: function_name(address),
+        section: None,
+        address,
+        size: None,
+    })
+}
@@ -0,0 +1,131 @@
+use iced_x86::FlowControl;
+use object::{elf, macho, Relocation, RelocationKind};
+
+// How an instruction refers to the target of its relocation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reference {
+    // Call or jump resolved at link time
+    DirectCall,
+    // Call or jump through the procedure linkage table
+    PltCall,
+    // Address loaded from the global offset table
+    GotLoad,
+    // Absolute address, which needs a dynamic relocation in a PIC image
+    Absolute,
+    // PC-relative reference to data
+    Relative,
+    // Thread-local storage access
+    Tls,
+    Other,
+}
+
+impl Reference {
+    pub fn classify(relocation: &Relocation, flow: FlowControl) -> Reference {
+        let branch = matches!(
+            flow,
+            FlowControl::Call | FlowControl::UnconditionalBranch | FlowControl::ConditionalBranch
+        );
+        match relocation.kind() {
+            RelocationKind::Elf(
+                elf::R_X86_64_DTPMOD64
+                | elf::R_X86_64_DTPOFF64
+                | elf::R_X86_64_TPOFF64
+                | elf::R_X86_64_TLSGD
+                | elf::R_X86_64_TLSLD
+                | elf::R_X86_64_DTPOFF32
+                | elf::R_X86_64_GOTTPOFF
+                | elf::R_X86_64_TPOFF32
+                | elf::R_X86_64_GOTPC32_TLSDESC
+                | elf::R_X86_64_TLSDESC_CALL
+                | elf::R_X86_64_TLSDESC,
+            )
+            | RelocationKind::MachO {
+                value: macho::X86_64_RELOC_TLV,
+                ..
+            } => Reference::Tls,
+            // Code only uses section relative offsets for TLS in COFF
+            RelocationKind::SectionOffset => Reference::Tls,
+            RelocationKind::PltRelative => Reference::PltCall,
+            RelocationKind::Got
+            | RelocationKind::GotRelative
+            | RelocationKind::GotBaseRelative
+            | RelocationKind::GotBaseOffset
+            | RelocationKind::Elf(elf::R_X86_64_GOTPCRELX | elf::R_X86_64_REX_GOTPCRELX)
+            | RelocationKind::MachO {
+                value: macho::X86_64_RELOC_GOT_LOAD | macho::X86_64_RELOC_GOT,
+                ..
+            } => Reference::GotLoad,
+            RelocationKind::Relative | RelocationKind::MachO { relative: true, .. } if branch => {
+                Reference::DirectCall
+            }
+            RelocationKind::Relative | RelocationKind::MachO { relative: true, .. } => {
+                Reference::Relative
+            }
+            RelocationKind::Absolute
+            | RelocationKind::ImageOffset
+            | RelocationKind::MachO {
+                relative: false, ..
+            } => Reference::Absolute,
+            _ => Reference::Other,
+        }
+    }
+
+    // ELF relocations are numbered per architecture, so those of ARM code are
+    // told apart separately
+    pub fn classify_arm(relocation: &Relocation) -> Reference {
+        match relocation.kind() {
+            RelocationKind::Elf(
+                elf::R_ARM_PC24
+                | elf::R_ARM_CALL
+                | elf::R_ARM_JUMP24
+                | elf::R_ARM_THM_PC22
+                | elf::R_ARM_THM_JUMP24
+                | elf::R_ARM_THM_JUMP19
+                | elf::R_ARM_THM_PC11
+                | elf::R_ARM_THM_PC9,
+            ) => Reference::DirectCall,
+            RelocationKind::Elf(elf::R_ARM_PLT32) => Reference::PltCall,
+            RelocationKind::Elf(
+                elf::R_ARM_GOT32 | elf::R_ARM_GOT_PREL | elf::R_ARM_GOT_ABS | elf::R_ARM_GOT_BREL12,
+            ) => Reference::GotLoad,
+            RelocationKind::Elf(
+                elf::R_ARM_TLS_GD32
+                | elf::R_ARM_TLS_LDM32
+                | elf::R_ARM_TLS_LDO32
+                | elf::R_ARM_TLS_IE32
+                | elf::R_ARM_TLS_LE32
+                | elf::R_ARM_TLS_GOTDESC
+                | elf::R_ARM_TLS_CALL
+                | elf::R_ARM_THM_TLS_CALL,
+            ) => Reference::Tls,
+            RelocationKind::Absolute
+            | RelocationKind::Elf(
+                elf::R_ARM_MOVW_ABS_NC
+                | elf::R_ARM_MOVT_ABS
+                | elf::R_ARM_THM_MOVW_ABS_NC
+                | elf::R_ARM_THM_MOVT_ABS,
+            ) => Reference::Absolute,
+            RelocationKind::Elf(
+                elf::R_ARM_REL32
+                | elf::R_ARM_PREL31
+                | elf::R_ARM_MOVW_PREL_NC
+                | elf::R_ARM_MOVT_PREL
+                | elf::R_ARM_THM_MOVW_PREL_NC
+                | elf::R_ARM_THM_MOVT_PREL,
+            ) => Reference::Relative,
+            _ => Reference::Other,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Reference::DirectCall => "call",
+            Reference::PltCall => "plt",
+            Reference::GotLoad => "got",
+            Reference::Absolute => "abs",
+            Reference::Relative => "rel",
+            Reference::Tls => "tls",
+            Reference::Other => "reloc",
+        }
+    }
+}
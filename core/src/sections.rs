@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use object::{
+    Object as _, ObjectSection, ObjectSymbol, RelocationTarget, SectionIndex, SectionKind,
+};
+
+// ELF `SHF_GNU_RETAIN`, which keeps a section alive with `--gc-sections`
+const SHF_GNU_RETAIN: u64 = 0x200000;
+
+// Subsections which the linker places separately from the rest of `.text`
+const TEXT_GROUPS: [&str; 4] = [".text.unlikely", ".text.hot", ".text.startup", ".text.exit"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Liveness {
+    // Referenced from another section of the object
+    Referenced,
+    // Only kept if another object references one of its global symbols
+    Exported,
+    // Explicitly retained by `SHF_GNU_RETAIN`
+    Retained,
+    // Nothing can refer to it, so `--gc-sections` discards it
+    Discardable,
+}
+
+impl Liveness {
+    pub fn label(self) -> &'static str {
+        match self {
+            Liveness::Referenced => "referenced",
+            Liveness::Exported => "kept if used by other objects",
+            Liveness::Retained => "retained",
+            Liveness::Discardable => "discarded by --gc-sections",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TextSection {
+    pub name: String,
+    pub size: u64,
+    // References from relocations in other sections
+    pub incoming: usize,
+    pub global_symbols: usize,
+    pub liveness: Liveness,
+}
+
+// The output section the linker groups `name` into
+pub fn group(name: &str) -> &str {
+    TEXT_GROUPS
+        .iter()
+        .find(|group| {
+            name.strip_prefix(**group)
+                .map(|rest| rest.is_empty() || rest.starts_with('.'))
+                .unwrap_or(false)
+        })
+        .copied()
+        .unwrap_or(".text")
+}
+
+// Finds the `.text` sections of objects built with `-ffunction-sections` and
+// whether each of them survives `--gc-sections`
+pub fn analyze(file: &object::File) -> Vec<TextSection> {
+    let text: Vec<_> = file
+        .sections()
+        .filter(|section| {
+            section
+                .name()
+                .map(|name| name.starts_with(".text."))
+                .unwrap_or(false)
+        })
+        .collect();
+    if text.is_empty() {
+        return Vec::new();
+    }
+
+    let mut incoming: HashMap<SectionIndex, usize> = HashMap::new();
+    // Debug info and unwind tables refer to every function, but don't keep
+    // them alive
+    let roots = file.sections().filter(|section| {
+        section.kind() != SectionKind::Debug && section.name() != Ok(".eh_frame")
+    });
+    for source in roots {
+        for (_, relocation) in source.relocations() {
+            let target = match relocation.target() {
+                RelocationTarget::Symbol(index) => file
+                    .symbol_by_index(index)
+                    .ok()
+                    .and_then(|symbol| symbol.section_index()),
+                RelocationTarget::Section(index) => Some(index),
+                _ => None,
+            };
+            // References of a section to itself don't keep it alive
+            if let Some(target) = target.filter(|&target| target != source.index()) {
+                *incoming.entry(target).or_default() += 1;
+            }
+        }
+    }
+
+    let mut global_symbols: HashMap<SectionIndex, usize> = HashMap::new();
+    for symbol in file.symbols() {
+        if symbol.is_global() && symbol.is_definition() {
+            if let Some(index) = symbol.section_index() {
+                *global_symbols.entry(index).or_default() += 1;
+            }
+        }
+    }
+
+    text.iter()
+        .map(|section| {
+            let incoming = incoming.get(&section.index()).copied().unwrap_or(0);
+            let global_symbols = global_symbols.get(&section.index()).copied().unwrap_or(0);
+            let retained = match section.flags() {
+                object::SectionFlags::Elf { sh_flags } => sh_flags & SHF_GNU_RETAIN != 0,
+                _ => false,
+            };
+            let liveness = if retained {
+                Liveness::Retained
+            } else if incoming > 0 {
+                Liveness::Referenced
+            } else if global_symbols > 0 {
+                Liveness::Exported
+            } else {
+                Liveness::Discardable
+            };
+            TextSection {
+                name: section.name().unwrap_or_default().to_owned(),
+                size: section.size(),
+                incoming,
+                global_symbols,
+                liveness,
+            }
+        })
+        .collect()
+}
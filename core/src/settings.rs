@@ -0,0 +1,394 @@
+use std::{
+    env,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, AtomicU8, Ordering},
+};
+
+use symbolic_demangle::{Demangle, DemangleOptions};
+
+use crate::{
+    config::{self, Config, Value},
+    pool,
+};
+
+// Where user configuration is stored
+pub fn config_dir() -> Option<PathBuf> {
+    let config = env::var_os("APPDATA")
+        .or_else(|| env::var_os("XDG_CONFIG_HOME"))
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config.join("asm-viewer"))
+}
+
+// Row height and padding of the symbol and assembly lists
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Density {
+    Comfortable,
+    Compact,
+}
+
+impl Density {
+    pub const ALL: [Density; 2] = [Density::Comfortable, Density::Compact];
+
+    fn key(self) -> &'static str {
+        match self {
+            Density::Comfortable => "comfortable",
+            Density::Compact => "compact",
+        }
+    }
+
+    pub fn row_height(self) -> f64 {
+        match self {
+            Density::Comfortable => 26.0,
+            Density::Compact => 20.0,
+        }
+    }
+
+    pub fn row_padding(self) -> f64 {
+        match self {
+            Density::Comfortable => 5.0,
+            Density::Compact => 2.0,
+        }
+    }
+}
+
+// Assembly syntax of the instruction formatter
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Syntax {
+    Intel,
+    Gas,
+    Nasm,
+    Masm,
+}
+
+impl Syntax {
+    pub const ALL: [Syntax; 4] = [Syntax::Intel, Syntax::Gas, Syntax::Nasm, Syntax::Masm];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Syntax::Intel => "Intel",
+            Syntax::Gas => "AT&T",
+            Syntax::Nasm => "NASM",
+            Syntax::Masm => "MASM",
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Syntax::Intel => "intel",
+            Syntax::Gas => "att",
+            Syntax::Nasm => "nasm",
+            Syntax::Masm => "masm",
+        }
+    }
+}
+
+// What instruction rows are colored by, to make bloated encodings stand out
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EncodingHeat {
+    Off,
+    Length,
+    Prefixes,
+}
+
+impl EncodingHeat {
+    pub const ALL: [EncodingHeat; 3] = [
+        EncodingHeat::Off,
+        EncodingHeat::Length,
+        EncodingHeat::Prefixes,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EncodingHeat::Off => "Off",
+            EncodingHeat::Length => "Length",
+            EncodingHeat::Prefixes => "Prefixes",
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            EncodingHeat::Off => "off",
+            EncodingHeat::Length => "length",
+            EncodingHeat::Prefixes => "prefixes",
+        }
+    }
+}
+
+// How symbol names are demangled
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Demangling {
+    Complete,
+    // Without parameters and return types
+    NameOnly,
+    Off,
+}
+
+impl Demangling {
+    pub const ALL: [Demangling; 3] = [Demangling::Complete, Demangling::NameOnly, Demangling::Off];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Demangling::Complete => "Full",
+            Demangling::NameOnly => "Names Only",
+            Demangling::Off => "Off",
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Demangling::Complete => "full",
+            Demangling::NameOnly => "names",
+            Demangling::Off => "off",
+        }
+    }
+
+    pub fn demangle(self, name: &str) -> Option<String> {
+        let options = match self {
+            Demangling::Complete => DemangleOptions::complete(),
+            Demangling::NameOnly => DemangleOptions::name_only(),
+            Demangling::Off => return None,
+        };
+        symbolic_common::Name::from(name).demangle(options)
+    }
+}
+
+// The demangling of objects as they're loaded. It's kept outside of the
+// settings since objects are parsed on worker threads.
+static DEMANGLING: AtomicU8 = AtomicU8::new(0);
+
+pub fn demangling() -> Demangling {
+    Demangling::ALL
+        .get(DEMANGLING.load(Ordering::Relaxed) as usize)
+        .copied()
+        .unwrap_or(Demangling::Complete)
+}
+
+pub fn set_demangling(demangling: Demangling) {
+    let i = Demangling::ALL
+        .iter()
+        .position(|&d| d == demangling)
+        .unwrap_or(0);
+    DEMANGLING.store(i as u8, Ordering::Relaxed);
+}
+
+// Whether PDBs are fetched from the Microsoft symbol server, which is told the
+// names of the PDBs of opened images. Kept here for the loading threads.
+static SYMBOL_SERVER: AtomicBool = AtomicBool::new(false);
+
+pub fn symbol_server() -> bool {
+    SYMBOL_SERVER.load(Ordering::Relaxed)
+}
+
+pub fn set_symbol_server(enabled: bool) {
+    SYMBOL_SERVER.store(enabled, Ordering::Relaxed);
+}
+
+// The target CPU, which picks the latencies, uop counts, macro-fusion rules
+// and supported instruction set extensions
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Microarch {
+    Skylake,
+    Zen2,
+    Zen4,
+}
+
+impl Microarch {
+    pub const ALL: [Microarch; 3] = [Microarch::Skylake, Microarch::Zen2, Microarch::Zen4];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Microarch::Skylake => "Skylake",
+            Microarch::Zen2 => "Zen 2",
+            Microarch::Zen4 => "Zen 4",
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Microarch::Skylake => "skylake",
+            Microarch::Zen2 => "zen2",
+            Microarch::Zen4 => "zen4",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Settings {
+    // Always print operand size keywords (`dword ptr`) and annotate instructions
+    // with implicit width effects, like 32-bit writes zero-extending to 64 bits.
+    pub operand_sizes: bool,
+    pub syntax: Syntax,
+    // Show runs of alignment NOPs as a single `align N` row.
+    pub collapse_padding: bool,
+    // Show source lines above their instructions in the assembly view.
+    pub show_source: bool,
+    // Show the encoded bytes of instructions beside their addresses.
+    pub show_bytes: bool,
+    // Show addresses as offsets from the start of the symbol, or from the
+    // image base outside of symbols.
+    pub relative_addresses: bool,
+    // Show complexity metric columns in the symbol list.
+    pub metric_columns: bool,
+    // Show a rail of initial letters beside the symbol list for coarse navigation.
+    pub symbol_index: bool,
+    // Group the symbol list by crate and module path instead of by object.
+    pub group_by_path: bool,
+    pub density: Density,
+    pub encoding_heat: EncodingHeat,
+    pub target: Microarch,
+    // Show estimated uop counts and note macro-fused pairs for the target.
+    pub show_uops: bool,
+    pub demangling: Demangling,
+    // Diff normalizations: compare registers by class only, skip padding NOPs
+    // and ignore the values of branch targets and RIP-relative addresses.
+    pub diff_ignore_registers: bool,
+    pub diff_ignore_padding: bool,
+    pub diff_ignore_addresses: bool,
+    // Workers parsing, demangling and loading in the background
+    pub threads: usize,
+    // Download missing PDBs from the Microsoft symbol server. Off unless
+    // chosen, since the server learns which images are opened.
+    pub symbol_server: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            operand_sizes: false,
+            syntax: Syntax::Intel,
+            collapse_padding: true,
+            show_source: false,
+            show_bytes: false,
+            relative_addresses: false,
+            metric_columns: false,
+            symbol_index: false,
+            group_by_path: false,
+            density: Density::Comfortable,
+            encoding_heat: EncodingHeat::Off,
+            target: Microarch::Skylake,
+            show_uops: false,
+            demangling: Demangling::Complete,
+            diff_ignore_registers: false,
+            diff_ignore_padding: false,
+            diff_ignore_addresses: false,
+            threads: pool::default_threads(),
+            symbol_server: false,
+        }
+    }
+}
+
+// Settings a single view can override, so views of the same code can be
+// compared in different forms. Unset ones follow the global settings.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub struct ViewSettings {
+    pub syntax: Option<Syntax>,
+    pub show_source: Option<bool>,
+    pub show_bytes: Option<bool>,
+    pub relative_addresses: Option<bool>,
+}
+
+impl ViewSettings {
+    pub fn is_empty(&self) -> bool {
+        *self == ViewSettings::default()
+    }
+
+    // `settings` with the overrides of the view
+    pub fn apply(&self, settings: &Settings) -> Settings {
+        Settings {
+            syntax: self.syntax.unwrap_or(settings.syntax),
+            show_source: self.show_source.unwrap_or(settings.show_source),
+            show_bytes: self.show_bytes.unwrap_or(settings.show_bytes),
+            relative_addresses: self
+                .relative_addresses
+                .unwrap_or(settings.relative_addresses),
+            ..settings.clone()
+        }
+    }
+}
+
+// The option of `all` stored under `key`
+fn option<T: Copy>(
+    config: &Config,
+    key: &str,
+    all: &[T],
+    name: fn(T) -> &'static str,
+) -> Option<T> {
+    let value = config.string(key)?;
+    all.iter().copied().find(|&option| name(option) == value)
+}
+
+impl Settings {
+    // The settings of the last session, with defaults for the ones missing
+    // from the config file
+    pub fn load() -> Settings {
+        let config = Config::load();
+        let default = Settings::default();
+        let bool = |key: &str, default: bool| config.bool(key).unwrap_or(default);
+        Settings {
+            operand_sizes: bool("operand_sizes", default.operand_sizes),
+            syntax: option(&config, "syntax", &Syntax::ALL, Syntax::key).unwrap_or(default.syntax),
+            collapse_padding: bool("collapse_padding", default.collapse_padding),
+            show_source: bool("show_source", default.show_source),
+            show_bytes: bool("show_bytes", default.show_bytes),
+            relative_addresses: bool("relative_addresses", default.relative_addresses),
+            metric_columns: bool("metric_columns", default.metric_columns),
+            symbol_index: bool("symbol_index", default.symbol_index),
+            group_by_path: bool("group_by_path", default.group_by_path),
+            density: option(&config, "density", &Density::ALL, Density::key)
+                .unwrap_or(default.density),
+            encoding_heat: option(
+                &config,
+                "encoding_heat",
+                &EncodingHeat::ALL,
+                EncodingHeat::key,
+            )
+            .unwrap_or(default.encoding_heat),
+            target: option(&config, "target", &Microarch::ALL, Microarch::key)
+                .unwrap_or(default.target),
+            show_uops: bool("show_uops", default.show_uops),
+            demangling: option(&config, "demangling", &Demangling::ALL, Demangling::key)
+                .unwrap_or(default.demangling),
+            diff_ignore_registers: bool("diff_ignore_registers", default.diff_ignore_registers),
+            diff_ignore_padding: bool("diff_ignore_padding", default.diff_ignore_padding),
+            diff_ignore_addresses: bool("diff_ignore_addresses", default.diff_ignore_addresses),
+            threads: config
+                .integer("threads")
+                .filter(|&threads| threads > 0)
+                .map_or(default.threads, |threads| threads as usize),
+            symbol_server: bool("symbol_server", default.symbol_server),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        config::update(|config| {
+            let mut set = |key: &str, value| config.set(key, value);
+            let string = |key: &str| Value::String(key.to_owned());
+            set("operand_sizes", Value::Bool(self.operand_sizes));
+            set("syntax", string(self.syntax.key()));
+            set("collapse_padding", Value::Bool(self.collapse_padding));
+            set("show_source", Value::Bool(self.show_source));
+            set("show_bytes", Value::Bool(self.show_bytes));
+            set("relative_addresses", Value::Bool(self.relative_addresses));
+            set("metric_columns", Value::Bool(self.metric_columns));
+            set("symbol_index", Value::Bool(self.symbol_index));
+            set("group_by_path", Value::Bool(self.group_by_path));
+            set("density", string(self.density.key()));
+            set("encoding_heat", string(self.encoding_heat.key()));
+            set("target", string(self.target.key()));
+            set("show_uops", Value::Bool(self.show_uops));
+            set("demangling", string(self.demangling.key()));
+            set(
+                "diff_ignore_registers",
+                Value::Bool(self.diff_ignore_registers),
+            );
+            set("diff_ignore_padding", Value::Bool(self.diff_ignore_padding));
+            set(
+                "diff_ignore_addresses",
+                Value::Bool(self.diff_ignore_addresses),
+            );
+            set("threads", Value::Integer(self.threads as i64));
+            set("symbol_server", Value::Bool(self.symbol_server));
+        })
+    }
+}
@@ -0,0 +1,430 @@
+use std::{
+    collections::HashMap,
+    env, fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use miniz_oxide::inflate::{
+    core::{decompress, inflate_flags, DecompressorOxide},
+    TINFLStatus,
+};
+use object::Object as _;
+use pdb::FallibleIterator;
+
+use crate::{overlay::ImportedSymbol, settings};
+
+const DEFAULT_SERVER: &str = "https://msdl.microsoft.com/download/symbols";
+
+// Identifies the PDB matching an image by its CodeView record
+#[derive(Clone, Debug)]
+pub struct PdbId {
+    // File name of the PDB, like `ntdll.pdb`
+    name: String,
+    // Path of the PDB recorded by the linker
+    path: PathBuf,
+    guid: [u8; 16],
+    age: u32,
+    image_base: u64,
+}
+
+impl PdbId {
+    pub fn from_file(file: &object::File) -> Option<PdbId> {
+        let info = file.pdb_info().ok()??;
+        let path = String::from_utf8_lossy(info.path()).into_owned();
+        let name = path.rsplit(['\\', '/']).next()?.to_owned();
+        Some(PdbId {
+            name,
+            path: PathBuf::from(path),
+            guid: info.guid(),
+            age: info.age(),
+            image_base: file.relative_address_base(),
+        })
+    }
+
+    // The `GUID` + `age` directory used by symbol servers
+    fn key(&self) -> String {
+        let g = &self.guid;
+        let mut key = format!(
+            "{:08X}{:04X}{:04X}",
+            u32::from_le_bytes([g[0], g[1], g[2], g[3]]),
+            u16::from_le_bytes([g[4], g[5]]),
+            u16::from_le_bytes([g[6], g[7]]),
+        );
+        for byte in &g[8..] {
+            key.push_str(&format!("{:02X}", byte));
+        }
+        key.push_str(&format!("{:X}", self.age));
+        key
+    }
+}
+
+// A symbol server and the local directory it is cached in
+struct Server {
+    cache: PathBuf,
+    url: String,
+}
+
+fn default_cache() -> PathBuf {
+    env::var_os("LOCALAPPDATA")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("XDG_CACHE_HOME").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+        .unwrap_or_else(env::temp_dir)
+        .join("asm-viewer")
+        .join("symbols")
+}
+
+// Reads `srv*cache*url` entries from `_NT_SYMBOL_PATH`, falling back to the
+// Microsoft symbol server if that's enabled in the settings. Servers are sent
+// the PDB names and GUIDs of the images opened.
+fn servers() -> Vec<Server> {
+    let path = env::var("_NT_SYMBOL_PATH").unwrap_or_default();
+    let mut servers: Vec<Server> = path
+        .split(';')
+        .filter_map(|entry| {
+            let mut parts = entry.split('*');
+            if !parts.next()?.eq_ignore_ascii_case("srv") {
+                return None;
+            }
+            let parts: Vec<&str> = parts.filter(|part| !part.is_empty()).collect();
+            let (cache, url) = match parts[..] {
+                [url] => (default_cache(), url),
+                [cache, url, ..] => (PathBuf::from(cache), url),
+                _ => return None,
+            };
+            url.starts_with("http").then(|| Server {
+                cache,
+                url: url.trim_end_matches('/').to_owned(),
+            })
+        })
+        .collect();
+
+    if servers.is_empty() && settings::symbol_server() {
+        servers.push(Server {
+            cache: default_cache(),
+            url: DEFAULT_SERVER.to_owned(),
+        });
+    }
+    servers
+}
+
+// Plain directories listed in `_NT_SYMBOL_PATH`, which hold PDBs either
+// directly or in the layout of a symbol store
+fn directories() -> Vec<PathBuf> {
+    let path = env::var("_NT_SYMBOL_PATH").unwrap_or_default();
+    path.split(';')
+        .filter(|entry| !entry.is_empty() && !entry.contains('*'))
+        .map(PathBuf::from)
+        .collect()
+}
+
+const MSF_SIGNATURE: &[u8] = b"Microsoft C/C++ MSF 7.00";
+
+// Extracts the first file of a cabinet, which compressed `.pd_` files are
+fn extract_cab(data: &[u8]) -> Result<Vec<u8>, String> {
+    let invalid = || "The cabinet is truncated or invalid".to_owned();
+    let u8_at = |offset: usize| data.get(offset).map(|&byte| byte as usize);
+    let u16_at = |offset: usize| {
+        data.get(offset..offset + 2)
+            .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]) as usize)
+    };
+    let u32_at = |offset: usize| {
+        data.get(offset..offset + 4)
+            .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+    };
+
+    if !data.starts_with(b"MSCF") {
+        return Err(invalid());
+    }
+    let files = u32_at(16).ok_or_else(invalid)?;
+    let flags = u16_at(30).ok_or_else(invalid)?;
+    if flags & 0x3 != 0 {
+        return Err("Cabinets split across files aren't supported".to_owned());
+    }
+    // Space reserved for the header, folders and data blocks
+    let (folder, data_reserve) = if flags & 0x4 != 0 {
+        let header_reserve = u16_at(36).ok_or_else(invalid)?;
+        (40 + header_reserve, u8_at(39).ok_or_else(invalid)?)
+    } else {
+        (36, 0)
+    };
+
+    let start = u32_at(folder).ok_or_else(invalid)?;
+    let blocks = u16_at(folder + 4).ok_or_else(invalid)?;
+    let compression = u16_at(folder + 6).ok_or_else(invalid)? & 0xf;
+
+    let mut output = Vec::new();
+    let mut block = start;
+    for _ in 0..blocks {
+        let compressed = u16_at(block + 4).ok_or_else(invalid)?;
+        let uncompressed = u16_at(block + 6).ok_or_else(invalid)?;
+        let input = block + 8 + data_reserve;
+        let input = data.get(input..input + compressed).ok_or_else(invalid)?;
+        block += 8 + data_reserve + compressed;
+        match compression {
+            0 => output.extend_from_slice(input),
+            1 => {
+                // MSZIP blocks are deflate streams which may refer back to
+                // the output of earlier blocks
+                let input = input.strip_prefix(b"CK").ok_or_else(invalid)?;
+                let position = output.len();
+                output.resize(position + uncompressed, 0);
+                let (status, _, written) = decompress(
+                    &mut DecompressorOxide::new(),
+                    input,
+                    &mut output,
+                    position,
+                    inflate_flags::TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF,
+                );
+                if status != TINFLStatus::Done || written != uncompressed {
+                    return Err(invalid());
+                }
+            }
+            _ => return Err("Only MSZIP compressed cabinets are supported".to_owned()),
+        }
+    }
+
+    let size = u32_at(files).ok_or_else(invalid)?;
+    let offset = u32_at(files + 4).ok_or_else(invalid)?;
+    output.drain(..offset.min(output.len()));
+    if output.len() < size {
+        return Err(invalid());
+    }
+    output.truncate(size);
+    Ok(output)
+}
+
+// Downloads `url` to `destination`, returning `false` if the server doesn't
+// have it
+fn download(agent: &ureq::Agent, url: &str, destination: &Path) -> Result<bool, String> {
+    let response = match agent.get(url).call() {
+        Ok(response) => response,
+        Err(ureq::Error::Status(404, _)) => return Ok(false),
+        Err(error) => return Err(error.to_string()),
+    };
+
+    let error = |error: io::Error| error.to_string();
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent).map_err(error)?;
+    }
+    let partial = destination.with_extension("partial");
+    let mut file = fs::File::create(&partial).map_err(error)?;
+    io::copy(&mut response.into_reader(), &mut file).map_err(error)?;
+    drop(file);
+
+    let mut signature = Vec::new();
+    fs::File::open(&partial)
+        .and_then(|file| {
+            file.take(MSF_SIGNATURE.len() as u64)
+                .read_to_end(&mut signature)
+        })
+        .map_err(error)?;
+    if signature.starts_with(b"MSCF") {
+        let pdb = fs::read(&partial)
+            .map_err(error)
+            .and_then(|data| extract_cab(&data))?;
+        fs::write(&partial, pdb).map_err(error)?;
+    } else if signature != MSF_SIGNATURE {
+        fs::remove_file(&partial).ok();
+        return Err("The reply isn't a PDB".to_owned());
+    }
+    fs::rename(partial, destination).map_err(error)?;
+    Ok(true)
+}
+
+// Finds the PDB next to the binary, in a symbol path directory, in a symbol
+// cache or on a symbol server. Fails if no server had it and one of them
+// couldn't be reached.
+pub fn locate(id: &PdbId, binary: &Path) -> Result<Option<PathBuf>, String> {
+    let local = [
+        id.path.clone(),
+        binary.with_file_name(&id.name),
+        binary.with_extension("pdb"),
+    ];
+    if let Some(path) = local.into_iter().find(|path| path.is_file()) {
+        return Ok(Some(path));
+    }
+
+    let key = id.key();
+    for directory in directories() {
+        let candidates = [
+            directory.join(&id.name),
+            directory.join(&id.name).join(&key).join(&id.name),
+        ];
+        if let Some(path) = candidates.into_iter().find(|path| path.is_file()) {
+            return Ok(Some(path));
+        }
+    }
+
+    let servers = servers();
+    for server in &servers {
+        let cached = server.cache.join(&id.name).join(&key).join(&id.name);
+        if cached.is_file() {
+            return Ok(Some(cached));
+        }
+    }
+
+    let agent = ureq::AgentBuilder::new()
+        .timeout_connect(Duration::from_secs(10))
+        .timeout_read(Duration::from_secs(30))
+        .build();
+    // Servers may only have the compressed form, named like `ntdll.pd_`
+    let mut compressed = id.name.clone();
+    compressed.pop();
+    compressed.push('_');
+    let mut failure = None;
+    for server in &servers {
+        let cached = server.cache.join(&id.name).join(&key).join(&id.name);
+        for name in [&id.name, &compressed] {
+            let url = format!("{}/{}/{}/{}", server.url, id.name, key, name);
+            match download(&agent, &url, &cached) {
+                Ok(true) => return Ok(Some(cached)),
+                Ok(false) => (),
+                Err(error) => {
+                    failure = Some(format!("Unable to download `{}`: {}", url, error));
+                    break;
+                }
+            }
+        }
+    }
+    failure.map_or(Ok(None), Err)
+}
+
+// Reads function symbols from a PDB. Public symbols provide the mangled
+// names, while procedure records add sizes and static functions.
+pub fn load(id: &PdbId, path: &Path) -> pdb::Result<Vec<ImportedSymbol>> {
+    let mut pdb = pdb::PDB::open(fs::File::open(path)?)?;
+    let address_map = pdb.address_map()?;
+    let address = |offset: pdb::PdbInternalSectionOffset| {
+        offset
+            .to_rva(&address_map)
+            .map(|rva| id.image_base + rva.0 as u64)
+    };
+
+    let mut sizes = HashMap::new();
+    let mut procedures = Vec::new();
+    let dbi = pdb.debug_information()?;
+    let mut modules = dbi.modules()?;
+    while let Some(module) = modules.next()? {
+        let Some(info) = pdb.module_info(&module)? else {
+            continue;
+        };
+        let mut symbols = info.symbols()?;
+        while let Some(symbol) = symbols.next()? {
+            let Ok(pdb::SymbolData::Procedure(procedure)) = symbol.parse() else {
+                continue;
+            };
+            let Some(address) = address(procedure.offset) else {
+                continue;
+            };
+            sizes.insert(address, procedure.len as u64);
+            procedures.push(ImportedSymbol {
+                name: procedure.name.to_string().into_owned(),
+                section: None,
+                address,
+                size: Some(procedure.len as u64),
+            });
+        }
+    }
+
+    let mut result = Vec::new();
+    let globals = pdb.global_symbols()?;
+    let mut symbols = globals.iter();
+    while let Some(symbol) = symbols.next()? {
+        let Ok(pdb::SymbolData::Public(public)) = symbol.parse() else {
+            continue;
+        };
+        if !public.function {
+            continue;
+        }
+        let Some(address) = address(public.offset) else {
+            continue;
+        };
+        result.push(ImportedSymbol {
+            name: public.name.to_string().into_owned(),
+            section: None,
+            address,
+            size: sizes.get(&address).copied(),
+        });
+    }
+    result.extend(procedures);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use miniz_oxide::deflate::core::{
+        compress_to_output, create_comp_flags_from_zip_params, CompressorOxide, TDEFLFlush,
+    };
+
+    use super::*;
+
+    // A cabinet holding `data` in `blocks` stored with `compression`
+    fn cabinet(compression: u16, blocks: &[(Vec<u8>, usize)], size: usize) -> Vec<u8> {
+        let folder = 36;
+        let file = folder + 8;
+        let name = b"test.pdb\0";
+        let start = file + 16 + name.len();
+
+        let mut cab = b"MSCF".to_vec();
+        cab.extend_from_slice(&[0; 12]);
+        cab.extend_from_slice(&(file as u32).to_le_bytes());
+        cab.extend_from_slice(&[0; 4]);
+        cab.extend_from_slice(&[3, 1, 1, 0, 1, 0, 0, 0, 0, 0, 0, 0]);
+        cab.extend_from_slice(&(start as u32).to_le_bytes());
+        cab.extend_from_slice(&(blocks.len() as u16).to_le_bytes());
+        cab.extend_from_slice(&compression.to_le_bytes());
+        cab.extend_from_slice(&(size as u32).to_le_bytes());
+        cab.extend_from_slice(&[0; 12]);
+        cab.extend_from_slice(name);
+        for (block, uncompressed) in blocks {
+            cab.extend_from_slice(&[0; 4]);
+            cab.extend_from_slice(&(block.len() as u16).to_le_bytes());
+            cab.extend_from_slice(&(*uncompressed as u16).to_le_bytes());
+            cab.extend_from_slice(block);
+        }
+        cab
+    }
+
+    #[test]
+    fn stored_cabinet() {
+        let cab = cabinet(0, &[(b"MSF".to_vec(), 3), (b" data".to_vec(), 5)], 8);
+        assert_eq!(extract_cab(&cab).unwrap(), b"MSF data");
+    }
+
+    #[test]
+    fn mszip_cabinet() {
+        // The second block refers back to the first one
+        let text = b"abcdefgh".repeat(8);
+        let (first, second) = text.split_at(32);
+        let blocks: Vec<_> = [first, second]
+            .iter()
+            .enumerate()
+            .map(|(i, block)| {
+                let mut compressor =
+                    CompressorOxide::new(create_comp_flags_from_zip_params(6, 0, 0));
+                if i > 0 {
+                    // Give the compressor the first block as history
+                    compress_to_output(&mut compressor, first, TDEFLFlush::Sync, |_| true);
+                }
+                let mut compressed = b"CK".to_vec();
+                compress_to_output(&mut compressor, block, TDEFLFlush::Finish, |output| {
+                    compressed.extend_from_slice(output);
+                    true
+                });
+                (compressed, block.len())
+            })
+            .collect();
+        let cab = cabinet(1, &blocks, text.len());
+        assert_eq!(extract_cab(&cab).unwrap(), text);
+    }
+
+    #[test]
+    fn invalid_cabinets() {
+        assert!(extract_cab(b"MSCF").is_err());
+        assert!(extract_cab(&cabinet(3, &[(vec![0; 4], 4)], 4)).is_err());
+        assert!(extract_cab(&cabinet(0, &[(b"abc".to_vec(), 3)], 8)).is_err());
+    }
+}
@@ -0,0 +1,172 @@
+use iced_x86::{
+    ConditionCode, CpuidFeature, FlowControl, Instruction, InstructionInfoFactory, Mnemonic,
+    OpAccess, OpKind,
+};
+
+use crate::settings::Microarch;
+
+// Fused domain uops of microcoded and multi-uop instructions, as measured
+// on Skylake and on Zen. These are for the register forms; memory operands
+// add to them as for other instructions.
+const COUNTS: &[(Mnemonic, u32, u32)] = &[
+    (Mnemonic::Div, 36, 2),
+    (Mnemonic::Idiv, 57, 2),
+    (Mnemonic::Mul, 2, 2),
+    (Mnemonic::Call, 2, 1),
+    (Mnemonic::Leave, 3, 2),
+    (Mnemonic::Xchg, 3, 2),
+    (Mnemonic::Xadd, 4, 4),
+    (Mnemonic::Cmpxchg, 5, 6),
+    (Mnemonic::Cmpxchg16b, 19, 28),
+    (Mnemonic::Pushfq, 3, 11),
+    (Mnemonic::Popfq, 9, 35),
+    (Mnemonic::Rdtsc, 20, 37),
+    (Mnemonic::Rdtscp, 22, 64),
+    (Mnemonic::Cpuid, 100, 100),
+    (Mnemonic::Pause, 4, 8),
+    (Mnemonic::Mfence, 3, 7),
+    (Mnemonic::Bswap, 2, 1),
+];
+
+fn is_immediate(kind: OpKind) -> bool {
+    matches!(
+        kind,
+        OpKind::Immediate8
+            | OpKind::Immediate16
+            | OpKind::Immediate32
+            | OpKind::Immediate64
+            | OpKind::Immediate8to16
+            | OpKind::Immediate8to32
+            | OpKind::Immediate8to64
+            | OpKind::Immediate32to64
+    )
+}
+
+fn has_memory(instruction: &Instruction) -> bool {
+    (0..instruction.op_count()).any(|i| instruction.op_kind(i) == OpKind::Memory)
+}
+
+fn has_immediate(instruction: &Instruction) -> bool {
+    (0..instruction.op_count()).any(|i| is_immediate(instruction.op_kind(i)))
+}
+
+// Estimated fused domain uops of `instruction`, or `None` for string
+// instructions with a `rep` prefix whose count depends on the data
+pub fn count(
+    microarch: Microarch,
+    instruction: &Instruction,
+    info_factory: &mut InstructionInfoFactory,
+) -> Option<u32> {
+    if (instruction.has_rep_prefix() || instruction.has_repne_prefix())
+        && instruction.is_string_instruction()
+    {
+        return None;
+    }
+    let intel = microarch == Microarch::Skylake;
+    let base = COUNTS
+        .iter()
+        .find(|(mnemonic, ..)| *mnemonic == instruction.mnemonic())
+        .map_or(1, |&(_, skylake, zen)| if intel { skylake } else { zen });
+
+    // Zen keeps a memory operand in the same macro-op. Intel splits a
+    // read-modify-write into a load+op and a store uop, and can't
+    // micro-fuse RIP-relative addresses with an immediate.
+    let mut extra = 0;
+    if intel && has_memory(instruction) {
+        let writes_back = info_factory
+            .info(instruction)
+            .used_memory()
+            .iter()
+            .any(|memory| {
+                matches!(
+                    memory.access(),
+                    OpAccess::ReadWrite | OpAccess::ReadCondWrite
+                )
+            });
+        if writes_back {
+            extra += 1;
+        }
+        if instruction.is_ip_rel_memory_operand() && has_immediate(instruction) {
+            extra += 1;
+        }
+    }
+    Some(base + extra)
+}
+
+// Whether `first` and the conditional branch `second` following it decode
+// into a single uop
+pub fn fuses(microarch: Microarch, first: &Instruction, second: &Instruction) -> bool {
+    use ConditionCode::*;
+
+    let condition = second.condition_code();
+    if second.flow_control() != FlowControl::ConditionalBranch || condition == ConditionCode::None {
+        return false;
+    }
+    // Neither vendor fuses a memory operand together with an immediate
+    if has_memory(first) && has_immediate(first) {
+        return false;
+    }
+    let mnemonic = first.mnemonic();
+    match microarch {
+        Microarch::Skylake => {
+            // Nor RIP-relative addresses, read-modify-writes or pairs split by
+            // a cache line
+            let compares = matches!(mnemonic, Mnemonic::Cmp | Mnemonic::Test);
+            if first.is_ip_rel_memory_operand()
+                || (!compares && first.op0_kind() == OpKind::Memory)
+                || first.ip() / 64 != second.ip() / 64
+            {
+                return false;
+            }
+            match mnemonic {
+                Mnemonic::Test | Mnemonic::And => true,
+                Mnemonic::Cmp | Mnemonic::Add | Mnemonic::Sub => {
+                    matches!(condition, e | ne | b | ae | be | a | l | ge | le | g)
+                }
+                Mnemonic::Inc | Mnemonic::Dec => matches!(condition, e | ne | l | ge | le | g),
+                _ => false,
+            }
+        }
+        Microarch::Zen2 => matches!(mnemonic, Mnemonic::Cmp | Mnemonic::Test),
+        Microarch::Zen4 => {
+            matches!(
+                mnemonic,
+                Mnemonic::Cmp
+                    | Mnemonic::Test
+                    | Mnemonic::Add
+                    | Mnemonic::Sub
+                    | Mnemonic::And
+                    | Mnemonic::Or
+                    | Mnemonic::Xor
+                    | Mnemonic::Inc
+                    | Mnemonic::Dec
+            ) && first.op0_kind() != OpKind::Memory
+        }
+    }
+}
+
+fn supports(target: Microarch, feature: CpuidFeature) -> bool {
+    use CpuidFeature::*;
+
+    match feature {
+        // Xeon Phi and later Intel extensions
+        AVX512ER | AVX512PF | AVX512_4FMAPS | AVX512_4VNNIW | AVX512_VP2INTERSECT | AVX512_FP16
+        | AVX_VNNI | AMX_BF16 | AMX_TILE | AMX_INT8 => false,
+        // AMD extensions of earlier families
+        XOP | FMA4 | TBM => false,
+        RTM | HLE => target == Microarch::Skylake,
+        SHA | SSE4A => target != Microarch::Skylake,
+        VAES | VPCLMULQDQ | GFNI => target == Microarch::Zen4,
+        _ if format!("{:?}", feature).starts_with("AVX512") => target == Microarch::Zen4,
+        _ => true,
+    }
+}
+
+// The first extension used by `instruction` which the target lacks
+pub fn unsupported(target: Microarch, instruction: &Instruction) -> Option<CpuidFeature> {
+    instruction
+        .cpuid_features()
+        .iter()
+        .copied()
+        .find(|&feature| !supports(target, feature))
+}
@@ -0,0 +1,143 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use crate::{arm, Object, SymbolData};
+
+// An instruction referring to a symbol
+#[derive(Clone, Debug)]
+pub struct XRef {
+    // Name of the function containing the instruction
+    pub from: String,
+    pub address: u64,
+}
+
+// References to symbols by name, built when the object is loaded or its
+// symbols change. Names are used so references to symbols defined in other
+// objects are found too.
+#[derive(Clone, Debug, Default)]
+pub struct Callers {
+    by_name: HashMap<String, Vec<XRef>>,
+    // Functions whose addresses are loaded by code, like callbacks. These
+    // aren't calls, so they're kept apart.
+    addresses: HashSet<String>,
+}
+
+impl Callers {
+    pub fn collect(object: &Object) -> Callers {
+        let mut callers = Callers::default();
+        let mut instruction = iced_x86::Instruction::default();
+        for symbol in &object.symbols_sorted {
+            let (Some(section), Some(bytes)) = (&symbol.section, symbol.data()) else {
+                continue;
+            };
+            // The address range of each instruction with the symbol it branches to
+            let mut steps: Vec<(u64, u64, Option<Arc<SymbolData>>)> = Vec::new();
+            let mut address = |target| {
+                if let Some(target) = symbol.target_symbol(object, target) {
+                    callers.addresses.insert(target.name.clone());
+                }
+            };
+            match symbol.arm_modes() {
+                // Addresses from `adr` and words loaded from literal pools
+                Some(modes) => {
+                    for decoded in arm::Decoder::new(bytes, symbol.address, modes) {
+                        let target = decoded
+                            .target
+                            .and_then(|target| symbol.target_symbol(object, target));
+                        steps.push((
+                            decoded.address,
+                            decoded.address + decoded.len as u64,
+                            target,
+                        ));
+                        match decoded.literal {
+                            Some((target, 0)) => address(target),
+                            Some((literal, 4)) => {
+                                if let Some(value) = arm::literal_value(section, literal) {
+                                    address(value as u64 & !1);
+                                }
+                            }
+                            _ => (),
+                        }
+                    }
+                }
+                None => {
+                    let mut decoder = iced_x86::Decoder::with_ip(
+                        section.bitness,
+                        bytes,
+                        symbol.address,
+                        iced_x86::DecoderOptions::NONE,
+                    );
+                    while decoder.can_decode() {
+                        decoder.decode_out(&mut instruction);
+                        if instruction.is_ip_rel_memory_operand() {
+                            address(instruction.ip_rel_memory_address());
+                        }
+                        steps.push((
+                            instruction.ip(),
+                            instruction.next_ip(),
+                            symbol.branch_target(object, &instruction),
+                        ));
+                    }
+                }
+            }
+            for (ip, next_ip, branch) in steps {
+                let relocation =
+                    (ip..next_ip).find(|offset| section.relocations.contains_key(offset));
+                let target = match relocation {
+                    Some(offset) => section.relocation_targets.get(&offset).cloned(),
+                    None => branch.map(|target| target.name.clone()),
+                };
+                let Some(target) = target.filter(|target| !target.is_empty()) else {
+                    continue;
+                };
+                callers.by_name.entry(target).or_default().push(XRef {
+                    from: symbol.name.clone(),
+                    address: ip,
+                });
+            }
+        }
+        callers
+    }
+
+    // Referenced names with the references to them
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[XRef])> {
+        self.by_name
+            .iter()
+            .map(|(name, xrefs)| (name.as_str(), xrefs.as_slice()))
+    }
+
+    // Whether code loads the address of the named function
+    pub fn address_taken(&self, name: &str) -> bool {
+        self.addresses.contains(name)
+    }
+
+    pub fn get(&self, name: &str) -> &[XRef] {
+        self.by_name
+            .get(name)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+// The number of references to the named symbol in the loaded objects, and of
+// distinct functions making them
+pub fn counts(objects: &[Arc<Object>], name: &str) -> (usize, usize) {
+    let mut references = 0;
+    let mut callers = HashSet::new();
+    for object in objects {
+        let xrefs = object.callers.get(name);
+        references += xrefs.len();
+        callers.extend(xrefs.iter().map(|xref| (object.key(), xref.from.as_str())));
+    }
+    (references, callers.len())
+}
+
+pub fn find(object: &Object, name: &str) -> Option<Arc<SymbolData>> {
+    let i = object
+        .symbols_sorted
+        .binary_search_by(|symbol| symbol.name.as_str().cmp(name))
+        .ok()?;
+    object.symbols_sorted.get(i).cloned()
+}
@@ -0,0 +1,232 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use asm_viewer_core::identity::ObjectId;
+use floem::{
+    event::{Event, EventListener},
+    keyboard::{Key, NamedKey},
+    reactive::{create_effect, create_rw_signal, RwSignal},
+    view::View,
+    views::{stack, text, text_input, Decorators},
+};
+use object::SectionKind;
+
+use crate::{
+    button,
+    project::{Comment, Project, Rename},
+    theme::theme,
+    Object, Symbol,
+};
+
+// Comments and renames are read from the project files of the objects. This
+// is bumped when they change, so views showing them are updated.
+pub type Revision = RwSignal<u64>;
+
+thread_local! {
+    // Keys by object and generation, as hashing the code of large objects
+    // takes a while
+    static KEYS: RefCell<HashMap<(ObjectId, u64), String>> = RefCell::new(HashMap::new());
+    // Project files as last read or written here, so rows don't each read
+    // the file
+    static PROJECTS: RefCell<HashMap<PathBuf, Rc<Project>>> = RefCell::new(HashMap::new());
+}
+
+// FNV-1a, which is stable across releases unlike the hashers of `std`
+fn hash(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100_0000_01b3)
+    })
+}
+
+// Identifies the build of `object` in project files by its build ID, or by a
+// hash of its code if it doesn't have one. Annotations of other builds
+// written next to the same path aren't shown.
+pub fn key(object: &Object) -> String {
+    let id = (object.id.clone(), object.generation);
+    if let Some(key) = KEYS.with(|keys| keys.borrow().get(&id).cloned()) {
+        return key;
+    }
+    let key = object.id.build_hex().unwrap_or_else(|| {
+        let mut sections: Vec<_> = object
+            .sections
+            .iter()
+            .filter(|section| section.kind == SectionKind::Text)
+            .collect();
+        sections.sort_by_key(|section| (section.address, &section.name));
+        let code = sections
+            .iter()
+            .fold(0xcbf2_9ce4_8422_2325, |code, section| {
+                hash(hash(code, section.name.as_bytes()), &section.data)
+            });
+        format!("hash-{:016x}", code)
+    });
+    KEYS.with(|keys| keys.borrow_mut().insert(id, key.clone()));
+    key
+}
+
+fn project(path: &Path) -> Rc<Project> {
+    PROJECTS.with(|projects| {
+        projects
+            .borrow_mut()
+            .entry(path.to_owned())
+            .or_insert_with(|| Rc::new(Project::load(path)))
+            .clone()
+    })
+}
+
+// Changes the project file of `object` as read from disk, so changes made
+// elsewhere since it was cached are kept
+fn update(object: &Object, revision: Revision, change: impl FnOnce(&mut Project)) {
+    let mut project = Project::load(&object.path);
+    change(&mut project);
+    project.save();
+    PROJECTS.with(|projects| {
+        projects
+            .borrow_mut()
+            .insert(object.path.clone(), Rc::new(project))
+    });
+    revision.update(|revision| *revision += 1);
+}
+
+// Tabs and line breaks would split the record
+fn single_line(text: &str) -> String {
+    text.trim().replace(['\t', '\n', '\r'], " ")
+}
+
+// The comments on the instructions of `symbol` by address
+pub fn comments(symbol: &Symbol) -> HashMap<u64, String> {
+    let build = key(&symbol.object);
+    project(&symbol.object.path)
+        .comments
+        .iter()
+        .filter(|comment| comment.build == build && comment.symbol == symbol.data.name)
+        .map(|comment| (symbol.data.address + comment.offset, comment.text.clone()))
+        .collect()
+}
+
+// Replaces the comment at `address`, removing it if `text` is empty
+pub fn set_comment(symbol: &Symbol, address: u64, text: &str, revision: Revision) {
+    let comment = Comment {
+        build: key(&symbol.object),
+        symbol: symbol.data.name.clone(),
+        offset: address - symbol.data.address,
+        text: single_line(text),
+    };
+    update(&symbol.object, revision, |project| {
+        project.comments.retain(|other| {
+            (&other.build, &other.symbol, other.offset)
+                != (&comment.build, &comment.symbol, comment.offset)
+        });
+        if !comment.text.is_empty() {
+            project.comments.push(comment);
+        }
+    });
+}
+
+// The name the user gave the symbol, if any
+pub fn alias(symbol: &Symbol) -> Option<String> {
+    let build = key(&symbol.object);
+    project(&symbol.object.path)
+        .renames
+        .iter()
+        .find(|rename| rename.build == build && rename.symbol == symbol.data.name)
+        .map(|rename| rename.alias.clone())
+}
+
+// The alias of the symbol, or its demangled name
+pub fn display_name(symbol: &Symbol) -> String {
+    alias(symbol).unwrap_or_else(|| {
+        symbol
+            .data
+            .demangled
+            .clone()
+            .unwrap_or_else(|| symbol.data.name.clone())
+    })
+}
+
+// Gives the symbol an alias, or removes it if `alias` is empty
+pub fn rename(symbol: &Symbol, alias: &str, revision: Revision) {
+    let rename = Rename {
+        build: key(&symbol.object),
+        symbol: symbol.data.name.clone(),
+        alias: single_line(alias),
+    };
+    update(&symbol.object, revision, |project| {
+        project
+            .renames
+            .retain(|other| (&other.build, &other.symbol) != (&rename.build, &rename.symbol));
+        if !rename.alias.is_empty() {
+            project.renames.push(rename);
+        }
+    });
+}
+
+// The comments of `symbol`, read again when they change
+pub fn comments_signal(symbol: &Symbol, revision: Revision) -> RwSignal<HashMap<u64, String>> {
+    let comments = create_rw_signal(HashMap::new());
+    let symbol = symbol.clone();
+    create_effect(move |_| {
+        revision.track();
+        comments.set(self::comments(&symbol));
+    });
+    comments
+}
+
+// A text input which calls `submit` with its text on enter or the button
+fn editor(
+    label: &'static str,
+    action: &'static str,
+    initial: String,
+    submit: impl Fn(&str) + Clone + 'static,
+) -> impl View {
+    let draft = create_rw_signal(initial);
+    let on_enter = submit.clone();
+    stack((
+        text(label).style(|s| s.padding(5.0)),
+        text_input(draft)
+            .style(|s| {
+                s.width(320)
+                    .padding(5)
+                    .margin(4)
+                    .border(0.5)
+                    .border_radius(3)
+                    .border_color(theme().button_border)
+            })
+            .on_event(EventListener::KeyDown, move |e| {
+                if let Event::KeyDown(e) = e {
+                    if e.key.logical_key == Key::Named(NamedKey::Enter) {
+                        on_enter(&draft.get_untracked());
+                        return true;
+                    }
+                }
+                false
+            }),
+        button(action, move |_| {
+            submit(&draft.get_untracked());
+            true
+        }),
+    ))
+    .style(|s| s.flex_row().items_center())
+}
+
+// Edits the comment of the instruction at `address`
+pub fn comment_editor(symbol: &Symbol, address: u64, revision: Revision) -> impl View {
+    let initial = comments(symbol).remove(&address).unwrap_or_default();
+    let symbol = symbol.clone();
+    editor("Comment:", "Save Comment", initial, move |text| {
+        set_comment(&symbol, address, text, revision)
+    })
+}
+
+// Edits the alias of `symbol`, which an empty name removes
+pub fn rename_editor(symbol: &Symbol, revision: Revision) -> impl View {
+    let initial = alias(symbol).unwrap_or_default();
+    let symbol = symbol.clone();
+    editor("Rename:", "Rename", initial, move |alias| {
+        rename(&symbol, alias, revision)
+    })
+}
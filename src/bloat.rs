@@ -0,0 +1,159 @@
+use std::{cmp::Reverse, sync::Arc};
+
+use asm_viewer_core::bloat::{groups, Group, Grouping};
+use floem::{
+    cosmic_text::Weight,
+    reactive::create_rw_signal,
+    style::{CursorStyle, TextOverflow},
+    view::View,
+    views::{
+        container, empty, label, list, scroll, stack, text, virtual_list, Decorators,
+        VirtualListDirection, VirtualListItemSize,
+    },
+};
+
+pub use asm_viewer_core::bloat::generic_path;
+
+use crate::{header, theme::theme, toggle_button, units, Object};
+
+const SIZE_WIDTH: f64 = 100.0;
+
+const BAR_WIDTH: f64 = 160.0;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SortKey {
+    Name,
+    Size,
+    Symbols,
+}
+
+fn sorted(mut groups: Vec<Group>, key: SortKey) -> Vec<Group> {
+    match key {
+        SortKey::Name => groups.sort_by(|a, b| a.name.cmp(&b.name)),
+        // Groups come largest first
+        SortKey::Size => {}
+        SortKey::Symbols => groups.sort_by_key(|group| Reverse(group.symbols)),
+    }
+    groups
+}
+
+// Where the bytes of the loaded objects come from, summed by crate, namespace
+// or section like `cargo bloat`
+pub fn report(objects: &[Arc<Object>]) -> Box<dyn View> {
+    let objects = objects.to_vec();
+    let grouping = create_rw_signal(Grouping::Crate);
+    let sort = create_rw_signal(SortKey::Size);
+    let groups = move || groups(&objects, grouping.get());
+    let current = create_rw_signal(groups());
+    let total = move || current.with(|groups| groups.iter().map(|group| group.size).sum::<u64>());
+
+    let groupings = list(
+        || Grouping::ALL.into_iter(),
+        |grouping| *grouping,
+        move |option| {
+            let groups = groups.clone();
+            toggle_button(
+                option.label(),
+                move || grouping.get() == option,
+                move |_| {
+                    grouping.set(option);
+                    current.set(groups());
+                    true
+                },
+            )
+        },
+    )
+    .style(|s| s.flex_row());
+    let summary = label(move || {
+        format!(
+            "{} in {} groups",
+            units::size(total()),
+            units::count(current.with(|groups| groups.len()) as u64)
+        )
+    })
+    .style(|s| s.padding(5.0).color(theme().dim));
+
+    let column = move |label: &'static str, key: SortKey, width: Option<f64>| {
+        text(label)
+            .style(move |s| {
+                let s = s.apply_if(sort.get() == key, |s| s.font_weight(Weight::BOLD));
+                match width {
+                    Some(width) => s.width(width).padding_left(10),
+                    None => s.flex_grow(1.0).flex_basis(0.0),
+                }
+                .cursor(CursorStyle::Pointer)
+            })
+            .on_click(move |_| {
+                sort.set(key);
+                true
+            })
+    };
+    let columns = stack((
+        column("Name", SortKey::Name, None),
+        column("Size", SortKey::Size, Some(SIZE_WIDTH)),
+        text("Share").style(|s| s.width(BAR_WIDTH).padding_left(10)),
+        column("Symbols", SortKey::Symbols, Some(SIZE_WIDTH)),
+    ))
+    .style(|s| {
+        s.flex_row()
+            .width_full()
+            .padding(5)
+            .border_bottom(0.5)
+            .border_color(theme().border)
+    });
+
+    let rows = virtual_list(
+        VirtualListDirection::Vertical,
+        VirtualListItemSize::Fixed(Box::new(|| 26.0)),
+        move || {
+            let groups = current.get();
+            sorted(groups, sort.get())
+                .into_iter()
+                .collect::<im::Vector<_>>()
+        },
+        |group| (group.name.clone(), group.size),
+        move |group| {
+            let share = group.size as f64 / total().max(1) as f64;
+            let bar = stack((
+                container(empty()).style(move |s| {
+                    s.width((BAR_WIDTH - 60.0) * share)
+                        .height(10.0)
+                        .background(theme().link)
+                }),
+                text(format!("{:.1}%", share * 100.0))
+                    .style(|s| s.padding_left(4).font_size(11.0).color(theme().dim)),
+            ))
+            .style(|s| {
+                s.flex_row()
+                    .items_center()
+                    .width(BAR_WIDTH)
+                    .padding_left(10)
+            });
+            stack((
+                text(group.name).style(|s| {
+                    s.flex_grow(1.0)
+                        .flex_basis(0.0)
+                        .min_width(0.0)
+                        .text_overflow(TextOverflow::Clip)
+                }),
+                units::size_text(group.size).style(|s| s.width(SIZE_WIDTH).padding_left(10)),
+                bar,
+                text(units::count(group.symbols as u64))
+                    .style(|s| s.width(SIZE_WIDTH).padding_left(10)),
+            ))
+            .style(|s| s.flex_row().width_full().padding(5).height(26.0))
+            .hover_style(|s| s.background(theme().hover))
+        },
+    )
+    .style(|s| s.flex_col().width_full());
+
+    Box::new(
+        stack((
+            header("Size Analysis"),
+            stack((groupings, summary)).style(|s| s.flex_row().items_center()),
+            columns,
+            scroll(rows).style(|s| s.width_full().flex_grow(1.0).flex_basis(0.0)),
+        ))
+        .style(|s| s.flex_col().width_full().height_full()),
+    )
+}
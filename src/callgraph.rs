@@ -0,0 +1,326 @@
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+    sync::Arc,
+};
+
+use floem::{
+    event::{Event, EventListener},
+    keyboard::{Key, NamedKey},
+    menu::{Menu, MenuItem},
+    reactive::{create_rw_signal, RwSignal},
+    style::{CursorStyle, TextOverflow},
+    view::View,
+    views::{
+        label, scroll, stack, text, text_input, virtual_list, Decorators, VirtualListDirection,
+        VirtualListItemSize,
+    },
+};
+
+use crate::{
+    button, callpaths, header, history, history::History, search, settings, theme::theme, Object,
+    ObjectList, Selection,
+};
+
+const ROW_HEIGHT: f64 = 24.0;
+
+const INDENT: f64 = 16.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Direction {
+    Callers,
+    Callees,
+}
+
+// Calls between functions by name over all loaded objects, in both directions
+struct Graph {
+    callers: HashMap<String, Vec<String>>,
+    callees: HashMap<String, Vec<String>>,
+}
+
+impl Graph {
+    fn new(objects: &[Arc<Object>]) -> Graph {
+        let mut graph = Graph {
+            callers: HashMap::new(),
+            callees: HashMap::new(),
+        };
+        let add = |map: &mut HashMap<String, Vec<String>>, from: &str, to: &str| {
+            let entry = map.entry(from.to_owned()).or_default();
+            if !entry.iter().any(|name| name == to) {
+                entry.push(to.to_owned());
+            }
+        };
+        for object in objects {
+            for (target, xrefs) in object.callers.iter() {
+                for xref in xrefs {
+                    add(&mut graph.callees, &xref.from, target);
+                    add(&mut graph.callers, target, &xref.from);
+                }
+            }
+        }
+        for names in graph.callers.values_mut().chain(graph.callees.values_mut()) {
+            names.sort();
+        }
+        graph
+    }
+
+    fn next(&self, direction: Direction, name: &str) -> &[String] {
+        let map = match direction {
+            Direction::Callers => &self.callers,
+            Direction::Callees => &self.callees,
+        };
+        map.get(name).map(Vec::as_slice).unwrap_or_default()
+    }
+}
+
+// A node of a caller or callee tree, identified by the functions on the way
+// to it from the root since a function can appear in many places
+#[derive(Clone)]
+struct Node {
+    path: Vec<String>,
+    children: usize,
+    // Set if the function is already on the path, so expanding it would
+    // repeat the tree above it
+    recursive: bool,
+}
+
+impl Node {
+    fn name(&self) -> &str {
+        self.path.last().unwrap()
+    }
+}
+
+// The visible nodes of the tree of `direction`, in display order
+fn nodes(
+    graph: &Graph,
+    root: &str,
+    direction: Direction,
+    expanded: &HashSet<(Direction, Vec<String>)>,
+) -> im::Vector<Node> {
+    let mut nodes = im::Vector::new();
+    let mut stack: Vec<Vec<String>> = graph
+        .next(direction, root)
+        .iter()
+        .rev()
+        .map(|name| vec![name.clone()])
+        .collect();
+    while let Some(path) = stack.pop() {
+        let name = path.last().unwrap();
+        let recursive = name == root || path[..path.len() - 1].contains(name);
+        let next = graph.next(direction, name);
+        if !recursive && expanded.contains(&(direction, path.clone())) {
+            stack.extend(next.iter().rev().map(|callee| {
+                let mut path = path.clone();
+                path.push(callee.clone());
+                path
+            }));
+        }
+        nodes.push_back(Node {
+            children: next.len(),
+            recursive,
+            path,
+        });
+    }
+    nodes
+}
+
+fn demangled(objects: &[Arc<Object>], name: &str) -> String {
+    callpaths::symbol(objects, name)
+        .and_then(|symbol| symbol.data.demangled.clone())
+        .or_else(|| settings::demangling().demangle(name))
+        .unwrap_or_else(|| name.to_owned())
+}
+
+fn tree(
+    graph: Rc<Graph>,
+    root: String,
+    direction: Direction,
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+    history: RwSignal<History>,
+) -> impl View {
+    let expanded = create_rw_signal(HashSet::new());
+    let count = graph.next(direction, &root).len();
+    let title = match direction {
+        Direction::Callers => format!("Callers ({})", count),
+        Direction::Callees => format!("Callees ({})", count),
+    };
+
+    let rows = virtual_list(
+        VirtualListDirection::Vertical,
+        VirtualListItemSize::Fixed(Box::new(|| ROW_HEIGHT)),
+        move || expanded.with(|expanded| nodes(&graph, &root, direction, expanded)),
+        |node| node.path.clone(),
+        move |node| {
+            let key = (direction, node.path.clone());
+            let is_expanded = {
+                let key = key.clone();
+                move || expanded.with(|expanded| expanded.contains(&key))
+            };
+            let expandable = node.children > 0 && !node.recursive;
+            let arrow = label(move || match (node.recursive, expandable, is_expanded()) {
+                (true, _, _) => "↻",
+                (_, false, _) => "",
+                (_, true, false) => "▸",
+                (_, true, true) => "▾",
+            })
+            .style(|s| s.width(14).color(theme().dim));
+
+            let name = node.name().to_owned();
+            let (target, label) = objects.with_untracked(|list| {
+                (
+                    callpaths::symbol(&list.objects, &name),
+                    demangled(&list.objects, &name),
+                )
+            });
+            let defined = target.is_some();
+            let place = target
+                .as_ref()
+                .map(|symbol| symbol.object.title())
+                .unwrap_or_else(|| "external".to_owned());
+            let depth = node.path.len() - 1;
+
+            stack((
+                arrow,
+                text(label).style(move |s| {
+                    s.flex_grow(1.0)
+                        .flex_basis(0.0)
+                        .min_width(0.0)
+                        .text_overflow(TextOverflow::Clip)
+                        .color(if defined { theme().text } else { theme().dim })
+                }),
+                text(place).style(|s| s.padding_left(10).color(theme().faint)),
+            ))
+            .style(move |s| {
+                s.flex_row()
+                    .items_center()
+                    .padding_horiz(5)
+                    .padding_left(5.0 + depth as f64 * INDENT)
+                    .width_full()
+                    .height(ROW_HEIGHT)
+                    .cursor(CursorStyle::Pointer)
+            })
+            .hover_style(|s| s.background(theme().hover))
+            .on_click(move |_| {
+                if expandable {
+                    expanded.update(|expanded| {
+                        if !expanded.remove(&key) {
+                            expanded.insert(key.clone());
+                        }
+                    });
+                }
+                true
+            })
+            .on_double_click({
+                let target = target.clone();
+                move |_| {
+                    if let Some(target) = &target {
+                        history::jump(selection, history, Selection::Symbol(target.clone()));
+                    }
+                    true
+                }
+            })
+            .context_menu(move || {
+                let name = name.clone();
+                let target = target.clone();
+                Menu::new("")
+                    .entry(
+                        MenuItem::new("Open Disassembly")
+                            .enabled(target.is_some())
+                            .action(move || {
+                                if let Some(target) = &target {
+                                    history::jump(
+                                        selection,
+                                        history,
+                                        Selection::Symbol(target.clone()),
+                                    );
+                                }
+                            }),
+                    )
+                    .entry(MenuItem::new("Show Call Graph").action(move || {
+                        history::jump(selection, history, Selection::CallGraph(name.clone()));
+                    }))
+            })
+        },
+    )
+    .style(|s| s.flex_col().width_full());
+
+    stack((
+        header(title),
+        scroll(rows).style(|s| s.width_full().flex_grow(1.0).flex_basis(0.0)),
+    ))
+    .style(|s| s.flex_col().width_full().flex_grow(1.0).flex_basis(0.0))
+}
+
+// Callers and callees of the named function as trees which expand on click,
+// with double clicking a function opening its disassembly
+pub fn graph_view(
+    root: String,
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+    history: RwSignal<History>,
+) -> Box<dyn View> {
+    let (graph, title) = objects.with_untracked(|list| {
+        (
+            Rc::new(Graph::new(&list.objects)),
+            demangled(&list.objects, &root),
+        )
+    });
+
+    let query = create_rw_signal(String::new());
+    let pick = move || {
+        let found = objects.with_untracked(|list| {
+            search::find_symbol(query.get_untracked().trim(), &list.objects)
+        });
+        if let Some(symbol) = found {
+            history::jump(
+                selection,
+                history,
+                Selection::CallGraph(symbol.data.name.clone()),
+            );
+        }
+    };
+    let picker = stack((
+        text("Function:").style(|s| s.padding_left(6.0)),
+        text_input(query)
+            .style(|s| {
+                s.width(320)
+                    .padding(5)
+                    .margin(4)
+                    .border(0.5)
+                    .border_radius(3)
+                    .border_color(theme().button_border)
+            })
+            .on_event(EventListener::KeyDown, move |e| {
+                if let Event::KeyDown(e) = e {
+                    if e.key.logical_key == Key::Named(NamedKey::Enter) {
+                        pick();
+                        return true;
+                    }
+                }
+                false
+            }),
+        button("Show", move |_| {
+            pick();
+            true
+        }),
+    ))
+    .style(|s| s.flex_row().items_center());
+
+    Box::new(
+        stack((
+            header(format!("Call Graph of `{}`", title)),
+            picker,
+            tree(
+                graph.clone(),
+                root.clone(),
+                Direction::Callers,
+                objects,
+                selection,
+                history,
+            ),
+            tree(graph, root, Direction::Callees, objects, selection, history),
+        ))
+        .style(|s| s.flex_col().width_full().height_full()),
+    )
+}
@@ -0,0 +1,173 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+};
+
+use floem::{
+    reactive::RwSignal,
+    view::View,
+    views::{list, scroll, stack, text, Decorators},
+};
+
+use crate::{
+    header, history, history::History, theme::theme, xrefs, Object, ObjectList, Selection, Symbol,
+};
+
+// Enumerating every shortest path can explode in call graphs with many
+// alternatives of the same length
+const MAX_PATHS: usize = 100;
+
+// Calls between functions by name, over all loaded objects so calls into other
+// objects are followed too
+fn callees(objects: &[Arc<Object>]) -> HashMap<&str, Vec<&str>> {
+    let mut callees: HashMap<&str, Vec<&str>> = HashMap::new();
+    for object in objects {
+        for (target, xrefs) in object.callers.iter() {
+            for xref in xrefs {
+                let entry = callees.entry(xref.from.as_str()).or_default();
+                if !entry.contains(&target) {
+                    entry.push(target);
+                }
+            }
+        }
+    }
+    callees
+}
+
+// The shortest call paths from `from` to `to`, each starting with `from` and
+// ending with `to`
+pub fn shortest_paths(objects: &[Arc<Object>], from: &str, to: &str) -> Vec<Vec<String>> {
+    let callees = callees(objects);
+
+    // Breadth first search, keeping every predecessor on a shortest path
+    let mut distance: HashMap<&str, usize> = HashMap::from([(from, 0)]);
+    let mut predecessors: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut queue = VecDeque::from([from]);
+    while let Some(name) = queue.pop_front() {
+        let next = distance[name] + 1;
+        if distance.get(to).is_some_and(|&found| next > found) {
+            break;
+        }
+        for &callee in callees.get(name).into_iter().flatten() {
+            match distance.get(callee) {
+                Some(&d) if d < next => continue,
+                Some(_) => {}
+                None => {
+                    distance.insert(callee, next);
+                    queue.push_back(callee);
+                }
+            }
+            predecessors.entry(callee).or_default().push(name);
+        }
+    }
+    if !distance.contains_key(to) {
+        return Vec::new();
+    }
+
+    // Walks back from `to` along the predecessors
+    let mut paths = Vec::new();
+    let mut stack = vec![vec![to]];
+    while let Some(path) = stack.pop() {
+        if paths.len() >= MAX_PATHS {
+            break;
+        }
+        let last = *path.last().unwrap();
+        if last == from {
+            paths.push(path.iter().rev().map(|name| name.to_string()).collect());
+            continue;
+        }
+        for &predecessor in predecessors.get(last).into_iter().flatten() {
+            let mut path = path.clone();
+            path.push(predecessor);
+            stack.push(path);
+        }
+    }
+    paths.sort();
+    paths
+}
+
+pub fn symbol(objects: &[Arc<Object>], name: &str) -> Option<Symbol> {
+    objects.iter().find_map(|object| {
+        Some(Symbol {
+            object: object.clone(),
+            data: xrefs::find(object, name)?,
+        })
+    })
+}
+
+pub fn paths_view(
+    from: String,
+    to: String,
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+    history: RwSignal<History>,
+) -> Box<dyn View> {
+    let (paths, names) = objects.with_untracked(|list| {
+        let paths = shortest_paths(&list.objects, &from, &to);
+        let demangled = |name: &str| {
+            symbol(&list.objects, name)
+                .and_then(|symbol| symbol.data.demangled.clone())
+                .unwrap_or_else(|| name.to_owned())
+        };
+        (paths, [demangled(&from), demangled(&to)])
+    });
+
+    let summary = match paths.len() {
+        0 => "No calls lead from the first function to the second".to_owned(),
+        MAX_PATHS => format!("The first {} shortest paths", MAX_PATHS),
+        count => format!("{} shortest paths with {} calls", count, paths[0].len() - 1),
+    };
+
+    let rows = list(
+        move || paths.clone().into_iter().enumerate(),
+        |(i, _)| *i,
+        move |(_, path)| {
+            list(
+                move || path.clone().into_iter().enumerate(),
+                |(i, _)| *i,
+                move |(i, name)| {
+                    let target = objects.with_untracked(|list| symbol(&list.objects, &name));
+                    let label = target
+                        .as_ref()
+                        .and_then(|symbol| symbol.data.demangled.clone())
+                        .unwrap_or_else(|| name.clone());
+                    let arrow = if i == 0 { "" } else { "  →  " };
+                    stack((
+                        text(arrow).style(|s| s.color(theme().faint)),
+                        text(label)
+                            .style(|s| s.color(theme().link))
+                            .hover_style(|s| s.background(theme().hover))
+                            .on_click(move |_| {
+                                if let Some(target) = &target {
+                                    history::jump(
+                                        selection,
+                                        history,
+                                        Selection::Symbol(target.clone()),
+                                    );
+                                }
+                                true
+                            }),
+                    ))
+                    .style(|s| s.flex_row())
+                },
+            )
+            .style(|s| {
+                s.flex_row()
+                    .padding(5)
+                    .width_full()
+                    .border_bottom(0.5)
+                    .border_color(theme().border)
+            })
+        },
+    )
+    .style(|s| s.flex_col().width_full());
+
+    Box::new(
+        stack((
+            header(format!("Call Paths from `{}` to `{}`", names[0], names[1])),
+            text(summary).style(|s| s.padding(5.0)),
+            scroll(rows).style(|s| s.width_full().flex_grow(1.0).flex_basis(0.0)),
+        ))
+        .style(|s| s.flex_col().width_full().height_full()),
+    )
+}
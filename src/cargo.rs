@@ -0,0 +1,189 @@
+use std::{
+    io::{BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+};
+
+use floem::{
+    ext_event::create_ext_action,
+    reactive::{RwSignal, Scope},
+};
+use rfd::AsyncFileDialog;
+
+use crate::{
+    dialog, open_paths, report_error, workspace::is_artifact, Loading, ObjectList, Selection,
+};
+
+// Lines of the compiler output shown when a build fails
+const ERROR_LINES: usize = 20;
+
+// An artifact of a workspace member and the crate it was built for
+pub struct Artifact {
+    pub path: PathBuf,
+    pub crate_name: String,
+}
+
+// The JSON string starting at `text`, unescaped, and the text after it
+fn json_string(text: &str) -> Option<(String, &str)> {
+    let mut chars = text.strip_prefix('"')?.char_indices();
+    let mut string = String::new();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Some((string, &text[i + 2..])),
+            '\\' => match chars.next()?.1 {
+                'n' => string.push('\n'),
+                't' => string.push('\t'),
+                'r' => string.push('\r'),
+                'u' => {
+                    let hex: String = (0..4)
+                        .filter_map(|_| chars.next())
+                        .map(|(_, c)| c)
+                        .collect();
+                    string.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+                }
+                c => string.push(c),
+            },
+            c => string.push(c),
+        }
+    }
+    None
+}
+
+// The value of the first string field named `key` after `from`
+fn field<'a>(text: &'a str, from: &str, key: &str) -> Option<(String, &'a str)> {
+    let start = text.find(from)?;
+    let text = &text[start..];
+    let start = text.find(&format!("\"{}\":", key))? + key.len() + 3;
+    json_string(text[start..].trim_start())
+}
+
+// The string array named `key`
+fn string_array(text: &str, key: &str) -> Vec<String> {
+    let mut strings = Vec::new();
+    let Some(start) = text.find(&format!("\"{}\":[", key)) else {
+        return strings;
+    };
+    let mut rest = &text[start + key.len() + 4..];
+    while let Some((string, after)) = json_string(rest.trim_start()) {
+        strings.push(string);
+        rest = after.trim_start().strip_prefix(',').unwrap_or(after);
+    }
+    strings
+}
+
+// Artifacts of workspace members in a `compiler-artifact` message. Build scripts
+// and dependencies from registries are left out.
+fn artifacts(message: &str) -> Vec<Artifact> {
+    if !message.contains("\"reason\":\"compiler-artifact\"") {
+        return Vec::new();
+    }
+    let member = field(message, "", "package_id")
+        .is_some_and(|(id, _)| id.starts_with("path+file://") || id.contains("(path+file://"));
+    let kinds = string_array(message, "kind");
+    if !member || kinds.iter().any(|kind| kind == "custom-build") {
+        return Vec::new();
+    }
+    let Some((crate_name, _)) = field(message, "\"target\":", "name") else {
+        return Vec::new();
+    };
+    string_array(message, "filenames")
+        .into_iter()
+        .map(PathBuf::from)
+        .filter(|path| is_artifact(path))
+        .map(|path| Artifact {
+            path,
+            crate_name: crate_name.clone(),
+        })
+        .collect()
+}
+
+// Builds the project at `root`, returning the artifacts or the end of the
+// compiler output
+fn build(root: &Path, profile: &str) -> Result<Vec<Artifact>, String> {
+    let mut child = Command::new("cargo")
+        .args(["build", "--message-format=json-render-diagnostics"])
+        .args(["--profile", profile])
+        .current_dir(root)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|error| format!("Running cargo: {}", error))?;
+
+    // Reads the diagnostics while cargo writes the messages, so neither pipe
+    // fills up and blocks it
+    let mut stderr = child.stderr.take().unwrap();
+    let diagnostics = thread::spawn(move || {
+        let mut text = String::new();
+        let _ = stderr.read_to_string(&mut text);
+        text
+    });
+
+    let mut found = Vec::new();
+    for line in BufReader::new(child.stdout.take().unwrap()).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        found.extend(artifacts(&line));
+    }
+    let status = child
+        .wait()
+        .map_err(|error| format!("Running cargo: {}", error))?;
+    let diagnostics = diagnostics.join().unwrap_or_default();
+    if !status.success() {
+        let lines: Vec<&str> = diagnostics.lines().collect();
+        let tail = lines[lines.len().saturating_sub(ERROR_LINES)..].join("\n");
+        return Err(format!("`cargo build` failed ({}):\n{}", status, tail));
+    }
+    Ok(found)
+}
+
+// Builds a Cargo project with the given profile and loads the artifacts of
+// its members
+pub fn open_project(objects: RwSignal<ObjectList>, selection: RwSignal<Selection>, profile: &str) {
+    let profile = profile.to_owned();
+    dialog::pick_folder(
+        AsyncFileDialog::new().set_title("Open a Cargo project..."),
+        move |root| build_project(objects, selection, root, profile),
+    );
+}
+
+fn build_project(
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+    root: PathBuf,
+    profile: String,
+) {
+    if !root.join("Cargo.toml").is_file() {
+        report_error(objects, format!("`{}` has no `Cargo.toml`", root.display()));
+        return;
+    }
+
+    objects.update(|list| {
+        list.loading.push(Loading {
+            path: root.clone(),
+            objects: 0,
+            members: None,
+        })
+    });
+    let built = root.clone();
+    let done = create_ext_action(
+        Scope::new(),
+        move |result: Result<Vec<Artifact>, String>| {
+            objects.update(|list| list.loading.retain(|loading| loading.path != built));
+            match result {
+                Ok(artifacts) => {
+                    let paths = artifacts.iter().map(|a| a.path.clone()).collect();
+                    objects.update(|list| {
+                        for artifact in artifacts {
+                            list.crates.insert(artifact.path, artifact.crate_name);
+                        }
+                    });
+                    open_paths(objects, selection, paths, None);
+                }
+                Err(error) => report_error(objects, error),
+            }
+        },
+    );
+    thread::spawn(move || done(build(&root, &profile)));
+}
@@ -0,0 +1,476 @@
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    io::{self, Write as _},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{
+    diff, each_object, export, mapping, parse_object, raw, search, settings::Syntax, Object,
+    Settings, Symbol,
+};
+
+const USAGE: &str = "usage: asm-viewer diff <old> <new> [--format json|text] \
+                     [--ignore-registers] [--ignore-padding] [--ignore-addresses] \
+                     [--fail-on-change]";
+
+const DUMP_USAGE: &str =
+    "usage: asm-viewer dump <file> <symbol> [--syntax intel|att|nasm|masm] [--bytes]";
+
+const GUI_USAGE: &str = "usage: asm-viewer [<file>...] [--symbol <name>]";
+
+// Files to open when the GUI starts
+#[derive(Clone, Default)]
+pub struct Startup {
+    pub paths: Vec<PathBuf>,
+    // Symbol to select once the files are loaded
+    pub symbol: Option<String>,
+    // Raw binaries with the layout they're decoded with
+    pub raw: Vec<(PathBuf, raw::Layout)>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Status {
+    Changed,
+    Added,
+    Removed,
+}
+
+impl Status {
+    fn label(self) -> &'static str {
+        match self {
+            Status::Changed => "changed",
+            Status::Added => "added",
+            Status::Removed => "removed",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Measure {
+    size: usize,
+    instructions: usize,
+}
+
+struct Function {
+    name: String,
+    demangled: Option<String>,
+    status: Status,
+    old: Option<Measure>,
+    new: Option<Measure>,
+    // Diff rows which aren't equal
+    changed_rows: usize,
+}
+
+struct Report {
+    functions: Vec<Function>,
+    unchanged: usize,
+}
+
+// Handles command line invocations, returning the exit code. Returns `None`
+// when the GUI should be started instead.
+pub fn run(args: &[String]) -> Option<i32> {
+    match args.first().map(|arg| arg.as_str()) {
+        Some("diff") => Some(run_diff(&args[1..])),
+        Some("dump") => Some(run_dump(&args[1..])),
+        _ => None,
+    }
+}
+
+fn run_diff(args: &[String]) -> i32 {
+    let mut paths = Vec::new();
+    let mut json = false;
+    // Exits with 1 when any function changed, for use in CI
+    let mut fail_on_change = false;
+    let mut settings = Settings::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => match args.next().map(|format| format.as_str()) {
+                Some("json") => json = true,
+                Some("text") => json = false,
+                _ => return usage(USAGE),
+            },
+            "--format=json" => json = true,
+            "--format=text" => json = false,
+            "--ignore-registers" => settings.diff_ignore_registers = true,
+            "--ignore-padding" => settings.diff_ignore_padding = true,
+            "--ignore-addresses" => settings.diff_ignore_addresses = true,
+            "--fail-on-change" => fail_on_change = true,
+            arg if arg.starts_with("--") => return usage(USAGE),
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    let [old, new] = &paths[..] else {
+        return usage(USAGE);
+    };
+
+    let (Some(old_objects), Some(new_objects)) = (load(old), load(new)) else {
+        return 2;
+    };
+    let report = compare(&old_objects, &new_objects, &settings);
+    if json {
+        println!("{}", to_json(&report, old, new));
+    } else {
+        print!("{}", to_text(&report));
+    }
+    // Only changed, added and removed functions are listed
+    if fail_on_change && !report.functions.is_empty() {
+        1
+    } else {
+        0
+    }
+}
+
+// Prints the listing of a symbol as the assembly view shows it, with
+// relocations resolved and names demangled
+fn run_dump(args: &[String]) -> i32 {
+    let mut positional = Vec::new();
+    let mut settings = Settings::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--syntax" => {
+                let syntax = match args.next().map(|syntax| syntax.as_str()) {
+                    Some("intel") => Syntax::Intel,
+                    Some("att") => Syntax::Gas,
+                    Some("nasm") => Syntax::Nasm,
+                    Some("masm") => Syntax::Masm,
+                    _ => return usage(DUMP_USAGE),
+                };
+                settings.syntax = syntax;
+            }
+            "--bytes" => settings.show_bytes = true,
+            arg if arg.starts_with("--") => return usage(DUMP_USAGE),
+            _ => positional.push(arg),
+        }
+    }
+    let [path, name] = positional[..] else {
+        return usage(DUMP_USAGE);
+    };
+
+    let Some(objects) = load(Path::new(path)) else {
+        return 2;
+    };
+    // Dumping a symbol whose name merely contains `name` would pass off the
+    // wrong listing, so that's only suggested
+    let Some(symbol) = search::find_named_symbol(name, &objects) else {
+        match search::find_symbol(name, &objects) {
+            Some(similar) => eprintln!(
+                "symbol `{}` not found in `{}`, did you mean `{}`?",
+                name,
+                path,
+                similar
+                    .data
+                    .demangled
+                    .as_ref()
+                    .unwrap_or(&similar.data.name)
+            ),
+            None => eprintln!("symbol `{}` not found in `{}`", name, path),
+        }
+        return 1;
+    };
+    let Some(listing) = export::listing(&symbol, &settings, None, false, None) else {
+        eprintln!("`{}` has no instructions", symbol.data.name);
+        return 1;
+    };
+    if let Err(error) = io::stdout().write_all(&listing) {
+        eprintln!("failed to write the listing: {}", error);
+        return 1;
+    }
+    0
+}
+
+pub fn startup(args: &[String]) -> Startup {
+    let mut startup = Startup::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--symbol" => match args.next() {
+                Some(name) => startup.symbol = Some(name.clone()),
+                None => eprintln!("{}", GUI_USAGE),
+            },
+            arg if arg.starts_with("--symbol=") => {
+                startup.symbol = Some(arg["--symbol=".len()..].to_owned())
+            }
+            arg if arg.starts_with("--") => eprintln!("unknown option `{}`\n{}", arg, GUI_USAGE),
+            _ => startup.paths.push(PathBuf::from(arg)),
+        }
+    }
+    startup
+}
+
+fn usage(usage: &str) -> i32 {
+    eprintln!("{}", usage);
+    2
+}
+
+// The objects of a file with the errors of the ones which couldn't be parsed
+pub fn load_objects(path: &Path) -> (Vec<Arc<Object>>, Vec<String>) {
+    let file = match mapping::map(path) {
+        Ok(file) => file,
+        Err(error) => {
+            return (
+                Vec::new(),
+                vec![format!("failed to read `{}`: {}", path.display(), error)],
+            )
+        }
+    };
+    let mut objects = Vec::new();
+    let errors = each_object(path, &file, |data, name, member, slice| {
+        let object = parse_object(&file, data, name, path.to_path_buf(), member, slice)?;
+        objects.push(Arc::new(object));
+        Ok(())
+    });
+    (objects, errors)
+}
+
+pub fn load(path: &Path) -> Option<Vec<Arc<Object>>> {
+    let (objects, errors) = load_objects(path);
+    for error in errors {
+        eprintln!("{}", error);
+    }
+    for object in &objects {
+        for warning in object.all_warnings() {
+            eprintln!("`{}`: {}", object.title(), warning);
+        }
+    }
+    if objects.is_empty() {
+        return None;
+    }
+    Some(objects)
+}
+
+// Defined functions by name. Archives may repeat local names, in which case
+// the first one is used.
+fn functions(objects: &[Arc<Object>]) -> HashMap<&str, Symbol> {
+    let mut functions = HashMap::new();
+    for object in objects {
+        for data in &object.symbols_sorted {
+            if data.section.is_some() {
+                functions
+                    .entry(data.name.as_str())
+                    .or_insert_with(|| Symbol {
+                        object: object.clone(),
+                        data: data.clone(),
+                    });
+            }
+        }
+    }
+    functions
+}
+
+fn measure(symbol: &Symbol, instructions: &Option<Vec<crate::Instruction>>) -> Measure {
+    Measure {
+        size: symbol
+            .data
+            .data()
+            .map(|data| data.len())
+            .unwrap_or_default(),
+        instructions: instructions.as_ref().map(|i| i.len()).unwrap_or_default(),
+    }
+}
+
+fn compare(old: &[Arc<Object>], new: &[Arc<Object>], settings: &Settings) -> Report {
+    let old = functions(old);
+    let new = functions(new);
+    let mut report = Report {
+        functions: Vec::new(),
+        unchanged: 0,
+    };
+
+    for (name, left) in &old {
+        let left_instructions = diff::instructions(left, settings);
+        let old_measure = measure(left, &left_instructions);
+        let Some(right) = new.get(name) else {
+            report.functions.push(Function {
+                name: name.to_string(),
+                demangled: left.data.demangled.clone(),
+                status: Status::Removed,
+                old: Some(old_measure),
+                new: None,
+                changed_rows: left_instructions.map(|i| i.len()).unwrap_or_default(),
+            });
+            continue;
+        };
+
+        let right_instructions = diff::instructions(right, settings);
+        let new_measure = measure(right, &right_instructions);
+        let changed_rows = diff::diff_instructions(
+            left,
+            left_instructions.as_deref().unwrap_or_default(),
+            right,
+            right_instructions.as_deref().unwrap_or_default(),
+            settings,
+        )
+        .iter()
+        .filter(|row| row.change != diff::Change::Equal)
+        .count();
+        if changed_rows == 0 {
+            report.unchanged += 1;
+            continue;
+        }
+        report.functions.push(Function {
+            name: name.to_string(),
+            demangled: left.data.demangled.clone(),
+            status: Status::Changed,
+            old: Some(old_measure),
+            new: Some(new_measure),
+            changed_rows,
+        });
+    }
+
+    for (name, right) in &new {
+        if old.contains_key(name) {
+            continue;
+        }
+        let right_instructions = diff::instructions(right, settings);
+        let new_measure = measure(right, &right_instructions);
+        report.functions.push(Function {
+            name: name.to_string(),
+            demangled: right.data.demangled.clone(),
+            status: Status::Added,
+            old: None,
+            new: Some(new_measure),
+            changed_rows: new_measure.instructions,
+        });
+    }
+
+    report
+        .functions
+        .sort_by(|a, b| a.status.cmp(&b.status).then_with(|| a.name.cmp(&b.name)));
+    report
+}
+
+fn count(report: &Report, status: Status) -> usize {
+    report
+        .functions
+        .iter()
+        .filter(|function| function.status == status)
+        .count()
+}
+
+fn delta(old: usize, new: usize) -> i64 {
+    new as i64 - old as i64
+}
+
+fn to_text(report: &Report) -> String {
+    let mut out = String::new();
+    for function in &report.functions {
+        let name = function.demangled.as_ref().unwrap_or(&function.name);
+        let _ = match (function.old, function.new) {
+            (Some(old), Some(new)) => writeln!(
+                out,
+                "{:<8} {}: size {} -> {} ({:+}), instructions {} -> {} ({:+}), {} rows differ",
+                function.status.label(),
+                name,
+                old.size,
+                new.size,
+                delta(old.size, new.size),
+                old.instructions,
+                new.instructions,
+                delta(old.instructions, new.instructions),
+                function.changed_rows
+            ),
+            (Some(measure), None) | (None, Some(measure)) => writeln!(
+                out,
+                "{:<8} {}: size {}, instructions {}",
+                function.status.label(),
+                name,
+                measure.size,
+                measure.instructions
+            ),
+            (None, None) => Ok(()),
+        };
+    }
+    let _ = writeln!(
+        out,
+        "{} changed, {} added, {} removed, {} unchanged",
+        count(report, Status::Changed),
+        count(report, Status::Added),
+        count(report, Status::Removed),
+        report.unchanged
+    );
+    out
+}
+
+fn json_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn json_measure(measure: Option<Measure>) -> String {
+    match measure {
+        Some(measure) => format!(
+            "{{\"size\": {}, \"instructions\": {}}}",
+            measure.size, measure.instructions
+        ),
+        None => "null".to_owned(),
+    }
+}
+
+fn to_json(report: &Report, old: &Path, new: &Path) -> String {
+    let mut out = String::from("{\n");
+    let _ = writeln!(
+        out,
+        "  \"old\": {},\n  \"new\": {},",
+        json_string(&old.to_string_lossy()),
+        json_string(&new.to_string_lossy())
+    );
+    let _ = writeln!(
+        out,
+        "  \"summary\": {{\"changed\": {}, \"added\": {}, \"removed\": {}, \"unchanged\": {}}},",
+        count(report, Status::Changed),
+        count(report, Status::Added),
+        count(report, Status::Removed),
+        report.unchanged
+    );
+    out.push_str("  \"functions\": [");
+    for (i, function) in report.functions.iter().enumerate() {
+        let measure = |m: Option<Measure>| {
+            m.unwrap_or(Measure {
+                size: 0,
+                instructions: 0,
+            })
+        };
+        let (old, new) = (measure(function.old), measure(function.new));
+        let _ = write!(
+            out,
+            "{}\n    {{\"name\": {}, \"demangled\": {}, \"status\": \"{}\", \"old\": {}, \"new\": {}, \
+             \"size_delta\": {}, \"instruction_delta\": {}, \"changed_rows\": {}}}",
+            if i == 0 { "" } else { "," },
+            json_string(&function.name),
+            function
+                .demangled
+                .as_deref()
+                .map(json_string)
+                .unwrap_or_else(|| "null".to_owned()),
+            function.status.label(),
+            json_measure(function.old),
+            json_measure(function.new),
+            delta(old.size, new.size),
+            delta(old.instructions, new.instructions),
+            function.changed_rows
+        );
+    }
+    out.push_str(if report.functions.is_empty() {
+        "]\n}"
+    } else {
+        "\n  ]\n}"
+    });
+    out
+}
@@ -0,0 +1,74 @@
+use floem::{
+    menu::{Menu, MenuItem},
+    reactive::RwSignal,
+    Clipboard,
+};
+
+use crate::{export, report_problem, tools, AddressRange, Instruction, Object, Settings, Symbol};
+
+fn copy(text: String) {
+    if let Err(error) = Clipboard::set_contents(text) {
+        report_problem(format!("Clipboard: {:?}", error));
+    }
+}
+
+fn copy_item(label: &str, text: Option<String>) -> MenuItem {
+    MenuItem::new(label)
+        .enabled(text.is_some())
+        .action(move || copy(text.clone().unwrap_or_default()))
+}
+
+// The symbol shown in an assembly view, for copying parts of its listing
+pub struct Listing {
+    pub symbol: Symbol,
+    pub settings: Settings,
+    pub range: RwSignal<Option<AddressRange>>,
+}
+
+impl Listing {
+    fn text(&self, range: Option<AddressRange>) -> Option<String> {
+        let listing = export::listing(&self.symbol, &self.settings, range, false, None)?;
+        Some(String::from_utf8_lossy(&listing).into_owned())
+    }
+}
+
+// The context menu of an instruction, with the external tools in a submenu
+pub fn instruction_menu(object: &Object, i: &Instruction, listing: Option<&Listing>) -> Menu {
+    let mut menu = Menu::new("")
+        .entry(copy_item(
+            "Copy Line",
+            Some(format!("{:016X}  {}", i.address, i.text())),
+        ))
+        .entry(copy_item("Copy Address", Some(format!("{:X}", i.address))));
+    if let Some(listing) = listing {
+        let selected = listing
+            .range
+            .get_untracked()
+            .and_then(|range| listing.text(Some(range)));
+        menu = menu
+            .entry(copy_item("Copy Selection", selected))
+            .entry(copy_item("Copy Listing", listing.text(None)));
+    }
+    menu.separator()
+        .entry(tools::menu(tools::ToolContext::at_address(
+            object, i.address,
+        )))
+}
+
+// The context menu of a symbol, with the external tools in a submenu
+pub fn symbol_menu(symbol: &Symbol) -> Menu {
+    let data = &symbol.data;
+    Menu::new("")
+        .entry(copy_item("Copy Name", Some(data.name.clone())))
+        .entry(copy_item("Copy Demangled Name", data.demangled.clone()))
+        .entry(copy_item(
+            "Copy Address",
+            Some(format!("{:X}", data.address)),
+        ))
+        .separator()
+        .entry(tools::menu(tools::ToolContext::new(
+            &symbol.object,
+            Some(data),
+            Some(data.address),
+        )))
+}
@@ -0,0 +1,110 @@
+use std::{cell::RefCell, sync::Arc};
+
+use asm_viewer_core::{
+    deadcode::{candidates, Candidate},
+    pool,
+};
+use floem::{
+    ext_event::create_ext_action,
+    reactive::{create_rw_signal, RwSignal, Scope},
+    view::View,
+    views::{
+        dyn_container, scroll, stack, text, virtual_list, Decorators, VirtualListDirection,
+        VirtualListItemSize,
+    },
+};
+
+use crate::{header, theme::theme, units, Object, Selection};
+
+// The candidates with the generations of the objects they're for
+type Report = (Vec<u64>, Arc<Vec<Candidate>>);
+
+thread_local! {
+    // The last report, as the view is rebuilt when the settings or the theme
+    // change
+    static REPORT: RefCell<Option<Report>> = const { RefCell::new(None) };
+}
+
+pub fn report(objects: &[Arc<Object>], selection: RwSignal<Selection>) -> Box<dyn View> {
+    let generations: Vec<u64> = objects.iter().map(|object| object.generation).collect();
+    let cached = REPORT.with(|report| {
+        report
+            .borrow()
+            .as_ref()
+            .filter(|(of, _)| *of == generations)
+            .map(|(_, candidates)| candidates.clone())
+    });
+    let found = create_rw_signal(cached.clone());
+    if cached.is_none() {
+        // Every function is decoded to find the references
+        let done = create_ext_action(Scope::new(), move |candidates: Arc<Vec<Candidate>>| {
+            REPORT.with(|report| *report.borrow_mut() = Some((generations, candidates.clone())));
+            found.set(Some(candidates));
+        });
+        let objects = objects.to_vec();
+        pool::spawn(pool::Priority::Interactive, move || {
+            done(Arc::new(candidates(&objects)))
+        });
+    }
+
+    let content = dyn_container(
+        move || found.get(),
+        move |candidates| match candidates {
+            Some(candidates) => candidates_view(&candidates, selection),
+            None => {
+                Box::new(text("Looking for unreferenced functions...").style(|s| s.padding(5.0)))
+            }
+        },
+    )
+    .style(|s| s.width_full().flex_grow(1.0).flex_basis(0.0));
+
+    Box::new(
+        stack((header("Dead Code Candidates"), content))
+            .style(|s| s.flex_col().width_full().height_full()),
+    )
+}
+
+fn candidates_view(candidates: &[Candidate], selection: RwSignal<Selection>) -> Box<dyn View> {
+    let total: u64 = candidates.iter().map(|candidate| candidate.size).sum();
+    let summary = text(format!(
+        "{} unreferenced functions, {}. Exports, entry points and functions \
+         referenced by relocations, code or pointers in data of any loaded object are excluded.",
+        units::count(candidates.len() as u64),
+        units::size(total)
+    ))
+    .style(|s| s.padding(5.0));
+
+    let candidates: im::Vector<_> = candidates.iter().cloned().enumerate().collect();
+    let list = virtual_list(
+        VirtualListDirection::Vertical,
+        VirtualListItemSize::Fixed(Box::new(|| 26.0)),
+        move || candidates.clone(),
+        |(i, _)| *i,
+        move |(_, candidate)| {
+            let data = &candidate.symbol.data;
+            let name = data.demangled.as_ref().unwrap_or(&data.name).clone();
+            let symbol = candidate.symbol.clone();
+            stack((
+                text(name).style(|s| s.flex_grow(1.0).flex_basis(0.0).min_width(0.0)),
+                text(candidate.symbol.object.title())
+                    .style(|s| s.width(200).padding_left(10).color(theme().dim)),
+                units::size_text(candidate.size).style(|s| s.width(100).padding_left(10)),
+            ))
+            .style(|s| s.flex_row().width_full().padding(5).height(26.0))
+            .hover_style(|s| s.background(theme().hover))
+            .on_click(move |_| {
+                selection.set(Selection::Symbol(symbol.clone()));
+                true
+            })
+        },
+    )
+    .style(|s| s.flex_col().width_full());
+
+    Box::new(
+        stack((
+            summary,
+            scroll(list).style(|s| s.width_full().flex_grow(1.0).flex_basis(0.0)),
+        ))
+        .style(|s| s.flex_col().width_full().height_full()),
+    )
+}
@@ -0,0 +1,102 @@
+use std::{env, path::Path, process::Command};
+
+// MIME types of the files the viewer can open
+#[cfg(all(unix, not(target_os = "macos")))]
+const MIME_TYPES: &[&str] = &[
+    "application/x-object",
+    "application/x-sharedlib",
+    "application/x-executable",
+    "application/x-pie-executable",
+    "application/x-archive",
+    "application/x-dosexec",
+    "application/x-mach-binary",
+];
+
+#[cfg(windows)]
+const EXTENSIONS: &[&str] = &[".o", ".obj", ".so", ".a", ".lib", ".rlib", ".dll", ".exe"];
+
+fn run(command: &mut Command) -> Result<(), String> {
+    let status = command
+        .status()
+        .map_err(|error| format!("failed to run `{:?}`: {}", command.get_program(), error))?;
+    if !status.success() {
+        return Err(format!("`{:?}` failed: {}", command.get_program(), status));
+    }
+    Ok(())
+}
+
+// Installs a `.desktop` entry for the current executable and makes it the
+// default application for object files
+#[cfg(all(unix, not(target_os = "macos")))]
+fn install(exe: &Path) -> Result<(), String> {
+    let data = env::var_os("XDG_DATA_HOME")
+        .map(std::path::PathBuf::from)
+        .or_else(|| {
+            env::var_os("HOME").map(|home| std::path::PathBuf::from(home).join(".local/share"))
+        })
+        .ok_or("no home directory")?;
+    let applications = data.join("applications");
+    std::fs::create_dir_all(&applications)
+        .map_err(|error| format!("`{}`: {}", applications.display(), error))?;
+
+    let entry = format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=asm-viewer\n\
+         Comment=View and diff the assembly of object files\n\
+         Exec=\"{}\" %F\n\
+         Terminal=false\n\
+         Categories=Development;Debugger;\n\
+         MimeType={};\n",
+        exe.display(),
+        MIME_TYPES.join(";")
+    );
+    let path = applications.join("asm-viewer.desktop");
+    std::fs::write(&path, entry).map_err(|error| format!("`{}`: {}", path.display(), error))?;
+
+    run(Command::new("xdg-mime")
+        .args(["default", "asm-viewer.desktop"])
+        .args(MIME_TYPES))?;
+    // Only refreshes the cache, the entry works without it
+    let _ = Command::new("update-desktop-database")
+        .arg(&applications)
+        .status();
+    Ok(())
+}
+
+// Adds the executable to the "Open with" list of each extension, without
+// replacing the default handler
+#[cfg(windows)]
+fn install(exe: &Path) -> Result<(), String> {
+    let class = r"HKCU\Software\Classes\asm-viewer.file";
+    run(Command::new("reg").args([
+        "add",
+        &format!(r"{}\shell\open\command", class),
+        "/ve",
+        "/d",
+        &format!("\"{}\" \"%1\"", exe.display()),
+        "/f",
+    ]))?;
+    for extension in EXTENSIONS {
+        run(Command::new("reg").args([
+            "add",
+            &format!(r"HKCU\Software\Classes\{}\OpenWithProgids", extension),
+            "/v",
+            "asm-viewer.file",
+            "/f",
+        ]))?;
+    }
+    Ok(())
+}
+
+#[cfg(not(any(windows, all(unix, not(target_os = "macos")))))]
+fn install(_exe: &Path) -> Result<(), String> {
+    Err("file associations need an application bundle on this platform".to_owned())
+}
+
+// Registers the running executable as a handler for object files, so opening
+// one from a file manager passes it on the command line
+pub fn register() -> Result<(), String> {
+    let exe = env::current_exe().map_err(|error| format!("no executable path: {}", error))?;
+    install(&exe)
+}
@@ -0,0 +1,50 @@
+use std::{future::Future, path::PathBuf, sync::Mutex, thread};
+
+use floem::{ext_event::create_ext_action, reactive::Scope};
+use rfd::{AsyncFileDialog, FileHandle};
+
+// Held while a dialog is shown, so dialogs requested meanwhile wait their turn
+// instead of opening on top of it
+static SHOWN: Mutex<()> = Mutex::new(());
+
+// Waits for the dialog on a worker thread, so the window stays responsive,
+// then calls `done` with the picked paths. Nothing is called if the dialog is
+// cancelled.
+fn show<F, T>(dialog: F, done: impl FnOnce(T) + 'static)
+where
+    F: Future<Output = Option<T>> + Send + 'static,
+    T: Send + 'static,
+{
+    let done = create_ext_action(Scope::new(), move |picked: Option<T>| {
+        if let Some(picked) = picked {
+            done(picked)
+        }
+    });
+    thread::spawn(move || {
+        let _shown = SHOWN.lock();
+        done(pollster::block_on(dialog))
+    });
+}
+
+fn path(file: FileHandle) -> PathBuf {
+    file.path().to_owned()
+}
+
+pub fn pick_file(dialog: AsyncFileDialog, done: impl FnOnce(PathBuf) + 'static) {
+    show(async { dialog.pick_file().await.map(path) }, done);
+}
+
+pub fn pick_files(dialog: AsyncFileDialog, done: impl FnOnce(Vec<PathBuf>) + 'static) {
+    show(
+        async { Some(dialog.pick_files().await?.into_iter().map(path).collect()) },
+        done,
+    );
+}
+
+pub fn pick_folder(dialog: AsyncFileDialog, done: impl FnOnce(PathBuf) + 'static) {
+    show(async { dialog.pick_folder().await.map(path) }, done);
+}
+
+pub fn save_file(dialog: AsyncFileDialog, done: impl FnOnce(PathBuf) + 'static) {
+    show(async { dialog.save_file().await.map(path) }, done);
+}
@@ -0,0 +1,769 @@
+use floem::{
+    kurbo::Point,
+    peniko::Color,
+    reactive::{create_rw_signal, RwSignal},
+    view::View,
+    views::{
+        dyn_container, empty, list, scroll, stack, text, virtual_list, Decorators,
+        VirtualListDirection, VirtualListItemSize,
+    },
+};
+
+use iced_x86::FormatterTextKind;
+
+use crate::{
+    button, header,
+    history::History,
+    instruction_view, overrides,
+    project::{Anchor, Project},
+    settings::Density,
+    theme::theme,
+    toggle_button, Instruction, RegisterHighlight, Selection, Settings, Symbol,
+};
+
+// Edit distance after which the diff gives up aligning and treats the
+// remaining middle part of the functions as changed.
+const MAX_EDIT_DISTANCE: usize = 2048;
+
+const MINIMAP_BUCKETS: usize = 200;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Change {
+    Equal,
+    Changed,
+    Removed,
+    Added,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct DiffRow {
+    pub left: Option<usize>,
+    pub right: Option<usize>,
+    pub change: Change,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Edit {
+    Equal,
+    Delete,
+    Insert,
+}
+
+// Myers' O(ND) shortest edit script
+fn edit_script<T: PartialEq>(a: &[T], b: &[T]) -> Option<Vec<Edit>> {
+    let n = a.len() as isize;
+    let m = b.len() as isize;
+    let max = (a.len() + b.len()).min(MAX_EDIT_DISTANCE) as isize;
+
+    let offset = max + 1;
+    let mut v = vec![0isize; 2 * offset as usize + 1];
+    let mut trace = Vec::new();
+
+    let mut distance = None;
+    'outer: for d in 0..=max {
+        trace.push(v.clone());
+        for k in (-d..=d).step_by(2) {
+            let index = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && v[index - 1] < v[index + 1]) {
+                v[index + 1]
+            } else {
+                v[index - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[index] = x;
+            if x >= n && y >= m {
+                distance = Some(d);
+                break 'outer;
+            }
+        }
+    }
+    let distance = distance?;
+
+    let mut edits = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for d in (0..=distance).rev() {
+        let v = &trace[d as usize];
+        let k = x - y;
+        let index = (k + offset) as usize;
+        let previous_k = if k == -d || (k != d && v[index - 1] < v[index + 1]) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let previous_x = v[(previous_k + offset) as usize];
+        let previous_y = previous_x - previous_k;
+        while x > previous_x && y > previous_y {
+            edits.push(Edit::Equal);
+            x -= 1;
+            y -= 1;
+        }
+        if d > 0 {
+            edits.push(if x == previous_x {
+                Edit::Insert
+            } else {
+                Edit::Delete
+            });
+        }
+        x = previous_x;
+        y = previous_y;
+    }
+    edits.reverse();
+    Some(edits)
+}
+
+// Aligns `a` and `b` into side-by-side rows. Runs of removed and added lines
+// are paired up into changed rows.
+pub fn diff<T: PartialEq>(a: &[T], b: &[T]) -> Vec<DiffRow> {
+    let prefix = a.iter().zip(b).take_while(|(a, b)| a == b).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let middle_a = &a[prefix..a.len() - suffix];
+    let middle_b = &b[prefix..b.len() - suffix];
+    let edits = edit_script(middle_a, middle_b).unwrap_or_else(|| {
+        let mut edits = vec![Edit::Delete; middle_a.len()];
+        edits.extend(vec![Edit::Insert; middle_b.len()]);
+        edits
+    });
+
+    let mut rows: Vec<DiffRow> = (0..prefix)
+        .map(|i| DiffRow {
+            left: Some(i),
+            right: Some(i),
+            change: Change::Equal,
+        })
+        .collect();
+
+    let (mut x, mut y) = (prefix, prefix);
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+    let flush = |rows: &mut Vec<DiffRow>, removed: &mut Vec<usize>, added: &mut Vec<usize>| {
+        for i in 0..removed.len().max(added.len()) {
+            let left = removed.get(i).copied();
+            let right = added.get(i).copied();
+            let change = match (left, right) {
+                (Some(_), Some(_)) => Change::Changed,
+                (Some(_), None) => Change::Removed,
+                _ => Change::Added,
+            };
+            rows.push(DiffRow {
+                left,
+                right,
+                change,
+            });
+        }
+        removed.clear();
+        added.clear();
+    };
+
+    for edit in edits {
+        match edit {
+            Edit::Equal => {
+                flush(&mut rows, &mut removed, &mut added);
+                rows.push(DiffRow {
+                    left: Some(x),
+                    right: Some(y),
+                    change: Change::Equal,
+                });
+                x += 1;
+                y += 1;
+            }
+            Edit::Delete => {
+                removed.push(x);
+                x += 1;
+            }
+            Edit::Insert => {
+                added.push(y);
+                y += 1;
+            }
+        }
+    }
+    flush(&mut rows, &mut removed, &mut added);
+
+    rows.extend((0..suffix).map(|i| DiffRow {
+        left: Some(a.len() - suffix + i),
+        right: Some(b.len() - suffix + i),
+        change: Change::Equal,
+    }));
+
+    rows
+}
+
+fn shifted(rows: Vec<DiffRow>, x: usize, y: usize) -> impl Iterator<Item = DiffRow> {
+    rows.into_iter().map(move |row| DiffRow {
+        left: row.left.map(|left| left + x),
+        right: row.right.map(|right| right + y),
+        change: row.change,
+    })
+}
+
+// Like `diff`, but puts each `(left, right)` anchor pair on the same row.
+// Anchors crossing an earlier one are ignored.
+pub fn diff_anchored<T: PartialEq>(a: &[T], b: &[T], anchors: &[(usize, usize)]) -> Vec<DiffRow> {
+    let mut anchors: Vec<_> = anchors
+        .iter()
+        .copied()
+        .filter(|&(left, right)| left < a.len() && right < b.len())
+        .collect();
+    anchors.sort_unstable();
+
+    let mut rows = Vec::new();
+    let (mut x, mut y) = (0, 0);
+    for (left, right) in anchors {
+        if left < x || right < y {
+            continue;
+        }
+        rows.extend(shifted(diff(&a[x..left], &b[y..right]), x, y));
+        rows.push(DiffRow {
+            left: Some(left),
+            right: Some(right),
+            change: if a[left] == b[right] {
+                Change::Equal
+            } else {
+                Change::Changed
+            },
+        });
+        x = left + 1;
+        y = right + 1;
+    }
+    rows.extend(shifted(diff(&a[x..], &b[y..]), x, y));
+    rows
+}
+
+// Indices of the first row of every run of changed rows
+fn hunks(rows: &[DiffRow]) -> Vec<usize> {
+    rows.iter()
+        .enumerate()
+        .filter(|&(i, row)| {
+            row.change != Change::Equal && (i == 0 || rows[i - 1].change == Change::Equal)
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+// The most significant change of each minimap bucket
+fn minimap(rows: &[DiffRow]) -> Vec<Change> {
+    let buckets = rows.len().min(MINIMAP_BUCKETS);
+    (0..buckets)
+        .map(|bucket| {
+            let start = bucket * rows.len() / buckets;
+            let end = ((bucket + 1) * rows.len() / buckets).max(start + 1);
+            rows[start..end]
+                .iter()
+                .map(|row| row.change)
+                .find(|&change| change != Change::Equal)
+                .unwrap_or(Change::Equal)
+        })
+        .collect()
+}
+
+// Abstract class of a register for diffs ignoring register allocation. The
+// stack and instruction pointers keep their names.
+fn register_class(name: &str) -> Option<&'static str> {
+    const LEGACY: [(&str, &str); 4] = [
+        ("rax rbx rcx rdx rsi rdi rbp", "r64"),
+        ("eax ebx ecx edx esi edi ebp", "r32"),
+        ("ax bx cx dx si di bp", "r16"),
+        ("al bl cl dl ah bh ch dh sil dil bpl", "r8"),
+    ];
+    if let Some((_, class)) = LEGACY
+        .iter()
+        .find(|(names, _)| names.split(' ').any(|n| n == name))
+    {
+        return Some(class);
+    }
+
+    let numbered = |prefix: &str| {
+        name.strip_prefix(prefix)
+            .map(|rest| {
+                let digits = rest.trim_end_matches(|c: char| !c.is_ascii_digit());
+                !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+            })
+            .unwrap_or(false)
+    };
+    if numbered("r") {
+        return Some(match name.chars().last() {
+            Some('d') => "r32",
+            Some('w') => "r16",
+            Some('b') => "r8",
+            _ => "r64",
+        });
+    }
+    ["xmm", "ymm", "zmm"]
+        .into_iter()
+        .find(|&prefix| numbered(prefix))
+}
+
+pub fn parse_number(text: &str) -> Option<u64> {
+    let hex = text
+        .strip_suffix('h')
+        .or_else(|| text.strip_prefix("0x"))
+        .unwrap_or(text);
+    u64::from_str_radix(hex, 16).ok()
+}
+
+// Text used to compare instructions, with the enabled normalizations applied
+fn normalized(instruction: &Instruction, settings: &Settings) -> String {
+    if !settings.diff_ignore_registers && !settings.diff_ignore_addresses {
+        return instruction.text();
+    }
+    let mut text = String::new();
+    for (part, kind) in &instruction.format {
+        match kind {
+            FormatterTextKind::Register if settings.diff_ignore_registers => {
+                text.push_str(register_class(part).unwrap_or(part))
+            }
+            FormatterTextKind::Number
+                if settings.diff_ignore_addresses
+                    && instruction.target.is_some()
+                    && parse_number(part) == instruction.target =>
+            {
+                text.push_str("addr")
+            }
+            _ => text.push_str(part),
+        }
+    }
+    if let Some(name) = instruction.relocation_name() {
+        text.push_str(&name);
+    }
+    text
+}
+
+fn change_color(change: Change) -> Color {
+    match change {
+        Change::Equal => Color::TRANSPARENT,
+        Change::Changed => theme().changed,
+        Change::Removed => theme().removed,
+        Change::Added => theme().added,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn pane(
+    rows: im::Vector<(usize, DiffRow)>,
+    instructions: Vec<Instruction>,
+    left: bool,
+    symbol: &Symbol,
+    anchored: Vec<usize>,
+    pending: RwSignal<Option<usize>>,
+    anchor: impl Fn(usize) + Clone + 'static,
+    scroll_y: RwSignal<f64>,
+    density: Density,
+    relative_addresses: bool,
+    show_bytes: bool,
+    registers: RwSignal<RegisterHighlight>,
+    selection: RwSignal<Selection>,
+    history: RwSignal<History>,
+) -> impl View {
+    let object = symbol.object.clone();
+    let relative_to = relative_addresses.then_some(symbol.data.address);
+    let row_height = density.row_height();
+    let list = virtual_list(
+        VirtualListDirection::Vertical,
+        VirtualListItemSize::Fixed(Box::new(move || row_height)),
+        move || rows.clone(),
+        |(i, _)| *i,
+        move |(_, row)| {
+            let index = if left { row.left } else { row.right };
+            let color = change_color(row.change);
+            let content: Box<dyn View> = match index {
+                Some(index) => Box::new(instruction_view(
+                    instructions[index].clone(),
+                    &object,
+                    density,
+                    true,
+                    relative_to,
+                    show_bytes,
+                    None,
+                    registers,
+                    selection,
+                    history,
+                    None,
+                    None,
+                )),
+                None => Box::new(empty().style(move |s| s.height(row_height))),
+            };
+            let is_anchor = index
+                .map(|index| anchored.contains(&index))
+                .unwrap_or(false);
+            let anchor = anchor.clone();
+            stack((content,))
+                .style(move |s| {
+                    let is_pending = left && index.is_some() && pending.get() == index;
+                    s.width_full()
+                        .background(color)
+                        .border_left(3.0)
+                        .border_color(Color::TRANSPARENT)
+                        .apply_if(is_anchor, |s| s.border_color(theme().number))
+                        .apply_if(is_pending, |s| s.border_color(Color::rgb8(200, 140, 40)))
+                })
+                .on_double_click(move |_| {
+                    if let Some(index) = index {
+                        anchor(index);
+                    }
+                    true
+                })
+        },
+    )
+    .style(|s| s.flex_col().width_full());
+
+    scroll(list)
+        .on_scroll(move |rect| {
+            if scroll_y.get_untracked() != rect.y0 {
+                scroll_y.set(rect.y0);
+            }
+        })
+        .on_scroll_to(move || Some(Point::new(0.0, scroll_y.get())))
+        .style(|s| {
+            s.flex_grow(1.0)
+                .flex_basis(0.0)
+                .min_width(0.0)
+                .height_full()
+                .background(theme().code)
+        })
+}
+
+// Indices of the anchored instructions of `left` and `right` in the project
+fn anchors(
+    project: &Project,
+    left: &Symbol,
+    left_instructions: &[Instruction],
+    right: &Symbol,
+    right_instructions: &[Instruction],
+) -> Vec<(usize, usize)> {
+    let position = |symbol: &Symbol, instructions: &[Instruction], offset: u64| {
+        instructions
+            .iter()
+            .position(|i| Some(i.address) == symbol.data.address.checked_add(offset))
+    };
+    project
+        .anchors
+        .iter()
+        .filter(|anchor| anchor.left == left.data.name && anchor.right == right.data.name)
+        .filter_map(|anchor| {
+            Some((
+                position(left, left_instructions, anchor.left_offset)?,
+                position(right, right_instructions, anchor.right_offset)?,
+            ))
+        })
+        .collect()
+}
+
+// Instructions of `symbol` which take part in diffs
+pub fn instructions(symbol: &Symbol, settings: &Settings) -> Option<Vec<Instruction>> {
+    let assembly = symbol.data.assembly(&symbol.object, settings)?;
+    let mut instructions = assembly.instructions.clone();
+    if settings.diff_ignore_padding {
+        instructions.retain(|i| !i.padding);
+    }
+    Some(instructions)
+}
+
+// Diffs two instruction lists the way the diff view does, with normalizations
+// and the anchors saved in the project of the left object
+pub fn diff_instructions(
+    left: &Symbol,
+    left_instructions: &[Instruction],
+    right: &Symbol,
+    right_instructions: &[Instruction],
+    settings: &Settings,
+) -> Vec<DiffRow> {
+    let project = Project::load(&left.object.path);
+    let anchors = anchors(&project, left, left_instructions, right, right_instructions);
+    rows(left_instructions, right_instructions, &anchors, settings)
+}
+
+fn rows(
+    left_instructions: &[Instruction],
+    right_instructions: &[Instruction],
+    anchors: &[(usize, usize)],
+    settings: &Settings,
+) -> Vec<DiffRow> {
+    let left_text: Vec<String> = left_instructions
+        .iter()
+        .map(|i| normalized(i, settings))
+        .collect();
+    let right_text: Vec<String> = right_instructions
+        .iter()
+        .map(|i| normalized(i, settings))
+        .collect();
+    diff_anchored(&left_text, &right_text, anchors)
+}
+
+pub fn diff_view(
+    left: Symbol,
+    right: Symbol,
+    settings: RwSignal<Settings>,
+    selection: RwSignal<Selection>,
+    history: RwSignal<History>,
+) -> Box<dyn View> {
+    let current = settings.get_untracked();
+    if left.data.data().is_none() || right.data.data().is_none() {
+        return Box::new(text("Assembly unavailable").style(|s| s.padding(5.0)));
+    }
+    // Both panes can show another syntax, other columns or offsets into the
+    // functions, which line up across builds, without changing other views
+    let view = overrides::signal(overrides::ViewKey::Diff(left.id(), right.id()));
+
+    // Anchors are stored with the project of the left object
+    let project = create_rw_signal(Project::load(&left.object.path));
+    let pending = create_rw_signal(None);
+
+    let title = format!(
+        "Diff: `{}` and `{}`",
+        left.data.demangled.as_ref().unwrap_or(&left.data.name),
+        right.data.demangled.as_ref().unwrap_or(&right.data.name)
+    );
+
+    let normalizations = stack((
+        text("Ignore:").style(|s| s.padding_left(10.0)),
+        toggle_button(
+            "Registers",
+            move || settings.with(|s| s.diff_ignore_registers),
+            move |_| {
+                settings.update(|s| s.diff_ignore_registers = !s.diff_ignore_registers);
+                true
+            },
+        ),
+        toggle_button(
+            "Padding",
+            move || settings.with(|s| s.diff_ignore_padding),
+            move |_| {
+                settings.update(|s| s.diff_ignore_padding = !s.diff_ignore_padding);
+                true
+            },
+        ),
+        toggle_button(
+            "Addresses",
+            move || settings.with(|s| s.diff_ignore_addresses),
+            move |_| {
+                settings.update(|s| s.diff_ignore_addresses = !s.diff_ignore_addresses);
+                true
+            },
+        ),
+        overrides::bar(
+            view,
+            &current,
+            &[
+                overrides::Override::Syntax,
+                overrides::Override::Bytes,
+                overrides::Override::RelativeAddresses,
+            ],
+        ),
+    ))
+    .style(|s| s.flex_row().items_center());
+
+    let comparison = dyn_container(
+        move || view.get(),
+        move |view| {
+            let settings = view.apply(&current);
+            let (Some(left_instructions), Some(right_instructions)) = (
+                instructions(&left, &settings),
+                instructions(&right, &settings),
+            ) else {
+                return Box::new(text("Assembly unavailable").style(|s| s.padding(5.0)));
+            };
+            let (left, right) = (left.clone(), right.clone());
+            let (left_, right_) = (left.clone(), right.clone());
+            let (left_instructions_, right_instructions_) =
+                (left_instructions.clone(), right_instructions.clone());
+            Box::new(
+                dyn_container(
+                    move || {
+                        project.with(|project| {
+                            anchors(
+                                project,
+                                &left_,
+                                &left_instructions_,
+                                &right_,
+                                &right_instructions_,
+                            )
+                        })
+                    },
+                    move |anchors| {
+                        comparison(
+                            &left,
+                            left_instructions.clone(),
+                            &right,
+                            right_instructions.clone(),
+                            anchors,
+                            &settings,
+                            project,
+                            pending,
+                            selection,
+                            history,
+                        )
+                    },
+                )
+                .style(|s| s.width_full().height_full()),
+            )
+        },
+    )
+    .style(|s| s.width_full().flex_grow(1.0).flex_basis(0.0));
+
+    Box::new(
+        stack((header(title), normalizations, comparison))
+            .style(|s| s.flex_col().width_full().height_full()),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn comparison(
+    left: &Symbol,
+    left_instructions: Vec<Instruction>,
+    right: &Symbol,
+    right_instructions: Vec<Instruction>,
+    anchors: Vec<(usize, usize)>,
+    settings: &Settings,
+    project: RwSignal<Project>,
+    pending: RwSignal<Option<usize>>,
+    selection: RwSignal<Selection>,
+    history: RwSignal<History>,
+) -> Box<dyn View> {
+    let rows = rows(&left_instructions, &right_instructions, &anchors, settings);
+    let hunks = hunks(&rows);
+    let minimap = minimap(&rows);
+    let row_count = rows.len();
+    let rows: im::Vector<_> = rows.into_iter().enumerate().collect();
+
+    let density = settings.density;
+    let row_height = density.row_height();
+    let scroll_y = create_rw_signal(0.0);
+    // Shared by the panes, so registers are highlighted on both sides
+    let registers = create_rw_signal(RegisterHighlight::default());
+    let current_row = move || (scroll_y.get_untracked() / row_height).round() as usize;
+
+    let next_hunks = hunks.clone();
+    let previous_hunks = hunks.clone();
+    let (left_name, right_name) = (left.data.name.clone(), right.data.name.clone());
+    let toolbar = stack((
+        button("Previous Change", move |_| {
+            let current = current_row();
+            if let Some(&row) = previous_hunks.iter().rev().find(|&&row| row < current) {
+                scroll_y.set(row as f64 * row_height);
+            }
+            true
+        }),
+        button("Next Change", move |_| {
+            let current = current_row();
+            if let Some(&row) = next_hunks.iter().find(|&&row| row > current) {
+                scroll_y.set(row as f64 * row_height);
+            }
+            true
+        }),
+        button("Clear Anchors", move |_| {
+            project.update(|project| {
+                project
+                    .anchors
+                    .retain(|anchor| anchor.left != left_name || anchor.right != right_name);
+                project.save();
+            });
+            true
+        }),
+        text(format!(
+            "{} changes, {} anchors. Double-click a left and then a right instruction to anchor them.",
+            hunks.len(),
+            anchors.len()
+        ))
+        .style(|s| s.padding(10.0)),
+    ))
+    .style(|s| s.flex_row().items_center());
+
+    let buckets = minimap.len();
+    let minimap = list(
+        move || minimap.clone().into_iter().enumerate(),
+        |(i, _)| *i,
+        move |(bucket, change)| {
+            empty()
+                .style(move |s| {
+                    s.width_full()
+                        .flex_grow(1.0)
+                        .flex_basis(0.0)
+                        .background(change_color(change))
+                })
+                .on_click(move |_| {
+                    let row = bucket * row_count / buckets.max(1);
+                    scroll_y.set(row as f64 * row_height);
+                    true
+                })
+        },
+    )
+    .style(|s| {
+        s.flex_col()
+            .width(14.0)
+            .height_full()
+            .border_left(0.5)
+            .border_right(0.5)
+            .border_color(theme().border)
+    });
+
+    let anchor_left = move |index| pending.set(Some(index));
+    let (left_symbol, right_symbol) = (left.clone(), right.clone());
+    let (left_addresses, right_addresses): (Vec<u64>, Vec<u64>) = (
+        left_instructions.iter().map(|i| i.address).collect(),
+        right_instructions.iter().map(|i| i.address).collect(),
+    );
+    let anchor_right = move |index: usize| {
+        let Some(left_index) = pending.get_untracked() else {
+            return;
+        };
+        pending.set(None);
+        let anchor = Anchor {
+            left: left_symbol.data.name.clone(),
+            left_offset: left_addresses[left_index] - left_symbol.data.address,
+            right: right_symbol.data.name.clone(),
+            right_offset: right_addresses[index] - right_symbol.data.address,
+        };
+        project.update(|project| {
+            project.anchors.push(anchor);
+            project.save();
+        });
+    };
+
+    let panes = stack((
+        pane(
+            rows.clone(),
+            left_instructions,
+            true,
+            left,
+            anchors.iter().map(|anchor| anchor.0).collect(),
+            pending,
+            anchor_left,
+            scroll_y,
+            density,
+            settings.relative_addresses,
+            settings.show_bytes,
+            registers,
+            selection,
+            history,
+        ),
+        minimap,
+        pane(
+            rows,
+            right_instructions,
+            false,
+            right,
+            anchors.iter().map(|anchor| anchor.1).collect(),
+            pending,
+            anchor_right,
+            scroll_y,
+            density,
+            settings.relative_addresses,
+            settings.show_bytes,
+            registers,
+            selection,
+            history,
+        ),
+    ))
+    .style(|s| s.flex_row().width_full().height_full());
+
+    Box::new(stack((toolbar, panes)).style(|s| s.flex_col().width_full().height_full()))
+}
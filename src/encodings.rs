@@ -0,0 +1,208 @@
+use floem::{
+    view::View,
+    views::{list, stack, text, Decorators},
+};
+use iced_x86::{
+    Code, Decoder, DecoderOptions, Encoder, Formatter, Instruction, IntelFormatter,
+    OpCodeOperandKind, OpKind, Register,
+};
+
+use crate::{
+    theme::{font, theme},
+    SymbolData,
+};
+
+// An encoding which decodes to the same instruction
+#[derive(Clone)]
+pub struct Encoding {
+    pub bytes: Vec<u8>,
+    pub code: Code,
+    // Set for the encoding used in the file
+    pub current: bool,
+}
+
+fn text_of(instruction: &Instruction) -> String {
+    let mut formatter = IntelFormatter::new();
+    // Short and near branches to the same target are the same instruction
+    formatter.options_mut().set_show_branch_size(false);
+    let mut out = String::new();
+    formatter.format(instruction, &mut out);
+    out
+}
+
+fn immediate_kind(kind: OpCodeOperandKind) -> Option<OpKind> {
+    Some(match kind {
+        OpCodeOperandKind::imm8 | OpCodeOperandKind::imm8_const_1 => OpKind::Immediate8,
+        OpCodeOperandKind::imm8sex16 => OpKind::Immediate8to16,
+        OpCodeOperandKind::imm8sex32 => OpKind::Immediate8to32,
+        OpCodeOperandKind::imm8sex64 => OpKind::Immediate8to64,
+        OpCodeOperandKind::imm16 => OpKind::Immediate16,
+        OpCodeOperandKind::imm32 => OpKind::Immediate32,
+        OpCodeOperandKind::imm32sex64 => OpKind::Immediate32to64,
+        OpCodeOperandKind::imm64 => OpKind::Immediate64,
+        _ => return None,
+    })
+}
+
+fn is_immediate(kind: OpKind) -> bool {
+    matches!(
+        kind,
+        OpKind::Immediate8
+            | OpKind::Immediate16
+            | OpKind::Immediate32
+            | OpKind::Immediate64
+            | OpKind::Immediate8to16
+            | OpKind::Immediate8to32
+            | OpKind::Immediate8to64
+            | OpKind::Immediate32to64
+    )
+}
+
+// `instruction` as `code`, with its immediates converted to the widths of the
+// new form
+fn with_code(instruction: &Instruction, code: Code) -> Option<Instruction> {
+    let mut candidate = *instruction;
+    candidate.set_code(code);
+    let op_code = candidate.op_code();
+    if op_code.op_count() != instruction.op_count() {
+        return None;
+    }
+    for i in 0..instruction.op_count() {
+        if !is_immediate(instruction.op_kind(i)) {
+            continue;
+        }
+        let kind = immediate_kind(op_code.op_kind(i))?;
+        candidate.set_op_kind(i, kind);
+        candidate.set_immediate_u64(i, instruction.immediate(i));
+    }
+    Some(candidate)
+}
+
+// Whether `bytes` decode back to an instruction printed as `expected`
+fn decodes_to(bytes: &[u8], ip: u64, bitness: u32, expected: &str) -> bool {
+    let mut decoder = Decoder::with_ip(bitness, bytes, ip, DecoderOptions::NONE);
+    let decoded = decoder.decode();
+    !decoded.is_invalid() && decoded.len() == bytes.len() && text_of(&decoded) == expected
+}
+
+// The encodings of `instruction` using other forms of the same mnemonic and
+// other displacement sizes, shortest first
+pub fn alternatives(instruction: &Instruction, bytes: &[u8], bitness: u32) -> Vec<Encoding> {
+    let expected = text_of(instruction);
+    let mut encodings = vec![Encoding {
+        bytes: bytes.to_vec(),
+        code: instruction.code(),
+        current: true,
+    }];
+    let has_memory = (0..instruction.op_count()).any(|i| instruction.op_kind(i) == OpKind::Memory);
+    // The full displacement size depends on the address size
+    let address_size = match instruction.memory_base() {
+        Register::None => instruction.memory_index().size(),
+        base => base.size(),
+    };
+    let full = match address_size {
+        8 => 8,
+        2 => 2,
+        _ => 4,
+    };
+    let displacements = if has_memory {
+        vec![0, 1, full]
+    } else {
+        vec![0]
+    };
+
+    let codes = Code::values().filter(|code| code.mnemonic() == instruction.mnemonic());
+    for code in codes {
+        let Some(mut candidate) = with_code(instruction, code) else {
+            continue;
+        };
+        for &size in &displacements {
+            if has_memory {
+                candidate.set_memory_displ_size(size);
+            }
+            let mut encoder = Encoder::new(bitness);
+            if encoder.encode(&candidate, candidate.ip()).is_err() {
+                continue;
+            }
+            let encoded = encoder.take_buffer();
+            if encodings.iter().any(|encoding| encoding.bytes == encoded)
+                || !decodes_to(&encoded, candidate.ip(), bitness, &expected)
+            {
+                continue;
+            }
+            encodings.push(Encoding {
+                bytes: encoded,
+                code,
+                current: false,
+            });
+        }
+    }
+    encodings.sort_by_key(|encoding| (encoding.bytes.len(), !encoding.current));
+    encodings
+}
+
+pub fn encodings_view(symbol: &SymbolData, address: u64) -> Box<dyn View> {
+    if symbol.arm_modes().is_some() {
+        return Box::new(
+            text("Other encodings are only listed for x86 code").style(|s| s.padding(5.0)),
+        );
+    }
+    let bitness = symbol.bitness();
+    let found = symbol.data().and_then(|data| {
+        let mut decoder = Decoder::with_ip(bitness, data, symbol.address, DecoderOptions::NONE);
+        decoder
+            .iter()
+            .find(|i| i.ip() == address)
+            .map(|instruction| {
+                let start = (address - symbol.address) as usize;
+                (instruction, data[start..start + instruction.len()].to_vec())
+            })
+    });
+    let Some((instruction, bytes)) = found else {
+        return Box::new(text("No instruction at this address").style(|s| s.padding(5.0)));
+    };
+
+    // Relocated operands are zero until linked, so their sizes may not hold
+    let relocated = symbol.section.as_ref().is_some_and(|section| {
+        (address..address + bytes.len() as u64).any(|a| section.relocations.contains_key(&a))
+    });
+    let title = format!(
+        "Encodings of `{}`{}",
+        text_of(&instruction),
+        if relocated {
+            ", which has a relocated operand"
+        } else {
+            ""
+        }
+    );
+
+    let encodings = alternatives(&instruction, &bytes, bitness);
+    let rows = list(
+        move || encodings.clone().into_iter().enumerate(),
+        |(i, _)| *i,
+        |(_, encoding)| {
+            let hex: Vec<String> = encoding
+                .bytes
+                .iter()
+                .map(|byte| format!("{:02X}", byte))
+                .collect();
+            stack((
+                text(hex.join(" ")).style(|s| {
+                    s.width(260)
+                        .font_family(font().family)
+                        .color(theme().number)
+                }),
+                text(format!("{} bytes", encoding.bytes.len())).style(|s| s.width(70)),
+                text(format!("{:?}", encoding.code)).style(|s| s.color(theme().dim)),
+                text(if encoding.current { "  (current)" } else { "" }),
+            ))
+            .style(|s| s.flex_row().padding_horiz(5).padding_vert(2))
+        },
+    )
+    .style(|s| s.flex_col());
+
+    Box::new(
+        stack((text(title).style(|s| s.padding(5.0)), rows))
+            .style(|s| s.flex_col().width_full().padding_bottom(5)),
+    )
+}
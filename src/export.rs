@@ -0,0 +1,292 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+};
+
+use floem::reactive::RwSignal;
+use rfd::AsyncFileDialog;
+
+use crate::{
+    dialog,
+    diff::parse_number,
+    encoding_heat, report_error,
+    settings::{EncodingHeat, Settings, Syntax},
+    AddressRange, Instruction, ObjectList, Symbol,
+};
+
+// Longest x86 instruction, which the bytes column is sized for
+const MAX_INSTRUCTION_BYTES: usize = 15;
+
+// Instruction text with the relocation target named where its operand was,
+// or the branch target replaced by a local label. Names which aren't demangled
+// are kept as their exact bytes.
+fn operands(instruction: &Instruction, label: Option<String>) -> Vec<u8> {
+    let name = instruction.relocation.as_ref().map(|relocation| {
+        let mut name = relocation
+            .demangled
+            .as_ref()
+            .map(|demangled| demangled.as_bytes())
+            .unwrap_or(relocation.name_bytes())
+            .to_vec();
+        name.extend_from_slice(instruction.offset_suffix().as_bytes());
+        name
+    });
+    let slot = match &name {
+        Some(_) => instruction
+            .format
+            .iter()
+            .rposition(|(part, _)| part.is_empty()),
+        None => label.as_ref().and_then(|_| {
+            instruction
+                .format
+                .iter()
+                .rposition(|(part, _)| parse_number(part) == instruction.target)
+        }),
+    };
+    let mut text = Vec::new();
+    for (i, (part, _)) in instruction.format.iter().enumerate() {
+        match (Some(i) == slot, &name, &label) {
+            (true, Some(name), _) => text.extend_from_slice(name),
+            (true, None, Some(label)) => text.extend_from_slice(label.as_bytes()),
+            _ => text.extend_from_slice(part.as_bytes()),
+        }
+    }
+    if slot.is_none() {
+        if let Some(name) = &name {
+            text.extend_from_slice(name);
+        }
+    }
+    while text.last().is_some_and(u8::is_ascii_whitespace) {
+        text.pop();
+    }
+    text
+}
+
+// The function as Compiler Explorer shows filtered output: Intel syntax under
+// a demangled label, without addresses or padding, and with branches within the
+// function going to local labels. Notes are kept as comments. Only the
+// instructions in `range` are included if it's given.
+pub fn compiler_explorer(
+    symbol: &Symbol,
+    settings: &Settings,
+    range: Option<AddressRange>,
+) -> Option<String> {
+    let settings = Settings {
+        syntax: Syntax::Intel,
+        collapse_padding: true,
+        ..settings.clone()
+    };
+    let assembly = symbol.data.assembly(&symbol.object, &settings)?;
+    let instructions: Vec<&Instruction> = assembly
+        .instructions
+        .iter()
+        .filter(|i| match range {
+            Some(range) => range.contains(i.address),
+            None => true,
+        })
+        .collect();
+
+    let targets: BTreeSet<u64> = instructions
+        .iter()
+        .filter_map(|i| i.local_target())
+        .filter(|target| instructions.iter().any(|i| i.address == *target))
+        .collect();
+    let label = |address: u64| {
+        targets
+            .iter()
+            .position(|target| *target == address)
+            .map(|n| format!(".L{}", n))
+    };
+
+    let name = symbol.data.demangled.as_ref().unwrap_or(&symbol.data.name);
+    let mut out = format!("{}:\n", name);
+    for instruction in &instructions {
+        if let Some(label) = label(instruction.address) {
+            out.push_str(&format!("{}:\n", label));
+        }
+        if instruction.padding {
+            continue;
+        }
+        let target = instruction.local_target().and_then(label);
+        out.push_str("        ");
+        out.push_str(&String::from_utf8_lossy(&operands(instruction, target)));
+        if !instruction.notes.is_empty() {
+            out.push_str(&format!("  # {}", instruction.notes.join("; ")));
+        }
+        out.push('\n');
+    }
+    Some(out)
+}
+
+// The overlays of the assembly view written as columns, in order
+fn overlay_columns(settings: &Settings, samples: bool) -> Vec<String> {
+    let mut columns = Vec::new();
+    if samples {
+        columns.push("share of the samples".to_owned());
+    }
+    if settings.show_uops {
+        columns.push(format!("uops on {}", settings.target.label()));
+    }
+    if settings.encoding_heat != EncodingHeat::Off {
+        columns.push(format!(
+            "heat by {}",
+            settings.encoding_heat.label().to_lowercase()
+        ));
+    }
+    columns
+}
+
+// The function as shown in the assembly view, with addresses, the encoded
+// bytes if they're shown and relocations named. Markdown listings are put in a
+// code block under the name of the function. Names are written as their exact
+// bytes, so the listing may not be valid UTF-8.
+//
+// With `overlays` the listing also carries what the view adds to it: local
+// labels, source lines, uop counts, encoding heat, misprediction hints and the
+// share of the profile samples, which are given by address.
+pub fn listing(
+    symbol: &Symbol,
+    settings: &Settings,
+    range: Option<AddressRange>,
+    markdown: bool,
+    overlays: Option<&HashMap<u64, u64>>,
+) -> Option<Vec<u8>> {
+    let samples = overlays.filter(|samples| !samples.is_empty());
+    let samples_total: u64 = samples.map_or(0, |samples| samples.values().sum());
+    let overlays = overlays.is_some();
+    let assembly = symbol.data.assembly(&symbol.object, settings)?;
+    let instructions: Vec<&Instruction> = assembly
+        .instructions
+        .iter()
+        .filter(|i| match range {
+            Some(range) => range.contains(i.address),
+            None => true,
+        })
+        .collect();
+    let bytes_width = instructions
+        .iter()
+        .map(|i| i.bytes.len().min(MAX_INSTRUCTION_BYTES))
+        .max()
+        .unwrap_or(0)
+        * 3
+        + 1;
+
+    let name = match &symbol.data.demangled {
+        Some(demangled) => demangled.as_bytes(),
+        None => symbol.data.name_bytes(),
+    };
+    let mut out = Vec::new();
+    if markdown {
+        out.push(b'`');
+        out.extend_from_slice(name);
+        out.extend_from_slice(b"`\n\n```asm\n");
+    } else {
+        out.extend_from_slice(name);
+        out.extend_from_slice(b":\n");
+    }
+
+    let mut labels = if overlays {
+        assembly.local_labels()
+    } else {
+        HashMap::new()
+    };
+    labels.retain(|address, _| instructions.iter().any(|i| i.address == *address));
+    let lines = symbol
+        .object
+        .lines
+        .as_ref()
+        .filter(|_| overlays && settings.show_source);
+    let columns = if overlays {
+        overlay_columns(settings, samples.is_some())
+    } else {
+        Vec::new()
+    };
+    if !columns.is_empty() {
+        out.extend_from_slice(format!("# After the address: {}\n", columns.join(", ")).as_bytes());
+    }
+
+    for instruction in &instructions {
+        if let Some(label) = labels.get(&instruction.address) {
+            out.extend_from_slice(format!("{}:\n", label).as_bytes());
+        }
+        if let (Some(lines), Some(location)) = (lines, instruction.source) {
+            out.extend_from_slice(format!("# {}\n", lines.describe(location)).as_bytes());
+        }
+        out.extend_from_slice(format!("{:016X}  ", instruction.address).as_bytes());
+        if let Some(samples) = samples {
+            let share = samples
+                .get(&instruction.address)
+                .map(|&count| format!("{:.1}%", count as f64 * 100.0 / samples_total as f64));
+            out.extend_from_slice(format!("{:>6}  ", share.unwrap_or_default()).as_bytes());
+        }
+        if overlays && settings.show_uops {
+            let uops = instruction
+                .uops
+                .map_or("?".to_owned(), |uops| uops.to_string());
+            out.extend_from_slice(format!("{:>3}  ", uops).as_bytes());
+        }
+        if overlays && settings.encoding_heat != EncodingHeat::Off {
+            let heat = encoding_heat(settings.encoding_heat, instruction);
+            out.extend_from_slice(format!("{:>4}  ", format!("{:.0}%", heat * 100.0)).as_bytes());
+        }
+        if settings.show_bytes {
+            let bytes: Vec<String> = instruction
+                .bytes
+                .iter()
+                .map(|byte| format!("{:02X}", byte))
+                .collect();
+            let bytes = format!("{:width$}", bytes.join(" "), width = bytes_width);
+            out.extend_from_slice(bytes.as_bytes());
+        }
+        let label = instruction
+            .local_target()
+            .and_then(|target| labels.get(&target).cloned());
+        out.extend_from_slice(&operands(instruction, label));
+        let mut notes = instruction.notes.clone();
+        if let Some(hint) = instruction.hint.filter(|_| overlays) {
+            notes.push(hint.to_owned());
+        }
+        if !notes.is_empty() {
+            out.extend_from_slice(format!("  # {}", notes.join("; ")).as_bytes());
+        }
+        out.push(b'\n');
+    }
+    if markdown {
+        out.extend_from_slice(b"```\n");
+    }
+    Some(out)
+}
+
+// Asks where to save the listing of `symbol`. Files ending in `.md` are
+// written as Markdown.
+pub fn save_listing(
+    objects: RwSignal<ObjectList>,
+    symbol: &Symbol,
+    settings: &Settings,
+    range: Option<AddressRange>,
+    samples: Option<&HashMap<u64, u64>>,
+) {
+    let dialog = AsyncFileDialog::new()
+        .set_title("Export listing...")
+        .set_file_name(format!("{}.txt", symbol.data.name))
+        .add_filter("Text", &["txt"])
+        .add_filter("Markdown", &["md"]);
+    let symbol = symbol.clone();
+    let settings = settings.clone();
+    let samples = samples.cloned();
+    dialog::save_file(dialog, move |path| {
+        let markdown = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("md"));
+        let Some(text) = listing(&symbol, &settings, range, markdown, samples.as_ref()) else {
+            report_error(
+                objects,
+                format!("`{}` has no instructions", symbol.data.name),
+            );
+            return;
+        };
+        if let Err(error) = fs::write(&path, text) {
+            report_error(objects, format!("Writing `{}`: {}", path.display(), error));
+        }
+    });
+}
@@ -0,0 +1,106 @@
+use floem::{
+    reactive::RwSignal,
+    style::TextOverflow,
+    view::View,
+    views::{list, stack, text, Decorators},
+};
+
+use crate::{
+    header, history, history::History, project::Project, theme::theme, xrefs, ObjectList,
+    Selection, Symbol,
+};
+
+// Favorites are read from the project files of the loaded objects. This is
+// bumped when they change, so views listing them are rebuilt.
+pub type Revision = RwSignal<u64>;
+
+pub fn contains(symbol: &Symbol) -> bool {
+    Project::load(&symbol.object.path)
+        .favorites
+        .contains(&symbol.data.name)
+}
+
+pub fn toggle(symbol: &Symbol, revision: Revision) {
+    let mut project = Project::load(&symbol.object.path);
+    let name = &symbol.data.name;
+    match project
+        .favorites
+        .iter()
+        .position(|favorite| favorite == name)
+    {
+        Some(i) => {
+            project.favorites.remove(i);
+        }
+        None => project.favorites.push(name.clone()),
+    }
+    project.save();
+    revision.update(|revision| *revision += 1);
+}
+
+// The pinned symbols of every loaded object, in the order they were pinned
+fn symbols(list: &ObjectList) -> Vec<Symbol> {
+    let mut symbols = Vec::new();
+    for object in &list.objects {
+        for name in Project::load(&object.path).favorites {
+            if let Some(data) = xrefs::find(object, &name) {
+                symbols.push(Symbol {
+                    object: object.clone(),
+                    data,
+                });
+            }
+        }
+    }
+    symbols
+}
+
+// Pinned symbols shown above the objects, regardless of the symbol filter
+pub fn favorites_panel(
+    objects: RwSignal<ObjectList>,
+    revision: Revision,
+    selection: RwSignal<Selection>,
+    history: RwSignal<History>,
+) -> impl View {
+    let favorites = move || {
+        revision.track();
+        objects.with(symbols)
+    };
+    let rows = list(
+        move || favorites().into_iter().enumerate(),
+        |(i, symbol)| (*i, symbol.data.name.clone()),
+        move |(_, symbol)| {
+            let unpinned = symbol.clone();
+            let name = symbol
+                .data
+                .demangled
+                .clone()
+                .unwrap_or_else(|| symbol.data.name.clone());
+            stack((
+                text(name).style(|s| {
+                    s.flex_grow(1.0)
+                        .min_width(0.0)
+                        .text_overflow(TextOverflow::Clip)
+                }),
+                text("×")
+                    .style(|s| s.padding_horiz(4))
+                    .hover_style(|s| s.color(theme().error))
+                    .on_click(move |_| {
+                        toggle(&unpinned, revision);
+                        true
+                    }),
+            ))
+            .style(|s| s.flex_row().items_center().padding(5).width_full())
+            .hover_style(|s| s.background(theme().button_hover))
+            .on_click(move |_| {
+                history::jump(selection, history, Selection::Symbol(symbol.clone()));
+                true
+            })
+        },
+    )
+    .style(|s| s.flex_col().width_full());
+
+    stack((header("Favorites"), rows)).style(move |s| {
+        s.flex_col()
+            .width_full()
+            .apply_if(favorites().is_empty(), |s| s.hide())
+    })
+}
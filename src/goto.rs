@@ -0,0 +1,122 @@
+use std::cell::Cell;
+
+use floem::{
+    event::{Event, EventListener},
+    id::Id,
+    keyboard::{Key, NamedKey},
+    reactive::{create_rw_signal, RwSignal},
+    view::View,
+    views::{label, stack, text, text_input, Decorators},
+};
+
+use crate::{history, history::History, theme::theme, Selection, Symbol};
+
+thread_local! {
+    // The address input of the assembly view being shown
+    static GOTO_BOX: Cell<Option<Id>> = const { Cell::new(None) };
+}
+
+pub fn focus_goto_box() {
+    if let Some(id) = GOTO_BOX.with(Cell::get) {
+        id.request_focus();
+    }
+}
+
+fn hex(text: &str) -> Option<u64> {
+    let digits = text
+        .strip_prefix("0x")
+        .or_else(|| text.strip_prefix("0X"))
+        .unwrap_or(text);
+    u64::from_str_radix(digits, 16).ok()
+}
+
+// The address `query` refers to, either absolute or as `+offset` from the
+// start of `symbol`, both in hex
+fn parse(query: &str, symbol: &Symbol) -> Option<u64> {
+    let query = query.trim();
+    match query.strip_prefix('+') {
+        Some(offset) => symbol.data.address.checked_add(hex(offset.trim())?),
+        None => hex(query),
+    }
+}
+
+// The symbol of the object of `symbol` which `address` is in, preferring
+// `symbol` itself
+fn containing(symbol: &Symbol, address: u64) -> Option<Symbol> {
+    let within = |symbol: &Symbol| {
+        let size = symbol.data.extent().unwrap_or(symbol.data.size).max(1);
+        address
+            .checked_sub(symbol.data.address)
+            .is_some_and(|offset| offset < size)
+    };
+    if within(symbol) {
+        return Some(symbol.clone());
+    }
+    let object = &symbol.object;
+    object
+        .sections
+        .iter()
+        .filter(|section| {
+            (section.address..section.address.saturating_add(section.size)).contains(&address)
+        })
+        .find_map(|section| object.symbol_containing(section.index, address))
+        .map(|data| Symbol {
+            object: object.clone(),
+            data,
+        })
+}
+
+// An input for an address to show. Addresses in `symbol` are sent to `goto`
+// for its assembly view to scroll to, others open the symbol they're in.
+pub fn goto_bar(
+    symbol: &Symbol,
+    goto: RwSignal<Option<u64>>,
+    selection: RwSignal<Selection>,
+    history: RwSignal<History>,
+) -> impl View {
+    let symbol = symbol.clone();
+    let query = create_rw_signal(String::new());
+    let error = create_rw_signal(false);
+
+    let go = move || {
+        let target = parse(&query.get_untracked(), &symbol)
+            .and_then(|address| Some((address, containing(&symbol, address)?)));
+        error.set(target.is_none());
+        match target {
+            Some((address, found)) if found.id() == symbol.id() => goto.set(Some(address)),
+            Some((address, found)) => history::jump_into(selection, history, found, address),
+            None => {}
+        }
+    };
+
+    let input = text_input(query)
+        .style(|s| {
+            s.width(140)
+                .padding(5)
+                .margin(4)
+                .border(0.5)
+                .border_radius(3)
+                .border_color(theme().button_border)
+        })
+        .on_event(EventListener::KeyDown, move |e| {
+            if let Event::KeyDown(e) = e {
+                if e.key.logical_key == Key::Named(NamedKey::Enter) {
+                    go();
+                    return true;
+                }
+            }
+            false
+        });
+    GOTO_BOX.with(|goto_box| goto_box.set(Some(input.id())));
+
+    stack((
+        text("Go to:").style(|s| s.padding_left(6.0)),
+        input,
+        label(|| "not found").style(move |s| {
+            s.padding_right(6.0)
+                .color(theme().error)
+                .apply_if(!error.get(), |s| s.hide())
+        }),
+    ))
+    .style(|s| s.flex_row().items_center())
+}
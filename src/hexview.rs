@@ -0,0 +1,360 @@
+use std::{collections::HashSet, sync::Arc};
+
+use floem::{
+    event::{Event, EventListener},
+    keyboard::{Key, NamedKey},
+    kurbo::Point,
+    reactive::{create_rw_signal, RwSignal},
+    view::View,
+    views::{
+        dyn_container, label, list, scroll, stack, text, text_input, virtual_list, Decorators,
+        VirtualListDirection, VirtualListItemSize,
+    },
+};
+
+use crate::{
+    button, format_address, header,
+    theme::{font, theme},
+    units, DataSymbol, Object, Section,
+};
+
+const ROW_BYTES: u64 = 16;
+
+const ROW_HEIGHT: f64 = 20.0;
+
+// Bytes shown at a time, so large sections are only paged in from the file as viewed
+const PAGE_BYTES: u64 = 64 * 1024;
+
+// Part of a section shown in the dump
+#[derive(Clone)]
+struct Dump {
+    section: Arc<Section>,
+    title: String,
+    offset: u64,
+    size: u64,
+    // What addresses are shown relative to
+    relative_to: Option<u64>,
+}
+
+impl Dump {
+    fn of_section(section: &Arc<Section>, relative_to: Option<u64>) -> Self {
+        Dump {
+            section: section.clone(),
+            title: section.name.clone(),
+            offset: 0,
+            size: section.size,
+            relative_to,
+        }
+    }
+
+    // Symbols without a size extend to the end of their section
+    fn of_symbol(object: &Object, symbol: &DataSymbol, relative_to: Option<u64>) -> Option<Self> {
+        let section = object
+            .sections
+            .iter()
+            .find(|section| section.index == symbol.section)?;
+        let offset = symbol
+            .address
+            .checked_sub(section.address)
+            .filter(|&offset| offset < section.size)?;
+        let rest = section.size - offset;
+        Some(Dump {
+            section: section.clone(),
+            title: symbol.name.clone(),
+            offset,
+            size: if symbol.size == 0 {
+                rest
+            } else {
+                symbol.size.min(rest)
+            },
+            relative_to,
+        })
+    }
+}
+
+// Offsets in `section` covered by relocations, between `start` and `end`
+fn relocated(section: &Section, start: u64, end: u64) -> HashSet<u64> {
+    let mut offsets = HashSet::new();
+    for (&offset, relocation) in section.relocations.iter() {
+        let len = (relocation.size() as u64 / 8).max(1);
+        for offset in offset.max(start)..(offset + len).min(end) {
+            offsets.insert(offset);
+        }
+    }
+    offsets
+}
+
+// Reads `len` bytes at `offset` into `section`
+fn read(section: &Section, offset: u64, len: u64) -> Vec<u8> {
+    let end = offset.saturating_add(len).min(section.size);
+    if offset >= end {
+        return Vec::new();
+    }
+    section
+        .data
+        .get(offset as usize..end as usize)
+        .unwrap_or_default()
+        .to_vec()
+}
+
+fn hex(text: &str) -> Option<u64> {
+    let digits = text
+        .strip_prefix("0x")
+        .or_else(|| text.strip_prefix("0X"))
+        .unwrap_or(text);
+    u64::from_str_radix(digits, 16).ok()
+}
+
+// The offset into `dump` which `query` refers to, either an absolute address
+// or `+offset` from the start of the dump, both in hex
+fn parse(query: &str, dump: &Dump) -> Option<u64> {
+    let query = query.trim();
+    let offset = match query.strip_prefix('+') {
+        Some(offset) => hex(offset.trim())?,
+        None => hex(query)?.checked_sub(dump.section.address + dump.offset)?,
+    };
+    (offset < dump.size).then_some(offset)
+}
+
+fn ascii(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&byte| {
+            if byte.is_ascii_graphic() || byte == b' ' {
+                byte as char
+            } else {
+                '.'
+            }
+        })
+        .collect()
+}
+
+// `start` is the offset of the row, which is highlighted when jumped into
+fn row_view(
+    address: String,
+    bytes: Vec<u8>,
+    relocated: Vec<bool>,
+    start: u64,
+    jump: RwSignal<Option<u64>>,
+) -> impl View {
+    let len = bytes.len() as u64;
+    let ascii = ascii(&bytes);
+    let cells = list(
+        move || bytes.clone().into_iter().zip(relocated.clone()).enumerate(),
+        |(i, _)| *i,
+        |(i, (byte, relocated))| {
+            text(format!("{:02X}", byte)).style(move |s| {
+                s.width(22)
+                    .padding_left(2)
+                    .apply_if(i == 8, |s| s.margin_left(8))
+                    .apply_if(relocated, |s| s.background(theme().changed))
+            })
+        },
+    )
+    .style(|s| s.flex_row().width(ROW_BYTES as f64 * 22.0 + 16.0));
+    stack((
+        text(address).style(|s| s.width(150).color(theme().address)),
+        cells,
+        text(ascii),
+    ))
+    .style(move |s| {
+        s.flex_row().height(ROW_HEIGHT).padding_horiz(5).apply_if(
+            jump.get()
+                .is_some_and(|offset| (start..start + len).contains(&offset)),
+            |s| s.background(theme().selection),
+        )
+    })
+}
+
+fn page_view(dump: &Dump, page: u64, jump: RwSignal<Option<u64>>) -> Box<dyn View> {
+    let offset = dump.offset + page * PAGE_BYTES;
+    let end = (offset + PAGE_BYTES).min(dump.offset + dump.size);
+    let data = read(&dump.section, offset, end.saturating_sub(offset));
+    let relocated = relocated(&dump.section, offset, end);
+    let address = dump.section.address + offset;
+    let rows: im::Vector<_> = data
+        .chunks(ROW_BYTES as usize)
+        .enumerate()
+        .map(|(i, bytes)| {
+            let start = offset + i as u64 * ROW_BYTES;
+            let marks = (start..start + bytes.len() as u64)
+                .map(|offset| relocated.contains(&offset))
+                .collect();
+            let address = format_address(address + i as u64 * ROW_BYTES, dump.relative_to);
+            (address, bytes.to_vec(), marks, start - dump.offset)
+        })
+        .enumerate()
+        .collect();
+    let rows = virtual_list(
+        VirtualListDirection::Vertical,
+        VirtualListItemSize::Fixed(Box::new(|| ROW_HEIGHT)),
+        move || rows.clone(),
+        |(i, _)| *i,
+        move |(_, (address, bytes, marks, start))| row_view(address, bytes, marks, start, jump),
+    )
+    .style(|s| s.flex_col().font_family(font().family));
+    // Rows jumped to on this page are scrolled into view
+    let first = page * PAGE_BYTES;
+    let scroll_to = move || {
+        let offset = jump.get()?.checked_sub(first).filter(|&o| o < PAGE_BYTES)?;
+        Some(Point::new(0.0, (offset / ROW_BYTES) as f64 * ROW_HEIGHT))
+    };
+    Box::new(scroll(rows).on_scroll_to(scroll_to).style(|s| {
+        s.width_full()
+            .flex_grow(1.0)
+            .flex_basis(0.0)
+            .background(theme().code)
+    }))
+}
+
+fn dump_view(dump: Dump) -> Box<dyn View> {
+    let pages = dump.size.div_ceil(PAGE_BYTES).max(1);
+    let page = create_rw_signal(0u64);
+    // Offset into the dump which was jumped to
+    let jump = create_rw_signal(None);
+    let query = create_rw_signal(String::new());
+    let error = create_rw_signal(false);
+
+    let go = {
+        let dump = dump.clone();
+        move || {
+            let offset = parse(&query.get_untracked(), &dump);
+            error.set(offset.is_none());
+            if let Some(offset) = offset {
+                page.set(offset / PAGE_BYTES);
+                jump.set(Some(offset));
+            }
+        }
+    };
+    let input = text_input(query)
+        .style(|s| {
+            s.width(140)
+                .padding(5)
+                .margin(4)
+                .border(0.5)
+                .border_radius(3)
+                .border_color(theme().button_border)
+        })
+        .on_event(EventListener::KeyDown, move |e| {
+            if let Event::KeyDown(e) = e {
+                if e.key.logical_key == Key::Named(NamedKey::Enter) {
+                    go();
+                    return true;
+                }
+            }
+            false
+        });
+
+    let toolbar = stack((
+        button("Previous", move |_| {
+            page.update(|page| *page = page.saturating_sub(1));
+            true
+        }),
+        button("Next", move |_| {
+            page.update(|page| *page = (*page + 1).min(pages - 1));
+            true
+        }),
+        stack((
+            text(format!("`{}`: ", dump.title)),
+            units::size_text(dump.size),
+        ))
+        .style(|s| s.flex_row().padding(5.0)),
+        label(move || format!("Page {} of {}", page.get() + 1, pages)).style(|s| s.padding(5.0)),
+        text("Go to:").style(|s| s.padding_left(6.0)),
+        input,
+        label(|| "not in the dump").style(move |s| {
+            s.padding_right(6.0)
+                .color(theme().error)
+                .apply_if(!error.get(), |s| s.hide())
+        }),
+    ))
+    .style(|s| s.flex_row().items_center());
+
+    let content = dyn_container(move || page.get(), move |page| page_view(&dump, page, jump))
+        .style(|s| s.width_full().flex_grow(1.0).flex_basis(0.0));
+
+    Box::new(stack((toolbar, content)).style(|s| s.flex_col().width_full().height_full()))
+}
+
+// A dump of a whole section
+pub fn section_dump(section: &Arc<Section>, relative_to: Option<u64>) -> Box<dyn View> {
+    dump_view(Dump::of_section(section, relative_to))
+}
+
+fn dump_list(dumps: Vec<Dump>, first: usize, selected: RwSignal<Option<usize>>) -> impl View {
+    let dumps: im::Vector<_> = dumps.into_iter().enumerate().collect();
+    virtual_list(
+        VirtualListDirection::Vertical,
+        VirtualListItemSize::Fixed(Box::new(|| 28.0)),
+        move || dumps.clone(),
+        |(i, _)| *i,
+        move |(i, dump)| {
+            let i = first + i;
+            stack((
+                text(dump.title.clone()).style(|s| s.flex_grow(1.0).min_width(0.0)),
+                units::size_text(dump.size).style(|s| s.padding_left(10)),
+            ))
+            .style(move |s| {
+                s.flex_row()
+                    .padding(5)
+                    .height(28.0)
+                    .width_full()
+                    .apply_if(selected.get() == Some(i), |s| {
+                        s.background(theme().selection)
+                    })
+            })
+            .hover_style(|s| s.background(theme().hover))
+            .on_click(move |_| {
+                selected.set(Some(i));
+                true
+            })
+        },
+    )
+    .style(|s| s.flex_col().width_full())
+}
+
+pub fn hex_tab(object: &Arc<Object>, relative_to: Option<u64>) -> Box<dyn View> {
+    let mut sections = object.sections.clone();
+    sections.sort_by_key(|section| (section.address, section.index.0));
+    let sections: Vec<Dump> = sections
+        .iter()
+        .map(|section| Dump::of_section(section, relative_to))
+        .collect();
+    let symbols: Vec<Dump> = object
+        .data_symbols
+        .iter()
+        .filter_map(|symbol| Dump::of_symbol(object, symbol, relative_to))
+        .collect();
+    let selected: RwSignal<Option<usize>> = create_rw_signal(None);
+
+    let lists = stack((
+        header("Sections"),
+        dump_list(sections.clone(), 0, selected),
+        header("Data Symbols"),
+        dump_list(symbols.clone(), sections.len(), selected),
+    ))
+    .style(|s| s.flex_col().width_full());
+
+    let dumps: Vec<Dump> = sections.into_iter().chain(symbols).collect();
+    let content = dyn_container(
+        move || selected.get(),
+        move |selected| match selected.and_then(|i| dumps.get(i)) {
+            Some(dump) => dump_view(dump.clone()),
+            None => Box::new(text("Select a section or data symbol").style(|s| s.padding(5.0))),
+        },
+    )
+    .style(|s| s.flex_grow(1.0).flex_basis(0.0).height_full());
+
+    Box::new(
+        stack((
+            scroll(lists).style(|s| {
+                s.width(260)
+                    .height_full()
+                    .border_right(0.5)
+                    .border_color(theme().border)
+            }),
+            content,
+        ))
+        .style(|s| s.flex_row().width_full().height_full()),
+    )
+}
@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use floem::reactive::RwSignal;
+
+use crate::{Object, Selection, Symbol};
+
+// Older entries are dropped so the history doesn't keep every visited object
+// alive
+const MAX_ENTRIES: usize = 100;
+
+// Selections visited before and after the current one
+#[derive(Clone, Default)]
+pub struct History {
+    back: Vec<Selection>,
+    forward: Vec<Selection>,
+    // The symbol and address jumped into, which is selected once it's shown
+    landing: Option<(String, u64)>,
+}
+
+// Selects `to`, remembering the current selection so it can be returned to
+pub fn jump(selection: RwSignal<Selection>, history: RwSignal<History>, to: Selection) {
+    let from = selection.get_untracked();
+    if !matches!(from, Selection::None) {
+        history.update(|history| {
+            history.back.push(from);
+            if history.back.len() > MAX_ENTRIES {
+                history.back.remove(0);
+            }
+            history.forward.clear();
+        });
+    }
+    selection.set(to);
+}
+
+// Selects `symbol` with the instruction at `address` selected in its view
+pub fn jump_into(
+    selection: RwSignal<Selection>,
+    history: RwSignal<History>,
+    symbol: Symbol,
+    address: u64,
+) {
+    history.update(|history| history.landing = Some((symbol.data.name.clone(), address)));
+    jump(selection, history, Selection::Symbol(symbol));
+}
+
+// The address jumped into if the view of `name` is being shown for the jump
+pub fn take_landing(history: RwSignal<History>, name: &str) -> Option<u64> {
+    let address = history.with_untracked(|history| match &history.landing {
+        Some((symbol, address)) if symbol == name => Some(*address),
+        _ => None,
+    })?;
+    history.update(|history| history.landing = None);
+    Some(address)
+}
+
+pub fn back(selection: RwSignal<Selection>, history: RwSignal<History>) {
+    let mut to = None;
+    history.update(|history| {
+        to = history.back.pop();
+        if to.is_some() {
+            history.forward.push(selection.get_untracked());
+        }
+    });
+    if let Some(to) = to {
+        selection.set(to);
+    }
+}
+
+pub fn forward(selection: RwSignal<Selection>, history: RwSignal<History>) {
+    let mut to = None;
+    history.update(|history| {
+        to = history.forward.pop();
+        if to.is_some() {
+            history.back.push(selection.get_untracked());
+        }
+    });
+    if let Some(to) = to {
+        selection.set(to);
+    }
+}
+
+// Drops the entries showing `object`, which is being closed
+pub fn forget(history: RwSignal<History>, object: &Arc<Object>) {
+    history.update(|history| {
+        history
+            .back
+            .retain(|selection| !selection.refers_to(object));
+        history
+            .forward
+            .retain(|selection| !selection.refers_to(object));
+    });
+}
@@ -0,0 +1,340 @@
+use floem::{
+    cosmic_text::Weight,
+    view::View,
+    views::{stack, text, Decorators},
+};
+use iced_x86::{ConditionCode, CpuidFeature, Instruction, Mnemonic, RflagsBits};
+
+use crate::theme::theme;
+
+// What the common instructions do. Other forms, like the VEX encodings of SSE
+// instructions, are described by the legacy instruction.
+const DESCRIPTIONS: &[(Mnemonic, &str)] = &[
+    (Mnemonic::Mov, "Copies the source to the destination"),
+    (Mnemonic::Movzx, "Copies the source, zero extended to the destination size"),
+    (Mnemonic::Movsx, "Copies the source, sign extended to the destination size"),
+    (Mnemonic::Movsxd, "Copies a doubleword, sign extended to the destination size"),
+    (Mnemonic::Lea, "Stores the address computed by the memory operand, without accessing memory"),
+    (Mnemonic::Xchg, "Swaps the operands. With a memory operand it's implicitly locked."),
+    (Mnemonic::Push, "Decrements the stack pointer and stores the operand on the stack"),
+    (Mnemonic::Pop, "Loads the operand from the stack and increments the stack pointer"),
+    (Mnemonic::Add, "Adds the source to the destination"),
+    (Mnemonic::Adc, "Adds the source and the carry flag to the destination"),
+    (Mnemonic::Sub, "Subtracts the source from the destination"),
+    (Mnemonic::Sbb, "Subtracts the source and the carry flag from the destination"),
+    (Mnemonic::Inc, "Adds one to the operand, leaving the carry flag unchanged"),
+    (Mnemonic::Dec, "Subtracts one from the operand, leaving the carry flag unchanged"),
+    (Mnemonic::Neg, "Replaces the operand with its two's complement negation"),
+    (Mnemonic::Cmp, "Subtracts the second operand from the first and sets the flags, discarding the result"),
+    (Mnemonic::Test, "Computes the bitwise AND of the operands and sets the flags, discarding the result"),
+    (Mnemonic::And, "Stores the bitwise AND of the operands in the destination"),
+    (Mnemonic::Or, "Stores the bitwise OR of the operands in the destination"),
+    (Mnemonic::Xor, "Stores the bitwise XOR of the operands in the destination. XOR of a register with itself is the idiom for zeroing it."),
+    (Mnemonic::Not, "Inverts each bit of the operand"),
+    (Mnemonic::Mul, "Unsigned multiply of the accumulator by the operand, with the double width product in rDX:rAX"),
+    (Mnemonic::Imul, "Signed multiply. The two and three operand forms keep the low half of the product."),
+    (Mnemonic::Div, "Unsigned divide of rDX:rAX by the operand, with the quotient in rAX and the remainder in rDX"),
+    (Mnemonic::Idiv, "Signed divide of rDX:rAX by the operand, with the quotient in rAX and the remainder in rDX"),
+    (Mnemonic::Shl, "Shifts left, filling with zeros"),
+    (Mnemonic::Shr, "Shifts right, filling with zeros"),
+    (Mnemonic::Sar, "Shifts right, filling with the sign bit"),
+    (Mnemonic::Rol, "Rotates left"),
+    (Mnemonic::Ror, "Rotates right"),
+    (Mnemonic::Rcl, "Rotates left through the carry flag"),
+    (Mnemonic::Rcr, "Rotates right through the carry flag"),
+    (Mnemonic::Shld, "Shifts the destination left, filling with bits from the source"),
+    (Mnemonic::Shrd, "Shifts the destination right, filling with bits from the source"),
+    (Mnemonic::Bt, "Copies the selected bit to the carry flag"),
+    (Mnemonic::Bts, "Copies the selected bit to the carry flag and sets it"),
+    (Mnemonic::Btr, "Copies the selected bit to the carry flag and clears it"),
+    (Mnemonic::Btc, "Copies the selected bit to the carry flag and inverts it"),
+    (Mnemonic::Bsf, "Finds the lowest set bit. The destination is undefined for a zero source."),
+    (Mnemonic::Bsr, "Finds the highest set bit. The destination is undefined for a zero source."),
+    (Mnemonic::Tzcnt, "Counts the trailing zero bits"),
+    (Mnemonic::Lzcnt, "Counts the leading zero bits"),
+    (Mnemonic::Popcnt, "Counts the set bits"),
+    (Mnemonic::Bswap, "Reverses the byte order of the register"),
+    (Mnemonic::Cbw, "Sign extends AL into AX"),
+    (Mnemonic::Cwde, "Sign extends AX into EAX"),
+    (Mnemonic::Cdqe, "Sign extends EAX into RAX"),
+    (Mnemonic::Cwd, "Sign extends AX into DX:AX"),
+    (Mnemonic::Cdq, "Sign extends EAX into EDX:EAX, usually before a signed divide"),
+    (Mnemonic::Cqo, "Sign extends RAX into RDX:RAX, usually before a signed divide"),
+    (Mnemonic::Cmpxchg, "Compares the accumulator with the destination. If equal the source is stored in the destination, otherwise the destination is loaded into the accumulator."),
+    (Mnemonic::Cmpxchg16b, "Compares RDX:RAX with the memory operand. If equal RCX:RBX is stored in it, otherwise it's loaded into RDX:RAX."),
+    (Mnemonic::Xadd, "Stores the sum of the operands in the destination and its old value in the source"),
+    (Mnemonic::Jmp, "Jumps to the target"),
+    (Mnemonic::Call, "Pushes the return address and jumps to the target"),
+    (Mnemonic::Ret, "Pops the return address and jumps to it"),
+    (Mnemonic::Leave, "Restores the stack pointer from the frame pointer and pops the frame pointer"),
+    (Mnemonic::Enter, "Pushes the frame pointer and allocates a stack frame"),
+    (Mnemonic::Nop, "Does nothing. Longer forms are used as padding."),
+    (Mnemonic::Pause, "Hints that the code is a spin loop"),
+    (Mnemonic::Int3, "Raises a breakpoint exception"),
+    (Mnemonic::Ud2, "Raises an invalid opcode exception. Compilers emit it for unreachable code."),
+    (Mnemonic::Hlt, "Halts the processor until an interrupt arrives"),
+    (Mnemonic::Syscall, "Calls the operating system"),
+    (Mnemonic::Cpuid, "Returns information about the processor selected by EAX and ECX"),
+    (Mnemonic::Rdtsc, "Reads the time stamp counter into EDX:EAX"),
+    (Mnemonic::Rdtscp, "Reads the time stamp counter into EDX:EAX and the processor ID into ECX, after earlier instructions complete"),
+    (Mnemonic::Lfence, "Waits for earlier instructions to complete before later ones start"),
+    (Mnemonic::Sfence, "Orders earlier stores before later stores"),
+    (Mnemonic::Mfence, "Orders earlier loads and stores before later ones"),
+    (Mnemonic::Prefetcht0, "Hints that the cache line will be used soon, into all cache levels"),
+    (Mnemonic::Prefetcht1, "Hints that the cache line will be used soon, into the second level cache and up"),
+    (Mnemonic::Prefetcht2, "Hints that the cache line will be used soon, into the third level cache and up"),
+    (Mnemonic::Prefetchnta, "Hints that the cache line will be used once, minimizing cache pollution"),
+    (Mnemonic::Prefetchw, "Hints that the cache line will be written soon"),
+    (Mnemonic::Stosb, "Stores AL at rDI and advances rDI. With `rep` it fills rCX bytes."),
+    (Mnemonic::Stosd, "Stores EAX at rDI and advances rDI. With `rep` it fills rCX doublewords."),
+    (Mnemonic::Stosq, "Stores RAX at rDI and advances rDI. With `rep` it fills rCX quadwords."),
+    (Mnemonic::Movsb, "Copies a byte from rSI to rDI and advances both. With `rep` it copies rCX bytes."),
+    (Mnemonic::Movsq, "Copies a quadword from rSI to rDI and advances both. With `rep` it copies rCX quadwords."),
+    (Mnemonic::Cmpsb, "Compares the bytes at rSI and rDI and advances both"),
+    (Mnemonic::Scasb, "Compares AL with the byte at rDI and advances rDI"),
+    (Mnemonic::Crc32, "Accumulates a CRC-32C checksum of the source"),
+    (Mnemonic::Andn, "Stores the bitwise AND of the inverted first source with the second source"),
+    (Mnemonic::Blsi, "Isolates the lowest set bit"),
+    (Mnemonic::Blsr, "Clears the lowest set bit"),
+    (Mnemonic::Blsmsk, "Sets the bits up to and including the lowest set bit"),
+    (Mnemonic::Bzhi, "Clears the bits from the given index up"),
+    (Mnemonic::Bextr, "Extracts a bit field given by a start and a length"),
+    (Mnemonic::Pdep, "Deposits the low bits of the source at the bits set in the mask"),
+    (Mnemonic::Pext, "Extracts the source bits set in the mask into the low bits"),
+    (Mnemonic::Shlx, "Shifts left without affecting the flags"),
+    (Mnemonic::Shrx, "Shifts right, filling with zeros, without affecting the flags"),
+    (Mnemonic::Sarx, "Shifts right, filling with the sign bit, without affecting the flags"),
+    (Mnemonic::Rorx, "Rotates right by an immediate without affecting the flags"),
+    (Mnemonic::Mulx, "Unsigned multiply of rDX by the source without affecting the flags"),
+    (Mnemonic::Adcx, "Adds with the carry flag, affecting only the carry flag"),
+    (Mnemonic::Adox, "Adds with the overflow flag, affecting only the overflow flag"),
+    (Mnemonic::Movd, "Copies a doubleword between a general purpose register or memory and a vector register"),
+    (Mnemonic::Movq, "Copies a quadword between a general purpose register or memory and a vector register"),
+    (Mnemonic::Movss, "Copies a single precision float. Loads zero the rest of the register."),
+    (Mnemonic::Movsd, "Copies a double precision float. Loads zero the rest of the register. Without operands it's the string copy."),
+    (Mnemonic::Movaps, "Copies packed single precision floats. Memory operands must be aligned."),
+    (Mnemonic::Movups, "Copies packed single precision floats. Memory operands may be unaligned."),
+    (Mnemonic::Movapd, "Copies packed double precision floats. Memory operands must be aligned."),
+    (Mnemonic::Movupd, "Copies packed double precision floats. Memory operands may be unaligned."),
+    (Mnemonic::Movdqa, "Copies a vector of integers. Memory operands must be aligned."),
+    (Mnemonic::Movdqu, "Copies a vector of integers. Memory operands may be unaligned."),
+    (Mnemonic::Addss, "Adds the low single precision floats"),
+    (Mnemonic::Addsd, "Adds the low double precision floats"),
+    (Mnemonic::Addps, "Adds packed single precision floats"),
+    (Mnemonic::Addpd, "Adds packed double precision floats"),
+    (Mnemonic::Subss, "Subtracts the low single precision floats"),
+    (Mnemonic::Subsd, "Subtracts the low double precision floats"),
+    (Mnemonic::Subps, "Subtracts packed single precision floats"),
+    (Mnemonic::Subpd, "Subtracts packed double precision floats"),
+    (Mnemonic::Mulss, "Multiplies the low single precision floats"),
+    (Mnemonic::Mulsd, "Multiplies the low double precision floats"),
+    (Mnemonic::Mulps, "Multiplies packed single precision floats"),
+    (Mnemonic::Mulpd, "Multiplies packed double precision floats"),
+    (Mnemonic::Divss, "Divides the low single precision floats"),
+    (Mnemonic::Divsd, "Divides the low double precision floats"),
+    (Mnemonic::Divps, "Divides packed single precision floats"),
+    (Mnemonic::Divpd, "Divides packed double precision floats"),
+    (Mnemonic::Sqrtss, "Computes the square root of the low single precision float"),
+    (Mnemonic::Sqrtsd, "Computes the square root of the low double precision float"),
+    (Mnemonic::Minss, "Stores the smaller of the low single precision floats, the second if either is NaN"),
+    (Mnemonic::Maxss, "Stores the larger of the low single precision floats, the second if either is NaN"),
+    (Mnemonic::Minsd, "Stores the smaller of the low double precision floats, the second if either is NaN"),
+    (Mnemonic::Maxsd, "Stores the larger of the low double precision floats, the second if either is NaN"),
+    (Mnemonic::Ucomiss, "Compares the low single precision floats and sets ZF, PF and CF. PF is set if they're unordered."),
+    (Mnemonic::Ucomisd, "Compares the low double precision floats and sets ZF, PF and CF. PF is set if they're unordered."),
+    (Mnemonic::Comiss, "Compares the low single precision floats like `ucomiss`, but signals on quiet NaNs"),
+    (Mnemonic::Comisd, "Compares the low double precision floats like `ucomisd`, but signals on quiet NaNs"),
+    (Mnemonic::Cvtsi2ss, "Converts an integer to a single precision float"),
+    (Mnemonic::Cvtsi2sd, "Converts an integer to a double precision float"),
+    (Mnemonic::Cvttss2si, "Converts a single precision float to an integer, rounding toward zero"),
+    (Mnemonic::Cvttsd2si, "Converts a double precision float to an integer, rounding toward zero"),
+    (Mnemonic::Cvtss2sd, "Converts a single precision float to double precision"),
+    (Mnemonic::Cvtsd2ss, "Converts a double precision float to single precision"),
+    (Mnemonic::Andps, "Bitwise AND of packed single precision floats"),
+    (Mnemonic::Andpd, "Bitwise AND of packed double precision floats"),
+    (Mnemonic::Andnps, "Bitwise AND of the inverted destination with the source"),
+    (Mnemonic::Orps, "Bitwise OR of packed single precision floats"),
+    (Mnemonic::Xorps, "Bitwise XOR of packed single precision floats. XOR of a register with itself zeroes it."),
+    (Mnemonic::Xorpd, "Bitwise XOR of packed double precision floats. XOR of a register with itself zeroes it."),
+    (Mnemonic::Pand, "Bitwise AND of vectors"),
+    (Mnemonic::Pandn, "Bitwise AND of the inverted destination with the source"),
+    (Mnemonic::Por, "Bitwise OR of vectors"),
+    (Mnemonic::Pxor, "Bitwise XOR of vectors. XOR of a register with itself zeroes it."),
+    (Mnemonic::Paddb, "Adds packed bytes"),
+    (Mnemonic::Paddw, "Adds packed words"),
+    (Mnemonic::Paddd, "Adds packed doublewords"),
+    (Mnemonic::Paddq, "Adds packed quadwords"),
+    (Mnemonic::Psubb, "Subtracts packed bytes"),
+    (Mnemonic::Psubd, "Subtracts packed doublewords"),
+    (Mnemonic::Psubq, "Subtracts packed quadwords"),
+    (Mnemonic::Pmulld, "Multiplies packed doublewords, keeping the low halves"),
+    (Mnemonic::Pcmpeqb, "Sets each byte to all ones where they're equal, otherwise zero"),
+    (Mnemonic::Pcmpeqd, "Sets each doubleword to all ones where they're equal, otherwise zero"),
+    (Mnemonic::Pcmpgtb, "Sets each byte to all ones where the destination is greater, otherwise zero"),
+    (Mnemonic::Pmovmskb, "Gathers the top bit of each byte into a general purpose register"),
+    (Mnemonic::Movmskps, "Gathers the sign bit of each single precision float into a general purpose register"),
+    (Mnemonic::Pshufb, "Shuffles the bytes using the indices in the source"),
+    (Mnemonic::Pshufd, "Shuffles the doublewords using an immediate"),
+    (Mnemonic::Shufps, "Selects single precision floats from both operands using an immediate"),
+    (Mnemonic::Punpcklbw, "Interleaves the low bytes of the operands"),
+    (Mnemonic::Punpckldq, "Interleaves the low doublewords of the operands"),
+    (Mnemonic::Punpcklqdq, "Interleaves the low quadwords of the operands"),
+    (Mnemonic::Unpcklps, "Interleaves the low single precision floats of the operands"),
+    (Mnemonic::Pslldq, "Shifts the whole vector left by bytes"),
+    (Mnemonic::Psrldq, "Shifts the whole vector right by bytes"),
+    (Mnemonic::Ptest, "Sets ZF if the AND of the operands is zero and CF if the AND with the inverted destination is"),
+    (Mnemonic::Vzeroupper, "Zeroes the upper halves of the vector registers, avoiding penalties when mixing with legacy SSE code"),
+    (Mnemonic::Vbroadcastss, "Copies a single precision float to every element"),
+    (Mnemonic::Vpbroadcastb, "Copies a byte to every element"),
+    (Mnemonic::Vpbroadcastd, "Copies a doubleword to every element"),
+    (Mnemonic::Vpbroadcastq, "Copies a quadword to every element"),
+    (Mnemonic::Vperm2i128, "Selects 128-bit lanes from both sources using an immediate"),
+    (Mnemonic::Vpermq, "Shuffles the quadwords across lanes using an immediate"),
+    (Mnemonic::Vextracti128, "Extracts a 128-bit lane"),
+    (Mnemonic::Vinserti128, "Inserts a 128-bit lane"),
+    (Mnemonic::Vfmadd231ps, "Multiplies the sources and adds the destination, rounding once"),
+    (Mnemonic::Vfmadd231pd, "Multiplies the sources and adds the destination, rounding once"),
+    (Mnemonic::Vfmadd231ss, "Multiplies the sources and adds the destination, rounding once"),
+    (Mnemonic::Vfmadd231sd, "Multiplies the sources and adds the destination, rounding once"),
+];
+
+// Names of the flags in the order they're listed
+const FLAGS: &[(u32, &str)] = &[
+    (RflagsBits::OF, "OF"),
+    (RflagsBits::SF, "SF"),
+    (RflagsBits::ZF, "ZF"),
+    (RflagsBits::AF, "AF"),
+    (RflagsBits::CF, "CF"),
+    (RflagsBits::PF, "PF"),
+    (RflagsBits::DF, "DF"),
+    (RflagsBits::IF, "IF"),
+    (RflagsBits::AC, "AC"),
+    (RflagsBits::UIF, "UIF"),
+    (RflagsBits::C0, "C0"),
+    (RflagsBits::C1, "C1"),
+    (RflagsBits::C2, "C2"),
+    (RflagsBits::C3, "C3"),
+];
+
+fn condition(code: ConditionCode) -> Option<&'static str> {
+    Some(match code {
+        ConditionCode::o => "it overflowed (OF = 1)",
+        ConditionCode::no => "it didn't overflow (OF = 0)",
+        ConditionCode::b => "below, unsigned (CF = 1)",
+        ConditionCode::ae => "above or equal, unsigned (CF = 0)",
+        ConditionCode::e => "equal or zero (ZF = 1)",
+        ConditionCode::ne => "not equal or not zero (ZF = 0)",
+        ConditionCode::be => "below or equal, unsigned (CF = 1 or ZF = 1)",
+        ConditionCode::a => "above, unsigned (CF = 0 and ZF = 0)",
+        ConditionCode::s => "negative (SF = 1)",
+        ConditionCode::ns => "not negative (SF = 0)",
+        ConditionCode::p => "the parity is even (PF = 1)",
+        ConditionCode::np => "the parity is odd (PF = 0)",
+        ConditionCode::l => "less, signed (SF != OF)",
+        ConditionCode::ge => "greater or equal, signed (SF = OF)",
+        ConditionCode::le => "less or equal, signed (ZF = 1 or SF != OF)",
+        ConditionCode::g => "greater, signed (ZF = 0 and SF = OF)",
+        ConditionCode::None => return None,
+    })
+}
+
+fn lookup(mnemonic: Mnemonic) -> Option<&'static str> {
+    DESCRIPTIONS
+        .iter()
+        .find(|(m, _)| *m == mnemonic)
+        .map(|(_, description)| *description)
+}
+
+// What `instruction` does, from the table or from its condition code
+pub fn description(instruction: &Instruction) -> Option<String> {
+    let mnemonic = instruction.mnemonic();
+    if let Some(description) = lookup(mnemonic) {
+        return Some(description.to_owned());
+    }
+    let name = format!("{:?}", mnemonic);
+    if let Some(condition) = condition(instruction.condition_code()) {
+        if name.starts_with("Cmov") {
+            return Some(format!("Copies the source if {}", condition));
+        }
+        if name.starts_with("Set") {
+            return Some(format!(
+                "Sets the byte to 1 if {}, otherwise to 0",
+                condition
+            ));
+        }
+        if name.starts_with('J') {
+            return Some(format!("Jumps if {}", condition));
+        }
+    }
+    // VEX and EVEX forms of SSE instructions do the same with a separate
+    // destination, zeroing the rest of the register
+    let legacy = name.strip_prefix('V')?;
+    let legacy = Mnemonic::values().find(|m| format!("{:?}", m).eq_ignore_ascii_case(legacy))?;
+    lookup(legacy).map(|description| format!("{}, with a separate destination", description))
+}
+
+fn flag_names(bits: u32) -> String {
+    let names: Vec<&str> = FLAGS
+        .iter()
+        .filter(|(bit, _)| bits & bit != 0)
+        .map(|(_, name)| *name)
+        .collect();
+    names.join(" ")
+}
+
+// The flags read and written by `instruction`, one line per kind of access
+pub fn flags(instruction: &Instruction) -> Vec<String> {
+    let accesses = [
+        ("Reads", instruction.rflags_read()),
+        ("Writes", instruction.rflags_written()),
+        ("Clears", instruction.rflags_cleared()),
+        ("Sets", instruction.rflags_set()),
+        ("Leaves undefined", instruction.rflags_undefined()),
+    ];
+    accesses
+        .into_iter()
+        .filter(|(_, bits)| *bits != 0)
+        .map(|(access, bits)| format!("{} {}", access, flag_names(bits)))
+        .collect()
+}
+
+fn is_base(feature: CpuidFeature) -> bool {
+    format!("{:?}", feature).starts_with("INTEL") || feature == CpuidFeature::X64
+}
+
+// The extensions needed for `instruction`, or `None` for the base instruction
+// set
+pub fn extensions(instruction: &Instruction) -> Option<String> {
+    let features: Vec<String> = instruction
+        .cpuid_features()
+        .iter()
+        .filter(|&&feature| !is_base(feature))
+        .map(|feature| format!("{:?}", feature))
+        .collect();
+    (!features.is_empty()).then(|| features.join(", "))
+}
+
+// The documentation of `instruction` shown when hovering it
+pub fn tooltip_view(instruction: &Instruction) -> impl View {
+    let op_code = instruction.op_code();
+    let mut details = vec![format!("Encoding: {}", op_code.op_code_string())];
+    details.extend(flags(instruction));
+    details.push(format!(
+        "Extension: {}",
+        extensions(instruction).unwrap_or_else(|| "none".to_owned())
+    ));
+    let description = description(instruction).unwrap_or_default();
+    stack((
+        text(op_code.instruction_string()).style(|s| s.font_weight(Weight::BOLD)),
+        text(description).style(|s| s.margin_vert(4.0)),
+        text(details.join("\n")).style(|s| s.color(theme().dim)),
+    ))
+    .style(|s| {
+        s.flex_col()
+            .max_width(400.0)
+            .padding(5.0)
+            .background(theme().panel)
+            .border(0.5)
+            .border_color(theme().border)
+    })
+}
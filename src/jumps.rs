@@ -0,0 +1,64 @@
+// Jumps further out than this many nested lanes are left out of the gutter
+const MAX_LANES: usize = 6;
+
+// Arrows for jumps between rows, drawn with box characters as one string per
+// row. Jumps are given by their source and target rows. Shorter jumps use the
+// inner lanes, next to the instructions.
+pub fn gutter(jumps: &[(usize, usize)], rows: usize) -> Vec<String> {
+    let mut jumps = jumps.to_vec();
+    jumps.sort_by_key(|&(from, to)| from.abs_diff(to));
+
+    let mut lanes: Vec<Vec<(usize, usize)>> = Vec::new();
+    for (from, to) in jumps {
+        let (top, bottom) = (from.min(to), from.max(to));
+        let overlaps = |lane: &Vec<(usize, usize)>| {
+            lane.iter()
+                .any(|&(f, t)| top <= f.max(t) && f.min(t) <= bottom)
+        };
+        match lanes.iter().position(|lane| !overlaps(lane)) {
+            Some(lane) => lanes[lane].push((from, to)),
+            None if lanes.len() < MAX_LANES => lanes.push(vec![(from, to)]),
+            None => {}
+        }
+    }
+    if lanes.is_empty() {
+        return Vec::new();
+    }
+
+    let columns = lanes.len();
+    (0..rows)
+        .map(|row| {
+            // The last cell holds the arrow heads
+            let mut cells = vec![' '; columns + 1];
+            for (lane, jumps) in lanes.iter().enumerate() {
+                let column = columns - 1 - lane;
+                for &(from, to) in jumps {
+                    if from.min(to) < row && row < from.max(to) {
+                        cells[column] = '│';
+                    }
+                }
+            }
+            for (lane, jumps) in lanes.iter().enumerate() {
+                let column = columns - 1 - lane;
+                for &(from, to) in jumps {
+                    if row != from && row != to {
+                        continue;
+                    }
+                    cells[column] = if row == from.min(to) { '┌' } else { '└' };
+                    for cell in &mut cells[column + 1..columns] {
+                        *cell = match *cell {
+                            '│' | '┼' => '┼',
+                            _ => '─',
+                        };
+                    }
+                    if row == to {
+                        cells[columns] = '►';
+                    } else if cells[columns] == ' ' {
+                        cells[columns] = '─';
+                    }
+                }
+            }
+            cells.into_iter().collect()
+        })
+        .collect()
+}
@@ -0,0 +1,204 @@
+use std::{collections::HashSet, sync::Arc};
+
+use floem::{
+    cosmic_text::Weight,
+    reactive::{create_rw_signal, RwSignal},
+    style::{CursorStyle, TextOverflow},
+    view::View,
+    views::{
+        scroll, stack, text, text_input, virtual_list, Decorators, VirtualListDirection,
+        VirtualListItemSize,
+    },
+};
+
+use crate::{
+    header, history, history::History, settings, theme::theme, xrefs, Object, Selection, Symbol,
+};
+
+const ROW_HEIGHT: f64 = 24.0;
+
+#[derive(Clone)]
+enum Row {
+    Header(String),
+    Library(String),
+    Import(String),
+    // An export with the function defined there, if it's one of the symbols
+    Export(String, u64, Option<Symbol>),
+}
+
+impl Row {
+    // Keys rows with their position and name, as filtering moves them
+    fn key(&self) -> &str {
+        match self {
+            Row::Header(name) | Row::Library(name) | Row::Import(name) => name,
+            Row::Export(name, _, _) => name,
+        }
+    }
+}
+
+fn demangled(name: &str) -> String {
+    settings::demangling()
+        .demangle(name)
+        .unwrap_or_else(|| name.to_owned())
+}
+
+// The rows matching `query`, with a header for each non-empty group
+fn rows(object: &Arc<Object>, query: &str) -> im::Vector<(usize, Row)> {
+    let query = query.to_lowercase();
+    let matches = |name: &str| {
+        query.is_empty()
+            || name.to_lowercase().contains(&query)
+            || demangled(name).to_lowercase().contains(&query)
+    };
+    let linkage = &object.linkage;
+    let mut rows = Vec::new();
+    let mut group = |title: &str, items: Vec<Row>| {
+        if !items.is_empty() {
+            rows.push(Row::Header(format!("{} ({})", title, items.len())));
+            rows.extend(items);
+        }
+    };
+
+    group(
+        "Needed Libraries",
+        linkage
+            .needed
+            .iter()
+            .filter(|library| matches(library))
+            .map(|library| Row::Library(library.clone()))
+            .collect(),
+    );
+    let mut imports = linkage.imports.iter().peekable();
+    while let Some(first) = imports.peek() {
+        let library = first.library.clone();
+        let mut items = Vec::new();
+        while let Some(import) = imports.next_if(|import| import.library == library) {
+            if matches(&import.name) || matches(&import.library) {
+                items.push(Row::Import(import.name.clone()));
+            }
+        }
+        match library.as_str() {
+            "" => group("Imports", items),
+            library => group(&format!("Imports from {}", library), items),
+        }
+    }
+    group(
+        "Exports",
+        linkage
+            .exports
+            .iter()
+            .filter(|export| matches(&export.name))
+            .map(|export| {
+                let symbol = xrefs::find(object, &export.name).map(|data| Symbol {
+                    object: object.clone(),
+                    data,
+                });
+                Row::Export(export.name.clone(), export.address, symbol)
+            })
+            .collect(),
+    );
+    rows.into_iter().enumerate().collect()
+}
+
+// The shared libraries and functions the object imports and the functions it
+// exports, filtered by a search
+pub fn linkage_tab(
+    object: &Arc<Object>,
+    selection: RwSignal<Selection>,
+    history: RwSignal<History>,
+) -> Box<dyn View> {
+    let object = object.clone();
+    let query = create_rw_signal(String::new());
+    let linkage = &object.linkage;
+    let libraries: HashSet<&str> = linkage
+        .needed
+        .iter()
+        .chain(linkage.imports.iter().map(|import| &import.library))
+        .map(String::as_str)
+        .filter(|library| !library.is_empty())
+        .collect();
+    let summary = text(format!(
+        "{} libraries, {} imports, {} exports",
+        libraries.len(),
+        linkage.imports.len(),
+        linkage.exports.len()
+    ))
+    .style(|s| s.padding(5.0).color(theme().dim));
+
+    let search = stack((
+        text("Search:").style(|s| s.padding_left(6.0)),
+        text_input(query).style(|s| {
+            s.width(320)
+                .padding(5)
+                .margin(4)
+                .border(0.5)
+                .border_radius(3)
+                .border_color(theme().button_border)
+        }),
+        summary,
+    ))
+    .style(|s| s.flex_row().items_center());
+
+    let list = virtual_list(
+        VirtualListDirection::Vertical,
+        VirtualListItemSize::Fixed(Box::new(|| ROW_HEIGHT)),
+        move || query.with(|query| rows(&object, query)),
+        |(i, row)| (*i, row.key().to_owned()),
+        move |(_, row)| {
+            let (name, detail, target) = match row {
+                Row::Header(title) => {
+                    return Box::new(text(title).style(|s| {
+                        s.width_full()
+                            .height(ROW_HEIGHT)
+                            .padding(5)
+                            .font_weight(Weight::BOLD)
+                            .background(theme().panel)
+                    })) as Box<dyn View>;
+                }
+                Row::Library(library) => (library, String::new(), None),
+                Row::Import(name) => (demangled(&name), String::new(), None),
+                Row::Export(name, address, symbol) => {
+                    (demangled(&name), format!("{:016X}", address), symbol)
+                }
+            };
+            let link = target.is_some();
+            Box::new(
+                stack((
+                    text(name).style(move |s| {
+                        s.flex_grow(1.0)
+                            .flex_basis(0.0)
+                            .min_width(0.0)
+                            .text_overflow(TextOverflow::Clip)
+                            .apply_if(link, |s| s.color(theme().link))
+                    }),
+                    text(detail).style(|s| s.padding_left(10).color(theme().address)),
+                ))
+                .style(move |s| {
+                    s.flex_row()
+                        .width_full()
+                        .height(ROW_HEIGHT)
+                        .padding(5)
+                        .padding_left(15)
+                        .apply_if(link, |s| s.cursor(CursorStyle::Pointer))
+                })
+                .hover_style(|s| s.background(theme().hover))
+                .on_click(move |_| {
+                    if let Some(target) = &target {
+                        history::jump(selection, history, Selection::Symbol(target.clone()));
+                    }
+                    true
+                }),
+            )
+        },
+    )
+    .style(|s| s.flex_col().width_full());
+
+    Box::new(
+        stack((
+            header("Imports / Exports"),
+            search,
+            scroll(list).style(|s| s.width_full().flex_grow(1.0).flex_basis(0.0)),
+        ))
+        .style(|s| s.flex_col().width_full().height_full()),
+    )
+}
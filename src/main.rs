@@ -1,310 +1,713 @@
-#![feature(strict_provenance)]
-
-use std::{collections::HashMap, fmt::Display, fs, ops::Range, path::PathBuf, sync::Arc};
+use std::{
+    cell::{Cell, RefCell},
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Display,
+    fs,
+    ops::Range,
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    rc::Rc,
+    sync::{mpsc, Arc},
+    time::{Duration, Instant},
+};
 
+use asm_viewer_core::{
+    breakpad, each_object, fat, identity, mapping, metrics, names, overlay, parse_file,
+    parse_object, pool, settings, symsrv, ArchiveMember, DataSymbol, Instruction, Isa, Loaded,
+    Object, Section, Symbol, SymbolData,
+};
 use floem::{
-    cosmic_text::{Attrs, AttrsList, FamilyOwned, Style, TextLayout, Weight},
-    event::Event,
+    cosmic_text::{Attrs, AttrsList, FamilyOwned, TextLayout, Weight},
+    event::{Event, EventListener},
+    ext_event::create_ext_action,
+    keyboard::{Key, NamedKey},
+    kurbo::Point,
     peniko::Color,
-    reactive::{create_rw_signal, RwSignal},
+    reactive::{create_effect, create_rw_signal, RwSignal, Scope},
     style::{CursorStyle, TextOverflow},
     view::View,
     views::{
         bg_active_color, container, container_box, dyn_container, empty, label, list, rich_text,
-        scroll, stack, text, virtual_list, Decorators, Label, VirtualListDirection,
+        scroll, stack, text, tooltip, virtual_list, Decorators, Label, Stack, VirtualListDirection,
         VirtualListItemSize,
     },
 };
-use iced_x86::Formatter;
-use object::{
-    read::archive::ArchiveFile, BinaryFormat, Object as _, ObjectSection, ObjectSymbol, Relocation,
-    RelocationTarget, SectionIndex, SymbolIndex, SymbolKind,
-};
-use symbolic_demangle::{Demangle, DemangleOptions};
-
-struct Object {
-    path: PathBuf,
-    name: String,
-    format: BinaryFormat,
-    symbols: HashMap<SymbolIndex, Arc<SymbolData>>,
-    symbols_sorted: Vec<Arc<SymbolData>>,
-    sections: Vec<Arc<Section>>,
+use history::History;
+use mapping::{Mapping, SectionData};
+use metrics::Metric;
+use object::read::archive::ArchiveFile;
+use rfd::AsyncFileDialog;
+use settings::{Demangling, Density, EncodingHeat, Microarch, Settings, Syntax, ViewSettings};
+use theme::theme;
+
+mod annotations;
+mod bloat;
+mod callgraph;
+mod callpaths;
+mod cargo;
+mod cli;
+mod copy;
+mod deadcode;
+mod desktop;
+mod dialog;
+mod diff;
+mod encodings;
+mod export;
+mod favorites;
+mod goto;
+mod hexview;
+mod history;
+mod isa;
+mod jumps;
+mod linkage;
+mod matcher;
+mod mca;
+mod overrides;
+mod preferences;
+mod profile;
+mod project;
+mod raw;
+mod relocations;
+mod rollup;
+mod sample;
+mod search;
+mod sections;
+mod session;
+mod stats;
+mod theme;
+mod timeline;
+mod tools;
+mod tour;
+mod tracking;
+mod units;
+mod workspace;
+mod xrefs;
+
+// Demangles the names in the loaded objects again on the thread pool, and
+// replaces the objects in which names changed
+fn redemangle(
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+    demangling: Demangling,
+) {
+    settings::set_demangling(demangling);
+    let loaded = objects.with_untracked(|list| list.objects.clone());
+    for object in loaded {
+        let done = create_ext_action(Scope::new(), move |(old, new): (Arc<Object>, Object)| {
+            // Results of an earlier choice are stale
+            if settings::demangling() != demangling {
+                return;
+            }
+            replace_object(objects, selection, &old, Arc::new(new));
+        });
+        pool::spawn(pool::Priority::Batch, move || {
+            if let Some(new) = overlay::with_demangling(&object, demangling) {
+                done((object, new));
+            }
+        });
+    }
 }
 
-#[derive(Debug)]
-struct Section {
-    name: String,
-    data: Vec<u8>,
-    address: u64,
-
-    relocations: HashMap<u64, Relocation>,
-
-    // A sorted list of symbol positions
-    symbols: Vec<u64>,
+// Decodes the object in another mode, for objects with an unknown architecture
+// or code which doesn't match it
+fn set_bitness(
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+    object: &Arc<Object>,
+    bitness: u32,
+) {
+    let old = object.clone();
+    let replace = create_ext_action(Scope::new(), move |new: Object| {
+        replace_object(objects, selection, &old, Arc::new(new));
+    });
+    let object = object.clone();
+    pool::spawn(pool::Priority::Interactive, move || {
+        replace(overlay::with_bitness(&object, bitness));
+    });
 }
 
-#[derive(Debug)]
-struct SymbolData {
-    name: String,
-    demangled: Option<String>,
-    address: u64,
-    section: Option<Arc<Section>>,
-    size: u64,
-}
-
-impl SymbolData {
-    fn estimate_size(&self) -> Option<u64> {
-        let section = self.section.as_ref()?;
-        let i = section.symbols.binary_search(&self.address).ok()?;
-        if i + 1 == section.symbols.len() {
-            section
-                .address
-                .checked_add(section.data.len().try_into().ok()?)?
-                .checked_sub(self.address)
-        } else {
-            section.symbols[i + 1].checked_sub(self.address)
-        }
+// How strongly to color the row of `instruction`, from 0 to 1. Lengths from 4
+// bytes and any prefixes count.
+fn encoding_heat(heat: EncodingHeat, instruction: &Instruction) -> f32 {
+    if instruction.padding {
+        return 0.0;
     }
-
-    fn data(&self) -> Option<&[u8]> {
-        let section = self.section.as_ref()?;
-        let size: usize = self.estimate_size()?.try_into().ok()?;
-        let offset: usize = self.address.checked_sub(section.address)?.try_into().ok()?;
-        let end = offset.checked_add(size)?;
-        section.data.get(offset..end)
+    match heat {
+        EncodingHeat::Off => 0.0,
+        EncodingHeat::Length => (instruction.bytes.len().saturating_sub(3) as f32 / 8.0).min(1.0),
+        EncodingHeat::Prefixes => (instruction.prefixes as f32 / 4.0).min(1.0),
     }
+}
 
-    fn assembly(&self, object: &Object) -> Option<Arc<Assembly>> {
-        let bytes = self.data()?;
-        let mut decoder =
-            iced_x86::Decoder::with_ip(64, bytes, self.address, iced_x86::DecoderOptions::NONE);
-
-        let mut formatter = iced_x86::IntelFormatter::new();
+// The largest of the 16, 32 and 64-byte boundaries at the start of or within
+// the instruction
+fn boundary(address: u64, len: usize) -> Option<u64> {
+    [64, 32, 16].into_iter().find(|&size| {
+        let next = address.next_multiple_of(size);
+        next < address + len.max(1) as u64
+    })
+}
 
-        formatter.options_mut().set_first_operand_char_index(10);
-        formatter
-            .options_mut()
-            .set_space_after_operand_separator(true);
+// Marks rows of instructions at or across a boundary, more visibly for larger
+// boundaries
+fn boundary_style(s: floem::style::Style, boundary: Option<u64>) -> floem::style::Style {
+    match boundary {
+        Some(64) => s.border_top(1.0).border_color(theme().faint),
+        Some(32) => s.border_top(1.0).border_color(theme().border),
+        Some(_) => s.border_top(0.5).border_color(theme().border),
+        None => s,
+    }
+}
 
-        let mut instruction = iced_x86::Instruction::default();
+#[derive(Clone)]
+enum Selection {
+    None,
+    Object(Arc<Object>),
+    Symbol(Symbol),
+    Diff(Symbol, Symbol),
+    Matches(Arc<Object>, Arc<Object>),
+    // Evolution of the named function across the loaded build series
+    Timeline(String),
+    // Shortest call paths between the named functions
+    CallPaths(String, String),
+    // Callers and callees of the named function
+    CallGraph(String),
+    DeadCode,
+    // Sizes of the loaded objects by crate, namespace or section
+    Bloat,
+    Preferences,
+    // Sizes and changes of the symbols tracked by the projects
+    Tracked,
+    // Choosing how to decode the picked file as raw code
+    RawBinary(PathBuf),
+}
 
-        let mut assembly = Assembly {
-            instructions: Vec::new(),
+impl Selection {
+    // The selection with parts of `old` replaced by `new`, or `None` if it
+    // doesn't refer to `old`
+    fn remap(&self, old: &Arc<Object>, new: &Arc<Object>) -> Option<Selection> {
+        let object = |object: &Arc<Object>| {
+            if object.key() == old.key() {
+                new.clone()
+            } else {
+                object.clone()
+            }
+        };
+        let symbol = |symbol: &Symbol| {
+            if symbol.object.key() != old.key() {
+                return Some(symbol.clone());
+            }
+            let data = new
+                .symbols_sorted
+                .iter()
+                .filter(|data| data.name_bytes() == symbol.data.name_bytes())
+                .min_by_key(|data| data.address.abs_diff(symbol.data.address))?;
+            Some(Symbol {
+                object: new.clone(),
+                data: data.clone(),
+            })
+        };
+        let refers = |o: &Arc<Object>| o.key() == old.key();
+        let remapped = match self {
+            Selection::Object(o) if refers(o) => Selection::Object(new.clone()),
+            Selection::Symbol(s) if refers(&s.object) => {
+                symbol(s).map_or_else(|| Selection::Object(new.clone()), Selection::Symbol)
+            }
+            Selection::Diff(l, r) if refers(&l.object) || refers(&r.object) => {
+                match (symbol(l), symbol(r)) {
+                    (Some(l), Some(r)) => Selection::Diff(l, r),
+                    _ => Selection::Object(new.clone()),
+                }
+            }
+            Selection::Matches(l, r) if refers(l) || refers(r) => {
+                Selection::Matches(object(l), object(r))
+            }
+            _ => return None,
         };
+        Some(remapped)
+    }
 
-        while decoder.can_decode() {
-            decoder.decode_out(&mut instruction);
+    // Whether the selection shows parts of `object`
+    fn refers_to(&self, object: &Arc<Object>) -> bool {
+        let refers = |o: &Arc<Object>| o.key() == object.key();
+        match self {
+            Selection::Object(o) => refers(o),
+            Selection::Symbol(s) => refers(&s.object),
+            Selection::Diff(l, r) => refers(&l.object) || refers(&r.object),
+            Selection::Matches(l, r) => refers(l) || refers(r),
+            _ => false,
+        }
+    }
+}
 
-            let start_index = (instruction.ip() - self.address) as usize;
+struct ObjectList {
+    objects: Vec<Arc<Object>>,
+    // Files which failed to load
+    errors: Vec<String>,
+    // Files still being parsed in the background
+    loading: Vec<Loading>,
+    workspaces: Vec<workspace::Workspace>,
+    // Crates of artifacts built from Cargo projects, which are listed together
+    crates: HashMap<PathBuf, String>,
+}
 
-            let mut relocation = None;
+struct Loading {
+    path: PathBuf,
+    objects: usize,
+    members: Option<usize>,
+}
 
-            self.section.as_ref().map(|section| {
-                for i in 0..instruction.len() {
-                    section
-                        .relocations
-                        .get(&(instruction.ip() + i as u64))
-                        .map(|r| {
-                            relocation = Some(r.target().clone());
-                        });
-                }
-            });
+#[allow(clippy::too_many_arguments)]
+fn load_object(
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+    mapping: &Mapping,
+    data: &[u8],
+    name: String,
+    path: PathBuf,
+    member: Option<ArchiveMember>,
+    slice: Option<String>,
+) -> object::Result<Arc<Object>> {
+    let object = Arc::new(parse_object(mapping, data, name, path, member, slice)?);
+    let id = object::File::parse(data)
+        .ok()
+        .and_then(|file| symsrv::PdbId::from_file(&file));
+    if let Some(id) = id {
+        load_pdb(objects, selection, object.clone(), id);
+    }
+    Ok(object)
+}
 
-            let relocation = relocation.and_then(|r| match r {
-                RelocationTarget::Symbol(i) => object.symbols.get(&i).cloned(),
-                _ => None,
-            });
+// Fetches the PDB of a Windows image in the background and merges its
+// symbols into the object once available
+fn load_pdb(
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+    object: Arc<Object>,
+    id: symsrv::PdbId,
+) {
+    let old = object.clone();
+    let done = create_ext_action(
+        Scope::new(),
+        move |merged: Result<Object, String>| match merged {
+            Ok(merged) => replace_object(objects, selection, &old, Arc::new(merged)),
+            Err(error) => report_error(objects, error),
+        },
+    );
+    pool::spawn(pool::Priority::Batch, move || {
+        let pdb = match symsrv::locate(&id, &object.path) {
+            Ok(Some(pdb)) => pdb,
+            Ok(None) => return,
+            Err(error) => return done(Err(error)),
+        };
+        match symsrv::load(&id, &pdb) {
+            Ok(symbols) if symbols.is_empty() => (),
+            Ok(symbols) => done(Ok(overlay::merge(&object, &symbols))),
+            Err(error) => done(Err(format!(
+                "Unable to read `{}`: {}",
+                pdb.display(),
+                error
+            ))),
+        }
+    });
+}
 
-            let mut inst = Instruction {
-                address: instruction.ip(),
-                bytes: bytes[start_index..start_index + instruction.len()].to_vec(),
-                format: Vec::new(),
-                relocation,
-            };
-            formatter.format(&instruction, &mut inst);
+// Replaces `old` in the object list and moves the selection over to the
+// corresponding parts of `new`
+fn replace_object(
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+    old: &Arc<Object>,
+    new: Arc<Object>,
+) {
+    objects.update(|list| {
+        for entry in &mut list.objects {
+            if entry.key() == old.key() {
+                *entry = new.clone();
+            }
+        }
+    });
+    if let Some(current) = selection.with_untracked(|current| current.remap(old, &new)) {
+        selection.set(current);
+    }
+}
 
-            assembly.instructions.push(inst);
+// Removes `object` from the object list and drops the references to it, so its
+// sections and file mapping are freed
+fn close_object(
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+    history: RwSignal<History>,
+    searches: RwSignal<search::Searches>,
+    diff_base: RwSignal<Option<Symbol>>,
+    path_start: RwSignal<Option<Symbol>>,
+    object: &Arc<Object>,
+) {
+    objects.update(|list| list.objects.retain(|o| o.key() != object.key()));
+    if selection.with_untracked(|current| current.refers_to(object)) {
+        selection.set(Selection::None);
+    }
+    history::forget(history, object);
+    searches.update(|searches| searches.forget(object));
+    for symbol in [diff_base, path_start] {
+        if symbol.with_untracked(|s| s.as_ref().is_some_and(|s| s.object.key() == object.key())) {
+            symbol.set(None);
         }
+    }
+}
 
-        Some(Arc::new(assembly))
+// Reads the object again from disk, re-extracting archive members from their
+// archive. Raw binaries keep their current layout.
+fn reload_object(
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+    object: &Arc<Object>,
+) {
+    if let Some(layout) = raw::layout(object) {
+        match raw::load(object.path.clone(), layout) {
+            Ok(reloaded) => replace_object(objects, selection, object, Arc::new(reloaded)),
+            Err(error) => report_error(objects, format!("`{}`: {}", object.path.display(), error)),
+        }
+        return;
+    }
+    let file = match mapping::map(&object.path) {
+        Ok(file) => file,
+        Err(error) => {
+            report_error(objects, format!("`{}`: {}", object.path.display(), error));
+            return;
+        }
+    };
+
+    let data = match &object.slice {
+        Some(slice) => match fat::find(&file, slice) {
+            Some(data) => data,
+            None => {
+                report_error(objects, format!("`{}`: slice is gone", object.title()));
+                return;
+            }
+        },
+        None => &file[..],
+    };
+
+    let reloaded = match &object.member {
+        Some(member) => {
+            let Ok(archive) = ArchiveFile::parse(data) else {
+                report_error(
+                    objects,
+                    format!("`{}`: no longer an archive", object.path.display()),
+                );
+                return;
+            };
+            // Prefer the member at the same offset in case names are repeated
+            let members: Vec<_> = archive
+                .members()
+                .filter_map(|m| m.ok())
+                .filter(|m| m.name() == member.name.as_bytes())
+                .collect();
+            let Some(found) = members
+                .iter()
+                .find(|m| m.file_range().0 == member.offset)
+                .or(members.first())
+            else {
+                report_error(objects, format!("`{}`: member is gone", object.title()));
+                return;
+            };
+            let (offset, size) = found.file_range();
+            found.data(data).and_then(|data| {
+                load_object(
+                    objects,
+                    selection,
+                    &file,
+                    data,
+                    object.name.clone(),
+                    object.path.clone(),
+                    Some(ArchiveMember {
+                        name: member.name.clone(),
+                        offset,
+                        size,
+                    }),
+                    object.slice.clone(),
+                )
+            })
+        }
+        None => load_object(
+            objects,
+            selection,
+            &file,
+            data,
+            object.name.clone(),
+            object.path.clone(),
+            None,
+            object.slice.clone(),
+        ),
+    };
+
+    match reloaded {
+        Ok(reloaded) => replace_object(objects, selection, object, reloaded),
+        Err(error) => report_error(
+            objects,
+            format!(
+                "`{}`: not a supported object file: {}",
+                object.title(),
+                error
+            ),
+        ),
     }
 }
 
-#[derive(Clone)]
-struct Symbol {
-    object: Arc<Object>,
-    data: Arc<SymbolData>,
+fn report_error(objects: RwSignal<ObjectList>, error: String) {
+    objects.update(|list| list.errors.push(error));
 }
 
-#[derive(Clone)]
-struct Instruction {
-    address: u64,
-    bytes: Vec<u8>,
-    format: Vec<(String, iced_x86::FormatterTextKind)>,
-    relocation: Option<Arc<SymbolData>>,
-}
-
-impl iced_x86::FormatterOutput for Instruction {
-    fn write(&mut self, text: &str, kind: iced_x86::FormatterTextKind) {
-        self.format.push((text.to_owned(), kind));
-    }
-
-    fn write_number(
-        &mut self,
-        _instruction: &iced_x86::Instruction,
-        _operand: u32,
-        _instruction_operand: Option<u32>,
-        text: &str,
-        _value: u64,
-        _number_kind: iced_x86::NumberKind,
-        kind: iced_x86::FormatterTextKind,
-    ) {
-        if self.relocation.is_none() {
-            self.write(text, kind);
-        }
+thread_local! {
+    // The objects of the window, for reporting errors where they aren't at hand
+    static PROBLEMS: Cell<Option<RwSignal<ObjectList>>> = const { Cell::new(None) };
+}
+
+// Shows `error` in the problems panel of the window
+fn report_problem(error: String) {
+    match PROBLEMS.with(Cell::get) {
+        Some(objects) => report_error(objects, error),
+        None => eprintln!("{}", error),
     }
 }
 
-struct Assembly {
-    instructions: Vec<Instruction>,
+fn open_file(objects: RwSignal<ObjectList>, selection: RwSignal<Selection>) {
+    dialog::pick_files(
+        AsyncFileDialog::new().set_title("Open a binary file..."),
+        move |paths| open_paths(objects, selection, paths, None),
+    );
 }
 
-#[derive(Clone)]
-enum Selection {
-    None,
-    Object(Arc<Object>),
-    Symbol(Symbol),
+// Loads files in the background, then selects `symbol` if given
+fn open_paths(
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+    paths: Vec<PathBuf>,
+    symbol: Option<String>,
+) {
+    session::add_recent(&paths);
+    let (sender, receiver) = mpsc::channel();
+    for path in paths {
+        objects.update(|list| {
+            list.loading.push(Loading {
+                path: path.clone(),
+                objects: 0,
+                members: None,
+            })
+        });
+        let sender = sender.clone();
+        pool::spawn(pool::Priority::Batch, move || {
+            // A panic while decoding fails only this file. `Done` is still sent
+            // by `parse_file` as it unwinds.
+            let errors = sender.clone();
+            let loaded = panic::catch_unwind(AssertUnwindSafe(|| parse_file(path.clone(), sender)));
+            if loaded.is_err() {
+                let error = format!("Loading `{}` failed with a panic", path.display());
+                let _ = errors.send(Loaded::Errors(vec![error]));
+            }
+        });
+    }
+    receive_objects(objects, selection, receiver, symbol);
 }
 
-struct ObjectList {
-    objects: Vec<Arc<Object>>,
+// Adds the objects from the workers to the object list in batches until all
+// workers are done
+// An action applying a batch of messages on the UI thread, with whether the
+// channel closed
+type Batch<T> = Box<dyn FnOnce((Vec<T>, bool)) + Send>;
+
+// Applies what arrives on `receiver` in batches. One thread waits for each
+// batch and hands it over through actions created in a single scope, which
+// is disposed once the channel closes.
+fn receive_batches<T: Send + 'static>(
+    receiver: mpsc::Receiver<T>,
+    apply: impl Fn(Vec<T>, bool) + 'static,
+) {
+    let (actions, armed) = mpsc::channel::<Batch<T>>();
+    std::thread::spawn(move || {
+        while let Ok(action) = armed.recv() {
+            // Wait for the next message, then take whatever else is ready
+            let Ok(first) = receiver.recv() else {
+                action((Vec::new(), true));
+                return;
+            };
+            let mut batch = vec![first];
+            batch.extend(receiver.try_iter());
+            action((batch, false));
+        }
+    });
+    arm_batch(Scope::new(), Rc::new(apply), actions);
 }
 
-fn open_object(objects: &RwSignal<ObjectList>, data: &[u8], name: String, path: PathBuf) {
-    object::File::parse(data)
-        .map(|file| {
-            let mut sections: HashMap<SectionIndex, Section> = file
-                .sections()
-                .filter_map(|section| {
-                    let name = String::from_utf8_lossy(section.name_bytes().ok()?).into_owned();
-                    let data = section.uncompressed_data().ok()?.into_owned();
-                    let relocations = section.relocations().collect();
-                    Some((
-                        section.index(),
-                        Section {
-                            name,
-                            address: section.address(),
-                            data,
-                            symbols: Vec::new(),
-                            relocations,
-                        },
-                    ))
-                })
-                .collect();
+// Hands the receiving thread the action applying the next batch
+fn arm_batch<T: Send + 'static>(
+    scope: Scope,
+    apply: Rc<dyn Fn(Vec<T>, bool)>,
+    actions: mpsc::Sender<Batch<T>>,
+) {
+    let next = actions.clone();
+    let action = create_ext_action(scope, move |(batch, done)| {
+        apply(batch, done);
+        if done {
+            scope.dispose();
+        } else {
+            arm_batch(scope, apply, next);
+        }
+    });
+    let _ = actions.send(Box::new(action));
+}
 
-            // Insert symbol addresses into sections
-            file.symbols().for_each(|symbol| {
-                if symbol.kind() != SymbolKind::Text {
-                    return;
+fn receive_objects(
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+    receiver: mpsc::Receiver<Loaded>,
+    symbol: Option<String>,
+) {
+    receive_batches(receiver, move |batch, done| {
+        let mut pdbs = Vec::new();
+        objects.update(|list| {
+            for loaded in batch {
+                match loaded {
+                    Loaded::Members(path, members) => {
+                        list.loading
+                            .iter_mut()
+                            .filter(|loading| loading.path == path)
+                            .for_each(|loading| loading.members = Some(members));
+                    }
+                    Loaded::Object(object, id) => {
+                        let object = Arc::new(*object);
+                        if let Some(loading) = list
+                            .loading
+                            .iter_mut()
+                            .find(|loading| loading.path == object.path)
+                        {
+                            loading.objects += 1;
+                        }
+                        if let Some(id) = id {
+                            pdbs.push((object.clone(), id));
+                        }
+                        // Members are parsed in parallel, so they're placed
+                        // in the order of the file rather than of arrival
+                        let index = list
+                            .objects
+                            .iter()
+                            .position(|other| {
+                                other.path == object.path && file_order(other) > file_order(&object)
+                            })
+                            .unwrap_or(list.objects.len());
+                        list.objects.insert(index, object);
+                    }
+                    Loaded::Errors(errors) => list.errors.extend(errors),
+                    Loaded::Done(path) => {
+                        if let Some(i) = list.loading.iter().position(|l| l.path == path) {
+                            list.loading.remove(i);
+                        }
+                    }
                 }
+            }
+        });
+        for (object, id) in pdbs {
+            load_pdb(objects, selection, object, id);
+        }
+        if let (true, Some(name)) = (done, &symbol) {
+            let found = objects.with_untracked(|list| search::find_symbol(name, &list.objects));
+            match found {
+                Some(symbol) => selection.set(Selection::Symbol(symbol)),
+                None => report_error(objects, format!("Symbol `{}` not found", name)),
+            }
+        }
+    });
+}
 
-                symbol
-                    .section()
-                    .index()
-                    .and_then(|index| sections.get_mut(&index))
-                    .map(|section| section.symbols.push(symbol.address()));
-            });
+fn file_order(object: &Object) -> (Option<&str>, Option<u64>) {
+    (
+        object.slice.as_deref(),
+        object.member.as_ref().map(|member| member.offset),
+    )
+}
 
-            let section_map: HashMap<SectionIndex, Arc<Section>> = sections
-                .into_iter()
-                .map(|(index, mut section)| {
-                    section.symbols.sort_unstable();
-                    (index, Arc::new(section))
-                })
-                .collect();
+fn loading_text(loading: &[Loading]) -> String {
+    let objects: usize = loading.iter().map(|loading| loading.objects).sum();
+    let members: Option<usize> = loading.iter().map(|loading| loading.members).sum();
+    let name = loading
+        .first()
+        .and_then(|loading| loading.path.file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let files = match loading.len() {
+        1 => format!("`{}`", name),
+        files => format!("{} files", files),
+    };
+    match members {
+        Some(members) => format!(
+            "Loading {}: {} of {} objects",
+            files,
+            units::count(objects as u64),
+            units::count(members as u64)
+        ),
+        None => format!(
+            "Loading {}: {} objects",
+            files,
+            units::count(objects as u64)
+        ),
+    }
+}
 
-            let sections = section_map.values().cloned().collect();
-
-            let symbols: HashMap<_, _> = file
-                .symbols()
-                .filter_map(|symbol| {
-                    // Filter out non-text symbols
-                    (symbol.kind() == SymbolKind::Text).then(|| ())?;
-
-                    let name = String::from_utf8_lossy(symbol.name_bytes().ok()?).into_owned();
-                    let demangled =
-                        symbolic_common::Name::from(&name).demangle(DemangleOptions::complete());
-
-                    let section = symbol
-                        .section()
-                        .index()
-                        .and_then(|index| section_map.get(&index).cloned());
-
-                    Some((
-                        symbol.index(),
-                        Arc::new(SymbolData {
-                            name,
-                            demangled,
-                            section,
-                            address: symbol.address(),
-                            size: symbol.size(),
-                        }),
-                    ))
-                })
-                .collect();
+// Merges a symbol list exported by `nm`, `readelf` or `dumpbin`, or a
+// Breakpad `.sym` file into the selected object
+fn import_symbols(objects: RwSignal<ObjectList>, selection: RwSignal<Selection>) {
+    let object = match selection.get_untracked() {
+        Selection::Object(object) => object,
+        Selection::Symbol(symbol) => symbol.object,
+        _ => return,
+    };
+
+    dialog::pick_file(
+        AsyncFileDialog::new()
+            .set_title("Import a symbol list...")
+            .add_filter("Symbol lists", &["txt", "nm", "sym"]),
+        move |path| merge_symbols(objects, selection, object, path),
+    );
+}
 
-            let mut symbols_sorted: Vec<_> = symbols.values().cloned().collect();
-            symbols_sorted.sort_unstable_by(|a, b| a.name.cmp(&b.name));
-
-            objects.update(|list| {
-                list.objects.push(Arc::new(Object {
-                    name,
-                    path,
-                    format: file.format(),
-                    symbols,
-                    symbols_sorted,
-                    sections,
-                }))
-            });
-        })
-        .ok();
-}
-
-fn open_file(objects: RwSignal<ObjectList>) {
-    let files = rfd::FileDialog::new()
-        .set_title("Open a binary file...")
-        .pick_files();
-
-    files.map(|files| {
-        for path in files {
-            let file = fs::read(&path).unwrap();
-
-            if let Ok(archive) = ArchiveFile::parse(file.as_slice()) {
-                for member in archive.members() {
-                    member
-                        .map(|member| {
-                            let name = String::from_utf8_lossy(member.name()).into_owned();
-                            member
-                                .data(file.as_slice())
-                                .map(|data| {
-                                    open_object(&objects, data, name, path.clone());
-                                })
-                                .ok();
-                        })
-                        .ok();
-                }
-            }
+fn merge_symbols(
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+    object: Arc<Object>,
+    path: PathBuf,
+) {
+    let old = object.clone();
+    let done = create_ext_action(
+        Scope::new(),
+        move |merged: Result<Object, String>| match merged {
+            Ok(merged) => replace_object(objects, selection, &old, Arc::new(merged)),
+            Err(error) => report_error(objects, error),
+        },
+    );
+    // Merging decodes every function again for the references
+    pool::spawn(pool::Priority::Interactive, move || {
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(error) => return done(Err(format!("`{}`: {}", path.display(), error))),
+        };
 
-            open_object(
-                &objects,
-                file.as_slice(),
-                path.file_name()
-                    .map(|name| name.to_string_lossy())
-                    .unwrap_or_default()
-                    .into_owned(),
-                path.clone(),
-            );
+        let (symbols, lines) = if breakpad::is_breakpad(&text) {
+            let breakpad = breakpad::parse(&text, object.image_base);
+            (breakpad.symbols, breakpad.lines)
+        } else {
+            (overlay::parse(&text), None)
+        };
+        let mut merged = overlay::merge(&object, &symbols);
+        if merged.symbols_sorted.len() == object.symbols_sorted.len() && lines.is_none() {
+            return done(Err(format!(
+                "`{}`: no new function symbols were found",
+                path.display()
+            )));
         }
+        merged.lines = merged.lines.or(lines);
+        done(Ok(merged));
     });
 }
 
@@ -313,301 +716,2511 @@ fn button(label: impl Display, click: impl Fn(&Event) -> bool + 'static) -> Labe
         .style(|s| {
             s.border_radius(3.0)
                 .padding(6.0)
-                .background(Color::WHITE)
+                .background(theme().button)
                 // .box_shadow_blur(1.0)
                 // .box_shadow_color(Color::GRAY)
-                .border_color(Color::GRAY)
+                .border_color(theme().button_border)
+                .border(0.5)
+                .margin(4)
+        })
+        .on_click(click)
+        .hover_style(|s| s.background(theme().button_hover))
+        .active_style(|s| s.color(Color::WHITE).background(theme().button_active))
+        .keyboard_navigatable()
+        .focus_visible_style(|s| s.border_color(Color::BLUE).border(2.))
+}
+
+fn toggle_button(
+    label: impl Display,
+    active: impl Fn() -> bool + 'static,
+    click: impl Fn(&Event) -> bool + 'static,
+) -> Label {
+    text(label)
+        .style(move |s| {
+            s.border_radius(3.0)
+                .padding(6.0)
+                .background(if active() {
+                    theme().button_on
+                } else {
+                    theme().button
+                })
+                .border_color(theme().button_border)
                 .border(0.5)
                 .margin(4)
         })
         .on_click(click)
-        .hover_style(|s| s.background(Color::LIGHT_GREEN))
-        .active_style(|s| s.color(Color::WHITE).background(Color::DARK_GREEN))
+        .hover_style(|s| s.background(theme().button_hover))
+        .active_style(|s| s.color(Color::WHITE).background(theme().button_active))
         .keyboard_navigatable()
         .focus_visible_style(|s| s.border_color(Color::BLUE).border(2.))
 }
 
+fn syntax_picker(
+    current: impl Fn() -> Syntax + Copy + 'static,
+    choose: impl Fn(Syntax) + Copy + 'static,
+) -> impl View {
+    stack((
+        text("Syntax:").style(|s| s.padding_left(6.0)),
+        list(
+            || Syntax::ALL.into_iter(),
+            |syntax| *syntax,
+            move |syntax| {
+                toggle_button(
+                    syntax.label(),
+                    move || current() == syntax,
+                    move |_| {
+                        choose(syntax);
+                        true
+                    },
+                )
+            },
+        )
+        .style(|s| s.flex_row()),
+    ))
+    .style(|s| s.flex_row().items_center())
+}
+
+fn heat_picker(settings: RwSignal<Settings>) -> impl View {
+    stack((
+        text("Encoding Heat:").style(|s| s.padding_left(6.0)),
+        list(
+            || EncodingHeat::ALL.into_iter(),
+            |heat| *heat,
+            move |heat| {
+                toggle_button(
+                    heat.label(),
+                    move || settings.with(|s| s.encoding_heat == heat),
+                    move |_| {
+                        settings.update(|s| s.encoding_heat = heat);
+                        true
+                    },
+                )
+            },
+        )
+        .style(|s| s.flex_row()),
+    ))
+    .style(|s| s.flex_row().items_center())
+}
+
+// Changing the demangling renames the symbols of the loaded objects in the
+// background
+fn demangling_picker(
+    settings: RwSignal<Settings>,
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+) -> impl View {
+    stack((
+        text("Demangling:").style(|s| s.padding_left(6.0)),
+        list(
+            || Demangling::ALL.into_iter(),
+            |demangling| *demangling,
+            move |demangling| {
+                toggle_button(
+                    demangling.label(),
+                    move || settings.with(|s| s.demangling == demangling),
+                    move |_| {
+                        settings.update(|s| s.demangling = demangling);
+                        redemangle(objects, selection, demangling);
+                        true
+                    },
+                )
+            },
+        )
+        .style(|s| s.flex_row()),
+    ))
+    .style(|s| s.flex_row().items_center())
+}
+
+// Background work already running finishes before a lower limit applies
+fn threads_picker(settings: RwSignal<Settings>) -> impl View {
+    let change = move |delta: isize| {
+        settings.update(|s| {
+            s.threads = s.threads.saturating_add_signed(delta).max(1);
+            pool::set_threads(s.threads);
+        })
+    };
+    stack((
+        text("Threads:").style(|s| s.padding_left(6.0)),
+        button("-", move |_| {
+            change(-1);
+            true
+        }),
+        label(move || settings.with(|s| s.threads.to_string())).style(|s| s.padding_horiz(4.0)),
+        button("+", move |_| {
+            change(1);
+            true
+        }),
+    ))
+    .style(|s| s.flex_row().items_center())
+}
+
+fn target_picker(settings: RwSignal<Settings>) -> impl View {
+    stack((
+        text("Target CPU:").style(|s| s.padding_left(6.0)),
+        list(
+            || Microarch::ALL.into_iter(),
+            |target| *target,
+            move |target| {
+                toggle_button(
+                    target.label(),
+                    move || settings.with(|s| s.target == target),
+                    move |_| {
+                        settings.update(|s| s.target = target);
+                        true
+                    },
+                )
+            },
+        )
+        .style(|s| s.flex_row()),
+        toggle_button(
+            "Uops",
+            move || settings.with(|s| s.show_uops),
+            move |_| {
+                settings.update(|s| s.show_uops = !s.show_uops);
+                true
+            },
+        ),
+    ))
+    .style(|s| s.flex_row().items_center())
+}
+
 fn header(label: impl Display) -> Label {
     text(label).style(|s| {
         s.padding(5.0)
-            .background(Color::WHITE_SMOKE)
+            .background(theme().panel)
             .width_full()
             .border_bottom(0.5)
-            .border_color(Color::LIGHT_GRAY)
+            .border_color(theme().border)
     })
 }
 
-fn assembly(symbol: Symbol, selection: RwSignal<Selection>) -> Box<dyn View> {
-    if let Some(assembly) = symbol.data.assembly(&symbol.object) {
-        let instr = virtual_list(
-            VirtualListDirection::Vertical,
-            VirtualListItemSize::Fixed(Box::new(|| 26.0)),
-            move || {
-                assembly
-                    .instructions
-                    .iter()
-                    .cloned()
-                    .collect::<im::Vector<_>>()
-            },
-            |i| i.address,
-            move |i| {
-                let address = text(format!("{:016X} ", i.address))
-                    .style(|s| s.width(200).color(Color::rgb8(118, 141, 169)));
-
-                let format: Vec<_> = i.format.iter().map(|(s, _)| &**s).collect();
-                let format: String = format.join("");
-
-                let family: Vec<FamilyOwned> = FamilyOwned::parse_list("Consolas").collect();
-                let attrs = Attrs::new()
-                    .color(Color::BLACK)
-                    .font_size(14.0)
-                    .family(&family);
-                let mut attrs_list = AttrsList::new(attrs);
-                let mut offset = 0;
-                for (string, kind) in i.format {
-                    let color = match kind {
-                        iced_x86::FormatterTextKind::Mnemonic
-                        | iced_x86::FormatterTextKind::Prefix => Color::rgb8(116, 94, 147),
-                        iced_x86::FormatterTextKind::Register => Color::rgb8(87, 103, 65),
-                        iced_x86::FormatterTextKind::Number => Color::rgb8(80, 107, 135),
-                        _ => Color::rgb8(102, 102, 102),
-                    };
-                    attrs_list.add_span(
-                        Range {
-                            start: offset,
-                            end: offset + string.len(),
-                        },
-                        Attrs::new()
-                            .color(color)
-                            .family(&family)
-                            .font_size(14.0)
-                            .weight(if kind == iced_x86::FormatterTextKind::Mnemonic {
-                                Weight::BOLD
-                            } else {
-                                Weight::NORMAL
-                            }),
-                    );
-                    offset += string.len();
-                }
-                let mut text_layout = TextLayout::new();
-                text_layout.set_text(&format, attrs_list);
-
-                let format = rich_text(move || text_layout.clone());
-                let reloc = i
-                    .relocation
-                    .map(|s| {
-                        let symbol = Symbol {
-                            object: symbol.object.clone(),
-                            data: s.clone(),
-                        };
-                        text(s.demangled.as_ref().unwrap_or(&s.name).clone()).on_click(move |_| {
-                            selection.set(Selection::Symbol(symbol.clone()));
-                            true
-                        })
-                    })
-                    .unwrap_or_else(|| text(""));
+// Laid out rows are reused while scrolling. Rows with the same text share a
+// layout, and the font is part of the key so font changes don't reuse stale
+// layouts.
+const LAYOUT_CACHE_SIZE: usize = 16 * 1024;
+
+type LayoutKey = (
+    String,
+    u32,
+    theme::Kind,
+    bool,
+    Vec<(String, iced_x86::FormatterTextKind)>,
+    Vec<usize>,
+);
+
+thread_local! {
+    static LAYOUTS: RefCell<HashMap<LayoutKey, TextLayout>> = RefCell::new(HashMap::new());
+}
 
-                let reloc = reloc
-                    .style(|s| {
-                        s.cursor(CursorStyle::Pointer)
-                            .color(Color::rgb8(50, 50, 50))
-                    })
-                    .hover_style(|s| {
-                        s.color(Color::rgb8(105, 89, 132))
-                            .border_radius(6)
-                            .border_bottom(2)
-                            .border_color(Color::rgb8(105, 89, 132))
-                            .background(Color::WHITE.with_alpha_factor(0.6))
-                    });
+// The layout of the formatted instruction, with the parts in `highlighted`
+// emphasized
+fn instruction_layout(
+    format_parts: &[(String, iced_x86::FormatterTextKind)],
+    padding: bool,
+    highlighted: &[usize],
+) -> TextLayout {
+    let font = theme::font();
+    let key = (
+        font.family.clone(),
+        font.size.to_bits(),
+        theme::kind(),
+        padding,
+        format_parts.to_vec(),
+        highlighted.to_vec(),
+    );
+    if let Some(layout) = LAYOUTS.with(|layouts| layouts.borrow().get(&key).cloned()) {
+        return layout;
+    }
 
-                //let bytes: Vec<String> = i.bytes.iter().map(|b| format!("{:02X} ", b)).collect();
-                //let bytes = text(bytes.join(" ")).style(|s| s.width(200).color(Color::GRAY));
-                stack((address, format, reloc))
-                    .style(|s| {
-                        s.font_family("Consolas".to_string())
-                            .font_size(14.0)
-                            .padding(3)
-                            .height(26.0)
-                    })
-                    .hover_style(|s| s.background(Color::rgba8(228, 237, 216, 160)))
+    let format: Vec<_> = format_parts.iter().map(|(s, _)| &**s).collect();
+    let format: String = format.join("");
+
+    let family: Vec<FamilyOwned> = FamilyOwned::parse_list(&font.family).collect();
+    let attrs = Attrs::new()
+        .color(theme().text)
+        .font_size(font.size)
+        .family(&family);
+    let mut attrs_list = AttrsList::new(attrs);
+    let mut offset = 0;
+    for (index, (string, kind)) in format_parts.iter().map(|(s, kind)| (s, *kind)).enumerate() {
+        let highlight = highlighted.contains(&index);
+        let color = match kind {
+            _ if padding => theme().padding,
+            _ if highlight => theme().register_highlight,
+            iced_x86::FormatterTextKind::Mnemonic | iced_x86::FormatterTextKind::Prefix => {
+                theme().mnemonic
+            }
+            iced_x86::FormatterTextKind::Register => theme().register,
+            iced_x86::FormatterTextKind::Number => theme().number,
+            _ => theme().dim,
+        };
+        attrs_list.add_span(
+            Range {
+                start: offset,
+                end: offset + string.len(),
             },
-        )
-        .style(|s| s.flex_col().padding(5).width_full());
+            Attrs::new()
+                .color(color)
+                .family(&family)
+                .font_size(font.size)
+                .weight(
+                    if (kind == iced_x86::FormatterTextKind::Mnemonic || highlight) && !padding {
+                        Weight::BOLD
+                    } else {
+                        Weight::NORMAL
+                    },
+                ),
+        );
+        offset += string.len();
+    }
+    let mut text_layout = TextLayout::new();
+    text_layout.set_text(&format, attrs_list);
 
-        let instr = scroll(instr).style(|s| {
-            s.width_full()
-                .height_full()
-                .background(Color::rgb8(248, 248, 248))
-        });
+    LAYOUTS.with(|layouts| {
+        let mut layouts = layouts.borrow_mut();
+        if layouts.len() >= LAYOUT_CACHE_SIZE {
+            layouts.clear();
+        }
+        layouts.insert(key, text_layout.clone());
+    });
+    text_layout
+}
 
-        Box::new(instr)
-    } else {
-        Box::new(text("Assembly unavailable").style(|s| s.padding(5.0)))
+// The address, or its offset from `relative_to`
+fn format_address(address: u64, relative_to: Option<u64>) -> String {
+    match relative_to {
+        Some(base) => format!("+{:X}", address.wrapping_sub(base)),
+        None => format!("{:016X}", address),
     }
 }
 
-fn main_container(current: Selection, selection: RwSignal<Selection>) -> Box<dyn View> {
-    match current {
-        Selection::None => Box::new(text("Nothing selected").style(|s| s.padding(5.0))),
-        Selection::Object(o) => {
-            let data = stack((
-                header("Object Info"),
-                text(format!("Object: `{}`", o.name)).style(|s| s.padding(5.0)),
-                text(format!("Format: {:?}", o.format)).style(|s| s.padding(5.0)),
-                text(format!("Symbols: {:?}", o.symbols.len())).style(|s| s.padding(5.0)),
-            ))
-            .style(|s| s.flex_col().width_full());
-            Box::new(data)
-        }
-        Selection::Symbol(symbol) => {
-            let o = &symbol.data;
-            let info = stack((
-                text(format!("Symbol: `{}`", o.name)).style(|s| s.padding(5.0)),
-                o.demangled
-                    .as_ref()
-                    .map(|demangled| {
-                        container_box(
-                            text(format!("Demangled: `{}`", demangled)).style(|s| s.padding(5.0)),
-                        )
-                    })
-                    .unwrap_or_else(|| container_box(empty())),
-                o.section
-                    .as_ref()
-                    .map(|section| {
-                        container_box(
-                            text(format!("Section: `{}`", section.name)).style(|s| s.padding(5.0)),
-                        )
-                    })
-                    .unwrap_or_else(|| container_box(empty())),
-                text(format!("Size: {} bytes", o.size)).style(|s| s.padding(5.0)),
-                text(format!(
-                    "Data Length: `{:?}`",
-                    o.data().map(|d| d.len()).unwrap_or_default()
-                ))
-                .style(|s| s.padding(5.0)),
-            ))
-            .style(|s| s.flex_col());
+fn address_text(address: u64, relative_to: Option<u64>) -> Label {
+    text(format!("{} ", format_address(address, relative_to)))
+        .style(|s| s.width(200).color(theme().address))
+}
 
-            let data = stack((
-                header("Symbol Info"),
-                scroll(info),
-                header("Assembly"),
-                assembly(symbol, selection),
-            ))
+// The address column of an instruction, for views which keep it pinned while
+// the instructions scroll horizontally
+fn address_view(i: &Instruction, density: Density, relative_to: Option<u64>) -> Label {
+    let stripe = i.stripe;
+    address_text(i.address, relative_to).style(move |s| {
+        s.apply_if(stripe, |s| s.background(theme().stripe))
+            .font_family(theme::font().family)
+            .font_size(theme::font().size)
+            .padding_horiz(3)
+            .padding_vert(density.row_padding() - 2.0)
+            .height(density.row_height())
+    })
+}
+
+// Instructions longer than this have the rest of their bytes elided, so the
+// column has a fixed width
+const BYTES_SHOWN: usize = 8;
+
+fn bytes_text(bytes: &[u8]) -> Label {
+    let mut parts: Vec<String> = bytes
+        .iter()
+        .take(BYTES_SHOWN)
+        .map(|b| format!("{:02X}", b))
+        .collect();
+    if bytes.len() > BYTES_SHOWN {
+        parts.push("..".to_owned());
+    }
+    text(parts.join(" ")).style(|s| {
+        s.width(210)
+            .min_width(210)
+            .color(theme().faint)
+            .text_overflow(TextOverflow::Clip)
+    })
+}
+
+// A branch to a local label, which scrolls the assembly view to `offset`
+struct LocalJump {
+    label: String,
+    scroll: RwSignal<f64>,
+    offset: f64,
+}
+
+// The register highlighted in the assembly while it's hovered, or after it's
+// clicked until another is
+#[derive(Clone, Copy, Default)]
+struct RegisterHighlight {
+    hovered: Option<iced_x86::Register>,
+    pinned: Option<iced_x86::Register>,
+}
+
+impl RegisterHighlight {
+    fn active(&self) -> Option<iced_x86::Register> {
+        self.hovered.or(self.pinned)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn instruction_view(
+    i: Instruction,
+    object: &Arc<Object>,
+    density: Density,
+    show_address: bool,
+    relative_to: Option<u64>,
+    show_bytes: bool,
+    jump: Option<LocalJump>,
+    registers: RwSignal<RegisterHighlight>,
+    selection: RwSignal<Selection>,
+    history: RwSignal<History>,
+    listing: Option<Rc<copy::Listing>>,
+    comments: Option<RwSignal<HashMap<u64, String>>>,
+) -> Stack {
+    let menu_instruction = i.clone();
+    let address = if show_address {
+        container_box(address_text(i.address, relative_to))
+    } else {
+        container_box(empty())
+    };
+
+    let bytes = if show_bytes {
+        container_box(bytes_text(&i.bytes))
+    } else {
+        container_box(empty())
+    };
+
+    let text_layout = instruction_layout(&i.format, i.padding, &[]);
+
+    // Finds the register under the pointer
+    let shared = Rc::new((i.clone(), text_layout.clone()));
+    let register_at = move |pos: Point| shared.0.register_at(shared.1.hit_point(pos).index);
+    let click_register = register_at.clone();
+
+    let highlighted = i.clone();
+    let format = rich_text(move || {
+        let parts = highlighted.register_parts(registers.with(RegisterHighlight::active));
+        if parts.is_empty() {
+            text_layout.clone()
+        } else {
+            instruction_layout(&highlighted.format, highlighted.padding, &parts)
+        }
+    })
+    .on_event(EventListener::PointerMove, move |e| {
+        if let Event::PointerMove(e) = e {
+            let register = register_at(e.pos);
+            if registers.with_untracked(|r| r.hovered != register) {
+                registers.update(|r| r.hovered = register);
+            }
+        }
+        false
+    })
+    .on_event(EventListener::PointerLeave, move |_| {
+        if registers.with_untracked(|r| r.hovered.is_some()) {
+            registers.update(|r| r.hovered = None);
+        }
+        false
+    })
+    .on_event(EventListener::PointerDown, move |e| {
+        if let Event::PointerDown(e) = e {
+            // Clicking the pinned register or no register clears the pin
+            let register = click_register(e.pos)
+                .filter(|&register| registers.with_untracked(|r| r.pinned != Some(register)));
+            registers.update(|r| r.pinned = register);
+        }
+        false
+    });
+    let format = match i.decoded {
+        Some(decoded) => container_box(tooltip(format, move || isa::tooltip_view(&decoded))),
+        None => container_box(format),
+    };
+    let reloc = match (&i.relocation, jump) {
+        (Some(s), _) => {
+            let symbol = Symbol {
+                object: object.clone(),
+                data: s.clone(),
+            };
+            // References past the start of a symbol land on the symbol they're
+            // within, with the referenced instruction selected
+            let address = i.relocation_address().filter(|_| i.relocation_offset != 0);
+            let within = address.and_then(|address| {
+                let data = object.symbol_containing(s.section.as_ref()?.index, address)?;
+                let symbol = Symbol {
+                    object: object.clone(),
+                    data,
+                };
+                Some((symbol, address))
+            });
+            text(i.relocation_name().unwrap_or_default()).on_click(move |_| {
+                match &within {
+                    Some((symbol, address)) => {
+                        history::jump_into(selection, history, symbol.clone(), *address)
+                    }
+                    None => history::jump(selection, history, Selection::Symbol(symbol.clone())),
+                }
+                true
+            })
+        }
+        (None, Some(jump)) => text(jump.label).on_click(move |_| {
+            jump.scroll.set(jump.offset);
+            true
+        }),
+        (None, None) => text(""),
+    };
+
+    let reloc = reloc
+        .style(|s| s.cursor(CursorStyle::Pointer).color(theme().text))
+        .hover_style(|s| {
+            s.color(theme().link)
+                .border_radius(6)
+                .border_bottom(2)
+                .border_color(theme().link)
+                .background(theme().background.with_alpha_factor(0.6))
+        });
+
+    let badge = i
+        .reference
+        .map(|reference| {
+            container_box(text(reference.label()).style(move |s| {
+                s.color(Color::WHITE)
+                    .background(relocations::color(reference))
+                    .border_radius(3)
+                    .padding_horiz(4)
+                    .margin_right(4)
+                    .font_size(11.0)
+            }))
+        })
+        .unwrap_or_else(|| container_box(empty()));
+
+    let notes = if i.notes.is_empty() {
+        String::new()
+    } else {
+        format!("  ; {}", i.notes.join("; "))
+    };
+    let notes = text(notes).style(|s| s.color(theme().faint));
+    let commented = i.address;
+    let comment = label(move || {
+        comments
+            .and_then(|comments| comments.with(|comments| comments.get(&commented).cloned()))
+            .map(|comment| format!("  ; {}", comment))
+            .unwrap_or_default()
+    })
+    .style(|s| s.color(theme().source));
+
+    let stripe = i.stripe;
+    let invalid = i.invalid;
+    let object = object.clone();
+    stack((address, bytes, format, badge, reloc, notes, comment))
+        .context_menu(move || {
+            copy::instruction_menu(&object, &menu_instruction, listing.as_deref())
+        })
+        .style(move |s| {
+            s.apply_if(stripe, |s| s.background(theme().stripe))
+                .apply_if(invalid, |s| {
+                    s.background(theme().error.with_alpha_factor(0.15))
+                })
+                .font_family(theme::font().family)
+                .font_size(theme::font().size)
+                .padding_horiz(3)
+                .padding_vert(density.row_padding() - 2.0)
+                .height(density.row_height())
+        })
+        .hover_style(|s| s.background(theme().instruction_hover))
+}
+
+fn tab_bar(tabs: &'static [&'static str], active: RwSignal<usize>) -> impl View {
+    list(
+        move || tabs.iter().copied().enumerate(),
+        |(i, _)| *i,
+        move |(i, name)| {
+            text(name)
+                .style(move |s| {
+                    s.padding_horiz(12.0)
+                        .padding_vert(5.0)
+                        .border_bottom(2.0)
+                        .border_color(if active.get() == i {
+                            theme().link
+                        } else {
+                            Color::TRANSPARENT
+                        })
+                })
+                .hover_style(|s| s.background(theme().hover))
+                .on_click(move |_| {
+                    active.set(i);
+                    true
+                })
+        },
+    )
+    .style(|s| {
+        s.flex_row()
+            .width_full()
+            .border_bottom(0.5)
+            .border_color(theme().border)
+    })
+}
+
+// A row of the assembly view. Label and source rows precede the instructions
+// of their statement.
+#[derive(Clone)]
+enum AssemblyRow {
+    Label(u64, String),
+    Source(u64, String),
+    Instruction(Instruction),
+}
+
+impl AssemblyRow {
+    fn address(&self) -> u64 {
+        match self {
+            AssemblyRow::Label(address, _) | AssemblyRow::Source(address, _) => *address,
+            AssemblyRow::Instruction(i) => i.address,
+        }
+    }
+
+    fn key(&self) -> (u64, u8) {
+        let order = match self {
+            AssemblyRow::Label(..) => 0,
+            AssemblyRow::Source(..) => 1,
+            AssemblyRow::Instruction(_) => 2,
+        };
+        (self.address(), order)
+    }
+}
+
+// The top of the assembly view as a symbol name and an offset into it, so the
+// view can return there when it's rebuilt for a reloaded object
+type ViewPosition = Option<(String, u64)>;
+
+// Instructions selected in the assembly view, from the clicked address to the
+// shift+clicked one
+#[derive(Clone, Copy, PartialEq)]
+struct AddressRange {
+    anchor: u64,
+    end: u64,
+}
+
+impl AddressRange {
+    fn bounds(self) -> (u64, u64) {
+        (self.anchor.min(self.end), self.anchor.max(self.end))
+    }
+
+    fn contains(self, address: u64) -> bool {
+        let (start, end) = self.bounds();
+        (start..=end).contains(&address)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn assembly(
+    symbol: Symbol,
+    settings: &Settings,
+    selection: RwSignal<Selection>,
+    history: RwSignal<History>,
+    position: RwSignal<ViewPosition>,
+    range: RwSignal<Option<AddressRange>>,
+    hint: RwSignal<Option<&'static str>>,
+    samples: Arc<HashMap<u64, u64>>,
+    cycles: Arc<HashMap<u64, mca::Cycles>>,
+    annotations: annotations::Revision,
+    goto: RwSignal<Option<u64>>,
+) -> Box<dyn View> {
+    if let Some(assembly) = symbol.data.assembly(&symbol.object, settings) {
+        let name = symbol.data.name.clone();
+        let comments = annotations::comments_signal(&symbol, annotations);
+        let base = symbol.data.address;
+        let lines = symbol.object.lines.clone();
+        let labels = assembly.local_labels();
+        let mut rows = im::Vector::new();
+        for i in &assembly.instructions {
+            if let Some(label) = labels.get(&i.address) {
+                rows.push_back(AssemblyRow::Label(i.address, format!("{}:", label)));
+            }
+            let location = i.source.filter(|_| settings.show_source);
+            if let (Some(location), Some(lines)) = (location, &lines) {
+                rows.push_back(AssemblyRow::Source(i.address, lines.describe(location)));
+            }
+            let mut i = i.clone();
+            // Local targets are named by a link to their label instead
+            if i.local_target()
+                .is_some_and(|target| labels.contains_key(&target))
+            {
+                let target = i.target;
+                if let Some(part) = i
+                    .format
+                    .iter_mut()
+                    .rev()
+                    .find(|(part, _)| diff::parse_number(part) == target)
+                {
+                    part.0.clear();
+                }
+            }
+            rows.push_back(AssemblyRow::Instruction(i));
+        }
+        let addresses: Vec<u64> = rows.iter().map(AssemblyRow::address).collect();
+        // Rows of instructions, which the arrow keys move the selection between
+        let instruction_rows: Vec<usize> = rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| matches!(row, AssemblyRow::Instruction(_)))
+            .map(|(index, _)| index)
+            .collect();
+
+        let label_rows: HashMap<u64, usize> = rows
+            .iter()
+            .enumerate()
+            .filter_map(|(index, row)| match row {
+                AssemblyRow::Label(address, _) => Some((*address, index)),
+                _ => None,
+            })
+            .collect();
+        let mut jumps = Vec::new();
+        let mut links = HashMap::new();
+        for (index, row) in rows.iter().enumerate() {
+            let AssemblyRow::Instruction(i) = row else {
+                continue;
+            };
+            let Some(target) = i.local_target() else {
+                continue;
+            };
+            if let Some(&label_row) = label_rows.get(&target) {
+                jumps.push((index, label_row));
+                links.insert(i.address, (labels[&target].clone(), label_row));
+            }
+        }
+        let gutter = jumps::gutter(&jumps, rows.len());
+        let gutter_width = gutter.first().map_or(0, |arrows| arrows.chars().count()) as f64 * 8.5;
+        // Jumps into the symbol select the instruction landed in and show it a
+        // few rows down
+        let landing = history::take_landing(history, &name)
+            .and_then(|landing| addresses.iter().rposition(|&address| address <= landing));
+        if let Some(row) = landing {
+            range.set(Some(AddressRange {
+                anchor: addresses[row],
+                end: addresses[row],
+            }));
+        }
+        let start = match landing {
+            Some(row) => Some(row.saturating_sub(2)),
+            None => position.with_untracked(|position| match position {
+                Some((symbol, offset)) if *symbol == name => addresses
+                    .iter()
+                    .position(|&address| address >= base + offset),
+                _ => None,
+            }),
+        };
+        let density = settings.density;
+        let show_bytes = settings.show_bytes;
+        let relative_to = settings.relative_addresses.then_some(base);
+        let heat = settings.encoding_heat;
+        let uops_width = if settings.show_uops { 28.0 } else { 0.0 };
+        // Profiled functions show the share of their samples on each instruction
+        // and are colored by them instead of by their encodings
+        let samples_total: u64 = samples.values().sum();
+        let hottest = samples.values().copied().max().unwrap_or(0);
+        let samples_width = if samples_total > 0 { 52.0 } else { 0.0 };
+        let row_samples = samples.clone();
+        // Latency and reciprocal throughput from an `llvm-mca` report
+        let cycles_width = if cycles.is_empty() { 0.0 } else { 64.0 };
+        let row_height = density.row_height();
+        let scroll_y = create_rw_signal(start.unwrap_or(0) as f64 * row_height);
+        // Selects the instruction gone to, a few rows down like jumps into the
+        // symbol
+        let goto_addresses = addresses.clone();
+        create_effect(move |_| {
+            let Some(address) = goto.get() else {
+                return;
+            };
+            if let Some(row) = goto_addresses.iter().rposition(|&a| a <= address) {
+                let address = goto_addresses[row];
+                range.set(Some(AddressRange {
+                    anchor: address,
+                    end: address,
+                }));
+                scroll_y.set(row.saturating_sub(2) as f64 * row_height);
+            }
+            goto.set(None);
+        });
+        let listing = Rc::new(copy::Listing {
+            symbol: symbol.clone(),
+            settings: settings.clone(),
+            range,
+        });
+
+        let scroll_x = create_rw_signal(0.0);
+        let registers = create_rw_signal(RegisterHighlight::default());
+
+        // Addresses stay in view when long instructions are scrolled horizontally
+        let address_rows: im::Vector<(AssemblyRow, String)> = rows
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(index, row)| (row, gutter.get(index).cloned().unwrap_or_default()))
+            .collect();
+        let address_list = virtual_list(
+            VirtualListDirection::Vertical,
+            VirtualListItemSize::Fixed(Box::new(move || row_height)),
+            move || address_rows.clone(),
+            |(row, _)| row.key(),
+            move |(row, arrows)| {
+                let explanation = match &row {
+                    AssemblyRow::Instruction(i) => i.hint,
+                    _ => None,
+                };
+                let marker = text(if explanation.is_some() { "⚑" } else { "" })
+                    .style(move |s| {
+                        s.width(14)
+                            .height(row_height)
+                            .padding_vert(density.row_padding() - 2.0)
+                            .color(theme().error)
+                    })
+                    .on_event(EventListener::PointerEnter, move |_| {
+                        if explanation.is_some() {
+                            hint.set(explanation);
+                        }
+                        false
+                    })
+                    .on_event(EventListener::PointerLeave, move |_| {
+                        if explanation.is_some() {
+                            hint.set(None);
+                        }
+                        false
+                    });
+                let uops = match &row {
+                    AssemblyRow::Instruction(i) if uops_width > 0.0 => {
+                        i.uops.map_or("?".to_owned(), |uops| uops.to_string())
+                    }
+                    _ => String::new(),
+                };
+                let uops = text(uops).style(move |s| {
+                    s.width(uops_width)
+                        .height(row_height)
+                        .padding_vert(density.row_padding() - 2.0)
+                        .justify_end()
+                        .color(theme().dim)
+                });
+                let share = match &row {
+                    AssemblyRow::Instruction(i) => row_samples.get(&i.address).map(|&count| {
+                        format!("{:.1}%", count as f64 * 100.0 / samples_total as f64)
+                    }),
+                    _ => None,
+                };
+                let share = text(share.unwrap_or_default()).style(move |s| {
+                    s.width(samples_width)
+                        .height(row_height)
+                        .padding_vert(density.row_padding() - 2.0)
+                        .justify_end()
+                        .color(theme().dim)
+                });
+                let row_cycles = match &row {
+                    AssemblyRow::Instruction(i) => cycles
+                        .get(&i.address)
+                        .map(|cycles| format!("{} / {:.2}", cycles.latency, cycles.throughput)),
+                    _ => None,
+                };
+                let row_cycles = text(row_cycles.unwrap_or_default()).style(move |s| {
+                    s.width(cycles_width)
+                        .height(row_height)
+                        .padding_vert(density.row_padding() - 2.0)
+                        .justify_end()
+                        .color(theme().dim)
+                });
+                let address = match row {
+                    AssemblyRow::Label(..) | AssemblyRow::Source(..) => {
+                        container_box(empty().style(move |s| s.width(200).height(row_height)))
+                    }
+                    AssemblyRow::Instruction(i) => {
+                        let boundary = boundary(i.address, i.bytes.len());
+                        container_box(
+                            address_view(&i, density, relative_to)
+                                .style(move |s| boundary_style(s, boundary)),
+                        )
+                    }
+                };
+                let arrows = text(arrows).style(move |s| {
+                    s.width(gutter_width)
+                        .height(row_height)
+                        .padding_vert(density.row_padding() - 2.0)
+                        .font_family(theme::font().family)
+                        .font_size(theme::font().size)
+                        .color(theme().faint)
+                });
+                stack((address, uops, share, row_cycles, marker, arrows)).style(|s| s.flex_row())
+            },
+        )
+        .style(|s| s.flex_col().padding_vert(5).padding_left(5));
+        let address_list = scroll(address_list)
+            .on_scroll(move |rect| {
+                if scroll_y.get_untracked() != rect.y0 {
+                    scroll_y.set(rect.y0);
+                }
+            })
+            .on_scroll_to(move || Some(Point::new(0.0, scroll_y.get())))
+            .style(move |s| {
+                s.width(226.0 + uops_width + samples_width + cycles_width + gutter_width)
+                    .height_full()
+                    .border_right(0.5)
+                    .border_color(theme().border)
+                    .background(theme().code)
+            });
+
+        let instr = virtual_list(
+            VirtualListDirection::Vertical,
+            VirtualListItemSize::Fixed(Box::new(move || row_height)),
+            move || rows.clone(),
+            AssemblyRow::key,
+            move |row| match row {
+                AssemblyRow::Label(_, label) => container_box(text(label).style(move |s| {
+                    s.height(row_height)
+                        .padding_horiz(3)
+                        .padding_vert(density.row_padding() - 2.0)
+                        .font_family(theme::font().family)
+                        .color(theme().link)
+                })),
+                AssemblyRow::Source(_, line) => container_box(text(line).style(move |s| {
+                    s.height(row_height)
+                        .padding_horiz(3)
+                        .padding_vert(density.row_padding() - 2.0)
+                        .font_family(theme::font().family)
+                        .color(theme().source)
+                        .background(theme().source_background)
+                })),
+                AssemblyRow::Instruction(i) => {
+                    let address = i.address;
+                    let boundary = boundary(address, i.bytes.len());
+                    let heat = match samples.get(&address) {
+                        _ if hottest == 0 => encoding_heat(heat, &i),
+                        Some(&count) => count as f32 / hottest as f32,
+                        None => 0.0,
+                    };
+                    // Keeps a few rows above the label in view for context
+                    let jump = links.get(&address).map(|(label, row)| LocalJump {
+                        label: label.clone(),
+                        scroll: scroll_y,
+                        offset: row.saturating_sub(2) as f64 * row_height,
+                    });
+                    let view = instruction_view(
+                        i,
+                        &symbol.object,
+                        density,
+                        false,
+                        None,
+                        show_bytes,
+                        jump,
+                        registers,
+                        selection,
+                        history,
+                        Some(listing.clone()),
+                        Some(comments),
+                    )
+                    .style(move |s| {
+                        let selected = range.get().is_some_and(|range| range.contains(address));
+                        boundary_style(s, boundary)
+                            .apply_if(heat > 0.0, |s| {
+                                s.background(theme().hot.with_alpha_factor(heat))
+                            })
+                            .apply_if(selected, |s| s.background(theme().selection))
+                    })
+                    .on_event(EventListener::PointerDown, move |e| {
+                        if let Event::PointerDown(e) = e {
+                            if e.button.is_primary() {
+                                let anchor = range
+                                    .get_untracked()
+                                    .filter(|_| e.modifiers.shift_key())
+                                    .map_or(address, |range| range.anchor);
+                                range.set(Some(AddressRange {
+                                    anchor,
+                                    end: address,
+                                }));
+                            }
+                        }
+                        false
+                    });
+                    container_box(view)
+                }
+            },
+        )
+        .style(|s| s.flex_col().padding(5).min_width_full());
+
+        let viewport = create_rw_signal(0.0);
+        let row_addresses = addresses.clone();
+        let instr = scroll(instr)
+            .on_scroll(move |rect| {
+                if scroll_y.get_untracked() != rect.y0 {
+                    scroll_y.set(rect.y0);
+                }
+                if viewport.get_untracked() != rect.height() {
+                    viewport.set(rect.height());
+                }
+                if scroll_x.get_untracked() != rect.x0 {
+                    scroll_x.set(rect.x0);
+                }
+                let row = (rect.y0 / row_height) as usize;
+                let top = addresses
+                    .get(row)
+                    .map(|&address| (name.clone(), address - base));
+                if position.with_untracked(|position| *position != top) {
+                    position.set(top);
+                }
+            })
+            .on_scroll_to(move || Some(Point::new(scroll_x.get_untracked(), scroll_y.get())))
+            .style(|s| {
+                s.flex_grow(1.0)
+                    .flex_basis(0.0)
+                    .min_width(0.0)
+                    .height_full()
+                    .background(theme().code)
+            })
+            .keyboard_navigatable()
+            // Shift extends the selection like shift+click
+            .on_event(EventListener::KeyDown, move |e| {
+                let Event::KeyDown(e) = e else {
+                    return false;
+                };
+                let selected = range.get_untracked();
+                let current = selected.and_then(|range| {
+                    instruction_rows
+                        .iter()
+                        .position(|&row| row_addresses[row] == range.end)
+                });
+                let page = (viewport.get_untracked() / row_height) as usize;
+                let Some(next) = step(&e.key.logical_key, current, instruction_rows.len(), page)
+                else {
+                    return false;
+                };
+                let row = instruction_rows[next];
+                let address = row_addresses[row];
+                let anchor = selected
+                    .filter(|_| e.modifiers.shift_key())
+                    .map_or(address, |range| range.anchor);
+                range.set(Some(AddressRange {
+                    anchor,
+                    end: address,
+                }));
+                reveal(scroll_y, viewport.get_untracked(), row, row_height);
+                true
+            });
+
+        Box::new(stack((address_list, instr)).style(|s| s.flex_row().width_full().height_full()))
+    } else {
+        Box::new(text("Assembly unavailable").style(|s| s.padding(5.0)))
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn main_container(
+    current: Selection,
+    objects: RwSignal<ObjectList>,
+    settings_signal: RwSignal<Settings>,
+    settings: &Settings,
+    selection: RwSignal<Selection>,
+    diff_base: RwSignal<Option<Symbol>>,
+    path_start: RwSignal<Option<Symbol>>,
+    series: RwSignal<timeline::Series>,
+    profile: Option<Arc<profile::Profile>>,
+    position: RwSignal<ViewPosition>,
+    history: RwSignal<History>,
+    favorites: favorites::Revision,
+    annotations: annotations::Revision,
+    tour: RwSignal<Option<usize>>,
+) -> Box<dyn View> {
+    match current {
+        Selection::None => Box::new(tour::welcome(objects, selection, tour)),
+        Selection::Object(o) => {
+            let tab = create_rw_signal(0);
+            // Addresses in the sections and the hex dumps can be shown relative
+            // to the image base for this object only
+            let view = overrides::signal(overrides::ViewKey::Object(o.id.clone()));
+            let view_settings = settings.clone();
+            let base = o.image_base;
+            let relative_to = move |view: ViewSettings| {
+                view.apply(&view_settings)
+                    .relative_addresses
+                    .then_some(base)
+            };
+            let content = dyn_container(
+                move || (tab.get(), view.get()),
+                move |(tab, view)| match tab {
+                    0 => {
+                        let location = match &o.member {
+                            Some(member) => format!(
+                                "Archive: `{}`, member `{}` at offset {:#x} ({} bytes)",
+                                o.path.display(),
+                                member.name,
+                                member.offset,
+                                member.size
+                            ),
+                            None => format!("Path: `{}`", o.path.display()),
+                        };
+                        let location = match &o.slice {
+                            Some(slice) => format!("{}, {} slice", location, slice),
+                            None => location,
+                        };
+                        let reload = o.clone();
+                        let current = o.bitness();
+                        let mode = |bitness: u32| {
+                            let object = o.clone();
+                            toggle_button(
+                                format!("{}-bit", bitness),
+                                move || current == bitness,
+                                move |_| {
+                                    set_bitness(objects, selection, &object, bitness);
+                                    true
+                                },
+                            )
+                        };
+                        // ARM code isn't decoded as x86
+                        let arm = o
+                            .sections
+                            .iter()
+                            .any(|section| matches!(section.isa, Isa::Arm(_)));
+                        let modes = stack((
+                            text("Decode as:").style(|s| s.padding(5.0)),
+                            mode(16),
+                            mode(32),
+                            mode(64),
+                        ))
+                        .style(move |s| s.flex_row().items_center().apply_if(arm, |s| s.hide()));
+                        let offsets: Box<dyn View> = match raw::layout(&o) {
+                            Some(_) => {
+                                Box::new(raw::offset_picker(&o, objects, selection, history))
+                            }
+                            None => Box::new(empty()),
+                        };
+                        let warnings = o.all_warnings();
+                        let warnings = list(
+                            move || warnings.clone().into_iter().enumerate(),
+                            |(i, _)| *i,
+                            |(_, warning)| {
+                                text(warning).style(|s| s.padding(5.0).color(theme().error))
+                            },
+                        )
+                        .style(|s| s.flex_col());
+                        let data = stack((
+                            header("Object Info"),
+                            text(format!("Object: `{}`", o.name)).style(|s| s.padding(5.0)),
+                            text(location).style(|s| s.padding(5.0)),
+                            text(format!("Format: {}", raw::format_name(&o)))
+                                .style(|s| s.padding(5.0)),
+                            text(format!(
+                                "Build ID: {}",
+                                o.id.build_hex().unwrap_or_else(|| "none".to_owned())
+                            ))
+                            .style(|s| s.padding(5.0)),
+                            text(format!("Symbols: {}", units::count(o.symbols.len() as u64)))
+                                .style(|s| s.padding(5.0)),
+                            modes,
+                            offsets,
+                            button("Reload", move |_| {
+                                reload_object(objects, selection, &reload);
+                                true
+                            }),
+                            warnings,
+                        ))
+                        .style(|s| s.flex_col().width_full());
+                        Box::new(data)
+                    }
+                    1 => stats::dashboard(&o),
+                    2 => sections::sections_view(&o, selection, relative_to(view)),
+                    3 => hexview::hex_tab(&o, relative_to(view)),
+                    4 => linkage::linkage_tab(&o, selection, history),
+                    _ => matcher::match_tab(&o, objects, selection),
+                },
+            )
+            .style(|s| s.width_full().height_full());
+
+            let tabs = stack((
+                tab_bar(
+                    &[
+                        "Info",
+                        "Dashboard",
+                        "Sections",
+                        "Hex",
+                        "Imports / Exports",
+                        "Matching",
+                    ],
+                    tab,
+                ),
+                overrides::bar(view, settings, &[overrides::Override::RelativeAddresses])
+                    .style(|s| s.border_bottom(0.5).border_color(theme().border)),
+            ))
+            .style(|s| s.flex_row().width_full());
+            Box::new(stack((tabs, content)).style(|s| s.flex_col().width_full().height_full()))
+        }
+        Selection::Symbol(symbol) => {
+            let o = &symbol.data;
+            let rollup = rollup::rollup(&symbol.object, o);
+            let samples = Arc::new(
+                profile
+                    .as_ref()
+                    .map(|profile| profile.symbol(&symbol))
+                    .unwrap_or_default(),
+            );
+            let alias = {
+                let symbol = symbol.clone();
+                move || {
+                    annotations.track();
+                    annotations::alias(&symbol)
+                }
+            };
+            let shown_alias = alias.clone();
+            let info = stack((
+                text(format!("Symbol: `{}`", o.name)).style(|s| s.padding(5.0)),
+                label(move || {
+                    shown_alias()
+                        .map(|alias| format!("Alias: `{}`", alias))
+                        .unwrap_or_default()
+                })
+                .style(move |s| s.padding(5.0).apply_if(alias().is_none(), |s| s.hide())),
+                annotations::rename_editor(&symbol, annotations),
+                o.demangled
+                    .as_ref()
+                    .map(|demangled| {
+                        container_box(
+                            text(format!("Demangled: `{}`", demangled)).style(|s| s.padding(5.0)),
+                        )
+                    })
+                    .unwrap_or_else(|| container_box(empty())),
+                o.section
+                    .as_ref()
+                    .map(|section| {
+                        container_box(
+                            text(format!("Section: `{}`", section.name)).style(|s| s.padding(5.0)),
+                        )
+                    })
+                    .unwrap_or_else(|| container_box(empty())),
+                symbol
+                    .object
+                    .lines
+                    .as_ref()
+                    .and_then(|lines| {
+                        let location = lines.find(o.section.as_ref()?.index, o.address)?;
+                        Some(container_box(
+                            text(format!(
+                                "Source: `{}:{}`",
+                                lines.file(location),
+                                location.line
+                            ))
+                            .style(|s| s.padding(5.0)),
+                        ))
+                    })
+                    .unwrap_or_else(|| container_box(empty())),
+                stack((text("Size: "), units::size_text(o.size)))
+                    .style(|s| s.flex_row().padding(5.0)),
+                text(format!(
+                    "Removal Size: {}, with {} callees only reachable through it",
+                    units::size(rollup.size),
+                    units::count(rollup.callees as u64)
+                ))
+                .style(|s| s.padding(5.0)),
+                text(format!(
+                    "Data Length: `{:?}`",
+                    o.data().map(|d| d.len()).unwrap_or_default()
+                ))
+                .style(|s| s.padding(5.0)),
+                profile
+                    .as_ref()
+                    .map(|profile| {
+                        let count = profile.symbol_total(&symbol);
+                        container_box(
+                            text(format!(
+                                "Samples: {} of {} ({:.1}%)",
+                                units::count(count),
+                                units::count(profile.total),
+                                count as f64 * 100.0 / profile.total.max(1) as f64
+                            ))
+                            .style(|s| s.padding(5.0)),
+                        )
+                    })
+                    .unwrap_or_else(|| container_box(empty())),
+            ))
+            .style(|s| s.flex_col());
+
+            let base_symbol = symbol.clone();
+            let diff_symbol = symbol.clone();
+            let counterpart_symbol = symbol.clone();
+            // The same symbol in other loaded objects, like another build of
+            // the same program
+            let counterparts: Vec<Symbol> = objects.with_untracked(|list| {
+                list.objects
+                    .iter()
+                    .filter(|object| object.key() != symbol.object.key())
+                    .filter_map(|object| {
+                        Some(Symbol {
+                            object: object.clone(),
+                            data: xrefs::find(object, &symbol.data.name)?,
+                        })
+                    })
+                    .collect()
+            });
+            let start_symbol = symbol.clone();
+            let end_name = symbol.data.name.clone();
+            let graph_root = symbol.data.name.clone();
+            let export_symbol = symbol.clone();
+            let export_settings = settings.clone();
+            let listing_symbol = symbol.clone();
+            let listing_settings = settings.clone();
+            let annotated_symbol = symbol.clone();
+            let annotated_settings = settings.clone();
+            let annotated_samples = samples.clone();
+            // The syntax, columns and addresses can be changed for this view
+            // only, to compare how the assemblers would spell the same
+            // instructions
+            let view = overrides::signal(overrides::ViewKey::Symbol(symbol.id()));
+            let range = create_rw_signal(None::<AddressRange>);
+            // Addresses entered to go to, which the assembly view scrolls to
+            let goto = create_rw_signal(None::<u64>);
+            let hint = create_rw_signal(None);
+            let analysis = create_rw_signal(None::<mca::Analysis>);
+            let mca_symbol = symbol.data.clone();
+            let mca_target = settings.target;
+            let pinned_symbol = symbol.clone();
+            let pin_symbol = symbol.clone();
+            let actions = stack((
+                toggle_button(
+                    "Pin",
+                    move || {
+                        favorites.track();
+                        favorites::contains(&pinned_symbol)
+                    },
+                    move |_| {
+                        favorites::toggle(&pin_symbol, favorites);
+                        true
+                    },
+                ),
+                button("Set as Diff Base", move |_| {
+                    diff_base.set(Some(base_symbol.clone()));
+                    true
+                }),
+                diff_base
+                    .get_untracked()
+                    .filter(|base| base.id() != symbol.id())
+                    .map(|base| {
+                        container_box(button(
+                            format!(
+                                "Diff with `{}`",
+                                base.data.demangled.as_ref().unwrap_or(&base.data.name)
+                            ),
+                            move |_| {
+                                selection.set(Selection::Diff(base.clone(), diff_symbol.clone()));
+                                true
+                            },
+                        ))
+                    })
+                    .unwrap_or_else(|| container_box(empty())),
+                button("Set as Path Start", move |_| {
+                    path_start.set(Some(start_symbol.clone()));
+                    true
+                }),
+                path_start
+                    .get_untracked()
+                    .filter(|start| start.data.name != symbol.data.name)
+                    .map(|start| {
+                        container_box(button(
+                            format!(
+                                "Call Paths from `{}`",
+                                start.data.demangled.as_ref().unwrap_or(&start.data.name)
+                            ),
+                            move |_| {
+                                history::jump(
+                                    selection,
+                                    history,
+                                    Selection::CallPaths(start.data.name.clone(), end_name.clone()),
+                                );
+                                true
+                            },
+                        ))
+                    })
+                    .unwrap_or_else(|| container_box(empty())),
+                button("Call Graph", move |_| {
+                    history::jump(selection, history, Selection::CallGraph(graph_root.clone()));
+                    true
+                }),
+                list(
+                    move || counterparts.clone().into_iter().enumerate(),
+                    |(i, _)| *i,
+                    move |(_, counterpart)| {
+                        let label = format!("Diff with `{}`", counterpart.object.name);
+                        let current = counterpart_symbol.clone();
+                        button(label, move |_| {
+                            selection.set(Selection::Diff(counterpart.clone(), current.clone()));
+                            true
+                        })
+                    },
+                )
+                .style(|s| s.flex_row()),
+                if series.with_untracked(|series| series.builds.is_empty()) {
+                    container_box(empty())
+                } else {
+                    let name = symbol.data.name.clone();
+                    container_box(button("Timeline", move |_| {
+                        selection.set(Selection::Timeline(name.clone()));
+                        true
+                    }))
+                },
+                button("Copy for Compiler Explorer", move |_| {
+                    let text = export::compiler_explorer(
+                        &export_symbol,
+                        &export_settings,
+                        range.get_untracked(),
+                    );
+                    if let Some(text) = text {
+                        if let Err(error) = floem::Clipboard::set_contents(text) {
+                            report_error(objects, format!("Clipboard: {:?}", error));
+                        }
+                    }
+                    true
+                }),
+                button("Export...", move |_| {
+                    let settings = view.get_untracked().apply(&listing_settings);
+                    export::save_listing(
+                        objects,
+                        &listing_symbol,
+                        &settings,
+                        range.get_untracked(),
+                        None,
+                    );
+                    true
+                }),
+                button("Export with Overlays...", move |_| {
+                    let settings = view.get_untracked().apply(&annotated_settings);
+                    export::save_listing(
+                        objects,
+                        &annotated_symbol,
+                        &settings,
+                        range.get_untracked(),
+                        Some(&annotated_samples),
+                    );
+                    true
+                }),
+                if mca::program().is_some() {
+                    container_box(button("Analyze with llvm-mca", move |_| {
+                        mca::analyze(
+                            mca_symbol.clone(),
+                            range.get_untracked(),
+                            mca_target,
+                            analysis,
+                        );
+                        true
+                    }))
+                } else {
+                    container_box(empty())
+                },
+            ))
+            .style(|s| s.flex_row());
+
+            let view_settings = settings.clone();
+            let view_symbol = symbol.clone();
+            let view_samples = samples.clone();
+            let assembly_header = stack((
+                header("Assembly"),
+                overrides::bar(
+                    view,
+                    settings,
+                    &[
+                        overrides::Override::Syntax,
+                        overrides::Override::Source,
+                        overrides::Override::Bytes,
+                        overrides::Override::RelativeAddresses,
+                    ],
+                )
+                .style(|s| s.background(theme().panel).border_bottom(0.5)),
+                goto::goto_bar(&symbol, goto, selection, history)
+                    .style(|s| s.background(theme().panel).border_bottom(0.5)),
+            ))
+            .style(|s| s.flex_row().width_full());
+            let assembly_view = dyn_container(
+                move || {
+                    let cycles = analysis.with(|analysis| {
+                        analysis
+                            .as_ref()
+                            .map(mca::Analysis::cycles)
+                            .unwrap_or_default()
+                    });
+                    (view.get(), cycles)
+                },
+                move |(view, cycles)| {
+                    let settings = view.apply(&view_settings);
+                    assembly(
+                        view_symbol.clone(),
+                        &settings,
+                        selection,
+                        history,
+                        position,
+                        range,
+                        hint,
+                        view_samples.clone(),
+                        cycles,
+                        annotations,
+                        goto,
+                    )
+                },
+            )
+            .style(|s| {
+                s.flex_grow(1.0)
+                    .flex_basis(0.0)
+                    .min_width(0.0)
+                    .height_full()
+            });
+            let assembly_view = stack((assembly_view, mca::mca_panel(analysis)))
+                .style(|s| s.flex_row().width_full().height_full());
+
+            let stats_symbol = symbol.data.clone();
+            let target = settings.target;
+            let range_stats = stack((
+                // Explains the branch prediction hint under the pointer
+                label(move || match (hint.get(), range.get()) {
+                    (Some(hint), _) => hint.to_owned(),
+                    (None, Some(range)) => {
+                        let (start, end) = range.bounds();
+                        stats::RangeStats::compute(&stats_symbol, start, end, target).summary()
+                    }
+                    (None, None) => {
+                        "Click an instruction to select it, shift+click to select a range"
+                            .to_owned()
+                    }
+                })
+                .style(|s| s.padding(5.0).color(theme().dim)),
+                button("Clear Selection", move |_| {
+                    range.set(None);
+                    true
+                })
+                .style(move |s| s.apply_if(range.get().is_none(), |s| s.hide())),
+            ))
+            .style(|s| s.flex_row().items_center());
+
+            // The comment and alternative encodings of a single selected
+            // instruction
+            let comment_symbol = symbol.clone();
+            let comment = dyn_container(
+                move || range.get().filter(|range| range.anchor == range.end),
+                move |range| match range {
+                    Some(range) => Box::new(annotations::comment_editor(
+                        &comment_symbol,
+                        range.anchor,
+                        annotations,
+                    )),
+                    None => Box::new(empty()) as Box<dyn View>,
+                },
+            );
+            let encodings_symbol = symbol.data.clone();
+            let encodings = dyn_container(
+                move || range.get().filter(|range| range.anchor == range.end),
+                move |range| match range {
+                    Some(range) => encodings::encodings_view(&encodings_symbol, range.anchor),
+                    None => Box::new(empty()),
+                },
+            );
+
+            let data = stack((
+                header("Symbol Info"),
+                scroll(info),
+                actions,
+                xrefs::references_view(&symbol, objects, selection, history, position),
+                assembly_header,
+                range_stats,
+                comment,
+                encodings,
+                assembly_view,
+            ))
             .style(|s| s.flex_col().width_full().height_full());
             Box::new(data)
         }
+        Selection::Diff(left, right) => {
+            diff::diff_view(left, right, settings_signal, selection, history)
+        }
+        Selection::Matches(left, right) => matcher::matches_view(left, right, selection),
+        Selection::Timeline(name) => timeline::timeline_view(name, series, objects, selection),
+        Selection::CallPaths(from, to) => {
+            callpaths::paths_view(from, to, objects, selection, history)
+        }
+        Selection::CallGraph(root) => callgraph::graph_view(root, objects, selection, history),
+        Selection::DeadCode => {
+            objects.with_untracked(|objects| deadcode::report(&objects.objects, selection))
+        }
+        Selection::Bloat => objects.with_untracked(|objects| bloat::report(&objects.objects)),
+        Selection::Preferences => {
+            preferences::preferences_view(settings_signal, objects, selection)
+        }
+        Selection::Tracked => objects
+            .with_untracked(|objects| tracking::dashboard(&objects.objects, settings, selection)),
+        Selection::RawBinary(path) => raw::options_view(path, objects, selection),
+    }
+}
+
+// The letter a symbol is filed under in the index rail
+fn index_key(name: &str) -> char {
+    match name
+        .trim_start_matches(|c: char| !c.is_alphanumeric())
+        .chars()
+        .next()
+    {
+        Some(c) if c.is_ascii_alphabetic() => c.to_ascii_uppercase(),
+        _ => '#',
+    }
+}
+
+// Marks the row moved to with the arrow keys
+fn cursor_style(s: floem::style::Style) -> floem::style::Style {
+    s.border(1.0).border_color(theme().link)
+}
+
+// Scrolls the least distance which shows all of `row`
+fn reveal(scroll_y: RwSignal<f64>, viewport: f64, row: usize, row_height: f64) {
+    let top = row as f64 * row_height;
+    let y = scroll_y.get_untracked();
+    if top < y {
+        scroll_y.set(top);
+    } else if top + row_height > y + viewport {
+        scroll_y.set((top + row_height - viewport).max(0.0));
+    }
+}
+
+// The row moved to by an arrow, page or home/end key from `current`, in a
+// list of `rows` showing `page` rows at once
+fn step(key: &Key, current: Option<usize>, rows: usize, page: usize) -> Option<usize> {
+    let last = rows.checked_sub(1)?;
+    let next = match (key, current) {
+        (Key::Named(NamedKey::Home), _) => 0,
+        (Key::Named(NamedKey::End), _) => last,
+        (Key::Named(NamedKey::ArrowDown), None) => 0,
+        (Key::Named(NamedKey::ArrowUp), None) => last,
+        (Key::Named(NamedKey::ArrowDown), Some(row)) => row + 1,
+        (Key::Named(NamedKey::ArrowUp), Some(row)) => row.saturating_sub(1),
+        (Key::Named(NamedKey::PageDown), row) => row.map_or(0, |row| row + page.max(1)),
+        (Key::Named(NamedKey::PageUp), row) => row.map_or(0, |row| row.saturating_sub(page.max(1))),
+        _ => return None,
+    };
+    Some(next.min(last))
+}
+
+// Typed characters within this time of each other form one type-ahead prefix
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_millis(1000);
+
+const BADGE_WIDTH: f64 = 130.0;
+
+const SIZE_WIDTH: f64 = 70.0;
+
+// Column the symbol list is sorted by, largest first except for addresses
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SortKey {
+    Address,
+    Size,
+    Metric(Metric),
+    References,
+    Callers,
+}
+
+impl SortKey {
+    fn value(self, symbol: &Symbol, objects: &[Arc<Object>]) -> u64 {
+        match self {
+            SortKey::Address => symbol.data.address,
+            SortKey::Size => symbol.data.extent().unwrap_or_default(),
+            SortKey::Metric(metric) => metric.value(&symbol.data.metrics()) as u64,
+            SortKey::References => xrefs::counts(objects, &symbol.data.name).0 as u64,
+            SortKey::Callers => xrefs::counts(objects, &symbol.data.name).1 as u64,
+        }
+    }
+}
+
+const PATH_INDENT: f64 = 14.0;
+
+// A crate, module or generic function in the symbol list grouped by path
+#[derive(Clone)]
+struct PathGroup {
+    path: Vec<String>,
+    symbols: usize,
+    // Set for a function with several instantiations
+    instantiations: bool,
+}
+
+// A row of the symbol list. Each object is a group which can be collapsed to
+// hide its symbols, unless the symbols are grouped by path. Symbols are
+// indented by their depth in the path tree.
+#[derive(Clone)]
+enum SymbolRow {
+    Group(Arc<Object>),
+    Path(PathGroup),
+    Symbol(Symbol, usize),
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum SymbolRowKey {
+    Group((identity::ObjectId, u64)),
+    Path(Vec<String>),
+    Symbol((identity::SymbolId, u64)),
+}
+
+impl SymbolRow {
+    fn key(&self) -> SymbolRowKey {
+        match self {
+            SymbolRow::Group(object) => SymbolRowKey::Group(object.key()),
+            SymbolRow::Path(group) => SymbolRowKey::Path(group.path.clone()),
+            SymbolRow::Symbol(symbol, _) => SymbolRowKey::Symbol(symbol.key()),
+        }
+    }
+
+    fn symbol(&self) -> Option<&Symbol> {
+        match self {
+            SymbolRow::Group(_) | SymbolRow::Path(_) => None,
+            SymbolRow::Symbol(symbol, _) => Some(symbol),
+        }
     }
 }
 
-fn app_view() -> impl View {
-    let objects = create_rw_signal(ObjectList {
-        objects: Vec::new(),
-    });
+// Symbols filed by the crate, modules and function of their demangled path
+#[derive(Default)]
+struct PathTree {
+    children: BTreeMap<String, PathTree>,
+    // Instantiations of the function at this path
+    symbols: Vec<Symbol>,
+    count: usize,
+}
 
-    let selection = create_rw_signal(Selection::None);
+impl PathTree {
+    fn insert(&mut self, path: &[String], symbol: Symbol) {
+        self.count += 1;
+        match path.split_first() {
+            Some((first, rest)) => self
+                .children
+                .entry(first.clone())
+                .or_default()
+                .insert(rest, symbol),
+            None => self.symbols.push(symbol),
+        }
+    }
 
-    let object_list = list(
-        move || objects.with(|objects| objects.objects.clone()),
-        |o| Arc::as_ptr(o).addr(),
-        move |o| {
-            let o_ = o.clone();
-            text(o.name.clone())
-                .style(move |s| {
-                    s.apply_if(
-                        selection.with(|s| {
-                            if let Selection::Object(so) = s {
-                                Arc::ptr_eq(so, &o_)
-                            } else {
-                                false
-                            }
-                        }),
-                        |s| s.background(Color::LIGHT_GRAY),
-                    )
-                    .padding(5)
-                    .width_full()
-                    .height(26.0)
-                    .text_overflow(TextOverflow::Clip)
+    // Functions with a single instantiation and nothing below them are shown
+    // as a symbol row instead of a group
+    fn rows(
+        &self,
+        path: &mut Vec<String>,
+        expanded: &HashSet<Vec<String>>,
+        rows: &mut im::Vector<SymbolRow>,
+    ) {
+        for (name, child) in &self.children {
+            path.push(name.clone());
+            if let ([symbol], true) = (&child.symbols[..], child.children.is_empty()) {
+                rows.push_back(SymbolRow::Symbol(symbol.clone(), path.len() - 1));
+            } else {
+                rows.push_back(SymbolRow::Path(PathGroup {
+                    path: path.clone(),
+                    symbols: child.count,
+                    instantiations: child.children.is_empty(),
+                }));
+                if expanded.contains(path) {
+                    child.rows(path, expanded, rows);
+                    let depth = path.len();
+                    rows.extend(
+                        child
+                            .symbols
+                            .iter()
+                            .map(|symbol| SymbolRow::Symbol(symbol.clone(), depth)),
+                    );
+                }
+            }
+            path.pop();
+        }
+    }
+}
+
+fn toggle_expanded(expanded: RwSignal<HashSet<Vec<String>>>, path: &[String]) {
+    expanded.update(|expanded| {
+        if !expanded.remove(path) {
+            expanded.insert(path.to_vec());
+        }
+    });
+}
+
+fn path_row(
+    group: PathGroup,
+    expanded: RwSignal<HashSet<Vec<String>>>,
+    cursor: RwSignal<Option<SymbolRowKey>>,
+    row_height: f64,
+) -> impl View {
+    let key = SymbolRowKey::Path(group.path.clone());
+    let depth = group.path.len() - 1;
+    let is_expanded = {
+        let path = group.path.clone();
+        move || expanded.with(|expanded| expanded.contains(&path))
+    };
+    let arrow = label(move || if is_expanded() { "▾" } else { "▸" })
+        .style(|s| s.width(14).color(theme().dim));
+    let title = text(group.path.last().unwrap()).style(move |s| {
+        s.flex_grow(1.0)
+            .flex_basis(0.0)
+            .min_width(0.0)
+            .apply_if(depth == 0, |s| s.font_weight(Weight::BOLD))
+            .text_overflow(TextOverflow::Clip)
+    });
+    let count = if group.instantiations {
+        format!("{} instantiations", group.symbols)
+    } else {
+        group.symbols.to_string()
+    };
+    let count = text(count).style(|s| s.padding_left(6).color(theme().dim));
+    let path = group.path;
+    stack((arrow, title, count))
+        .style(move |s| {
+            s.flex_row()
+                .items_center()
+                .padding_horiz(5)
+                .padding_left(5.0 + depth as f64 * PATH_INDENT)
+                .width_full()
+                .height(row_height)
+                .cursor(CursorStyle::Pointer)
+                .apply_if(cursor.with(|cursor| cursor.as_ref() == Some(&key)), |s| {
+                    cursor_style(s)
                 })
-                .hover_style(|s| s.background(Color::LIGHT_GREEN))
-                .on_click(move |_| {
-                    selection.set(Selection::Object(o.clone()));
-                    true
+        })
+        .hover_style(|s| s.background(theme().hover))
+        .on_click(move |_| {
+            toggle_expanded(expanded, &path);
+            true
+        })
+}
+
+// The header of the symbols of `object`, which toggles whether they're shown
+fn toggle_collapsed(collapsed: RwSignal<HashSet<identity::ObjectId>>, id: &identity::ObjectId) {
+    collapsed.update(|collapsed| {
+        if !collapsed.remove(id) {
+            collapsed.insert(id.clone());
+        }
+    });
+}
+
+fn group_row(
+    object: &Arc<Object>,
+    collapsed: RwSignal<HashSet<identity::ObjectId>>,
+    cursor: RwSignal<Option<SymbolRowKey>>,
+    row_height: f64,
+) -> impl View {
+    let key = SymbolRowKey::Group(object.key());
+    let id = object.id.clone();
+    let is_collapsed = {
+        let id = id.clone();
+        move || collapsed.with(|collapsed| collapsed.contains(&id))
+    };
+    let arrow = label(move || if is_collapsed() { "▸" } else { "▾" })
+        .style(|s| s.width(14).color(theme().dim));
+    let title = text(object.title()).style(|s| {
+        s.flex_grow(1.0)
+            .flex_basis(0.0)
+            .min_width(0.0)
+            .font_weight(Weight::BOLD)
+            .text_overflow(TextOverflow::Clip)
+    });
+    let count = text(object.symbols_sorted.len()).style(|s| s.padding_left(6).color(theme().dim));
+    stack((arrow, title, count))
+        .style(move |s| {
+            s.flex_row()
+                .items_center()
+                .padding_horiz(5)
+                .width_full()
+                .height(row_height)
+                .background(theme().panel)
+                .cursor(CursorStyle::Pointer)
+                .apply_if(cursor.with(|cursor| cursor.as_ref() == Some(&key)), |s| {
+                    cursor_style(s)
                 })
-        },
-    )
-    .style(|s| s.flex_col().height_full());
+        })
+        .hover_style(|s| s.background(theme().hover))
+        .on_click(move |_| {
+            toggle_collapsed(collapsed, &id);
+            true
+        })
+}
 
-    let symbol_list = virtual_list(
-        VirtualListDirection::Vertical,
-        VirtualListItemSize::Fixed(Box::new(|| 26.0)),
-        move || {
-            objects.with(|objects| {
-                objects
+#[allow(clippy::too_many_arguments)]
+fn symbol_list(
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+    history: RwSignal<History>,
+    sort: RwSignal<Option<SortKey>>,
+    collapsed: RwSignal<HashSet<identity::ObjectId>>,
+    expanded: RwSignal<HashSet<Vec<String>>>,
+    metric_columns: bool,
+    show_index: bool,
+    group_by_path: bool,
+    density: Density,
+    annotations: annotations::Revision,
+) -> Box<dyn View> {
+    let row_height = density.row_height();
+    // Symbols are sorted within their object, or within their function when
+    // grouped by path
+    let sorted = move || {
+        let key = sort.get();
+        objects.with(|list| {
+            let mut rows = im::Vector::new();
+            if group_by_path {
+                let mut symbols: Vec<_> = list
                     .objects
                     .iter()
                     .flat_map(|o| {
-                        o.symbols_sorted.iter().cloned().map(|s| Symbol {
+                        o.symbols_sorted.iter().map(|s| Symbol {
                             object: o.clone(),
-                            data: s,
+                            data: s.clone(),
                         })
                     })
-                    .collect::<im::Vector<_>>()
-            })
-        },
-        |o| Arc::as_ptr(&o.data).addr(),
-        move |o| {
+                    .collect();
+                match key {
+                    Some(SortKey::Address) => symbols.sort_by_key(|s| s.data.address),
+                    Some(key) => {
+                        symbols.sort_by_cached_key(|s| Reverse(key.value(s, &list.objects)))
+                    }
+                    None => {}
+                }
+                let mut tree = PathTree::default();
+                for symbol in symbols {
+                    let name = symbol.data.demangled.as_ref().unwrap_or(&symbol.data.name);
+                    tree.insert(&bloat::generic_path(name), symbol);
+                }
+                expanded.with(|expanded| tree.rows(&mut Vec::new(), expanded, &mut rows));
+                return rows;
+            }
+            for o in &list.objects {
+                rows.push_back(SymbolRow::Group(o.clone()));
+                if collapsed.with(|collapsed| collapsed.contains(&o.id)) {
+                    continue;
+                }
+                let mut symbols: Vec<_> = o
+                    .symbols_sorted
+                    .iter()
+                    .cloned()
+                    .map(|s| Symbol {
+                        object: o.clone(),
+                        data: s,
+                    })
+                    .collect();
+                match key {
+                    Some(SortKey::Address) => symbols.sort_by_key(|s| s.data.address),
+                    Some(key) => {
+                        symbols.sort_by_cached_key(|s| Reverse(key.value(s, &list.objects)))
+                    }
+                    None => {}
+                }
+                rows.extend(
+                    symbols
+                        .into_iter()
+                        .map(|symbol| SymbolRow::Symbol(symbol, 0)),
+                );
+            }
+            rows
+        })
+    };
+
+    let scroll_y = create_rw_signal(0.0);
+    let viewport = create_rw_signal(0.0);
+    let jump = move |row: usize| scroll_y.set(row as f64 * row_height);
+    // The row moved to with the keyboard, which enter selects
+    let cursor = create_rw_signal(None::<SymbolRowKey>);
+
+    let symbols = virtual_list(
+        VirtualListDirection::Vertical,
+        VirtualListItemSize::Fixed(Box::new(move || row_height)),
+        sorted,
+        |row| row.key(),
+        move |row| {
+            let key = row.key();
+            let (o, depth) = match row {
+                SymbolRow::Group(object) => {
+                    return Box::new(group_row(&object, collapsed, cursor, row_height))
+                        as Box<dyn View>;
+                }
+                SymbolRow::Path(group) => {
+                    return Box::new(path_row(group, expanded, cursor, row_height));
+                }
+                SymbolRow::Symbol(symbol, depth) => (symbol, depth),
+            };
             let o_ = o.clone();
-            text(o.data.demangled.as_ref().unwrap_or(&o.data.name).clone())
+            let named = o.clone();
+            let name = label(move || {
+                annotations.track();
+                annotations::display_name(&named)
+            })
+            .style(|s| {
+                s.flex_grow(1.0)
+                    .flex_basis(0.0)
+                    .min_width(0.0)
+                    .text_overflow(TextOverflow::Clip)
+            });
+            let (references, callers) =
+                objects.with_untracked(|list| xrefs::counts(&list.objects, &o.data.name));
+            let badge =
+                text(format!("refs: {} / callers: {}", references, callers)).style(move |s| {
+                    // Unreferenced and hot functions stand out
+                    let badge_color = if references == 0 {
+                        theme().removed
+                    } else if callers >= xrefs::HOT_CALLERS {
+                        theme().hot
+                    } else {
+                        Color::TRANSPARENT
+                    };
+                    s.width(BADGE_WIDTH)
+                        .padding_horiz(4)
+                        .margin_left(4)
+                        .border_radius(3)
+                        .font_size(11.0)
+                        .color(theme().dim)
+                        .background(badge_color)
+                        .text_overflow(TextOverflow::Clip)
+                });
+            let size = o.data.extent().unwrap_or_default();
+            let size = container(units::size_text(size).style(|s| s.color(theme().dim)))
+                .style(|s| s.width(SIZE_WIDTH).justify_end());
+            let metrics = o.data.metrics();
+            let columns = list(
+                move || {
+                    metric_columns
+                        .then_some(Metric::ALL.map(|metric| metric.value(&metrics)))
+                        .into_iter()
+                        .flatten()
+                        .enumerate()
+                },
+                |(i, _)| *i,
+                |(_, value)| text(value).style(|s| s.width(40).padding_left(6)),
+            )
+            .style(|s| s.flex_row());
+            let row = stack((name, size, badge, columns))
                 .style(move |mut s| {
                     if selection.with(|s| {
                         if let Selection::Symbol(so) = s {
-                            Arc::ptr_eq(&so.data, &o_.data)
+                            so.id() == o_.id()
                         } else {
                             false
                         }
                     }) {
-                        s = s.background(Color::LIGHT_GRAY);
+                        s = s.background(theme().selection);
                     }
-                    s.padding(5)
+                    s.flex_row()
+                        .padding(density.row_padding())
+                        .padding_horiz(5)
+                        .padding_left(5.0 + depth as f64 * PATH_INDENT)
                         .width_full()
-                        .height(26.0)
-                        .text_overflow(TextOverflow::Clip)
+                        .height(row_height)
+                        .apply_if(cursor.with(|cursor| cursor.as_ref() == Some(&key)), |s| {
+                            cursor_style(s)
+                        })
+                })
+                .hover_style(|s| s.background(theme().hover))
+                .context_menu({
+                    let o = o.clone();
+                    move || copy::symbol_menu(&o)
+                })
+                .on_click(move |_| {
+                    history::jump(selection, history, Selection::Symbol(o.clone()));
+                    true
+                });
+            Box::new(row)
+        },
+    )
+    .style(|s| s.flex_col().background(theme().list).width_full());
+
+    let symbols = scroll(symbols)
+        .on_scroll(move |rect| {
+            if scroll_y.get_untracked() != rect.y0 {
+                scroll_y.set(rect.y0);
+            }
+            if viewport.get_untracked() != rect.height() {
+                viewport.set(rect.height());
+            }
+        })
+        .on_scroll_to(move || Some(Point::new(0.0, scroll_y.get())))
+        .style(|s| s.flex_grow(1.0).min_width(0.0).height_full());
+
+    // The first row filed under each letter
+    let index = list(
+        move || {
+            let mut index: Vec<(char, usize)> = Vec::new();
+            if show_index {
+                for (row, o) in sorted()
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(row, o)| Some((row, o.symbol()?)))
+                {
+                    let key = index_key(o.data.demangled.as_ref().unwrap_or(&o.data.name));
+                    if !index.iter().any(|(k, _)| *k == key) {
+                        index.push((key, row));
+                    }
+                }
+                index.sort_unstable();
+            }
+            index.into_iter()
+        },
+        |(key, _)| *key,
+        move |(key, row)| {
+            text(key)
+                .style(|s| {
+                    s.width_full()
+                        .justify_center()
+                        .font_size(10.0)
+                        .cursor(CursorStyle::Pointer)
                 })
-                .hover_style(|s| s.background(Color::rgb8(226, 226, 205)))
+                .hover_style(|s| s.background(theme().hover))
                 .on_click(move |_| {
-                    selection.set(Selection::Symbol(o.clone()));
+                    jump(row);
                     true
                 })
         },
     )
-    .style(|s| {
+    .style(move |s| {
         s.flex_col()
-            .background(Color::rgb8(243, 243, 228))
+            .width(16)
+            .height_full()
+            .items_center()
+            .background(theme().panel)
+            .apply_if(!show_index, |s| s.display(floem::style::Display::None))
+    });
+
+    // Typing jumps to the first symbol starting with the typed text, without
+    // filtering the list
+    let typed = create_rw_signal((String::new(), Instant::now()));
+    let prompt =
+        label(move || typed.with(|(prefix, _)| format!("Jump to: {}", prefix))).style(move |s| {
+            s.padding(5)
+                .width_full()
+                .background(theme().prompt)
+                .apply_if(typed.with(|(prefix, _)| prefix.is_empty()), |s| {
+                    s.display(floem::style::Display::None)
+                })
+        });
+
+    let symbols = stack((symbols, index))
+        .style(|s| s.flex_row().width_full().flex_grow(1.0).flex_basis(0.0))
+        .keyboard_navigatable()
+        .on_event(EventListener::KeyDown, move |e| {
+            let Event::KeyDown(e) = e else {
+                return false;
+            };
+            if e.modifiers.control_key() || e.modifiers.alt_key() {
+                return false;
+            }
+            let rows = sorted();
+            let current = cursor
+                .get_untracked()
+                .and_then(|key| rows.iter().position(|row| row.key() == key))
+                .or_else(|| {
+                    selection.with_untracked(|selection| match selection {
+                        Selection::Symbol(symbol) => rows.iter().position(|row| {
+                            row.symbol().is_some_and(|row| row.id() == symbol.id())
+                        }),
+                        _ => None,
+                    })
+                });
+            if e.key.logical_key == Key::Named(NamedKey::Enter) {
+                match current.map(|row| &rows[row]) {
+                    Some(SymbolRow::Group(object)) => toggle_collapsed(collapsed, &object.id),
+                    Some(SymbolRow::Path(group)) => toggle_expanded(expanded, &group.path),
+                    Some(SymbolRow::Symbol(symbol, _)) => {
+                        history::jump(selection, history, Selection::Symbol(symbol.clone()))
+                    }
+                    None => return false,
+                }
+                return true;
+            }
+            let page = (viewport.get_untracked() / row_height) as usize;
+            if let Some(row) = step(&e.key.logical_key, current, rows.len(), page) {
+                cursor.set(Some(rows[row].key()));
+                reveal(scroll_y, viewport.get_untracked(), row, row_height);
+                return true;
+            }
+            let mut prefix = typed.with_untracked(|(prefix, last)| {
+                if last.elapsed() < TYPE_AHEAD_TIMEOUT {
+                    prefix.clone()
+                } else {
+                    String::new()
+                }
+            });
+            match &e.key.logical_key {
+                Key::Character(c) => prefix.push_str(c),
+                Key::Named(NamedKey::Backspace) => {
+                    prefix.pop();
+                }
+                Key::Named(NamedKey::Escape) => prefix.clear(),
+                _ => return false,
+            }
+            if !prefix.is_empty() {
+                let lower = prefix.to_lowercase();
+                let row = rows.iter().position(|o| {
+                    o.symbol().is_some_and(|o| {
+                        o.data
+                            .demangled
+                            .as_ref()
+                            .unwrap_or(&o.data.name)
+                            .to_lowercase()
+                            .starts_with(&lower)
+                    })
+                });
+                if let Some(row) = row {
+                    cursor.set(Some(rows[row].key()));
+                    jump(row);
+                }
+            }
+            typed.set((prefix, Instant::now()));
+            true
+        });
+
+    let symbols = stack((prompt, symbols)).style(|s| s.flex_col().width_full().height_full());
+
+    let sort_header = move |label: &'static str, key: Option<SortKey>| {
+        text(label)
+            .style(move |s| {
+                s.apply_if(sort.get() == key, |s| s.font_weight(Weight::BOLD))
+                    .apply_if(matches!(key, Some(SortKey::Metric(_))), |s| {
+                        s.width(40).padding_left(6)
+                    })
+                    .apply_if(key.is_none(), |s| s.flex_grow(1.0))
+                    .cursor(CursorStyle::Pointer)
+            })
+            .on_click(move |_| {
+                sort.set(key);
+                true
+            })
+    };
+    let references = stack((
+        sort_header("Refs", Some(SortKey::References)),
+        text(" / "),
+        sort_header("Callers", Some(SortKey::Callers)),
+    ))
+    .style(|s| {
+        s.flex_row()
+            .width(BADGE_WIDTH)
+            .padding_horiz(4)
+            .margin_left(4)
+    });
+    let columns = list(
+        move || metric_columns.then_some(Metric::ALL).into_iter().flatten(),
+        |metric| *metric,
+        move |metric| sort_header(metric.label(), Some(SortKey::Metric(metric))),
+    )
+    .style(|s| s.flex_row());
+    let names = stack((
+        sort_header("Name", None),
+        text(" / "),
+        sort_header("Address", Some(SortKey::Address)),
+    ))
+    .style(|s| s.flex_row().flex_grow(1.0));
+    let size = container(sort_header("Size", Some(SortKey::Size)))
+        .style(|s| s.width(SIZE_WIDTH).justify_end());
+    let header = stack((names, size, references, columns)).style(|s| {
+        s.flex_row()
+            .padding(5)
             .width_full()
+            .border_bottom(0.5)
+            .border_color(theme().border)
+    });
+
+    Box::new(stack((header, symbols)).style(|s| s.flex_col().width_full().height_full()))
+}
+
+fn app_view(startup: cli::Startup) -> impl View {
+    let objects = create_rw_signal(ObjectList {
+        objects: Vec::new(),
+        errors: Vec::new(),
+        loading: Vec::new(),
+        workspaces: Vec::new(),
+        crates: HashMap::new(),
+    });
+    PROBLEMS.with(|problems| problems.set(Some(objects)));
+
+    let selection = create_rw_signal(Selection::None);
+
+    let tour = create_rw_signal(None);
+
+    // Loaded before any files, which are parsed with the saved demangling
+    let settings = create_rw_signal(Settings::load());
+    settings.with_untracked(|s| {
+        settings::set_demangling(s.demangling);
+        pool::set_threads(s.threads);
+    });
+    create_effect(move |_| {
+        settings.with(|s| settings::set_symbol_server(s.symbol_server));
+        if let Err(error) = settings.with(|s| s.save()) {
+            report_error(objects, error);
+        }
+    });
+
+    if !startup.paths.is_empty() {
+        open_paths(objects, selection, startup.paths, startup.symbol);
+    } else if let Some(session) = session::recover() {
+        for (path, layout) in session.raw {
+            raw::open_with(objects, selection, path, layout);
+        }
+        if !session.paths.is_empty() {
+            open_paths(objects, selection, session.paths, session.symbol);
+        }
+    } else if tour::first_run() {
+        tour::start(objects, selection, tour);
+    }
+
+    session::autosave(objects, selection);
+
+    let diff_base = create_rw_signal(None);
+
+    let path_start = create_rw_signal(None);
+
+    let position = create_rw_signal(None);
+
+    let history = create_rw_signal(History::default());
+
+    let searches = create_rw_signal(search::Searches::default());
+
+    let series = create_rw_signal(timeline::Series::default());
+
+    let cargo_release = create_rw_signal(false);
+
+    let profile = create_rw_signal(None::<Arc<profile::Profile>>);
+
+    let favorites = create_rw_signal(0);
+    let annotations = create_rw_signal(0);
+    let cargo_actions = stack((
+        button("Open Cargo Project", move |_| {
+            let profile = if cargo_release.get_untracked() {
+                "release"
+            } else {
+                "dev"
+            };
+            cargo::open_project(objects, selection, profile);
+            true
+        }),
+        toggle_button(
+            "Release",
+            move || cargo_release.get(),
+            move |_| {
+                cargo_release.update(|release| *release = !*release);
+                true
+            },
+        ),
+    ))
+    .style(|s| s.flex_row());
+
+    let listed_objects = move || {
+        objects.with(|list| {
+            let mut objects: Vec<_> = list
+                .objects
+                .iter()
+                .map(|o| (o.clone(), list.crates.get(&o.path).cloned()))
+                .collect();
+            objects.sort_by(|a, b| a.1.cmp(&b.1));
+            objects
+        })
+    };
+    // The object moved to with the keyboard, which enter selects
+    let object_cursor = create_rw_signal(None);
+    let object_list = list(
+        listed_objects,
+        |(o, _)| o.key(),
+        move |(o, crate_name)| {
+            let o_ = o.clone();
+            let key = o.key();
+            let closed = o.clone();
+            stack((
+                text(
+                    crate_name
+                        .map(|name| format!("{} ", name))
+                        .unwrap_or_default(),
+                )
+                .style(|s| s.color(theme().dim)),
+                text(o.title()).style(|s| {
+                    s.flex_grow(1.0)
+                        .min_width(0.0)
+                        .text_overflow(TextOverflow::Clip)
+                }),
+                text("×")
+                    .style(|s| s.padding_horiz(4).cursor(CursorStyle::Pointer))
+                    .hover_style(|s| s.color(theme().error))
+                    .on_click(move |_| {
+                        close_object(
+                            objects, selection, history, searches, diff_base, path_start, &closed,
+                        );
+                        true
+                    }),
+            ))
+            .style(move |s| {
+                s.apply_if(
+                    selection.with(|s| {
+                        if let Selection::Object(so) = s {
+                            so.key() == o_.key()
+                        } else {
+                            false
+                        }
+                    }),
+                    |s| s.background(theme().selection),
+                )
+                .flex_row()
+                .items_center()
+                .padding(5)
+                .width_full()
+                .height(26.0)
+                .apply_if(object_cursor.get().as_ref() == Some(&key), cursor_style)
+            })
+            .hover_style(|s| s.background(theme().button_hover))
+            .on_click(move |_| {
+                selection.set(Selection::Object(o.clone()));
+                true
+            })
+        },
+    )
+    .style(|s| s.flex_col().height_full())
+    .keyboard_navigatable()
+    .on_event(EventListener::KeyDown, move |e| {
+        let Event::KeyDown(e) = e else {
+            return false;
+        };
+        let listed = listed_objects();
+        let current = object_cursor
+            .get_untracked()
+            .and_then(|key| listed.iter().position(|(o, _)| o.key() == key))
+            .or_else(|| {
+                selection.with_untracked(|selection| match selection {
+                    Selection::Object(object) => {
+                        listed.iter().position(|(o, _)| o.key() == object.key())
+                    }
+                    _ => None,
+                })
+            });
+        if e.key.logical_key == Key::Named(NamedKey::Enter) {
+            let Some(row) = current else {
+                return false;
+            };
+            selection.set(Selection::Object(listed[row].0.clone()));
+            return true;
+        }
+        match step(&e.key.logical_key, current, listed.len(), listed.len()) {
+            Some(row) => {
+                object_cursor.set(Some(listed[row].0.key()));
+                true
+            }
+            None => false,
+        }
     });
 
-    let symbol_list = scroll(symbol_list).style(|s| s.width_full().height_full());
+    let symbol_sort = create_rw_signal(None);
+
+    let collapsed_objects = create_rw_signal(HashSet::new());
+    let expanded_paths = create_rw_signal(HashSet::new());
+
+    let symbol_list = dyn_container(
+        move || settings.with(|s| (s.metric_columns, s.symbol_index, s.group_by_path, s.density)),
+        move |(metric_columns, show_index, group_by_path, density)| {
+            symbol_list(
+                objects,
+                selection,
+                history,
+                symbol_sort,
+                collapsed_objects,
+                expanded_paths,
+                metric_columns,
+                show_index,
+                group_by_path,
+                density,
+                annotations,
+            )
+        },
+    )
+    .style(|s| s.width_full().height_full());
+
+    // Files and members which failed to load, until dismissed
+    let errors = list(
+        move || objects.with(|objects| objects.errors.clone().into_iter().enumerate()),
+        |(i, error)| (*i, error.clone()),
+        move |(i, error)| {
+            stack((
+                text(error).style(|s| {
+                    s.padding(5)
+                        .flex_grow(1.0)
+                        .flex_basis(0.0)
+                        .min_width(0.0)
+                        .color(theme().error)
+                        .text_overflow(TextOverflow::Clip)
+                }),
+                button("Dismiss", move |_| {
+                    objects.update(|list| {
+                        if i < list.errors.len() {
+                            list.errors.remove(i);
+                        }
+                    });
+                    true
+                }),
+            ))
+            .style(|s| s.flex_row().width_full().items_center())
+        },
+    )
+    .style(|s| s.flex_col().width_full());
+    let errors = stack((
+        stack((
+            label(move || format!("Problems ({})", objects.with(|list| list.errors.len())))
+                .style(|s| s.padding(5.0).flex_grow(1.0).color(theme().error)),
+            button("Clear", move |_| {
+                objects.update(|list| list.errors.clear());
+                true
+            }),
+        ))
+        .style(|s| {
+            s.flex_row()
+                .width_full()
+                .items_center()
+                .background(theme().panel)
+        }),
+        errors,
+    ))
+    .style(move |s| {
+        s.flex_col()
+            .width_full()
+            .apply_if(objects.with(|list| list.errors.is_empty()), |s| s.hide())
+    });
 
     let object_list = stack((
+        favorites::favorites_panel(objects, favorites, selection, history),
         header("Objects"),
+        errors,
         object_list,
+        workspace::workspace_list(objects, selection),
         header("Symbols"),
         symbol_list,
     ))
-    .style(|s| {
-        s.flex_col()
-            .width(300)
+    .style(move |s| {
+        let s = s
+            .flex_col()
+            .width(if settings.with(|s| s.metric_columns) {
+                650
+            } else {
+                430
+            })
             .height_full()
             .border_right(0.5)
-            .border_color(Color::LIGHT_GRAY)
+            .border_color(theme().border);
+        tour::highlight(s, tour, tour::Area::Symbols)
     });
 
     let content = dyn_container(
-        move || selection.with(|s| s.clone()),
-        move |current| main_container(current, selection),
+        // Rebuilt for a new theme or font as instruction layouts have their
+        // colors and font
+        move || {
+            (
+                selection.get(),
+                settings.get(),
+                (theme::kind(), theme::font()),
+                profile.get(),
+            )
+        },
+        move |(current, current_settings, _, current_profile)| {
+            main_container(
+                current,
+                objects,
+                settings,
+                &current_settings,
+                selection,
+                diff_base,
+                path_start,
+                series,
+                current_profile,
+                position,
+                history,
+                favorites,
+                annotations,
+                tour,
+            )
+        },
     )
-    .style(|s| s.width_full().height_full().background(Color::WHITE));
+    .style(move |s| {
+        let s = s.width_full().height_full().background(theme().background);
+        tour::highlight(s, tour, tour::Area::Assembly)
+    });
+
+    let content = stack((content, search::search_panel(searches, objects, selection)))
+        .style(|s| s.flex_col().width_full().height_full());
 
     let lower = stack((object_list, content)).style(|s| {
         s.flex_row()
@@ -617,24 +3230,274 @@ fn app_view() -> impl View {
             .height_full()
     });
 
-    let bar = stack((button("Open", move |_| {
-        open_file(objects);
-        true
-    }),))
+    let syntax = syntax_picker(
+        move || settings.with(|s| s.syntax),
+        move |syntax| settings.update(|s| s.syntax = syntax),
+    );
+
+    let registered = create_rw_signal(false);
+    let file_types = stack((
+        button("Register File Types", move |_| {
+            match desktop::register() {
+                Ok(()) => registered.set(true),
+                Err(error) => report_error(objects, format!("Registering file types: {}", error)),
+            }
+            true
+        }),
+        text("Registered").style(move |s| {
+            s.padding(5.0)
+                .color(theme().dim)
+                .apply_if(!registered.get(), |s| {
+                    s.display(floem::style::Display::None)
+                })
+        }),
+    ))
+    .style(|s| s.flex_row().items_center());
+
+    let view_options = stack((
+        toggle_button(
+            "Collapse Padding",
+            move || settings.with(|s| s.collapse_padding),
+            move |_| {
+                settings.update(|s| s.collapse_padding = !s.collapse_padding);
+                true
+            },
+        ),
+        toggle_button(
+            "Metrics",
+            move || settings.with(|s| s.metric_columns),
+            move |_| {
+                settings.update(|s| s.metric_columns = !s.metric_columns);
+                true
+            },
+        ),
+        stack((
+            toggle_button(
+                "Symbol Index",
+                move || settings.with(|s| s.symbol_index),
+                move |_| {
+                    settings.update(|s| s.symbol_index = !s.symbol_index);
+                    true
+                },
+            ),
+            toggle_button(
+                "Group by Path",
+                move || settings.with(|s| s.group_by_path),
+                move |_| {
+                    settings.update(|s| s.group_by_path = !s.group_by_path);
+                    true
+                },
+            ),
+        ))
+        .style(|s| s.flex_row()),
+        toggle_button(
+            "Source",
+            move || settings.with(|s| s.show_source),
+            move |_| {
+                settings.update(|s| s.show_source = !s.show_source);
+                true
+            },
+        ),
+        stack((
+            toggle_button(
+                "Bytes",
+                move || settings.with(|s| s.show_bytes),
+                move |_| {
+                    settings.update(|s| s.show_bytes = !s.show_bytes);
+                    true
+                },
+            ),
+            toggle_button(
+                "Relative Addresses",
+                move || settings.with(|s| s.relative_addresses),
+                move |_| {
+                    settings.update(|s| s.relative_addresses = !s.relative_addresses);
+                    true
+                },
+            ),
+        ))
+        .style(|s| s.flex_row()),
+        toggle_button(
+            "Compact Rows",
+            move || settings.with(|s| s.density == Density::Compact),
+            move |_| {
+                settings.update(|s| {
+                    s.density = match s.density {
+                        Density::Comfortable => Density::Compact,
+                        Density::Compact => Density::Comfortable,
+                    }
+                });
+                true
+            },
+        ),
+        toggle_button(
+            "Operand Sizes",
+            move || settings.with(|s| s.operand_sizes),
+            move |_| {
+                settings.update(|s| s.operand_sizes = !s.operand_sizes);
+                true
+            },
+        ),
+        toggle_button(
+            "Dark Theme",
+            || theme::kind() == theme::Kind::Dark,
+            |_| {
+                theme::set(match theme::kind() {
+                    theme::Kind::Light => theme::Kind::Dark,
+                    theme::Kind::Dark => theme::Kind::Light,
+                });
+                true
+            },
+        ),
+        syntax,
+        target_picker(settings),
+        heat_picker(settings),
+        stack((
+            demangling_picker(settings, objects, selection),
+            threads_picker(settings),
+        ))
+        .style(|s| s.flex_row().items_start()),
+    ))
+    .style(|s| s.flex_row().items_start());
+
+    let navigation = stack((
+        button("Back", move |_| {
+            history::back(selection, history);
+            true
+        }),
+        button("Forward", move |_| {
+            history::forward(selection, history);
+            true
+        }),
+    ))
+    .style(move |s| tour::highlight(s.flex_row(), tour, tour::Area::Navigation));
+
+    let reports = stack((
+        button("Dead Code", move |_| {
+            selection.set(Selection::DeadCode);
+            true
+        }),
+        button("Size", move |_| {
+            selection.set(Selection::Bloat);
+            true
+        }),
+        button("Tracked", move |_| {
+            selection.set(Selection::Tracked);
+            true
+        }),
+        button("Settings", move |_| {
+            selection.set(Selection::Preferences);
+            true
+        }),
+    ))
+    .style(|s| s.flex_row());
+
+    let imports = stack((
+        button("Import Symbols", move |_| {
+            import_symbols(objects, selection);
+            true
+        }),
+        button("Load Profile", move |_| {
+            profile::open_profile(objects, profile);
+            true
+        }),
+        label(move || {
+            profile.with(|profile| {
+                profile
+                    .as_ref()
+                    .map(|profile| {
+                        format!(
+                            "{} ({} samples)",
+                            profile
+                                .path
+                                .file_name()
+                                .unwrap_or_default()
+                                .to_string_lossy(),
+                            units::count(profile.total)
+                        )
+                    })
+                    .unwrap_or_default()
+            })
+        })
+        .style(move |s| {
+            s.padding(5.0)
+                .color(theme().dim)
+                .apply_if(profile.with(|profile| profile.is_none()), |s| {
+                    s.display(floem::style::Display::None)
+                })
+        }),
+        text("×")
+            .style(move |s| {
+                s.padding(5.0)
+                    .cursor(CursorStyle::Pointer)
+                    .apply_if(profile.with(|profile| profile.is_none()), |s| {
+                        s.display(floem::style::Display::None)
+                    })
+            })
+            .hover_style(|s| s.color(theme().error))
+            .on_click(move |_| {
+                profile.set(None);
+                true
+            }),
+    ))
+    .style(|s| s.flex_row().items_center());
+
+    let bar = stack((
+        navigation,
+        container(button("Open", move |_| {
+            open_file(objects, selection);
+            true
+        }))
+        .style(move |s| tour::highlight(s, tour, tour::Area::Open)),
+        stack((
+            button("Open Raw", move |_| {
+                raw::open_raw(selection);
+                true
+            }),
+            button("Add Folder", move |_| {
+                workspace::add_folder(objects, selection);
+                true
+            }),
+        )),
+        cargo_actions,
+        button("Open Series", move |_| {
+            timeline::open_series(series, objects);
+            true
+        }),
+        reports,
+        imports,
+        file_types,
+        view_options,
+        container(search::search_bar(searches, objects))
+            .style(move |s| tour::highlight(s, tour, tour::Area::Search)),
+        button("Find from Clipboard", move |_| {
+            search::find_from_clipboard(searches, objects, selection);
+            true
+        }),
+        label(move || objects.with(|list| loading_text(&list.loading))).style(move |s| {
+            s.padding(5.0)
+                .color(theme().dim)
+                .apply_if(objects.with(|list| list.loading.is_empty()), |s| {
+                    s.display(floem::style::Display::None)
+                })
+        }),
+    ))
     .style(|s| {
         s.flex_row()
             .items_start()
             .justify_start()
             .border_bottom(0.5)
-            .border_color(Color::LIGHT_GRAY)
+            .border_color(theme().border)
     });
 
-    stack((bar, lower))
+    stack((bar, tour::tour_card(tour), lower))
         .style(|s| {
             s.flex_col()
                 .width_full()
                 .height_full()
                 .font_size(12.0)
+                .color(theme().text)
+                .background(theme().background)
                 .scroll_bar_thickness(20.0)
                 .scroll_bar_rounded(false)
                 .scroll_bar_color(Color::rgba8(166, 166, 166, 140))
@@ -642,10 +3505,67 @@ fn app_view() -> impl View {
                 .scroll_bar_hover_color(Color::rgb8(184, 184, 184))
                 .set(bg_active_color, Color::rgba8(166, 166, 166, 40))
         })
+        .on_event(EventListener::KeyDown, move |e| {
+            if let Event::KeyDown(e) = e {
+                // Ctrl+Shift+V finds the symbol named on the clipboard
+                if e.modifiers.control_key()
+                    && e.modifiers.shift_key()
+                    && e.key.logical_key == Key::Character("V".into())
+                {
+                    search::find_from_clipboard(searches, objects, selection);
+                    return true;
+                }
+                if e.modifiers.control_key()
+                    && matches!(&e.key.logical_key, Key::Character(c) if c.eq_ignore_ascii_case("f"))
+                {
+                    search::focus_search_bar();
+                    return true;
+                }
+                if e.modifiers.control_key()
+                    && matches!(&e.key.logical_key, Key::Character(c) if c.eq_ignore_ascii_case("g"))
+                {
+                    goto::focus_goto_box();
+                    return true;
+                }
+                if e.modifiers.alt_key() {
+                    match e.key.logical_key {
+                        Key::Named(NamedKey::ArrowLeft) => {
+                            history::back(selection, history);
+                            return true;
+                        }
+                        Key::Named(NamedKey::ArrowRight) => {
+                            history::forward(selection, history);
+                            return true;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            false
+        })
+        // The side buttons of the mouse
+        .on_event(EventListener::PointerDown, move |e| {
+            if let Event::PointerDown(e) = e {
+                if e.button.is_x1() {
+                    history::back(selection, history);
+                    return true;
+                }
+                if e.button.is_x2() {
+                    history::forward(selection, history);
+                    return true;
+                }
+            }
+            false
+        })
         .window_title(|| "Assembly Viewer".to_string())
 }
 
 fn main() {
     env_logger::init();
-    floem::launch(app_view);
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+    let startup = cli::startup(&args);
+    floem::launch(move || app_view(startup.clone()));
 }
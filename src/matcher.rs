@@ -0,0 +1,440 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use asm_viewer_core::{arm, discover, identity, overlay, pool, raw};
+use floem::{
+    ext_event::create_ext_action,
+    reactive::{create_rw_signal, RwSignal, Scope},
+    view::View,
+    views::{
+        dyn_container, list, scroll, stack, text, virtual_list, Decorators, VirtualListDirection,
+        VirtualListItemSize,
+    },
+};
+
+use crate::{
+    button, header, theme::theme, units, xrefs, Object, ObjectList, Selection, Symbol, SymbolData,
+};
+
+// Fuzzy matches below this similarity are not reported
+const MIN_SIMILARITY: f64 = 0.75;
+
+// The matches with the keys of the objects they're for
+type Matches = (
+    ((identity::ObjectId, u64), (identity::ObjectId, u64)),
+    Arc<Vec<Match>>,
+);
+
+thread_local! {
+    // The last matches, as the view is rebuilt when the settings or the theme
+    // change
+    static MATCHES: RefCell<Option<Matches>> = const { RefCell::new(None) };
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Method {
+    Name,
+    Shape,
+    Similarity,
+}
+
+impl Method {
+    fn label(self) -> &'static str {
+        match self {
+            Method::Name => "name",
+            Method::Shape => "CFG shape",
+            Method::Similarity => "similarity",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Match {
+    left: Symbol,
+    right: Symbol,
+    confidence: f64,
+    method: Method,
+}
+
+// Name independent features of a function
+struct Features {
+    data: Arc<SymbolData>,
+    instructions: usize,
+    size: usize,
+    basic_blocks: usize,
+    branches: usize,
+    complexity: usize,
+    calls: usize,
+    loop_depth: usize,
+    // Names of the functions called, through relocations or resolved
+    // branches. Functions found without symbols are left out, as their names
+    // are only addresses.
+    callees: HashSet<String>,
+}
+
+// Whether the function was found without a symbol, so its name is made up
+fn found_by_hand(data: &SymbolData) -> bool {
+    data.index.is_none() && data.name == raw::function_name(data.address)
+}
+
+impl Features {
+    fn compute(
+        data: &Arc<SymbolData>,
+        callees: &HashMap<&str, HashSet<String>>,
+    ) -> Option<Features> {
+        let bytes = data.data()?;
+        let metrics = data.metrics();
+        let instructions = match data.arm_modes() {
+            Some(modes) => arm::Decoder::new(bytes, data.address, modes).count(),
+            None => iced_x86::Decoder::with_ip(
+                data.bitness(),
+                bytes,
+                data.address,
+                iced_x86::DecoderOptions::NONE,
+            )
+            .into_iter()
+            .count(),
+        };
+
+        let callees = callees.get(data.name.as_str()).cloned().unwrap_or_default();
+
+        Some(Features {
+            data: data.clone(),
+            instructions,
+            size: bytes.len(),
+            basic_blocks: metrics.basic_blocks,
+            branches: metrics.branches,
+            complexity: metrics.complexity,
+            calls: metrics.calls,
+            loop_depth: metrics.loop_depth,
+            callees,
+        })
+    }
+
+    fn shape(&self) -> (usize, usize, usize, usize, usize, usize) {
+        (
+            self.instructions,
+            self.basic_blocks,
+            self.branches,
+            self.complexity,
+            self.calls,
+            self.loop_depth,
+        )
+    }
+
+    fn similarity(&self, other: &Features) -> f64 {
+        let close = |a: usize, b: usize| 1.0 - a.abs_diff(b) as f64 / a.max(b).max(1) as f64;
+        let mut score = [
+            close(self.instructions, other.instructions),
+            close(self.size, other.size),
+            close(self.basic_blocks, other.basic_blocks),
+            close(self.branches, other.branches),
+            close(self.complexity, other.complexity),
+            close(self.calls, other.calls),
+            close(self.loop_depth, other.loop_depth),
+        ]
+        .iter()
+        .sum::<f64>()
+            / 7.0;
+
+        if !self.callees.is_empty() || !other.callees.is_empty() {
+            let shared = self.callees.intersection(&other.callees).count();
+            let all = self.callees.union(&other.callees).count();
+            score = (score + shared as f64 / all as f64) / 2.0;
+        }
+        score
+    }
+}
+
+fn features(object: &Object) -> Vec<Features> {
+    let mut callees: HashMap<&str, HashSet<String>> = HashMap::new();
+    for (target, xrefs) in object.callers.iter() {
+        if !xrefs::find(object, target).is_some_and(|target| found_by_hand(&target)) {
+            for xref in xrefs {
+                callees
+                    .entry(xref.from.as_str())
+                    .or_default()
+                    .insert(target.to_owned());
+            }
+        }
+    }
+    object
+        .symbols_sorted
+        .iter()
+        .filter_map(|data| Features::compute(data, &callees))
+        .collect()
+}
+
+// The object with the functions of stripped images found, so they can be
+// matched too
+fn with_functions(object: &Arc<Object>) -> Arc<Object> {
+    if discover::has_functions(object) {
+        return object.clone();
+    }
+    Arc::new(overlay::merge(object, &discover::functions(object)))
+}
+
+// Pairs up functions of two objects. Names are matched first, then unique CFG
+// shapes, and remaining functions are paired greedily by feature similarity.
+pub fn match_functions(left: &Arc<Object>, right: &Arc<Object>) -> Vec<Match> {
+    let (left, right) = (&with_functions(left), &with_functions(right));
+    let left_features = features(left);
+    let right_features = features(right);
+    let mut left_done = vec![false; left_features.len()];
+    let mut right_done = vec![false; right_features.len()];
+    let mut matches = Vec::new();
+
+    let push = |matches: &mut Vec<Match>, l: &Features, r: &Features, method| {
+        matches.push(Match {
+            left: Symbol {
+                object: left.clone(),
+                data: l.data.clone(),
+            },
+            right: Symbol {
+                object: right.clone(),
+                data: r.data.clone(),
+            },
+            confidence: if method == Method::Name {
+                1.0
+            } else {
+                l.similarity(r)
+            },
+            method,
+        })
+    };
+
+    // Names made up from addresses only match by chance
+    let by_name: HashMap<&str, usize> = right_features
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| !found_by_hand(&f.data))
+        .map(|(i, f)| (f.data.name.as_str(), i))
+        .collect();
+    for (i, l) in left_features.iter().enumerate() {
+        if found_by_hand(&l.data) {
+            continue;
+        }
+        if let Some(&j) = by_name.get(l.data.name.as_str()) {
+            if !right_done[j] {
+                left_done[i] = true;
+                right_done[j] = true;
+                push(&mut matches, l, &right_features[j], Method::Name);
+            }
+        }
+    }
+
+    let shapes = |features: &[Features], done: &[bool]| {
+        let mut shapes: HashMap<_, Vec<usize>> = HashMap::new();
+        for (i, f) in features.iter().enumerate() {
+            if !done[i] {
+                shapes.entry(f.shape()).or_default().push(i);
+            }
+        }
+        shapes
+    };
+    let left_shapes = shapes(&left_features, &left_done);
+    let right_shapes = shapes(&right_features, &right_done);
+    for (shape, left_indices) in &left_shapes {
+        if let ([i], Some([j])) = (&left_indices[..], right_shapes.get(shape).map(|v| &v[..])) {
+            left_done[*i] = true;
+            right_done[*j] = true;
+            push(
+                &mut matches,
+                &left_features[*i],
+                &right_features[*j],
+                Method::Shape,
+            );
+        }
+    }
+
+    // Only compare functions of similar length to keep this tractable
+    let mut remaining: Vec<usize> = (0..right_features.len())
+        .filter(|&j| !right_done[j])
+        .collect();
+    remaining.sort_by_key(|&j| right_features[j].instructions);
+    let mut candidates = Vec::new();
+    for (i, l) in left_features.iter().enumerate() {
+        if left_done[i] {
+            continue;
+        }
+        let low = l.instructions * 3 / 4;
+        let high = l.instructions * 4 / 3 + 1;
+        let start = remaining.partition_point(|&j| right_features[j].instructions < low);
+        for &j in &remaining[start..] {
+            if right_features[j].instructions > high {
+                break;
+            }
+            let score = l.similarity(&right_features[j]);
+            if score >= MIN_SIMILARITY {
+                candidates.push((score, i, j));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+    for (_, i, j) in candidates {
+        if !left_done[i] && !right_done[j] {
+            left_done[i] = true;
+            right_done[j] = true;
+            push(
+                &mut matches,
+                &left_features[i],
+                &right_features[j],
+                Method::Similarity,
+            );
+        }
+    }
+
+    matches.sort_by(|a, b| {
+        a.confidence
+            .total_cmp(&b.confidence)
+            .then_with(|| a.left.data.name.cmp(&b.left.data.name))
+    });
+    matches
+}
+
+// Lets the user pick another loaded object to match `object` against
+pub fn match_tab(
+    object: &Arc<Object>,
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+) -> Box<dyn View> {
+    let left = object.clone();
+    let others = list(
+        move || {
+            objects.with(|objects| {
+                objects
+                    .objects
+                    .iter()
+                    .filter(|o| o.key() != left.key())
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+        },
+        |o| o.key(),
+        {
+            let left = object.clone();
+            move |right: Arc<Object>| {
+                let left = left.clone();
+                button(format!("Match against `{}`", right.title()), move |_| {
+                    selection.set(Selection::Matches(left.clone(), right.clone()));
+                    true
+                })
+            }
+        },
+    )
+    .style(|s| s.flex_col().items_start());
+
+    Box::new(
+        stack((
+            header("Match Functions"),
+            text(
+                "Pairs functions by name, CFG shape and call targets, also for stripped binaries.",
+            )
+            .style(|s| s.padding(5.0)),
+            others,
+        ))
+        .style(|s| s.flex_col().width_full()),
+    )
+}
+
+pub fn matches_view(
+    left: Arc<Object>,
+    right: Arc<Object>,
+    selection: RwSignal<Selection>,
+) -> Box<dyn View> {
+    let key = (left.key(), right.key());
+    let cached = MATCHES.with(|matches| {
+        matches
+            .borrow()
+            .as_ref()
+            .filter(|(of, _)| *of == key)
+            .map(|(_, matches)| matches.clone())
+    });
+    let found = create_rw_signal(cached.clone());
+    if cached.is_none() {
+        // Every function of both objects is decoded
+        let done = create_ext_action(Scope::new(), move |matches: Arc<Vec<Match>>| {
+            MATCHES.with(|cache| *cache.borrow_mut() = Some((key, matches.clone())));
+            found.set(Some(matches));
+        });
+        let (left, right) = (left.clone(), right.clone());
+        pool::spawn(pool::Priority::Interactive, move || {
+            done(Arc::new(match_functions(&left, &right)))
+        });
+    }
+
+    let content = dyn_container(
+        move || found.get(),
+        move |matches| match matches {
+            Some(matches) => matches_list(&matches, selection),
+            None => Box::new(text("Matching functions...").style(|s| s.padding(5.0))),
+        },
+    )
+    .style(|s| s.width_full().flex_grow(1.0).flex_basis(0.0));
+
+    Box::new(
+        stack((
+            header(format!(
+                "Function Matches: `{}` and `{}`",
+                left.title(),
+                right.title()
+            )),
+            content,
+        ))
+        .style(|s| s.flex_col().width_full().height_full()),
+    )
+}
+
+fn matches_list(matches: &[Match], selection: RwSignal<Selection>) -> Box<dyn View> {
+    let renamed = matches.iter().filter(|m| m.method != Method::Name).count();
+    let summary = text(format!(
+        "{} matched functions, {} matched without names. Click a pair to diff it.",
+        units::count(matches.len() as u64),
+        units::count(renamed as u64)
+    ))
+    .style(|s| s.padding(5.0));
+
+    let matches: im::Vector<_> = matches.iter().cloned().enumerate().collect();
+    let list = virtual_list(
+        VirtualListDirection::Vertical,
+        VirtualListItemSize::Fixed(Box::new(|| 26.0)),
+        move || matches.clone(),
+        |(i, _)| *i,
+        move |(_, m)| {
+            let name = |symbol: &Symbol| {
+                symbol
+                    .data
+                    .demangled
+                    .as_ref()
+                    .unwrap_or(&symbol.data.name)
+                    .clone()
+            };
+            let cell = |s: floem::style::Style| s.flex_grow(1.0).flex_basis(0.0).min_width(0.0);
+            let (left, right) = (m.left.clone(), m.right.clone());
+            stack((
+                text(name(&m.left)).style(cell),
+                text(name(&m.right)).style(cell),
+                text(format!("{:.0}%", m.confidence * 100.0)).style(|s| s.width(60)),
+                text(m.method.label()).style(|s| s.width(90).color(theme().dim)),
+            ))
+            .style(|s| s.flex_row().width_full().padding(5).height(26.0))
+            .hover_style(|s| s.background(theme().hover))
+            .on_click(move |_| {
+                selection.set(Selection::Diff(left.clone(), right.clone()));
+                true
+            })
+        },
+    )
+    .style(|s| s.flex_col().width_full());
+
+    Box::new(
+        stack((
+            summary,
+            scroll(list).style(|s| s.width_full().flex_grow(1.0).flex_basis(0.0)),
+        ))
+        .style(|s| s.flex_col().width_full().height_full()),
+    )
+}
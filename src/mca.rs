@@ -0,0 +1,239 @@
+use std::{
+    collections::HashMap,
+    env,
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+    sync::{Arc, OnceLock},
+};
+
+use floem::{
+    ext_event::create_ext_action,
+    reactive::{RwSignal, Scope},
+    style::TextOverflow,
+    view::View,
+    views::{label, scroll, stack, text, Decorators},
+};
+use iced_x86::{Decoder, DecoderOptions, Formatter, GasFormatter};
+
+use crate::{
+    header, pool,
+    settings::Microarch,
+    theme::{font, theme},
+    AddressRange, SymbolData,
+};
+
+// Cycles of an instruction in the `Instruction Info` table of the report
+#[derive(Clone, Copy)]
+pub struct Cycles {
+    pub latency: u32,
+    pub throughput: f32,
+}
+
+pub struct Report {
+    pub text: String,
+    pub cycles: Arc<HashMap<u64, Cycles>>,
+}
+
+#[derive(Clone)]
+pub enum Analysis {
+    Running,
+    Done(Arc<Report>),
+    Failed(String),
+}
+
+impl Analysis {
+    pub fn cycles(&self) -> Arc<HashMap<u64, Cycles>> {
+        match self {
+            Analysis::Done(report) => report.cycles.clone(),
+            _ => Arc::default(),
+        }
+    }
+}
+
+// `llvm-mca` on the `PATH`, possibly with the version suffix of Debian packages
+pub fn program() -> Option<&'static PathBuf> {
+    static PROGRAM: OnceLock<Option<PathBuf>> = OnceLock::new();
+    PROGRAM
+        .get_or_init(|| {
+            let names: Vec<String> = std::iter::once("llvm-mca".to_owned())
+                .chain(
+                    (14..=20)
+                        .rev()
+                        .map(|version| format!("llvm-mca-{}", version)),
+                )
+                .map(|name| format!("{}{}", name, env::consts::EXE_SUFFIX))
+                .collect();
+            let path = env::var_os("PATH")?;
+            names.iter().find_map(|name| {
+                env::split_paths(&path)
+                    .map(|dir| dir.join(name))
+                    .find(|path| path.is_file())
+            })
+        })
+        .as_ref()
+}
+
+fn cpu(target: Microarch) -> &'static str {
+    match target {
+        Microarch::Skylake => "skylake",
+        Microarch::Zen2 => "znver2",
+        Microarch::Zen4 => "znver4",
+    }
+}
+
+// The instructions of `symbol` in AT&T syntax, which `llvm-mca` reads by
+// default, with their addresses. Branch targets are left as plain addresses.
+fn input(symbol: &SymbolData, range: Option<AddressRange>) -> (String, Vec<u64>) {
+    let bitness = symbol.bitness();
+    let mut text = String::new();
+    if bitness == 16 {
+        text.push_str(".code16\n");
+    }
+    let mut addresses = Vec::new();
+    let data = symbol.data().unwrap_or_default();
+    let mut decoder = Decoder::with_ip(bitness, data, symbol.address, DecoderOptions::NONE);
+    let mut formatter = GasFormatter::new();
+    for instruction in decoder.iter() {
+        if instruction.is_invalid() || range.is_some_and(|range| !range.contains(instruction.ip()))
+        {
+            continue;
+        }
+        formatter.format(&instruction, &mut text);
+        text.push('\n');
+        addresses.push(instruction.ip());
+    }
+    (text, addresses)
+}
+
+// Reads the rows of the `Instruction Info` table, which lists the input
+// instructions in order, starting with their uop count, latency and
+// reciprocal throughput
+fn cycles(report: &str, addresses: &[u64]) -> HashMap<u64, Cycles> {
+    let rows = report
+        .lines()
+        .skip_while(|line| !(line.starts_with("[1]") && line.ends_with("Instructions:")))
+        .skip(1)
+        .take_while(|line| !line.trim().is_empty());
+    rows.zip(addresses)
+        .filter_map(|(row, &address)| {
+            let mut columns = row.split_whitespace().skip(1);
+            let latency = columns.next()?.parse().ok()?;
+            let throughput = columns.next()?.parse().ok()?;
+            Some((
+                address,
+                Cycles {
+                    latency,
+                    throughput,
+                },
+            ))
+        })
+        .collect()
+}
+
+fn run(input: &str, bitness: u32, target: Microarch) -> Result<String, String> {
+    let program = program().ok_or("`llvm-mca` wasn't found on the PATH")?;
+    let triple = if bitness == 64 { "x86_64" } else { "i686" };
+    let mut child = Command::new(program)
+        .arg(format!("-mtriple={}", triple))
+        .arg(format!("-mcpu={}", cpu(target)))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|error| format!("Running `{}`: {}", program.display(), error))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(input.as_bytes())
+            .map_err(|error| format!("Writing to `llvm-mca`: {}", error))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|error| format!("Running `llvm-mca`: {}", error))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).into_owned());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// Runs `llvm-mca` on the selected instructions of `symbol` in the background
+pub fn analyze(
+    symbol: Arc<SymbolData>,
+    range: Option<AddressRange>,
+    target: Microarch,
+    analysis: RwSignal<Option<Analysis>>,
+) {
+    // The CPUs picked in the settings are all x86 ones
+    if symbol.arm_modes().is_some() {
+        analysis.set(Some(Analysis::Failed(
+            "llvm-mca is only run on x86 code".to_owned(),
+        )));
+        return;
+    }
+    analysis.set(Some(Analysis::Running));
+    let done = create_ext_action(Scope::new(), move |result: Result<Report, String>| {
+        analysis.set(Some(match result {
+            Ok(report) => Analysis::Done(Arc::new(report)),
+            Err(error) => Analysis::Failed(error),
+        }))
+    });
+    pool::spawn(pool::Priority::Interactive, move || {
+        let (input, addresses) = input(&symbol, range);
+        done(run(&input, symbol.bitness(), target).map(|text| Report {
+            cycles: Arc::new(cycles(&text, &addresses)),
+            text,
+        }))
+    });
+}
+
+// The report beside the assembly, hidden until an analysis is started
+pub fn mca_panel(analysis: RwSignal<Option<Analysis>>) -> impl View {
+    let report = label(move || {
+        analysis.with(|analysis| match analysis {
+            Some(Analysis::Running) => "Running llvm-mca...".to_owned(),
+            Some(Analysis::Done(report)) => report.text.clone(),
+            Some(Analysis::Failed(error)) => error.clone(),
+            None => String::new(),
+        })
+    })
+    .style(move |s| {
+        s.padding(5.0).font_family(font().family).apply_if(
+            analysis.with(|analysis| matches!(analysis, Some(Analysis::Failed(_)))),
+            |s| s.color(theme().error),
+        )
+    });
+    stack((
+        stack((
+            header("llvm-mca"),
+            text("×")
+                .style(|s| {
+                    s.padding_horiz(8.0)
+                        .padding_vert(5.0)
+                        .background(theme().panel)
+                        .border_bottom(0.5)
+                        .border_color(theme().border)
+                        .cursor(floem::style::CursorStyle::Pointer)
+                })
+                .hover_style(|s| s.color(theme().error))
+                .on_click(move |_| {
+                    analysis.set(None);
+                    true
+                }),
+        ))
+        .style(|s| s.flex_row().width_full()),
+        text("Latency and reciprocal throughput are shown beside each instruction").style(|s| {
+            s.padding(5.0)
+                .color(theme().dim)
+                .text_overflow(TextOverflow::Clip)
+        }),
+        scroll(report).style(|s| s.flex_grow(1.0).flex_basis(0.0).width_full()),
+    ))
+    .style(move |s| {
+        s.flex_col()
+            .width(460.0)
+            .height_full()
+            .border_left(0.5)
+            .border_color(theme().border)
+            .apply_if(analysis.with(|analysis| analysis.is_none()), |s| s.hide())
+    })
+}
@@ -0,0 +1,122 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use floem::{
+    reactive::{create_effect, create_rw_signal, RwSignal},
+    view::View,
+    views::{container_box, list, stack, Decorators},
+};
+
+use crate::{
+    button, identity,
+    settings::{Settings, ViewSettings},
+    syntax_picker, toggle_button,
+};
+
+// The views which keep settings of their own
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum ViewKey {
+    Symbol(identity::SymbolId),
+    Diff(identity::SymbolId, identity::SymbolId),
+    Object(identity::ObjectId),
+}
+
+thread_local! {
+    // Views are rebuilt when the global settings change, so their overrides
+    // are kept here for as long as the app runs
+    static OVERRIDES: RefCell<HashMap<ViewKey, ViewSettings>> = RefCell::new(HashMap::new());
+}
+
+// The overrides of the view `key`, which are kept as they're changed
+pub fn signal(key: ViewKey) -> RwSignal<ViewSettings> {
+    let initial =
+        OVERRIDES.with(|overrides| overrides.borrow().get(&key).copied().unwrap_or_default());
+    let view = create_rw_signal(initial);
+    create_effect(move |_| {
+        let current = view.get();
+        OVERRIDES.with(|overrides| {
+            let mut overrides = overrides.borrow_mut();
+            if current.is_empty() {
+                overrides.remove(&key);
+            } else {
+                overrides.insert(key.clone(), current);
+            }
+        });
+    });
+    view
+}
+
+// A setting a view can override
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Override {
+    Syntax,
+    Source,
+    Bytes,
+    RelativeAddresses,
+}
+
+fn toggle(
+    label: &'static str,
+    view: RwSignal<ViewSettings>,
+    setting: fn(&mut ViewSettings) -> &mut Option<bool>,
+    global: bool,
+) -> impl View {
+    let current = move || {
+        let mut current = view.get();
+        setting(&mut current).unwrap_or(global)
+    };
+    toggle_button(label, current, move |_| {
+        let value = !current();
+        view.update(|view| *setting(view) = Some(value));
+        true
+    })
+}
+
+// Controls for the `options` of a view, showing the settings in effect with
+// the global ones in `settings` where they aren't overridden
+pub fn bar(
+    view: RwSignal<ViewSettings>,
+    settings: &Settings,
+    options: &'static [Override],
+) -> impl View {
+    let settings = settings.clone();
+    stack((
+        list(
+            move || options.iter().copied(),
+            |option| *option,
+            move |option| match option {
+                Override::Syntax => {
+                    let global = settings.syntax;
+                    container_box(syntax_picker(
+                        move || view.with(|view| view.syntax.unwrap_or(global)),
+                        move |syntax| view.update(|view| view.syntax = Some(syntax)),
+                    ))
+                }
+                Override::Source => container_box(toggle(
+                    "Source",
+                    view,
+                    |view| &mut view.show_source,
+                    settings.show_source,
+                )),
+                Override::Bytes => container_box(toggle(
+                    "Bytes",
+                    view,
+                    |view| &mut view.show_bytes,
+                    settings.show_bytes,
+                )),
+                Override::RelativeAddresses => container_box(toggle(
+                    "Relative Addresses",
+                    view,
+                    |view| &mut view.relative_addresses,
+                    settings.relative_addresses,
+                )),
+            },
+        )
+        .style(|s| s.flex_row().items_center()),
+        button("Use Global Settings", move |_| {
+            view.set(ViewSettings::default());
+            true
+        })
+        .style(move |s| s.apply_if(view.with(ViewSettings::is_empty), |s| s.hide())),
+    ))
+    .style(|s| s.flex_row().items_center())
+}
@@ -0,0 +1,203 @@
+use std::path::PathBuf;
+
+use asm_viewer_core::config;
+use floem::{
+    event::{Event, EventListener},
+    keyboard::{Key, NamedKey},
+    reactive::{create_rw_signal, RwSignal},
+    view::View,
+    views::{label, list, scroll, stack, text, text_input, Decorators},
+};
+
+use crate::{
+    button, header, open_paths, session, syntax_picker,
+    theme::{self, theme, Font, FONT_SIZES},
+    toggle_button, ObjectList, Selection, Settings,
+};
+
+fn section(title: &'static str) -> impl View {
+    text(title).style(|s| s.padding(5.0).padding_top(12.0).color(theme().dim))
+}
+
+// Toggles a flag of the settings
+fn flag(
+    settings: RwSignal<Settings>,
+    label: &'static str,
+    field: fn(&mut Settings) -> &mut bool,
+) -> impl View {
+    toggle_button(
+        label,
+        move || *field(&mut settings.get()),
+        move |_| {
+            settings.update(|s| *field(s) = !*field(s));
+            true
+        },
+    )
+}
+
+fn font_picker() -> impl View {
+    let family = create_rw_signal(theme::font().family);
+    // The family is applied on enter rather than while typing, as changing the
+    // font rebuilds the views
+    let apply = move || {
+        let family = family.get_untracked();
+        if !family.trim().is_empty() {
+            theme::set_font(Font {
+                family: family.trim().to_owned(),
+                ..theme::font()
+            });
+        }
+    };
+    let resize = move |delta: f32| {
+        let size = (theme::font().size + delta).clamp(*FONT_SIZES.start(), *FONT_SIZES.end());
+        theme::set_font(Font {
+            size,
+            ..theme::font()
+        });
+    };
+    stack((
+        text("Font:").style(|s| s.padding_left(6.0)),
+        text_input(family)
+            .style(|s| {
+                s.width(220)
+                    .padding(5)
+                    .margin(4)
+                    .border(0.5)
+                    .border_radius(3)
+                    .border_color(theme().button_border)
+            })
+            .on_event(EventListener::KeyDown, move |e| {
+                if let Event::KeyDown(e) = e {
+                    if e.key.logical_key == Key::Named(NamedKey::Enter) {
+                        apply();
+                        return true;
+                    }
+                }
+                false
+            }),
+        button("Apply", move |_| {
+            apply();
+            true
+        }),
+        text("Size:").style(|s| s.padding_left(6.0)),
+        button("-", move |_| {
+            resize(-1.0);
+            true
+        }),
+        label(|| theme::font().size.to_string()).style(|s| s.padding_horiz(4.0)),
+        button("+", move |_| {
+            resize(1.0);
+            true
+        }),
+    ))
+    .style(|s| s.flex_row().items_center())
+}
+
+// Files opened in earlier sessions, opened again by clicking them
+fn recent_files(objects: RwSignal<ObjectList>, selection: RwSignal<Selection>) -> impl View {
+    let recent = create_rw_signal(session::recent());
+    let files = list(
+        move || recent.get().into_iter(),
+        |path| path.clone(),
+        move |path: PathBuf| {
+            text(path.display())
+                .style(|s| s.padding(5.0).color(theme().link))
+                .hover_style(|s| s.background(theme().hover))
+                .on_click(move |_| {
+                    open_paths(objects, selection, vec![path.clone()], None);
+                    true
+                })
+        },
+    )
+    .style(|s| s.flex_col());
+    let none = text("No files opened yet").style(move |s| {
+        s.padding(5.0)
+            .color(theme().dim)
+            .apply_if(!recent.with(|recent| recent.is_empty()), |s| s.hide())
+    });
+    stack((
+        files,
+        none,
+        button("Clear", move |_| {
+            session::clear_recent();
+            recent.set(Vec::new());
+            true
+        }),
+    ))
+    .style(|s| s.flex_col().items_start())
+}
+
+// Settings which are saved to the config file as they change
+pub fn preferences_view(
+    settings: RwSignal<Settings>,
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+) -> Box<dyn View> {
+    let location = config::config_file()
+        .map(|path| format!("Saved in `{}`", path.display()))
+        .unwrap_or_else(|| "No configuration directory was found".to_owned());
+    let appearance = stack((
+        toggle_button(
+            "Dark Theme",
+            || theme::kind() == theme::Kind::Dark,
+            |_| {
+                theme::set(match theme::kind() {
+                    theme::Kind::Light => theme::Kind::Dark,
+                    theme::Kind::Dark => theme::Kind::Light,
+                });
+                true
+            },
+        ),
+        font_picker(),
+    ))
+    .style(|s| s.flex_row().items_center());
+    let assembly = stack((
+        syntax_picker(
+            move || settings.with(|s| s.syntax),
+            move |syntax| settings.update(|s| s.syntax = syntax),
+        ),
+        flag(settings, "Bytes", |s| &mut s.show_bytes),
+        flag(settings, "Source", |s| &mut s.show_source),
+        flag(settings, "Relative Addresses", |s| {
+            &mut s.relative_addresses
+        }),
+        flag(settings, "Operand Sizes", |s| &mut s.operand_sizes),
+        flag(settings, "Uops", |s| &mut s.show_uops),
+    ))
+    .style(|s| s.flex_row().items_center());
+    let columns = stack((
+        flag(settings, "Metrics", |s| &mut s.metric_columns),
+        flag(settings, "Symbol Index", |s| &mut s.symbol_index),
+        flag(settings, "Group by Path", |s| &mut s.group_by_path),
+    ))
+    .style(|s| s.flex_row().items_center());
+    let symbols = stack((
+        flag(settings, "Microsoft Symbol Server", |s| {
+            &mut s.symbol_server
+        }),
+        text("Sends the PDB names and GUIDs of opened images to msdl.microsoft.com")
+            .style(|s| s.padding(5.0).color(theme().dim)),
+    ))
+    .style(|s| s.flex_row().items_center());
+    let content = stack((
+        text(location).style(|s| s.padding(5.0).color(theme().dim)),
+        section("Appearance"),
+        appearance,
+        section("Assembly"),
+        assembly,
+        section("Symbol List"),
+        columns,
+        section("Symbols"),
+        symbols,
+        section("Recent Files"),
+        recent_files(objects, selection),
+    ))
+    .style(|s| s.flex_col().width_full());
+    Box::new(
+        stack((
+            header("Settings"),
+            scroll(content).style(|s| s.width_full().flex_grow(1.0).flex_basis(0.0)),
+        ))
+        .style(|s| s.flex_col().width_full().height_full()),
+    )
+}
@@ -0,0 +1,278 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use floem::{
+    ext_event::create_ext_action,
+    reactive::{RwSignal, Scope},
+};
+
+use rfd::AsyncFileDialog;
+
+use crate::{dialog, pool, report_error, Object, ObjectList, SectionData, Symbol};
+
+const PERF_MAGIC: &[u8] = b"PERFILE2";
+
+const RECORD_MMAP: u32 = 1;
+const RECORD_SAMPLE: u32 = 9;
+const RECORD_MMAP2: u32 = 10;
+
+const SAMPLE_IP: u64 = 1 << 0;
+const SAMPLE_TID: u64 = 1 << 1;
+const SAMPLE_IDENTIFIER: u64 = 1 << 16;
+
+// Samples by the file offset of the instruction, for each mapped file
+type Offsets = HashMap<PathBuf, HashMap<u64, u64>>;
+
+// Samples of a `perf record` or folded stacks file
+pub struct Profile {
+    pub path: PathBuf,
+    pub total: u64,
+    offsets: Offsets,
+    // Samples of the leaf functions of folded stacks, which have no addresses
+    functions: HashMap<String, u64>,
+}
+
+impl Profile {
+    fn file(&self, object: &Object) -> Option<&HashMap<u64, u64>> {
+        if object.member.is_some() {
+            return None;
+        }
+        let path = fs::canonicalize(&object.path).unwrap_or_else(|_| object.path.clone());
+        // Profiles recorded on another machine only match by file name
+        self.offsets.get(&path).or_else(|| {
+            self.offsets
+                .iter()
+                .find(|(mapped, _)| mapped.file_name() == object.path.file_name())
+                .map(|(_, samples)| samples)
+        })
+    }
+
+    // Samples of the instructions of `object` by address
+    pub fn instructions(&self, object: &Object) -> HashMap<u64, u64> {
+        let mut samples = HashMap::new();
+        let Some(offsets) = self.file(object) else {
+            return samples;
+        };
+        let ranges: Vec<_> = object
+            .sections
+            .iter()
+            .filter_map(|section| match &section.data {
+                SectionData::Mapped(_, range) if section.address != 0 => {
+                    Some((range.start as u64..range.end as u64, section.address))
+                }
+                _ => None,
+            })
+            .collect();
+        for (&offset, &count) in offsets {
+            if let Some((range, address)) = ranges.iter().find(|(range, _)| range.contains(&offset))
+            {
+                *samples.entry(address + offset - range.start).or_default() += count;
+            }
+        }
+        samples
+    }
+
+    // Samples of the instructions of `symbol` by address
+    pub fn symbol(&self, symbol: &Symbol) -> HashMap<u64, u64> {
+        let start = symbol.data.address;
+        let end = start + symbol.data.data().unwrap_or_default().len() as u64;
+        let mut samples = self.instructions(&symbol.object);
+        samples.retain(|&address, _| start <= address && address < end);
+        samples
+    }
+
+    // The number of samples in `symbol`, found by its name in folded stacks
+    // which have no addresses
+    pub fn symbol_total(&self, symbol: &Symbol) -> u64 {
+        let samples: u64 = self.symbol(symbol).values().sum();
+        if samples > 0 {
+            return samples;
+        }
+        let function = |name: &str| self.functions.get(name).copied();
+        function(&symbol.data.name)
+            .or_else(|| function(symbol.data.demangled.as_ref()?))
+            .unwrap_or(0)
+    }
+}
+
+fn bytes_at<const N: usize>(data: &[u8], offset: usize) -> Option<[u8; N]> {
+    data.get(offset..offset.checked_add(N)?)?.try_into().ok()
+}
+
+fn u16_at(data: &[u8], offset: usize) -> Option<u16> {
+    bytes_at(data, offset).map(u16::from_le_bytes)
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Option<u32> {
+    bytes_at(data, offset).map(u32::from_le_bytes)
+}
+
+fn u64_at(data: &[u8], offset: usize) -> Option<u64> {
+    bytes_at(data, offset).map(u64::from_le_bytes)
+}
+
+// A file mapped into a profiled process
+struct Map {
+    start: u64,
+    end: u64,
+    offset: u64,
+    path: PathBuf,
+}
+
+fn c_string(data: &[u8]) -> PathBuf {
+    let end = data
+        .iter()
+        .position(|&byte| byte == 0)
+        .unwrap_or(data.len());
+    PathBuf::from(String::from_utf8_lossy(&data[..end]).into_owned())
+}
+
+// Samples from a little-endian `perf.data` file. Piped output, which has no
+// header with the sample format, isn't supported.
+fn perf(data: &[u8]) -> Result<(Offsets, u64), String> {
+    let header = || -> Option<(u64, usize, usize)> {
+        let attrs = u64_at(data, 24)? as usize;
+        let sample_type = u64_at(data, attrs.checked_add(24)?)?;
+        let start = u64_at(data, 40)? as usize;
+        let size = u64_at(data, 48)? as usize;
+        Some((sample_type, start, start.checked_add(size)?))
+    };
+    let (sample_type, start, end) = header().ok_or("truncated `perf.data` header")?;
+    if sample_type & SAMPLE_IP == 0 {
+        return Err("the profile was recorded without instruction addresses".to_owned());
+    }
+    let data = data
+        .get(start..end)
+        .ok_or("the sample data is outside of the file")?;
+
+    // Maps by process, with kernel maps under `u32::MAX`
+    let mut maps: HashMap<u32, Vec<Map>> = HashMap::new();
+    let mut offsets: Offsets = HashMap::new();
+    let mut total = 0;
+    let mut record = 0;
+    while let (Some(kind), Some(size)) = (u32_at(data, record), u16_at(data, record + 6)) {
+        let size = size as usize;
+        if size < 8 {
+            return Err(format!("malformed record at {:#x}", start + record));
+        }
+        let body = data.get(record + 8..record + size).unwrap_or_default();
+        record += size;
+        match kind {
+            RECORD_MMAP | RECORD_MMAP2 => {
+                let name = if kind == RECORD_MMAP { 32 } else { 64 };
+                let (Some(pid), Some(address), Some(len), Some(offset)) = (
+                    u32_at(body, 0),
+                    u64_at(body, 8),
+                    u64_at(body, 16),
+                    u64_at(body, 24),
+                ) else {
+                    continue;
+                };
+                maps.entry(pid).or_default().push(Map {
+                    start: address,
+                    end: address.saturating_add(len),
+                    offset,
+                    path: c_string(body.get(name..).unwrap_or_default()),
+                });
+            }
+            RECORD_SAMPLE => {
+                let mut field = 0;
+                if sample_type & SAMPLE_IDENTIFIER != 0 {
+                    field += 8;
+                }
+                let Some(ip) = u64_at(body, field) else {
+                    continue;
+                };
+                field += 8;
+                let pid = (sample_type & SAMPLE_TID != 0)
+                    .then(|| u32_at(body, field))
+                    .flatten();
+                total += 1;
+                // Later maps replace earlier ones at the same addresses
+                let map = pid
+                    .and_then(|pid| maps.get(&pid))
+                    .into_iter()
+                    .chain(maps.get(&u32::MAX))
+                    .flat_map(|maps| maps.iter().rev())
+                    .find(|map| map.start <= ip && ip < map.end);
+                if let Some(map) = map {
+                    *offsets
+                        .entry(map.path.clone())
+                        .or_default()
+                        .entry(ip - map.start + map.offset)
+                        .or_default() += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok((offsets, total))
+}
+
+// Samples of the leaf functions of folded stacks, lines like
+// `main;run;compute 120`
+fn folded(text: &str) -> Result<(HashMap<String, u64>, u64), String> {
+    let mut functions: HashMap<String, u64> = HashMap::new();
+    let mut total = 0;
+    for (line, text) in text.lines().enumerate() {
+        let text = text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let count = text
+            .rsplit_once(' ')
+            .and_then(|(stack, count)| Some((stack, count.parse::<u64>().ok()?)));
+        let Some((stack, count)) = count else {
+            return Err(format!("line {} isn't a folded stack", line + 1));
+        };
+        let leaf = stack.rsplit(';').next().unwrap_or(stack);
+        // `perf script` marks kernel and JIT frames with `_[k]` and `_[j]`
+        let leaf = match leaf.rfind("_[") {
+            Some(end) if leaf.ends_with(']') => &leaf[..end],
+            _ => leaf,
+        };
+        *functions.entry(leaf.to_owned()).or_default() += count;
+        total += count;
+    }
+    Ok((functions, total))
+}
+
+pub fn load(path: &Path) -> Result<Profile, String> {
+    let data =
+        fs::read(path).map_err(|error| format!("Reading `{}`: {}", path.display(), error))?;
+    let mut profile = Profile {
+        path: path.to_owned(),
+        total: 0,
+        offsets: HashMap::new(),
+        functions: HashMap::new(),
+    };
+    let error = |error| format!("`{}`: {}", path.display(), error);
+    if data.starts_with(PERF_MAGIC) {
+        (profile.offsets, profile.total) = perf(&data).map_err(error)?;
+    } else {
+        let text = std::str::from_utf8(&data)
+            .map_err(|_| error("not a `perf.data` or folded stacks file".to_owned()))?;
+        (profile.functions, profile.total) = folded(text).map_err(error)?;
+    }
+    Ok(profile)
+}
+
+// Loads a profile in the background
+pub fn open_profile(objects: RwSignal<ObjectList>, profile: RwSignal<Option<Arc<Profile>>>) {
+    let dialog = AsyncFileDialog::new().set_title("Load a perf.data or folded stacks file...");
+    dialog::pick_file(dialog, move |path| {
+        let done =
+            create_ext_action(
+                Scope::new(),
+                move |result: Result<Profile, String>| match result {
+                    Ok(loaded) => profile.set(Some(Arc::new(loaded))),
+                    Err(error) => report_error(objects, error),
+                },
+            );
+        pool::spawn(pool::Priority::Interactive, move || done(load(&path)));
+    });
+}
@@ -0,0 +1,140 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{report_problem, session};
+
+// A pair of instructions the user marked as corresponding in a diff. Offsets
+// are relative to the start of their symbols.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Anchor {
+    pub left: String,
+    pub left_offset: u64,
+    pub right: String,
+    pub right_offset: u64,
+}
+
+// A note on an instruction, at an offset into its symbol. `build` is the
+// key of the object it was written for, from `annotations::key`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Comment {
+    pub build: String,
+    pub symbol: String,
+    pub offset: u64,
+    pub text: String,
+}
+
+// A name given to a symbol, shown instead of its own
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rename {
+    pub build: String,
+    pub symbol: String,
+    pub alias: String,
+}
+
+// User data stored in a sidecar file next to an object, `<object>.asmv`.
+// Each line is a tab separated record starting with its kind.
+#[derive(Clone, Debug)]
+pub struct Project {
+    path: PathBuf,
+    pub anchors: Vec<Anchor>,
+    // Names of symbols pinned to the favorites panel
+    pub favorites: Vec<String>,
+    // Name patterns of symbols shown in the tracking dashboard, where `*`
+    // matches any text
+    pub tracked: Vec<String>,
+    pub comments: Vec<Comment>,
+    pub renames: Vec<Rename>,
+}
+
+fn hex(field: &str) -> Option<u64> {
+    u64::from_str_radix(field, 16).ok()
+}
+
+impl Project {
+    pub fn load(object: &Path) -> Project {
+        let mut path = object.as_os_str().to_owned();
+        path.push(".asmv");
+        let mut project = Project {
+            path: PathBuf::from(path),
+            anchors: Vec::new(),
+            favorites: Vec::new(),
+            tracked: Vec::new(),
+            comments: Vec::new(),
+            renames: Vec::new(),
+        };
+
+        let text = fs::read_to_string(&project.path).unwrap_or_default();
+        for line in text.lines() {
+            let fields: Vec<&str> = line.split('\t').collect();
+            if let ["favorite", name] = fields[..] {
+                project.favorites.push(name.to_owned());
+            }
+            if let ["track", pattern] = fields[..] {
+                project.tracked.push(pattern.to_owned());
+            }
+            if let ["comment", build, symbol, offset, text] = fields[..] {
+                if let Some(offset) = hex(offset) {
+                    project.comments.push(Comment {
+                        build: build.to_owned(),
+                        symbol: symbol.to_owned(),
+                        offset,
+                        text: text.to_owned(),
+                    });
+                }
+            }
+            if let ["rename", build, symbol, alias] = fields[..] {
+                project.renames.push(Rename {
+                    build: build.to_owned(),
+                    symbol: symbol.to_owned(),
+                    alias: alias.to_owned(),
+                });
+            }
+            if let ["anchor", left, left_offset, right, right_offset] = fields[..] {
+                if let (Some(left_offset), Some(right_offset)) =
+                    (hex(left_offset), hex(right_offset))
+                {
+                    project.anchors.push(Anchor {
+                        left: left.to_owned(),
+                        left_offset,
+                        right: right.to_owned(),
+                        right_offset,
+                    });
+                }
+            }
+        }
+        project
+    }
+
+    pub fn save(&self) {
+        let mut text = String::new();
+        for anchor in &self.anchors {
+            text.push_str(&format!(
+                "anchor\t{}\t{:x}\t{}\t{:x}\n",
+                anchor.left, anchor.left_offset, anchor.right, anchor.right_offset
+            ));
+        }
+        for name in &self.favorites {
+            text.push_str(&format!("favorite\t{}\n", name));
+        }
+        for pattern in &self.tracked {
+            text.push_str(&format!("track\t{}\n", pattern));
+        }
+        for comment in &self.comments {
+            text.push_str(&format!(
+                "comment\t{}\t{}\t{:x}\t{}\n",
+                comment.build, comment.symbol, comment.offset, comment.text
+            ));
+        }
+        for rename in &self.renames {
+            text.push_str(&format!(
+                "rename\t{}\t{}\t{}\n",
+                rename.build, rename.symbol, rename.alias
+            ));
+        }
+        if let Err(error) = session::write_atomic(&self.path, &text) {
+            report_problem(format!("Saving `{}`: {}", self.path.display(), error));
+        }
+    }
+}
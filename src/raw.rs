@@ -0,0 +1,247 @@
+use std::{
+    path::PathBuf,
+    sync::{mpsc, Arc},
+};
+
+use asm_viewer_core::{overlay, pool, raw, Loaded, Object};
+use floem::{
+    event::{Event, EventListener},
+    keyboard::{Key, NamedKey},
+    reactive::{create_rw_signal, RwSignal},
+    view::View,
+    views::{label, stack, text, text_input, Decorators},
+};
+use rfd::AsyncFileDialog;
+
+pub use asm_viewer_core::raw::{layout, load, Layout};
+
+use crate::{
+    button, dialog, header, history, history::History, receive_objects, replace_object,
+    theme::theme, toggle_button, Loading, ObjectList, Selection, Symbol,
+};
+
+// Decoder modes by the name of the architecture
+const ARCHITECTURES: [(&str, u32); 3] = [("x86-16", 16), ("x86", 32), ("x86-64", 64)];
+
+fn hex(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let digits = text
+        .strip_prefix("0x")
+        .or_else(|| text.strip_prefix("0X"))
+        .unwrap_or(text);
+    u64::from_str_radix(digits, 16).ok()
+}
+
+fn input_style(s: floem::style::Style) -> floem::style::Style {
+    s.width(200)
+        .padding(5)
+        .margin(4)
+        .border(0.5)
+        .border_radius(3)
+        .border_color(theme().button_border)
+}
+
+// The format shown in the object info, with the layout of raw binaries
+pub fn format_name(object: &Object) -> String {
+    match (object.format, layout(object)) {
+        (Some(format), _) => format!("{:?}", format),
+        (None, Some(layout)) => format!(
+            "raw {}-bit binary loaded at {:#x}",
+            layout.bitness, layout.base
+        ),
+        (None, None) => "raw binary".to_owned(),
+    }
+}
+
+// Picks a file to load as raw code, then asks how to decode it
+pub fn open_raw(selection: RwSignal<Selection>) {
+    dialog::pick_file(
+        AsyncFileDialog::new().set_title("Open a raw binary..."),
+        move |path| selection.set(Selection::RawBinary(path)),
+    );
+}
+
+// Loads the file in the background, then selects the function at its start
+pub fn open_with(
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+    path: PathBuf,
+    layout: raw::Layout,
+) {
+    objects.update(|list| {
+        list.loading.push(Loading {
+            path: path.clone(),
+            objects: 0,
+            members: None,
+        })
+    });
+    let (sender, receiver) = mpsc::channel();
+    pool::spawn(pool::Priority::Batch, move || {
+        let loaded = match load(path.clone(), layout) {
+            Ok(object) => Loaded::Object(Box::new(object), None),
+            Err(error) => Loaded::Errors(vec![format!("`{}`: {}", path.display(), error)]),
+        };
+        let _ = sender.send(loaded);
+        let _ = sender.send(Loaded::Done(path));
+    });
+    let entry = raw::function_name(layout.base);
+    receive_objects(objects, selection, receiver, Some(entry));
+}
+
+// Asks for the architecture and load address of a raw binary
+pub fn options_view(
+    path: PathBuf,
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+) -> Box<dyn View> {
+    let bitness = create_rw_signal(raw::Layout::default().bitness);
+    let base = create_rw_signal("0".to_owned());
+    let error = create_rw_signal(None::<String>);
+
+    let open = {
+        let path = path.clone();
+        move || match hex(&base.get_untracked()) {
+            Some(base) => {
+                let layout = raw::Layout {
+                    bitness: bitness.get_untracked(),
+                    base,
+                };
+                open_with(objects, selection, path.clone(), layout);
+            }
+            None => error.set(Some("The load address isn't a hex number".to_owned())),
+        }
+    };
+    let architecture = |(name, mode): (&str, u32)| {
+        toggle_button(
+            name,
+            move || bitness.get() == mode,
+            move |_| {
+                bitness.set(mode);
+                true
+            },
+        )
+    };
+    let [a, b, c] = ARCHITECTURES;
+    let architectures = stack((
+        text("Architecture:").style(|s| s.width(100).padding(5.0)),
+        architecture(a),
+        architecture(b),
+        architecture(c),
+    ))
+    .style(|s| s.flex_row().items_center());
+
+    let address = stack((
+        text("Load address:").style(|s| s.width(100).padding(5.0)),
+        text_input(base)
+            .style(input_style)
+            .on_event(EventListener::KeyDown, {
+                let open = open.clone();
+                move |e| {
+                    if let Event::KeyDown(e) = e {
+                        if e.key.logical_key == Key::Named(NamedKey::Enter) {
+                            open();
+                            return true;
+                        }
+                    }
+                    false
+                }
+            }),
+    ))
+    .style(|s| s.flex_row().items_center());
+
+    Box::new(
+        stack((
+            header("Open Raw Binary"),
+            text(format!("Path: `{}`", path.display())).style(|s| s.padding(5.0)),
+            architectures,
+            address,
+            label(move || error.get().unwrap_or_default()).style(move |s| {
+                s.padding(5.0)
+                    .color(theme().error)
+                    .apply_if(error.with(Option::is_none), |s| {
+                        s.display(floem::style::Display::None)
+                    })
+            }),
+            button("Open", move |_| {
+                open();
+                true
+            }),
+        ))
+        .style(|s| s.flex_col().width_full()),
+    )
+}
+
+// Adds a function starting at a hex offset into a raw binary and opens it
+pub fn offset_picker(
+    object: &Arc<Object>,
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+    history: RwSignal<History>,
+) -> impl View {
+    let object = object.clone();
+    let offset = create_rw_signal(String::new());
+    let error = create_rw_signal(None::<String>);
+
+    let disassemble = move || {
+        let Some(function) =
+            hex(&offset.get_untracked()).and_then(|offset| raw::function_at(&object, offset))
+        else {
+            error.set(Some(
+                "The offset isn't a hex number within the file".to_owned(),
+            ));
+            return;
+        };
+        error.set(None);
+        let existing = object
+            .symbols_sorted
+            .iter()
+            .find(|symbol| symbol.address == function.address)
+            .cloned();
+        let (object, data) = match existing {
+            Some(data) => (object.clone(), data),
+            None => {
+                let merged = Arc::new(overlay::merge(&object, std::slice::from_ref(&function)));
+                replace_object(objects, selection, &object, merged.clone());
+                let Some(data) = merged
+                    .symbols_sorted
+                    .iter()
+                    .find(|symbol| symbol.name == function.name)
+                    .cloned()
+                else {
+                    return;
+                };
+                (merged, data)
+            }
+        };
+        history::jump(
+            selection,
+            history,
+            Selection::Symbol(Symbol { object, data }),
+        );
+    };
+
+    stack((
+        text("Disassemble at offset:").style(|s| s.padding(5.0)),
+        text_input(offset)
+            .style(input_style)
+            .on_event(EventListener::KeyDown, {
+                let disassemble = disassemble.clone();
+                move |e| {
+                    if let Event::KeyDown(e) = e {
+                        if e.key.logical_key == Key::Named(NamedKey::Enter) {
+                            disassemble();
+                            return true;
+                        }
+                    }
+                    false
+                }
+            }),
+        button("Disassemble", move |_| {
+            disassemble();
+            true
+        }),
+        label(move || error.get().unwrap_or_default())
+            .style(|s| s.padding(5.0).color(theme().error)),
+    ))
+    .style(|s| s.flex_row().items_center())
+}
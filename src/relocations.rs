@@ -0,0 +1,15 @@
+use asm_viewer_core::relocations::Reference;
+use floem::peniko::Color;
+
+// The color of the badge of an instruction with a relocation
+pub fn color(reference: Reference) -> Color {
+    match reference {
+        Reference::DirectCall => Color::rgb8(87, 103, 65),
+        Reference::PltCall => Color::rgb8(80, 107, 135),
+        Reference::GotLoad => Color::rgb8(170, 110, 40),
+        Reference::Absolute => Color::rgb8(170, 60, 60),
+        Reference::Relative => Color::rgb8(116, 94, 147),
+        Reference::Tls => Color::rgb8(40, 140, 140),
+        Reference::Other => Color::rgb8(120, 120, 120),
+    }
+}
@@ -0,0 +1,72 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{Object, SymbolData};
+
+// A function together with the functions only reachable through it, which is
+// roughly what removing it would save
+pub struct Rollup {
+    pub size: u64,
+    // Dominated functions, not counting the root
+    pub callees: usize,
+}
+
+fn reachable<'a>(
+    callees: &HashMap<&'a str, Vec<&'a str>>,
+    starts: impl IntoIterator<Item = &'a str>,
+    blocked: Option<&str>,
+) -> HashSet<&'a str> {
+    let mut seen = HashSet::new();
+    let mut stack: Vec<&str> = starts.into_iter().collect();
+    while let Some(name) = stack.pop() {
+        if Some(name) == blocked || !seen.insert(name) {
+            continue;
+        }
+        stack.extend(callees.get(name).into_iter().flatten().copied());
+    }
+    seen
+}
+
+// Symbols nothing in the object refers to are treated as entry points. Callees
+// reachable from the root but not from any other entry point without passing
+// through the root are dominated by it.
+pub fn rollup(object: &Object, root: &SymbolData) -> Rollup {
+    let mut callees: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut referenced = HashSet::new();
+    for (target, xrefs) in object.callers.iter() {
+        for xref in xrefs {
+            // Recursion doesn't keep a function alive
+            if xref.from != target {
+                referenced.insert(target);
+            }
+            callees.entry(xref.from.as_str()).or_default().push(target);
+        }
+    }
+
+    let from_root = reachable(&callees, [root.name.as_str()], None);
+    let entries = object
+        .symbols_sorted
+        .iter()
+        .map(|symbol| symbol.name.as_str())
+        .filter(|name| *name != root.name && !referenced.contains(name));
+    let elsewhere = reachable(&callees, entries, Some(&root.name));
+
+    let mut rollup = Rollup {
+        size: 0,
+        callees: 0,
+    };
+    for name in from_root.difference(&elsewhere) {
+        let Ok(i) = object
+            .symbols_sorted
+            .binary_search_by(|symbol| symbol.name.as_str().cmp(name))
+        else {
+            // Defined in another object
+            continue;
+        };
+        let symbol = &object.symbols_sorted[i];
+        rollup.size += symbol.extent().unwrap_or(symbol.size);
+        if *name != root.name {
+            rollup.callees += 1;
+        }
+    }
+    rollup
+}
@@ -0,0 +1,282 @@
+use std::{fs, path::PathBuf};
+
+use floem::reactive::RwSignal;
+use iced_x86::{
+    BlockEncoder, BlockEncoderOptions, Code, IcedError, Instruction, InstructionBlock,
+    MemoryOperand, Register,
+};
+use object::{
+    write::{self, StandardSection, SymbolSection},
+    Architecture, BinaryFormat, Endianness, SymbolFlags, SymbolKind, SymbolScope,
+};
+
+use crate::{open_paths, report_error, search, ObjectList, Selection};
+
+// Branch targets inside a function, kept apart from the addresses of functions
+const LABELS: u64 = 1 << 48;
+
+// The instructions of a function. Instructions are numbered with labels and
+// branches to labels are resolved by the block encoder.
+struct Function {
+    instructions: Vec<Instruction>,
+    labels: u64,
+    bound: Option<u64>,
+}
+
+impl Function {
+    fn new() -> Self {
+        Function {
+            instructions: Vec::new(),
+            labels: LABELS,
+            bound: None,
+        }
+    }
+
+    // A label for a later instruction, placed by `bind`
+    fn label(&mut self) -> u64 {
+        self.labels += 1;
+        self.labels
+    }
+
+    // Places `label` at the next instruction
+    fn bind(&mut self, label: u64) {
+        self.bound = Some(label);
+    }
+
+    fn push(&mut self, instruction: Result<Instruction, IcedError>) -> Result<(), IcedError> {
+        let mut instruction = instruction?;
+        let label = match self.bound.take() {
+            Some(label) => label,
+            None => self.label(),
+        };
+        instruction.set_ip(label);
+        self.instructions.push(instruction);
+        Ok(())
+    }
+}
+
+fn sum() -> Result<Function, IcedError> {
+    let mut f = Function::new();
+    let done = f.label();
+    let repeat = f.label();
+    f.push(Instruction::with2(
+        Code::Xor_r32_rm32,
+        Register::EAX,
+        Register::EAX,
+    ))?;
+    f.push(Instruction::with2(
+        Code::Test_rm32_r32,
+        Register::ESI,
+        Register::ESI,
+    ))?;
+    f.push(Instruction::with_branch(Code::Je_rel8_64, done))?;
+    f.bind(repeat);
+    f.push(Instruction::with2(
+        Code::Add_r32_rm32,
+        Register::EAX,
+        MemoryOperand::with_base(Register::RDI),
+    ))?;
+    f.push(Instruction::with2(Code::Add_rm64_imm8, Register::RDI, 4))?;
+    f.push(Instruction::with1(Code::Dec_rm32, Register::ESI))?;
+    f.push(Instruction::with_branch(Code::Jne_rel8_64, repeat))?;
+    f.bind(done);
+    f.push(Ok(Instruction::with(Code::Retnq)))?;
+    Ok(f)
+}
+
+fn max() -> Result<Function, IcedError> {
+    let mut f = Function::new();
+    f.push(Instruction::with2(
+        Code::Mov_r32_rm32,
+        Register::EAX,
+        Register::EDI,
+    ))?;
+    f.push(Instruction::with2(
+        Code::Cmp_r32_rm32,
+        Register::EAX,
+        Register::ESI,
+    ))?;
+    f.push(Instruction::with2(
+        Code::Cmovl_r32_rm32,
+        Register::EAX,
+        Register::ESI,
+    ))?;
+    f.push(Ok(Instruction::with(Code::Retnq)))?;
+    Ok(f)
+}
+
+fn clamp(max: u64) -> Result<Function, IcedError> {
+    let mut f = Function::new();
+    f.push(Instruction::with1(Code::Push_r64, Register::RBX))?;
+    f.push(Instruction::with2(
+        Code::Mov_r32_rm32,
+        Register::EBX,
+        Register::EDX,
+    ))?;
+    f.push(Instruction::with_branch(Code::Call_rel32_64, max))?;
+    f.push(Instruction::with2(
+        Code::Cmp_r32_rm32,
+        Register::EAX,
+        Register::EBX,
+    ))?;
+    f.push(Instruction::with2(
+        Code::Cmovg_r32_rm32,
+        Register::EAX,
+        Register::EBX,
+    ))?;
+    f.push(Instruction::with1(Code::Pop_r64, Register::RBX))?;
+    f.push(Ok(Instruction::with(Code::Retnq)))?;
+    Ok(f)
+}
+
+fn average(sum: u64) -> Result<Function, IcedError> {
+    let mut f = Function::new();
+    let empty = f.label();
+    f.push(Instruction::with1(Code::Push_r64, Register::RBX))?;
+    f.push(Instruction::with2(
+        Code::Mov_r32_rm32,
+        Register::EBX,
+        Register::ESI,
+    ))?;
+    f.push(Instruction::with_branch(Code::Call_rel32_64, sum))?;
+    f.push(Instruction::with2(
+        Code::Test_rm32_r32,
+        Register::EBX,
+        Register::EBX,
+    ))?;
+    f.push(Instruction::with_branch(Code::Je_rel8_64, empty))?;
+    f.push(Ok(Instruction::with(Code::Cdq)))?;
+    f.push(Instruction::with1(Code::Idiv_rm32, Register::EBX))?;
+    f.push(Instruction::with1(Code::Pop_r64, Register::RBX))?;
+    f.push(Ok(Instruction::with(Code::Retnq)))?;
+    f.bind(empty);
+    f.push(Instruction::with2(
+        Code::Xor_r32_rm32,
+        Register::EAX,
+        Register::EAX,
+    ))?;
+    f.push(Instruction::with1(Code::Pop_r64, Register::RBX))?;
+    f.push(Ok(Instruction::with(Code::Retnq)))?;
+    Ok(f)
+}
+
+fn main(average: u64, clamp: u64) -> Result<Function, IcedError> {
+    let mut f = Function::new();
+    f.push(Instruction::with2(Code::Sub_rm64_imm8, Register::RSP, 24))?;
+    for (i, value) in [3, 9, 4, 7].into_iter().enumerate() {
+        f.push(Instruction::with2(
+            Code::Mov_rm32_imm32,
+            MemoryOperand::with_base_displ(Register::RSP, i as i64 * 4),
+            value,
+        ))?;
+    }
+    f.push(Instruction::with2(
+        Code::Mov_r64_rm64,
+        Register::RDI,
+        Register::RSP,
+    ))?;
+    f.push(Instruction::with2(Code::Mov_r32_imm32, Register::ESI, 4))?;
+    f.push(Instruction::with_branch(Code::Call_rel32_64, average))?;
+    f.push(Instruction::with2(
+        Code::Mov_r32_rm32,
+        Register::EDI,
+        Register::EAX,
+    ))?;
+    f.push(Instruction::with2(Code::Mov_r32_imm32, Register::ESI, 2))?;
+    f.push(Instruction::with2(Code::Mov_r32_imm32, Register::EDX, 6))?;
+    f.push(Instruction::with_branch(Code::Call_rel32_64, clamp))?;
+    f.push(Instruction::with2(Code::Add_rm64_imm8, Register::RSP, 24))?;
+    f.push(Ok(Instruction::with(Code::Retnq)))?;
+    Ok(f)
+}
+
+// Never called, so it shows up in the dead code report
+fn unused() -> Result<Function, IcedError> {
+    let mut f = Function::new();
+    f.push(Instruction::with2(
+        Code::Lea_r32_m,
+        Register::EAX,
+        MemoryOperand::with_base_index_scale(Register::RDI, Register::RSI, 2),
+    ))?;
+    f.push(Ok(Instruction::with(Code::Retnq)))?;
+    Ok(f)
+}
+
+// The name, offset and size of each function
+type Functions = Vec<(&'static str, u64, u64)>;
+
+// The code of the sample with its functions. Functions are 16-byte aligned
+// with NOP padding between them and call the functions placed before them.
+fn code() -> Result<(Vec<u8>, Functions), IcedError> {
+    let mut code = Vec::new();
+    let mut functions = Vec::new();
+    let mut add = |name, function: Result<Function, IcedError>| -> Result<u64, IcedError> {
+        let function = function?;
+        code.resize(code.len().next_multiple_of(16), 0x90);
+        let address = code.len() as u64;
+        let encoded = BlockEncoder::encode(
+            64,
+            InstructionBlock::new(&function.instructions, address),
+            BlockEncoderOptions::NONE,
+        )?;
+        code.extend_from_slice(&encoded.code_buffer);
+        functions.push((name, address, encoded.code_buffer.len() as u64));
+        Ok(address)
+    };
+    let sum = add("sum", sum())?;
+    let max = add("max", max())?;
+    let clamp = add("clamp", clamp(max))?;
+    let average = add("average", average(sum))?;
+    add("main", main(average, clamp))?;
+    add("unused", unused())?;
+    Ok((code, functions))
+}
+
+// An x86-64 ELF object with a few small functions to explore
+pub fn object() -> Result<Vec<u8>, String> {
+    let (code, functions) = code().map_err(|error| format!("Encoding the sample: {}", error))?;
+    let mut file = write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let text = file.section_id(StandardSection::Text);
+    file.append_section_data(text, &code, 16);
+    for (name, address, size) in functions {
+        file.add_symbol(write::Symbol {
+            name: name.as_bytes().to_vec(),
+            value: address,
+            size,
+            kind: SymbolKind::Text,
+            scope: SymbolScope::Linkage,
+            weak: false,
+            section: SymbolSection::Section(text),
+            flags: SymbolFlags::None,
+        });
+    }
+    file.write()
+        .map_err(|error| format!("Writing the sample: {}", error))
+}
+
+// Writes the sample to the temporary directory, so it loads like any file
+pub fn write() -> Result<PathBuf, String> {
+    let path = std::env::temp_dir().join("asm-viewer-sample.o");
+    fs::write(&path, object()?)
+        .map_err(|error| format!("Writing `{}`: {}", path.display(), error))?;
+    Ok(path)
+}
+
+// Opens the sample and shows `main`, or just shows it if already open
+pub fn open(objects: RwSignal<ObjectList>, selection: RwSignal<Selection>) {
+    let path = match write() {
+        Ok(path) => path,
+        Err(error) => return report_error(objects, error),
+    };
+    let found = objects.with_untracked(|list| {
+        list.objects
+            .iter()
+            .any(|object| object.path == path)
+            .then(|| search::find_symbol("main", &list.objects))
+            .flatten()
+    });
+    match found {
+        Some(symbol) => selection.set(Selection::Symbol(symbol)),
+        None => open_paths(objects, selection, vec![path], Some("main".to_owned())),
+    }
+}
@@ -0,0 +1,950 @@
+use std::{
+    cell::Cell,
+    slice,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+};
+
+use floem::{
+    event::{Event, EventListener},
+    id::Id,
+    keyboard::{Key, NamedKey},
+    reactive::{create_rw_signal, RwSignal},
+    style::{CursorStyle, TextOverflow},
+    view::View,
+    views::{
+        dyn_container, label, list, scroll, stack, text, text_input, virtual_list, Decorators,
+        VirtualListDirection, VirtualListItemSize,
+    },
+    Clipboard,
+};
+use iced_x86::Formatter;
+use object::SectionKind;
+
+use crate::{
+    button, pool, receive_batches, report_error, settings, theme::theme, Object, ObjectList,
+    Selection, Symbol,
+};
+
+// Searches stop collecting results after this many hits
+const MAX_RESULTS: usize = 10_000;
+
+thread_local! {
+    // The query input of the toolbar
+    static SEARCH_BOX: Cell<Option<Id>> = const { Cell::new(None) };
+}
+
+pub fn focus_search_bar() {
+    if let Some(id) = SEARCH_BOX.with(Cell::get) {
+        id.request_focus();
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SearchKind {
+    Symbol,
+    Mnemonic,
+    // Hex bytes in section data, with `??` matching any byte
+    Bytes,
+    // An immediate operand, in decimal or `0x` hex
+    Immediate,
+}
+
+impl SearchKind {
+    const ALL: [SearchKind; 4] = [
+        SearchKind::Symbol,
+        SearchKind::Mnemonic,
+        SearchKind::Bytes,
+        SearchKind::Immediate,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            SearchKind::Symbol => "Symbol",
+            SearchKind::Mnemonic => "Mnemonic",
+            SearchKind::Bytes => "Bytes",
+            SearchKind::Immediate => "Immediate",
+        }
+    }
+
+    fn next(self) -> SearchKind {
+        let i = SearchKind::ALL.iter().position(|&k| k == self).unwrap_or(0);
+        SearchKind::ALL[(i + 1) % SearchKind::ALL.len()]
+    }
+}
+
+#[derive(Clone)]
+pub struct SearchResult {
+    object: Arc<Object>,
+    // Byte patterns are also found outside of functions
+    symbol: Option<Symbol>,
+    // Offset of the hit within the symbol, or within `section` if there's no
+    // symbol
+    offset: Option<u64>,
+    section: Option<String>,
+    preview: String,
+}
+
+#[derive(Clone)]
+pub struct Search {
+    id: u64,
+    kind: SearchKind,
+    query: String,
+    results: Arc<Vec<SearchResult>>,
+    // Counts the times the results were replaced
+    revision: u64,
+    // Counts the times the search was started
+    run: u64,
+    // Objects searched so far and in total while the search runs
+    progress: Option<(usize, usize)>,
+    // Set to stop the running search
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Search {
+    fn label(&self) -> String {
+        match self.progress {
+            Some((searched, total)) => format!(
+                "{}: {} ({}, searched {} of {} objects)",
+                self.kind.name(),
+                self.query,
+                self.results.len(),
+                searched,
+                total
+            ),
+            None => format!(
+                "{}: {} ({})",
+                self.kind.name(),
+                self.query,
+                self.results.len()
+            ),
+        }
+    }
+}
+
+// The results in an object, sent from the pool as each one is searched
+struct Found {
+    searched: usize,
+    results: Vec<SearchResult>,
+}
+
+#[derive(Default)]
+pub struct Searches {
+    searches: Vec<Search>,
+    active: Option<u64>,
+    next_id: u64,
+}
+
+impl Searches {
+    fn add(&mut self, kind: SearchKind, query: String) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.searches.push(Search {
+            id,
+            kind,
+            results: Arc::new(Vec::new()),
+            query,
+            revision: 0,
+            run: 0,
+            progress: None,
+            cancelled: Arc::new(AtomicBool::new(false)),
+        });
+        self.active = Some(id);
+        id
+    }
+
+    // Clears the results of search `id` for a new run over `total` objects,
+    // stopping the previous one
+    fn restart(&mut self, id: u64, total: usize) -> Option<Search> {
+        let search = self.searches.iter_mut().find(|s| s.id == id)?;
+        search.cancelled.store(true, Ordering::Relaxed);
+        search.cancelled = Arc::new(AtomicBool::new(false));
+        search.results = Arc::new(Vec::new());
+        search.revision += 1;
+        search.run += 1;
+        search.progress = Some((0, total));
+        self.active = Some(id);
+        Some(search.clone())
+    }
+
+    // Drops the results in `object`, which is being closed
+    pub fn forget(&mut self, object: &Arc<Object>) {
+        for search in &mut self.searches {
+            if search
+                .results
+                .iter()
+                .any(|result| result.object.key() == object.key())
+            {
+                let results = search
+                    .results
+                    .iter()
+                    .filter(|result| result.object.key() != object.key())
+                    .cloned()
+                    .collect();
+                search.results = Arc::new(results);
+                search.revision += 1;
+            }
+        }
+    }
+
+    fn close(&mut self, id: u64) {
+        let Some(i) = self.searches.iter().position(|s| s.id == id) else {
+            return;
+        };
+        self.searches
+            .remove(i)
+            .cancelled
+            .store(true, Ordering::Relaxed);
+        if self.active == Some(id) {
+            self.active = self
+                .searches
+                .get(i)
+                .or_else(|| self.searches.last())
+                .map(|s| s.id);
+        }
+    }
+}
+
+// Adds a search for `query` and starts it
+fn start(
+    searches: RwSignal<Searches>,
+    objects: RwSignal<ObjectList>,
+    kind: SearchKind,
+    query: String,
+) {
+    let id = searches.try_update(|searches| searches.add(kind, query));
+    if let Some(id) = id {
+        rerun(searches, objects, id);
+    }
+}
+
+// Searches the loaded objects again for search `id` on the pool, one object
+// at a time so results show up and the search can be stopped as it goes
+fn rerun(searches: RwSignal<Searches>, objects: RwSignal<ObjectList>, id: u64) {
+    let loaded = objects.with_untracked(|objects| objects.objects.clone());
+    let Some(search) = searches
+        .try_update(|searches| searches.restart(id, loaded.len()))
+        .flatten()
+    else {
+        return;
+    };
+    let (sender, receiver) = mpsc::channel();
+    let cancelled = search.cancelled.clone();
+    let (kind, query) = (search.kind, search.query);
+    pool::spawn(pool::Priority::Interactive, move || {
+        let mut found = 0;
+        for (i, object) in loaded.iter().enumerate() {
+            if found >= MAX_RESULTS || cancelled.load(Ordering::Relaxed) {
+                break;
+            }
+            let results = run(kind, &query, slice::from_ref(object), &cancelled);
+            found += results.len();
+            let message = Found {
+                searched: i + 1,
+                results,
+            };
+            if sender.send(message).is_err() {
+                break;
+            }
+        }
+    });
+    receive(searches, objects, id, search.run, receiver);
+}
+
+// Adds the results arriving from a run of search `id` until it's done. Runs
+// replaced by a newer one and results in objects closed meanwhile are
+// ignored.
+fn receive(
+    searches: RwSignal<Searches>,
+    objects: RwSignal<ObjectList>,
+    id: u64,
+    run: u64,
+    receiver: mpsc::Receiver<Found>,
+) {
+    receive_batches(receiver, move |batch, done| {
+        searches.update(|searches| {
+            let Some(search) = searches
+                .searches
+                .iter_mut()
+                .find(|s| s.id == id && s.run == run)
+            else {
+                return;
+            };
+            let mut results = (*search.results).clone();
+            for found in batch {
+                results.extend(found.results.into_iter().filter(|result| {
+                    objects.with_untracked(|list| {
+                        list.objects
+                            .iter()
+                            .any(|object| object.key() == result.object.key())
+                    })
+                }));
+                if let Some((searched, _)) = &mut search.progress {
+                    *searched = found.searched;
+                }
+            }
+            results.truncate(MAX_RESULTS);
+            search.results = Arc::new(results);
+            if done {
+                search.progress = None;
+            }
+        });
+    });
+}
+
+fn symbols(objects: &[Arc<Object>]) -> impl Iterator<Item = Symbol> + '_ {
+    objects.iter().flat_map(|object| {
+        object.symbols_sorted.iter().map(|data| Symbol {
+            object: object.clone(),
+            data: data.clone(),
+        })
+    })
+}
+
+fn matches_mnemonic(pattern: &str, mnemonic: iced_x86::Mnemonic) -> bool {
+    let name = format!("{:?}", mnemonic).to_lowercase();
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => name == pattern,
+    }
+}
+
+// Parses hex bytes like `48 8b ?? 05` or `488b??05`, with `?` or `??` as
+// wildcards. At least one byte has to be given.
+fn byte_pattern(query: &str) -> Option<Vec<Option<u8>>> {
+    let mut pattern = Vec::new();
+    for token in query.split_whitespace() {
+        if token == "?" {
+            pattern.push(None);
+            continue;
+        }
+        let token = token.as_bytes();
+        if token.len() % 2 != 0 {
+            return None;
+        }
+        for pair in token.chunks(2) {
+            let pair = std::str::from_utf8(pair).ok()?;
+            pattern.push(match pair {
+                "??" => None,
+                _ => Some(u8::from_str_radix(pair, 16).ok()?),
+            });
+        }
+    }
+    pattern.iter().any(Option::is_some).then_some(pattern)
+}
+
+// Parses a decimal or `0x` hex number, with negative numbers in two's
+// complement
+fn number(query: &str) -> Option<u64> {
+    let (negative, digits) = match query.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, query),
+    };
+    let value = match digits.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok()?,
+        None => digits.parse().ok()?,
+    };
+    Some(if negative {
+        value.wrapping_neg()
+    } else {
+        value
+    })
+}
+
+// Whether operand `operand` is an immediate equal to `value`. Immediates are
+// compared at their encoded width, so -1 matches both `0FFh` and
+// `0FFFFFFFFFFFFFFFFh`.
+fn matches_immediate(instruction: &iced_x86::Instruction, operand: u32, value: u64) -> bool {
+    use iced_x86::OpKind;
+    let bits = match instruction.op_kind(operand) {
+        OpKind::Immediate8 | OpKind::Immediate8_2nd => 8,
+        OpKind::Immediate16 | OpKind::Immediate8to16 => 16,
+        OpKind::Immediate32 | OpKind::Immediate8to32 => 32,
+        OpKind::Immediate64 | OpKind::Immediate8to64 | OpKind::Immediate32to64 => 64,
+        _ => return false,
+    };
+    let mask = u64::MAX >> (64 - bits);
+    let sign_extended = (((value & mask) << (64 - bits)) as i64 >> (64 - bits)) as u64;
+    let fits = value <= mask || sign_extended == value;
+    fits && instruction.immediate(operand) & mask == value & mask
+}
+
+// Decodes the functions and lists the instructions for which `hit` holds
+fn find_instructions(
+    objects: &[Arc<Object>],
+    cancelled: &AtomicBool,
+    hit: impl Fn(&iced_x86::Instruction) -> bool,
+) -> Vec<SearchResult> {
+    let mut results = Vec::new();
+    let mut formatter = iced_x86::IntelFormatter::new();
+    let mut instruction = iced_x86::Instruction::default();
+    for symbol in symbols(objects) {
+        if cancelled.load(Ordering::Relaxed) {
+            break;
+        }
+        // Operands are matched as x86 ones
+        if symbol.data.arm_modes().is_some() {
+            continue;
+        }
+        let Some(bytes) = symbol.data.data() else {
+            continue;
+        };
+        let mut decoder = iced_x86::Decoder::with_ip(
+            symbol.data.bitness(),
+            bytes,
+            symbol.data.address,
+            iced_x86::DecoderOptions::NONE,
+        );
+        while decoder.can_decode() && results.len() < MAX_RESULTS {
+            decoder.decode_out(&mut instruction);
+            if hit(&instruction) {
+                let mut preview = String::new();
+                formatter.format(&instruction, &mut preview);
+                results.push(SearchResult {
+                    object: symbol.object.clone(),
+                    symbol: Some(symbol.clone()),
+                    offset: Some(instruction.ip() - symbol.data.address),
+                    section: None,
+                    preview,
+                });
+            }
+        }
+    }
+    results
+}
+
+// Finds `pattern` in the data of all sections except debug info, naming hits
+// by the function they're in where there is one
+fn find_bytes(
+    objects: &[Arc<Object>],
+    pattern: &[Option<u8>],
+    cancelled: &AtomicBool,
+) -> Vec<SearchResult> {
+    let mut results = Vec::new();
+    for object in objects {
+        for section in &object.sections {
+            if cancelled.load(Ordering::Relaxed) {
+                return results;
+            }
+            if matches!(
+                section.kind,
+                SectionKind::Debug | SectionKind::UninitializedData
+            ) {
+                continue;
+            }
+            let data: &[u8] = &section.data;
+            for (offset, window) in data.windows(pattern.len()).enumerate() {
+                if results.len() >= MAX_RESULTS {
+                    return results;
+                }
+                let hit = window
+                    .iter()
+                    .zip(pattern)
+                    .all(|(byte, wanted)| wanted.is_none_or(|wanted| *byte == wanted));
+                if !hit {
+                    continue;
+                }
+                let address = section.address + offset as u64;
+                let preview = window
+                    .iter()
+                    .map(|byte| format!("{:02X}", byte))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let result = match object.symbol_containing(section.index, address) {
+                    Some(data) => SearchResult {
+                        object: object.clone(),
+                        offset: Some(address - data.address),
+                        symbol: Some(Symbol {
+                            object: object.clone(),
+                            data,
+                        }),
+                        section: None,
+                        preview,
+                    },
+                    None => SearchResult {
+                        object: object.clone(),
+                        symbol: None,
+                        offset: Some(offset as u64),
+                        section: Some(section.name.clone()),
+                        preview,
+                    },
+                };
+                results.push(result);
+            }
+        }
+    }
+    results
+}
+
+pub fn run(
+    kind: SearchKind,
+    query: &str,
+    objects: &[Arc<Object>],
+    cancelled: &AtomicBool,
+) -> Vec<SearchResult> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    match kind {
+        SearchKind::Symbol => symbols(objects)
+            .filter(|symbol| {
+                symbol.data.exact_name().to_lowercase().contains(&query)
+                    || symbol
+                        .data
+                        .demangled
+                        .as_ref()
+                        .map(|d| d.to_lowercase().contains(&query))
+                        .unwrap_or(false)
+            })
+            .take(MAX_RESULTS)
+            .map(|symbol| SearchResult {
+                object: symbol.object.clone(),
+                preview: symbol.object.name.clone(),
+                offset: None,
+                section: None,
+                symbol: Some(symbol),
+            })
+            .collect(),
+        SearchKind::Mnemonic => find_instructions(objects, cancelled, |instruction| {
+            matches_mnemonic(&query, instruction.mnemonic())
+        }),
+        SearchKind::Bytes => match byte_pattern(&query) {
+            Some(pattern) => find_bytes(objects, &pattern, cancelled),
+            None => Vec::new(),
+        },
+        SearchKind::Immediate => match number(&query) {
+            Some(value) => find_instructions(objects, cancelled, |instruction| {
+                (0..instruction.op_count())
+                    .any(|operand| matches_immediate(instruction, operand, value))
+            }),
+            None => Vec::new(),
+        },
+    }
+}
+
+// Extracts a symbol name from text copied from other tools, like `nm` output
+// lines or quoted names in linker errors.
+fn clipboard_name(text: &str) -> Option<&str> {
+    let line = text.lines().map(str::trim).find(|line| !line.is_empty())?;
+
+    if let Some((_, rest)) = line.split_once('`') {
+        if let Some((name, _)) = rest.split_once(['\'', '`']) {
+            return Some(name.trim()).filter(|name| !name.is_empty());
+        }
+    }
+
+    // `nm` lines are `[address] type name`
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    fn rest<'a>(line: &'a str, field: &str) -> &'a str {
+        line[field.len()..].trim_start()
+    }
+    let line = match fields[..] {
+        [address, kind, _, ..]
+            if kind.len() == 1 && address.chars().all(|c| c.is_ascii_hexdigit()) =>
+        {
+            rest(rest(line, address), kind)
+        }
+        [kind, _, ..] if kind.len() == 1 && kind.chars().all(|c| c.is_ascii_alphabetic()) => {
+            rest(line, kind)
+        }
+        _ => line,
+    };
+
+    let name = line.trim_matches(|c| matches!(c, '"' | '\'' | '`'));
+    Some(name).filter(|name| !name.is_empty())
+}
+
+// Removes the `::h0123456789abcdef` hash suffix of legacy Rust symbols
+fn strip_hash(name: &str) -> &str {
+    match name.rsplit_once("::h") {
+        Some((path, hash)) if hash.len() == 16 && hash.chars().all(|c| c.is_ascii_hexdigit()) => {
+            path
+        }
+        _ => name,
+    }
+}
+
+// Finds the symbol best matching `name`, which may be mangled or demangled.
+// Exact matches are preferred over matches ignoring hashes and leading
+// underscores, which are preferred over the shortest substring match.
+pub fn find_symbol(name: &str, objects: &[Arc<Object>]) -> Option<Symbol> {
+    best_symbol(name, objects, true)
+}
+
+// Finds the symbol named `name`, ignoring hashes and leading underscores but
+// not matching substrings
+pub fn find_named_symbol(name: &str, objects: &[Arc<Object>]) -> Option<Symbol> {
+    best_symbol(name, objects, false)
+}
+
+fn best_symbol(name: &str, objects: &[Arc<Object>], substrings: bool) -> Option<Symbol> {
+    let demangled = settings::demangling()
+        .demangle(name)
+        .unwrap_or_else(|| name.to_owned());
+    let wanted = [name, &demangled];
+    let lower = strip_hash(&demangled).to_lowercase();
+
+    symbols(objects)
+        .filter_map(|symbol| {
+            let data = symbol.data.clone();
+            let names = [Some(data.name.as_str()), data.demangled.as_deref()];
+            let names = names.into_iter().flatten();
+
+            let rank = if names.clone().any(|n| wanted.contains(&n)) {
+                0
+            } else if names.clone().any(|n| {
+                wanted.iter().any(|w| {
+                    strip_hash(n.trim_start_matches('_')) == strip_hash(w.trim_start_matches('_'))
+                })
+            }) {
+                1
+            } else if substrings && names.clone().any(|n| n.to_lowercase().contains(&lower)) {
+                2
+            } else {
+                return None;
+            };
+
+            let length = data.demangled.as_ref().unwrap_or(&data.name).len();
+            Some(((rank, length), symbol))
+        })
+        .min_by_key(|(key, _)| *key)
+        .map(|(_, symbol)| symbol)
+}
+
+// Jumps to the symbol named on the clipboard. Opens a symbol search instead
+// when nothing matches, so the query stays visible.
+pub fn find_from_clipboard(
+    searches: RwSignal<Searches>,
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+) {
+    let contents = match Clipboard::get_contents() {
+        Ok(contents) => contents,
+        Err(error) => {
+            report_error(objects, format!("Clipboard: {:?}", error));
+            return;
+        }
+    };
+    let Some(name) = clipboard_name(&contents) else {
+        report_error(objects, "The clipboard holds no symbol name".to_owned());
+        return;
+    };
+
+    match objects.with_untracked(|objects| find_symbol(name, &objects.objects)) {
+        Some(symbol) => selection.set(Selection::Symbol(symbol)),
+        None => start(searches, objects, SearchKind::Symbol, name.to_owned()),
+    }
+}
+
+pub fn search_bar(searches: RwSignal<Searches>, objects: RwSignal<ObjectList>) -> impl View {
+    let query = create_rw_signal(String::new());
+    let kind = create_rw_signal(SearchKind::Symbol);
+
+    let search = move || {
+        let text = query.get_untracked();
+        if text.trim().is_empty() {
+            return;
+        }
+        start(searches, objects, kind.get_untracked(), text);
+    };
+
+    let kind_selector = label(move || kind.get().name())
+        .style(|s| {
+            s.min_width(70)
+                .border_radius(3.0)
+                .padding(6.0)
+                .background(theme().button)
+                .border_color(theme().button_border)
+                .border(0.5)
+                .margin(4)
+                .cursor(CursorStyle::Pointer)
+        })
+        .hover_style(|s| s.background(theme().button_hover))
+        .on_click(move |_| {
+            kind.update(|kind| *kind = kind.next());
+            true
+        });
+
+    let input = text_input(query)
+        .style(|s| {
+            s.width(220)
+                .padding(5)
+                .margin_vert(4)
+                .border(0.5)
+                .border_radius(3)
+                .border_color(theme().button_border)
+        })
+        .on_event(EventListener::KeyDown, move |e| {
+            if let Event::KeyDown(e) = e {
+                if e.key.logical_key == Key::Named(NamedKey::Enter) {
+                    search();
+                    return true;
+                }
+            }
+            false
+        });
+    SEARCH_BOX.with(|search_box| search_box.set(Some(input.id())));
+
+    stack((
+        kind_selector,
+        input,
+        button("Search", move |_| {
+            search();
+            true
+        }),
+    ))
+    .style(|s| s.flex_row().items_center())
+}
+
+fn search_tab(
+    search: Search,
+    searches: RwSignal<Searches>,
+    objects: RwSignal<ObjectList>,
+) -> impl View {
+    let id = search.id;
+    let current =
+        move || searches.with(|searches| searches.searches.iter().find(|s| s.id == id).cloned());
+    let running = move || current().is_some_and(|search| search.progress.is_some());
+    stack((
+        label(move || current().map(|search| search.label()).unwrap_or_default()).on_click(
+            move |_| {
+                searches.update(|searches| searches.active = Some(id));
+                true
+            },
+        ),
+        text("↻")
+            .style(move |s| {
+                s.padding_horiz(4)
+                    .cursor(CursorStyle::Pointer)
+                    .apply_if(running(), |s| s.hide())
+            })
+            .on_click(move |_| {
+                rerun(searches, objects, id);
+                true
+            }),
+        text("■")
+            .style(move |s| {
+                s.padding_horiz(4)
+                    .cursor(CursorStyle::Pointer)
+                    .apply_if(!running(), |s| s.hide())
+            })
+            .on_click(move |_| {
+                if let Some(search) = current() {
+                    search.cancelled.store(true, Ordering::Relaxed);
+                }
+                true
+            }),
+        text("×")
+            .style(|s| s.padding_horiz(4).cursor(CursorStyle::Pointer))
+            .on_click(move |_| {
+                searches.update(|searches| searches.close(id));
+                true
+            }),
+    ))
+    .style(move |s| {
+        s.flex_row()
+            .items_center()
+            .padding_horiz(8)
+            .padding_vert(4)
+            .border_right(0.5)
+            .border_color(theme().border)
+            .apply_if(searches.with(|s| s.active == Some(id)), |s| {
+                s.background(theme().background)
+            })
+    })
+}
+
+fn results(search: Option<Search>, selection: RwSignal<Selection>) -> Box<dyn View> {
+    let Some(search) = search else {
+        return Box::new(text(""));
+    };
+    if search.results.is_empty() {
+        let message = match search.progress {
+            Some(_) => "Searching…",
+            None => "No results",
+        };
+        return Box::new(text(message).style(|s| s.padding(5.0)));
+    }
+
+    let results = search.results.clone();
+    let list = virtual_list(
+        VirtualListDirection::Vertical,
+        VirtualListItemSize::Fixed(Box::new(|| 22.0)),
+        move || {
+            results
+                .iter()
+                .cloned()
+                .enumerate()
+                .collect::<im::Vector<_>>()
+        },
+        |(i, _)| *i,
+        move |(_, result)| {
+            let name = match (&result.symbol, &result.section) {
+                (Some(symbol), _) => symbol
+                    .data
+                    .demangled
+                    .as_ref()
+                    .unwrap_or(&symbol.data.name)
+                    .clone(),
+                (None, section) => format!(
+                    "{}:{}",
+                    result.object.name,
+                    section.as_deref().unwrap_or_default()
+                ),
+            };
+            let location = match result.offset {
+                Some(offset) => format!("{}+{:#x}", name, offset),
+                None => name,
+            };
+            let target = match result.symbol {
+                Some(symbol) => Selection::Symbol(symbol),
+                None => Selection::Object(result.object.clone()),
+            };
+            stack((
+                text(location).style(|s| {
+                    s.width_pct(50.0)
+                        .padding_right(10)
+                        .text_overflow(TextOverflow::Clip)
+                }),
+                text(result.preview).style(|s| s.color(theme().dim)),
+            ))
+            .style(|s| s.flex_row().padding_horiz(5).height(22.0).width_full())
+            .hover_style(|s| s.background(theme().hover))
+            .on_click(move |_| {
+                selection.set(target.clone());
+                true
+            })
+        },
+    )
+    .style(|s| s.flex_col().width_full());
+
+    Box::new(scroll(list).style(|s| s.width_full().height_full()))
+}
+
+pub fn search_panel(
+    searches: RwSignal<Searches>,
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+) -> impl View {
+    let tabs = list(
+        move || searches.with(|s| s.searches.clone()),
+        |search| (search.id, search.revision),
+        move |search| search_tab(search, searches, objects),
+    )
+    .style(|s| {
+        s.flex_row()
+            .width_full()
+            .background(theme().panel)
+            .border_bottom(0.5)
+            .border_color(theme().border)
+    });
+
+    let results = dyn_container(
+        move || {
+            searches.with(|s| {
+                s.searches
+                    .iter()
+                    .find(|search| Some(search.id) == s.active)
+                    .cloned()
+            })
+        },
+        move |search| results(search, selection),
+    )
+    .style(|s| s.width_full().height_full());
+
+    stack((tabs, results)).style(move |s| {
+        s.flex_col()
+            .width_full()
+            .height(220)
+            .border_top(0.5)
+            .border_color(theme().border)
+            .apply_if(searches.with(|s| s.searches.is_empty()), |s| s.hide())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(code: &[u8]) -> iced_x86::Instruction {
+        iced_x86::Decoder::new(64, code, iced_x86::DecoderOptions::NONE).decode()
+    }
+
+    #[test]
+    fn parses_byte_patterns() {
+        assert_eq!(
+            byte_pattern("48 8b ?? 05"),
+            Some(vec![Some(0x48), Some(0x8b), None, Some(0x05)])
+        );
+        assert_eq!(
+            byte_pattern("488B ? e8"),
+            Some(vec![Some(0x48), Some(0x8b), None, Some(0xe8)])
+        );
+        assert_eq!(
+            byte_pattern("c3??90"),
+            Some(vec![Some(0xc3), None, Some(0x90)])
+        );
+        // Odd digits, non-hex digits and patterns of only wildcards
+        assert_eq!(byte_pattern("4"), None);
+        assert_eq!(byte_pattern("zz"), None);
+        assert_eq!(byte_pattern("?? ?"), None);
+        assert_eq!(byte_pattern(""), None);
+    }
+
+    #[test]
+    fn parses_numbers() {
+        assert_eq!(number("42"), Some(42));
+        assert_eq!(number("0x2a"), Some(42));
+        assert_eq!(number("-1"), Some(u64::MAX));
+        assert_eq!(number("-0x10"), Some(0x10u64.wrapping_neg()));
+        assert_eq!(number("0x"), None);
+        assert_eq!(number("ff"), None);
+    }
+
+    #[test]
+    fn matches_immediates_at_their_width() {
+        // mov eax, 0FFFFFFFFh
+        let mov = decode(&[0xb8, 0xff, 0xff, 0xff, 0xff]);
+        assert!(matches_immediate(&mov, 1, 0xffff_ffff));
+        assert!(matches_immediate(&mov, 1, u64::MAX));
+        assert!(!matches_immediate(&mov, 1, 0xff));
+        assert!(!matches_immediate(&mov, 0, 0xffff_ffff));
+
+        // cmp al, 80h
+        let cmp = decode(&[0x3c, 0x80]);
+        assert!(matches_immediate(&cmp, 1, 0x80));
+        assert!(matches_immediate(&cmp, 1, 0x80u64.wrapping_neg()));
+        assert!(!matches_immediate(&cmp, 1, 0x180));
+
+        // add rax, -1
+        let add = decode(&[0x48, 0x83, 0xc0, 0xff]);
+        assert!(matches_immediate(&add, 1, u64::MAX));
+        assert!(!matches_immediate(&add, 1, 0xff));
+    }
+
+    #[test]
+    fn finds_clipboard_names() {
+        assert_eq!(
+            clipboard_name("main.c:(.text+0x5): undefined reference to `foo'"),
+            Some("foo")
+        );
+        assert_eq!(clipboard_name("in `bar::baz` at 0x10"), Some("bar::baz"));
+        assert_eq!(clipboard_name("0000000000401000 T main"), Some("main"));
+        assert_eq!(clipboard_name("                 U puts"), Some("puts"));
+        assert_eq!(
+            clipboard_name("\n  _ZN3foo3barEv  \nnext"),
+            Some("_ZN3foo3barEv")
+        );
+        assert_eq!(clipboard_name("\"quoted\""), Some("quoted"));
+        assert_eq!(
+            clipboard_name("operator new(unsigned long)"),
+            Some("operator new(unsigned long)")
+        );
+        assert_eq!(clipboard_name(" \n\t"), None);
+        assert_eq!(clipboard_name("``"), None);
+    }
+}
@@ -0,0 +1,258 @@
+use std::sync::Arc;
+
+use asm_viewer_core::sections::{group, Liveness, TextSection};
+use floem::{
+    peniko::Color,
+    reactive::{create_rw_signal, RwSignal},
+    view::View,
+    views::{dyn_container, list, scroll, stack, text, Decorators},
+};
+use object::{SectionFlags, SectionIndex, SectionKind};
+
+use crate::{
+    format_address, header, hexview, theme::theme, units, Object, Section, Selection, Symbol,
+    SymbolData,
+};
+
+const SHF_WRITE: u64 = 0x1;
+const SHF_ALLOC: u64 = 0x2;
+const SHF_EXECINSTR: u64 = 0x4;
+
+const IMAGE_SCN_MEM_EXECUTE: u32 = 0x20000000;
+const IMAGE_SCN_MEM_READ: u32 = 0x40000000;
+const IMAGE_SCN_MEM_WRITE: u32 = 0x80000000;
+
+fn liveness_color(liveness: Liveness) -> Color {
+    match liveness {
+        Liveness::Referenced | Liveness::Retained => Color::rgb8(87, 103, 65),
+        Liveness::Exported => Color::rgb8(80, 107, 135),
+        Liveness::Discardable => Color::rgb8(170, 60, 60),
+    }
+}
+
+// Permissions of a section when loaded, as `rwx`. Mach-O keeps them on the
+// segments, so they're guessed from the kind of section.
+fn permissions(section: &Section) -> String {
+    let (read, write, execute) = match section.flags {
+        SectionFlags::Elf { sh_flags } => (
+            sh_flags & SHF_ALLOC != 0,
+            sh_flags & SHF_WRITE != 0,
+            sh_flags & SHF_EXECINSTR != 0,
+        ),
+        SectionFlags::Coff { characteristics } => (
+            characteristics & IMAGE_SCN_MEM_READ != 0,
+            characteristics & IMAGE_SCN_MEM_WRITE != 0,
+            characteristics & IMAGE_SCN_MEM_EXECUTE != 0,
+        ),
+        _ => match section.kind {
+            SectionKind::Text => (true, false, true),
+            SectionKind::Data | SectionKind::UninitializedData | SectionKind::Tls => {
+                (true, true, false)
+            }
+            SectionKind::ReadOnlyData
+            | SectionKind::ReadOnlyDataWithRel
+            | SectionKind::ReadOnlyString => (true, false, false),
+            _ => (false, false, false),
+        },
+    };
+    [(read, 'r'), (write, 'w'), (execute, 'x')]
+        .iter()
+        .map(|&(set, c)| if set { c } else { '-' })
+        .collect()
+}
+
+fn is_executable(section: &Section) -> bool {
+    section.kind == SectionKind::Text || permissions(section).ends_with('x')
+}
+
+// The whole section as a symbol, so it can be disassembled. The copy of the
+// section only has a symbol at its start, so none of the contents are cut off.
+fn section_symbol(object: &Arc<Object>, section: &Arc<Section>) -> Symbol {
+    let section = Section {
+        symbols: vec![section.address],
+        ..(**section).clone()
+    };
+    Symbol {
+        object: object.clone(),
+        data: Arc::new(SymbolData {
+            index: None,
+            name: section.name.clone(),
+            raw_name: section.raw_name.clone(),
+            demangled: None,
+            address: section.address,
+            size: section.size,
+            section: Some(Arc::new(section)),
+            metrics: Default::default(),
+        }),
+    }
+}
+
+fn all_sections(
+    object: &Arc<Object>,
+    selected: RwSignal<Option<SectionIndex>>,
+    selection: RwSignal<Selection>,
+    relative_to: Option<u64>,
+) -> impl View {
+    let mut sections = object.sections.clone();
+    sections.sort_by_key(|section| (section.address, section.index.0));
+    let object = object.clone();
+    list(
+        move || sections.clone().into_iter(),
+        |section| section.index,
+        move |section| {
+            let index = section.index;
+            let executable = is_executable(&section);
+            let object = object.clone();
+            stack((
+                text(section.name.clone()).style(|s| {
+                    s.flex_grow(1.0)
+                        .flex_basis(0.0)
+                        .min_width(0.0)
+                        .padding_left(5)
+                }),
+                text(format_address(section.address, relative_to))
+                    .style(|s| s.width(150).color(theme().address)),
+                units::size_text(section.size).style(|s| s.width(100)),
+                text(permissions(&section)).style(|s| s.width(40)),
+                text(format!("{:?}", section.kind)).style(|s| s.width(140).color(theme().dim)),
+                text(format!(
+                    "{} relocs",
+                    units::count(section.relocations.len() as u64)
+                ))
+                .style(|s| s.width(80)),
+            ))
+            .style(move |s| {
+                s.flex_row()
+                    .width_full()
+                    .padding(3)
+                    .apply_if(selected.get() == Some(index), |s| {
+                        s.background(theme().selection)
+                    })
+            })
+            .hover_style(|s| s.background(theme().hover))
+            .on_click(move |_| {
+                if executable {
+                    selection.set(Selection::Symbol(section_symbol(&object, &section)));
+                } else {
+                    selected.set(Some(index));
+                }
+                true
+            })
+        },
+    )
+    .style(|s| s.flex_col().width_full())
+}
+
+fn text_sections(object: &Object) -> Box<dyn View> {
+    if object.text_sections.is_empty() {
+        return Box::new(
+            text("No per-function text sections (-ffunction-sections)").style(|s| s.padding(5.0)),
+        );
+    }
+
+    let mut groups: Vec<(String, Vec<TextSection>)> = Vec::new();
+    for section in &object.text_sections {
+        let name = group(&section.name);
+        match groups.iter_mut().find(|(group, _)| group == name) {
+            Some((_, sections)) => sections.push(section.clone()),
+            None => groups.push((name.to_owned(), vec![section.clone()])),
+        }
+    }
+    groups.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let discardable: u64 = object
+        .text_sections
+        .iter()
+        .filter(|section| section.liveness == Liveness::Discardable)
+        .map(|section| section.size)
+        .sum();
+    let summary = text(format!(
+        "{} sections, {} discarded by --gc-sections",
+        units::count(object.text_sections.len() as u64),
+        units::size(discardable)
+    ))
+    .style(|s| s.padding(5.0));
+
+    let groups = list(
+        move || groups.clone().into_iter().enumerate(),
+        |(i, _)| *i,
+        |(_, (name, mut sections))| {
+            sections.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+            let size: u64 = sections.iter().map(|section| section.size).sum();
+            let title = format!(
+                "{} ({} sections, {})",
+                name,
+                units::count(sections.len() as u64),
+                units::size(size)
+            );
+            let rows = list(
+                move || sections.clone().into_iter().enumerate(),
+                |(i, _)| *i,
+                |(_, section)| {
+                    let liveness = section.liveness;
+                    stack((
+                        text(section.name).style(|s| {
+                            s.flex_grow(1.0)
+                                .flex_basis(0.0)
+                                .min_width(0.0)
+                                .padding_left(15)
+                        }),
+                        units::size_text(section.size).style(|s| s.width(100)),
+                        text(format!("{} refs", section.incoming)).style(|s| s.width(70)),
+                        text(format!("{} globals", section.global_symbols)).style(|s| s.width(80)),
+                        text(liveness.label())
+                            .style(move |s| s.width(200).color(liveness_color(liveness))),
+                    ))
+                    .style(|s| s.flex_row().width_full().padding(3))
+                },
+            )
+            .style(|s| s.flex_col().width_full());
+            stack((header(title), rows)).style(|s| s.flex_col().width_full())
+        },
+    )
+    .style(|s| s.flex_col().width_full());
+
+    Box::new(stack((summary, groups)).style(|s| s.flex_col().width_full()))
+}
+
+// All sections of the object. Executable sections open as disassembly and the
+// others as a dump below the list.
+pub fn sections_view(
+    object: &Arc<Object>,
+    selection: RwSignal<Selection>,
+    relative_to: Option<u64>,
+) -> Box<dyn View> {
+    let selected: RwSignal<Option<SectionIndex>> = create_rw_signal(None);
+    let lists = stack((
+        header(format!("Sections ({})", object.sections.len())),
+        all_sections(object, selected, selection, relative_to),
+        header("Function Sections"),
+        text_sections(object),
+    ))
+    .style(|s| s.flex_col().width_full());
+
+    let sections = object.sections.clone();
+    let detail = dyn_container(
+        move || selected.get(),
+        move |selected| match selected.and_then(|index| sections.iter().find(|s| s.index == index))
+        {
+            Some(section) => hexview::section_dump(section, relative_to),
+            None => Box::new(text("")),
+        },
+    )
+    .style(move |s| {
+        s.width_full()
+            .height(300)
+            .border_top(0.5)
+            .border_color(theme().border)
+            .apply_if(selected.get().is_none(), |s| s.hide())
+    });
+
+    Box::new(
+        stack((
+            scroll(lists).style(|s| s.width_full().flex_grow(1.0).flex_basis(0.0)),
+            detail,
+        ))
+        .style(|s| s.flex_col().width_full().height_full()),
+    )
+}
@@ -0,0 +1,206 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use asm_viewer_core::config::{self, Config, Value};
+use floem::{
+    ext_event::create_ext_action,
+    reactive::{create_effect, RwSignal, Scope},
+};
+
+use crate::{
+    cli::Startup, raw, report_error, report_problem, settings::config_dir, ObjectList, Selection,
+};
+
+// Changes are written once they've settled for this long, and at least this
+// often while they keep coming
+const DEBOUNCE: Duration = Duration::from_secs(1);
+
+fn session_file() -> Option<PathBuf> {
+    Some(config_dir()?.join("session.txt"))
+}
+
+// Replaces `path` through a temporary file, so a crash while writing leaves
+// the previous contents in place
+pub fn write_atomic(path: &Path, text: &str) -> io::Result<()> {
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    fs::write(&temporary, text)?;
+    fs::rename(&temporary, path)
+}
+
+// The open files and the selected symbol, in the tab separated records of
+// project files. Raw binaries are recorded with their layout, so they aren't
+// reopened as object files. Nothing is recorded while files are loading, as
+// the session is incomplete until they're done.
+fn journal(list: &ObjectList, selection: &Selection) -> Option<String> {
+    if !list.loading.is_empty() {
+        return None;
+    }
+    let mut paths: Vec<&Path> = Vec::new();
+    let mut text = String::new();
+    for object in &list.objects {
+        if let Some(layout) = raw::layout(object) {
+            text.push_str(&format!(
+                "raw\t{:x}\t{}\t{}\n",
+                layout.base,
+                layout.bitness,
+                object.path.display()
+            ));
+        } else if !paths.contains(&object.path.as_path()) {
+            paths.push(&object.path);
+        }
+    }
+    for path in paths {
+        text.push_str(&format!("open\t{}\n", path.display()));
+    }
+    if let Selection::Symbol(symbol) = selection {
+        text.push_str(&format!("symbol\t{}\n", symbol.data.name));
+    }
+    Some(text)
+}
+
+fn save(text: &str) -> Result<(), String> {
+    let Some(path) = session_file() else {
+        return Ok(());
+    };
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    write_atomic(&path, text).map_err(|error| format!("Saving `{}`: {}", path.display(), error))
+}
+
+enum Message {
+    State(String),
+    // The action reporting the next error saving the session
+    Report(Box<dyn FnOnce(String) + Send>),
+}
+
+// Hands the saving thread the action reporting its next error
+fn arm(objects: RwSignal<ObjectList>, scope: Scope, sender: mpsc::Sender<Message>) {
+    let next = sender.clone();
+    let report = create_ext_action(scope, move |error| {
+        report_error(objects, error);
+        arm(objects, scope, next);
+    });
+    let _ = sender.send(Message::Report(Box::new(report)));
+}
+
+// Keeps the session file up to date in the background, so it survives a
+// crash of the GUI
+pub fn autosave(objects: RwSignal<ObjectList>, selection: RwSignal<Selection>) {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut report: Option<Box<dyn FnOnce(String) + Send>> = None;
+        let mut pending: Option<(String, Instant)> = None;
+        loop {
+            let message = match &pending {
+                Some((_, deadline)) => {
+                    match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                    {
+                        Ok(message) => Some(message),
+                        Err(mpsc::RecvTimeoutError::Timeout) => None,
+                        Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                    }
+                }
+                None => match receiver.recv() {
+                    Ok(message) => Some(message),
+                    Err(_) => break,
+                },
+            };
+            match message {
+                Some(Message::Report(action)) => report = Some(action),
+                // Only the last state of a burst of changes is written, by the
+                // deadline set by the first one
+                Some(Message::State(text)) => {
+                    let deadline = pending.map_or(Instant::now() + DEBOUNCE, |(_, at)| at);
+                    pending = Some((text, deadline));
+                }
+                None => {
+                    let Some((text, _)) = pending.take() else {
+                        continue;
+                    };
+                    if let Err(error) = save(&text) {
+                        // Errors while the last one is shown are the same failure
+                        if let Some(report) = report.take() {
+                            report(error);
+                        }
+                    }
+                }
+            }
+        }
+    });
+    arm(objects, Scope::new(), sender.clone());
+    create_effect(move |last: Option<Option<String>>| {
+        let text = objects.with(|list| selection.with(|selection| journal(list, selection)));
+        if let Some(text) = &text {
+            if last.flatten().as_ref() != Some(text) {
+                let _ = sender.send(Message::State(text.clone()));
+            }
+        }
+        text
+    });
+}
+
+// The files and symbol of the last autosaved session which still exist
+pub fn recover() -> Option<Startup> {
+    let text = fs::read_to_string(session_file()?).ok()?;
+    let mut startup = Startup::default();
+    for line in text.lines() {
+        let fields: Vec<&str> = line.split('\t').collect();
+        match fields[..] {
+            ["open", path] if Path::new(path).exists() => startup.paths.push(path.into()),
+            ["raw", base, bitness, path] if Path::new(path).exists() => {
+                let (Ok(base), Ok(bitness)) = (u64::from_str_radix(base, 16), bitness.parse())
+                else {
+                    continue;
+                };
+                startup
+                    .raw
+                    .push((path.into(), raw::Layout { bitness, base }));
+            }
+            ["symbol", name] => startup.symbol = Some(name.to_owned()),
+            _ => {}
+        }
+    }
+    (!startup.paths.is_empty() || !startup.raw.is_empty()).then_some(startup)
+}
+
+// How many recently opened files are remembered
+const RECENT: usize = 10;
+
+// Files opened in earlier sessions, most recent first
+pub fn recent() -> Vec<PathBuf> {
+    Config::load()
+        .strings("recent")
+        .unwrap_or_default()
+        .iter()
+        .map(PathBuf::from)
+        .collect()
+}
+
+pub fn add_recent(paths: &[PathBuf]) {
+    let saved = config::update(|config| {
+        let mut recent = config.strings("recent").unwrap_or_default();
+        for path in paths {
+            let path = path.display().to_string();
+            recent.retain(|other| *other != path);
+            recent.insert(0, path);
+        }
+        recent.truncate(RECENT);
+        config.set("recent", Value::Strings(recent));
+    });
+    if let Err(error) = saved {
+        report_problem(error);
+    }
+}
+
+pub fn clear_recent() {
+    if let Err(error) = config::update(|config| config.set("recent", Value::Strings(Vec::new()))) {
+        report_problem(error);
+    }
+}
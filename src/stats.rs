@@ -0,0 +1,375 @@
+use std::{cmp::Reverse, collections::HashMap};
+
+use asm_viewer_core::arm;
+use floem::{
+    peniko::Color,
+    view::View,
+    views::{container_box, empty, list, scroll, stack, text, Decorators},
+};
+use iced_x86::{FlowControl, Mnemonic, OpKind};
+
+use crate::{header, settings::Microarch, units, Object, SymbolData};
+
+const TOP_FUNCTIONS: usize = 20;
+
+// Rough latencies in cycles on Skylake, Zen 2 and Zen 4. Unlisted instructions
+// count as a single cycle.
+const LATENCIES: &[(Mnemonic, [u64; 3])] = &[
+    (Mnemonic::Nop, [0, 0, 0]),
+    (Mnemonic::Imul, [3, 3, 3]),
+    (Mnemonic::Mul, [3, 3, 3]),
+    (Mnemonic::Div, [26, 30, 14]),
+    (Mnemonic::Idiv, [26, 30, 14]),
+    (Mnemonic::Popcnt, [3, 1, 1]),
+    (Mnemonic::Lzcnt, [3, 1, 1]),
+    (Mnemonic::Tzcnt, [3, 2, 2]),
+    (Mnemonic::Bsf, [3, 3, 1]),
+    (Mnemonic::Bsr, [3, 4, 1]),
+    (Mnemonic::Crc32, [3, 3, 3]),
+    (Mnemonic::Call, [3, 3, 3]),
+    (Mnemonic::Ret, [2, 2, 2]),
+    (Mnemonic::Addss, [4, 3, 3]),
+    (Mnemonic::Addsd, [4, 3, 3]),
+    (Mnemonic::Mulss, [4, 3, 3]),
+    (Mnemonic::Mulsd, [4, 3, 3]),
+    (Mnemonic::Divss, [11, 10, 11]),
+    (Mnemonic::Divsd, [14, 13, 13]),
+    (Mnemonic::Sqrtss, [12, 14, 15]),
+    (Mnemonic::Sqrtsd, [18, 20, 21]),
+    (Mnemonic::Cvtsi2sd, [4, 4, 4]),
+    (Mnemonic::Cvttsd2si, [6, 6, 6]),
+];
+
+// Added for instructions reading memory, assuming it hits the L1 cache
+const LOAD_LATENCY: u64 = 4;
+
+pub struct ObjectStats {
+    sections: Vec<(String, u64)>,
+    functions: Vec<(String, u64)>,
+    categories: Vec<(String, u64)>,
+    extensions: Vec<(String, u64)>,
+    kinds: Vec<(String, u64)>,
+}
+
+fn flow_category(flow: FlowControl) -> Option<&'static str> {
+    match flow {
+        FlowControl::Call | FlowControl::IndirectCall => Some("Call"),
+        FlowControl::Return => Some("Return"),
+        FlowControl::UnconditionalBranch | FlowControl::IndirectBranch => Some("Jump"),
+        FlowControl::ConditionalBranch => Some("Conditional Branch"),
+        FlowControl::Interrupt | FlowControl::Exception => Some("Trap"),
+        _ => None,
+    }
+}
+
+pub fn category(instruction: &iced_x86::Instruction) -> &'static str {
+    if let Some(category) = flow_category(instruction.flow_control()) {
+        return category;
+    }
+
+    let simd = (0..instruction.op_count()).any(|i| {
+        instruction.op_kind(i) == iced_x86::OpKind::Register
+            && (instruction.op_register(i).is_xmm()
+                || instruction.op_register(i).is_ymm()
+                || instruction.op_register(i).is_zmm())
+    });
+    if simd {
+        return "SIMD / FP";
+    }
+
+    match instruction.mnemonic() {
+        Mnemonic::Nop | Mnemonic::Int3 => "Padding",
+        Mnemonic::Mov
+        | Mnemonic::Movzx
+        | Mnemonic::Movsx
+        | Mnemonic::Movsxd
+        | Mnemonic::Lea
+        | Mnemonic::Push
+        | Mnemonic::Pop
+        | Mnemonic::Xchg
+        | Mnemonic::Cmove
+        | Mnemonic::Cmovne
+        | Mnemonic::Cmovl
+        | Mnemonic::Cmovle
+        | Mnemonic::Cmovg
+        | Mnemonic::Cmovge
+        | Mnemonic::Cmovb
+        | Mnemonic::Cmovbe
+        | Mnemonic::Cmova
+        | Mnemonic::Cmovae
+        | Mnemonic::Cmovs
+        | Mnemonic::Cmovns => "Data Movement",
+        Mnemonic::Cmp | Mnemonic::Test | Mnemonic::Bt => "Compare",
+        Mnemonic::Add
+        | Mnemonic::Sub
+        | Mnemonic::Adc
+        | Mnemonic::Sbb
+        | Mnemonic::Inc
+        | Mnemonic::Dec
+        | Mnemonic::Neg
+        | Mnemonic::Imul
+        | Mnemonic::Mul
+        | Mnemonic::Idiv
+        | Mnemonic::Div => "Arithmetic",
+        Mnemonic::And
+        | Mnemonic::Or
+        | Mnemonic::Xor
+        | Mnemonic::Not
+        | Mnemonic::Shl
+        | Mnemonic::Shr
+        | Mnemonic::Sar
+        | Mnemonic::Rol
+        | Mnemonic::Ror => "Logic",
+        _ => "Other",
+    }
+}
+
+// ARM instructions are only told apart by how they affect control flow
+pub fn arm_category(decoded: &arm::Decoded) -> &'static str {
+    match flow_category(decoded.flow) {
+        Some(category) => category,
+        None if decoded.nop => "Padding",
+        None => "Other",
+    }
+}
+
+// The ARM instructions of `symbol`, without the data between them
+pub fn arm_instructions(symbol: &SymbolData) -> Option<impl Iterator<Item = arm::Decoded> + '_> {
+    let (bytes, modes) = (symbol.data()?, symbol.arm_modes()?);
+    Some(
+        arm::Decoder::new(bytes, symbol.address, modes)
+            .filter(|decoded| decoded.mode != arm::Mode::Data),
+    )
+}
+
+pub fn latency(instruction: &iced_x86::Instruction, target: Microarch) -> u64 {
+    let column = match target {
+        Microarch::Skylake => 0,
+        Microarch::Zen2 => 1,
+        Microarch::Zen4 => 2,
+    };
+    let base = LATENCIES
+        .iter()
+        .find(|(mnemonic, _)| *mnemonic == instruction.mnemonic())
+        .map_or(1, |(_, cycles)| cycles[column]);
+    let load = instruction.mnemonic() != Mnemonic::Lea
+        && (0..instruction.op_count()).any(|i| instruction.op_kind(i) == OpKind::Memory);
+    if load {
+        base + LOAD_LATENCY
+    } else {
+        base
+    }
+}
+
+// Totals for the instructions of a symbol in an address range
+pub struct RangeStats {
+    pub bytes: u64,
+    pub instructions: u64,
+    // Sum of the latencies, as if every instruction depended on the previous
+    pub cycles: u64,
+    pub categories: Vec<(String, u64)>,
+}
+
+impl RangeStats {
+    // Covers the instructions starting within `start..=end`
+    pub fn compute(symbol: &SymbolData, start: u64, end: u64, target: Microarch) -> RangeStats {
+        let mut stats = RangeStats {
+            bytes: 0,
+            instructions: 0,
+            cycles: 0,
+            categories: Vec::new(),
+        };
+        let Some(bytes) = symbol.data() else {
+            return stats;
+        };
+        let mut categories = HashMap::new();
+        // There are no latencies for ARM, so its instructions count as a cycle
+        if let Some(instructions) = arm_instructions(symbol) {
+            for decoded in instructions {
+                if decoded.address > end {
+                    break;
+                }
+                if decoded.address < start {
+                    continue;
+                }
+                stats.bytes += decoded.len as u64;
+                stats.instructions += 1;
+                stats.cycles += 1;
+                *categories
+                    .entry(arm_category(&decoded).to_owned())
+                    .or_default() += 1;
+            }
+            stats.categories = sorted(categories);
+            return stats;
+        }
+        let mut instruction = iced_x86::Instruction::default();
+        let mut decoder = iced_x86::Decoder::with_ip(
+            symbol.bitness(),
+            bytes,
+            symbol.address,
+            iced_x86::DecoderOptions::NONE,
+        );
+        while decoder.can_decode() {
+            decoder.decode_out(&mut instruction);
+            if instruction.ip() > end {
+                break;
+            }
+            if instruction.ip() < start {
+                continue;
+            }
+            stats.bytes += instruction.len() as u64;
+            stats.instructions += 1;
+            stats.cycles += latency(&instruction, target);
+            *categories
+                .entry(category(&instruction).to_owned())
+                .or_default() += 1;
+        }
+        stats.categories = sorted(categories);
+        stats
+    }
+
+    pub fn summary(&self) -> String {
+        let mix: Vec<String> = self
+            .categories
+            .iter()
+            .map(|(category, count)| format!("{} {}", category, count))
+            .collect();
+        format!(
+            "{} instructions, {}, about {} cycles: {}",
+            units::count(self.instructions),
+            units::size(self.bytes),
+            units::count(self.cycles),
+            mix.join(", ")
+        )
+    }
+}
+
+fn sorted(entries: impl IntoIterator<Item = (String, u64)>) -> Vec<(String, u64)> {
+    let mut entries: Vec<_> = entries.into_iter().collect();
+    entries.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    entries
+}
+
+impl ObjectStats {
+    pub fn compute(object: &Object) -> ObjectStats {
+        let mut sections: Vec<_> = object
+            .sections
+            .iter()
+            .filter(|section| section.size > 0)
+            .map(|section| (section.name.clone(), section.size))
+            .collect();
+        sections.sort_unstable_by_key(|section| Reverse(section.1));
+
+        let mut functions: Vec<_> = object
+            .symbols_sorted
+            .iter()
+            .map(|symbol| {
+                (
+                    symbol.demangled.as_ref().unwrap_or(&symbol.name).clone(),
+                    symbol.data().map(|data| data.len()).unwrap_or_default() as u64,
+                )
+            })
+            .collect();
+        functions.sort_unstable_by_key(|function| Reverse(function.1));
+        functions.truncate(TOP_FUNCTIONS);
+
+        let mut categories = HashMap::new();
+        let mut extensions = HashMap::new();
+        let mut instruction = iced_x86::Instruction::default();
+        for symbol in &object.symbols_sorted {
+            if let Some(instructions) = arm_instructions(symbol) {
+                for decoded in instructions {
+                    *categories
+                        .entry(arm_category(&decoded).to_owned())
+                        .or_default() += 1;
+                }
+                continue;
+            }
+            let Some(bytes) = symbol.data() else {
+                continue;
+            };
+            let mut decoder = iced_x86::Decoder::with_ip(
+                symbol.bitness(),
+                bytes,
+                symbol.address,
+                iced_x86::DecoderOptions::NONE,
+            );
+            while decoder.can_decode() {
+                decoder.decode_out(&mut instruction);
+                *categories
+                    .entry(category(&instruction).to_owned())
+                    .or_default() += 1;
+                for feature in instruction.cpuid_features() {
+                    *extensions.entry(format!("{:?}", feature)).or_default() += 1;
+                }
+            }
+        }
+
+        let kinds = object
+            .symbol_kinds
+            .iter()
+            .map(|(kind, count)| (format!("{:?}", kind), *count as u64));
+
+        ObjectStats {
+            sections,
+            functions,
+            categories: sorted(categories),
+            extensions: sorted(extensions),
+            kinds: sorted(kinds),
+        }
+    }
+}
+
+// Values without a unit are sizes in bytes
+fn bar_chart(title: &str, rows: Vec<(String, u64)>, unit: Option<&'static str>) -> impl View {
+    let max = rows
+        .iter()
+        .map(|(_, value)| *value)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let rows = list(
+        move || rows.clone().into_iter().enumerate(),
+        |(i, _)| *i,
+        move |(_, (name, value))| {
+            let width = 300.0 * value as f64 / max as f64;
+            stack((
+                text(name).style(|s| s.width(320).padding_right(10)),
+                empty().style(move |s| {
+                    s.width(width.max(1.0))
+                        .height(12)
+                        .border_radius(2)
+                        .background(Color::rgb8(150, 178, 206))
+                }),
+                match unit {
+                    Some(unit) => container_box(text(format!("{} {}", units::count(value), unit))),
+                    None => container_box(units::size_text(value)),
+                }
+                .style(|s| s.padding_left(8)),
+            ))
+            .style(|s| s.flex_row().items_center().padding(3))
+        },
+    )
+    .style(|s| s.flex_col().padding(5));
+
+    stack((header(title), rows)).style(|s| s.flex_col().width_full())
+}
+
+pub fn dashboard(object: &Object) -> Box<dyn View> {
+    let stats = ObjectStats::compute(object);
+
+    let charts = stack((
+        bar_chart("Section Sizes", stats.sections, None),
+        bar_chart("Largest Functions", stats.functions, None),
+        bar_chart(
+            "Instruction Categories",
+            stats.categories,
+            Some("instructions"),
+        ),
+        bar_chart("ISA Extensions", stats.extensions, Some("instructions")),
+        bar_chart("Symbols by Kind", stats.kinds, Some("symbols")),
+    ))
+    .style(|s| s.flex_col().width_full());
+
+    Box::new(scroll(charts).style(|s| s.width_full().height_full()))
+}
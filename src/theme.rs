@@ -0,0 +1,213 @@
+use asm_viewer_core::config::{self, Config, Value};
+use floem::{
+    peniko::Color,
+    reactive::{RwSignal, Scope},
+};
+
+use crate::report_problem;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Kind {
+    Light,
+    Dark,
+}
+
+// Colors of the interface by role
+pub struct Theme {
+    pub background: Color,
+    // Headers, toolbars and rails
+    pub panel: Color,
+    pub text: Color,
+    pub dim: Color,
+    pub faint: Color,
+    pub border: Color,
+    pub selection: Color,
+    pub hover: Color,
+    pub button: Color,
+    pub button_border: Color,
+    pub button_hover: Color,
+    pub button_active: Color,
+    // Toggle buttons which are on
+    pub button_on: Color,
+    pub list: Color,
+    pub code: Color,
+    // Alternate source statements in the assembly
+    pub stripe: Color,
+    pub instruction_hover: Color,
+    pub address: Color,
+    pub mnemonic: Color,
+    pub register: Color,
+    // Occurrences of the hovered or clicked register
+    pub register_highlight: Color,
+    pub number: Color,
+    pub padding: Color,
+    pub link: Color,
+    pub source: Color,
+    pub source_background: Color,
+    pub error: Color,
+    pub prompt: Color,
+    pub changed: Color,
+    pub removed: Color,
+    pub added: Color,
+    pub hot: Color,
+}
+
+pub const LIGHT: Theme = Theme {
+    background: Color::WHITE,
+    panel: Color::WHITE_SMOKE,
+    text: Color::BLACK,
+    dim: Color::rgb8(102, 102, 102),
+    faint: Color::rgb8(140, 140, 140),
+    border: Color::LIGHT_GRAY,
+    selection: Color::LIGHT_GRAY,
+    hover: Color::rgb8(226, 226, 205),
+    button: Color::WHITE,
+    button_border: Color::GRAY,
+    button_hover: Color::LIGHT_GREEN,
+    button_active: Color::DARK_GREEN,
+    button_on: Color::rgb8(206, 228, 190),
+    list: Color::rgb8(243, 243, 228),
+    code: Color::rgb8(248, 248, 248),
+    stripe: Color::rgb8(239, 241, 246),
+    instruction_hover: Color::rgba8(228, 237, 216, 160),
+    address: Color::rgb8(118, 141, 169),
+    mnemonic: Color::rgb8(116, 94, 147),
+    register: Color::rgb8(87, 103, 65),
+    register_highlight: Color::rgb8(200, 90, 20),
+    number: Color::rgb8(80, 107, 135),
+    padding: Color::rgb8(175, 175, 175),
+    link: Color::rgb8(105, 89, 132),
+    source: Color::rgb8(130, 130, 110),
+    source_background: Color::rgb8(240, 240, 228),
+    error: Color::rgb8(170, 60, 60),
+    prompt: Color::rgb8(255, 250, 205),
+    changed: Color::rgb8(250, 238, 200),
+    removed: Color::rgb8(250, 218, 218),
+    added: Color::rgb8(214, 242, 214),
+    hot: Color::rgb8(250, 224, 180),
+};
+
+pub const DARK: Theme = Theme {
+    background: Color::rgb8(30, 30, 30),
+    panel: Color::rgb8(45, 45, 48),
+    text: Color::rgb8(212, 212, 212),
+    dim: Color::rgb8(150, 150, 150),
+    faint: Color::rgb8(120, 120, 120),
+    border: Color::rgb8(70, 70, 70),
+    selection: Color::rgb8(62, 66, 80),
+    hover: Color::rgb8(58, 60, 50),
+    button: Color::rgb8(50, 50, 52),
+    button_border: Color::rgb8(90, 90, 90),
+    button_hover: Color::rgb8(62, 88, 56),
+    button_active: Color::rgb8(40, 110, 40),
+    button_on: Color::rgb8(56, 80, 50),
+    list: Color::rgb8(37, 37, 38),
+    code: Color::rgb8(30, 30, 30),
+    stripe: Color::rgb8(38, 40, 46),
+    instruction_hover: Color::rgba8(70, 80, 60, 160),
+    address: Color::rgb8(110, 140, 180),
+    mnemonic: Color::rgb8(197, 134, 192),
+    register: Color::rgb8(156, 200, 130),
+    register_highlight: Color::rgb8(255, 180, 80),
+    number: Color::rgb8(120, 170, 220),
+    padding: Color::rgb8(100, 100, 100),
+    link: Color::rgb8(190, 160, 230),
+    source: Color::rgb8(170, 170, 140),
+    source_background: Color::rgb8(44, 44, 36),
+    error: Color::rgb8(240, 110, 110),
+    prompt: Color::rgb8(80, 70, 30),
+    changed: Color::rgb8(80, 70, 30),
+    removed: Color::rgb8(90, 40, 40),
+    added: Color::rgb8(40, 80, 40),
+    hot: Color::rgb8(90, 65, 30),
+};
+
+// The font of instructions and reports
+#[derive(Clone, PartialEq, Debug)]
+pub struct Font {
+    pub family: String,
+    pub size: f32,
+}
+
+impl Default for Font {
+    fn default() -> Self {
+        Font {
+            family: "Consolas".to_owned(),
+            size: 14.0,
+        }
+    }
+}
+
+pub const FONT_SIZES: std::ops::RangeInclusive<f32> = 8.0..=32.0;
+
+thread_local! {
+    // Outlive the views, so styles reading them are updated when they change
+    static CURRENT: RwSignal<Kind> = Scope::new().create_rw_signal(load());
+    static FONT: RwSignal<Font> = Scope::new().create_rw_signal(load_font());
+}
+
+fn load() -> Kind {
+    match Config::load().string("theme") {
+        Some("dark") => Kind::Dark,
+        _ => Kind::Light,
+    }
+}
+
+fn save(kind: Kind) {
+    let text = match kind {
+        Kind::Light => "light",
+        Kind::Dark => "dark",
+    };
+    if let Err(error) = config::update(|config| config.set("theme", Value::String(text.to_owned())))
+    {
+        report_problem(error);
+    }
+}
+
+fn load_font() -> Font {
+    let config = Config::load();
+    let default = Font::default();
+    Font {
+        family: config
+            .string("font_family")
+            .filter(|family| !family.trim().is_empty())
+            .map_or(default.family, str::to_owned),
+        size: config
+            .float("font_size")
+            .map_or(default.size, |size| size as f32)
+            .clamp(*FONT_SIZES.start(), *FONT_SIZES.end()),
+    }
+}
+
+// The current theme. Reading it from a style subscribes the style to changes.
+pub fn kind() -> Kind {
+    CURRENT.with(|current| current.get())
+}
+
+pub fn theme() -> &'static Theme {
+    match kind() {
+        Kind::Light => &LIGHT,
+        Kind::Dark => &DARK,
+    }
+}
+
+pub fn set(kind: Kind) {
+    CURRENT.with(|current| current.set(kind));
+    save(kind);
+}
+
+// The current font. Reading it from a style subscribes the style to changes.
+pub fn font() -> Font {
+    FONT.with(|font| font.get())
+}
+
+pub fn set_font(font: Font) {
+    let saved = config::update(|config| {
+        config.set("font_family", Value::String(font.family.clone()));
+        config.set("font_size", Value::Float(font.size as f64));
+    });
+    if let Err(error) = saved {
+        report_problem(error);
+    }
+    FONT.with(|current| current.set(font));
+}
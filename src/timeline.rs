@@ -0,0 +1,455 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use floem::{
+    event::{Event, EventListener},
+    ext_event::create_ext_action,
+    keyboard::{Key, NamedKey},
+    peniko::Color,
+    reactive::{create_rw_signal, RwSignal, Scope},
+    view::View,
+    views::{empty, list, scroll, stack, text, text_input, Decorators},
+};
+use rfd::AsyncFileDialog;
+
+use crate::{
+    button, cli, dialog, header, pool, report_error, stats, theme::theme, units, Object,
+    ObjectList, Selection, Symbol,
+};
+
+const CHART_HEIGHT: f64 = 160.0;
+
+const PALETTE: [Color; 8] = [
+    Color::rgb8(150, 178, 206),
+    Color::rgb8(206, 150, 150),
+    Color::rgb8(160, 200, 150),
+    Color::rgb8(214, 190, 130),
+    Color::rgb8(180, 160, 210),
+    Color::rgb8(130, 190, 190),
+    Color::rgb8(200, 170, 190),
+    Color::rgb8(180, 180, 180),
+];
+
+// One build of the artifact in a series
+pub struct Build {
+    label: String,
+    objects: Vec<Arc<Object>>,
+}
+
+#[derive(Default)]
+pub struct Series {
+    pub builds: Vec<Build>,
+}
+
+struct Point {
+    symbol: Symbol,
+    size: usize,
+    instructions: usize,
+    // Instruction count per category
+    mix: BTreeMap<&'static str, usize>,
+}
+
+impl Point {
+    fn compute(symbol: Symbol) -> Point {
+        let bytes = symbol.data.data().unwrap_or_default();
+        let mut mix = BTreeMap::new();
+        if let Some(instructions) = stats::arm_instructions(&symbol.data) {
+            for decoded in instructions {
+                *mix.entry(stats::arm_category(&decoded)).or_default() += 1;
+            }
+        } else {
+            let mut instruction = iced_x86::Instruction::default();
+            let mut decoder = iced_x86::Decoder::with_ip(
+                symbol.data.bitness(),
+                bytes,
+                symbol.data.address,
+                iced_x86::DecoderOptions::NONE,
+            );
+            while decoder.can_decode() {
+                decoder.decode_out(&mut instruction);
+                *mix.entry(stats::category(&instruction)).or_default() += 1;
+            }
+        }
+        Point {
+            size: bytes.len(),
+            instructions: mix.values().sum(),
+            mix,
+            symbol,
+        }
+    }
+}
+
+// Orders `build-9` before `build-9a` and `build-10`, as pairs of text and the
+// number following it
+fn natural_key(text: &str) -> Vec<(String, u64)> {
+    let mut key = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let alpha = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let (prefix, tail) = rest.split_at(alpha);
+        let digits = tail
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(tail.len());
+        key.push((prefix.to_owned(), tail[..digits].parse().unwrap_or(0)));
+        rest = &tail[digits..];
+    }
+    key
+}
+
+// Whether `name` matches a path component pattern with `*` and `?`
+fn wildcard(pattern: &[char], name: &[char]) -> bool {
+    match (pattern.split_first(), name.split_first()) {
+        (None, None) => true,
+        (Some(('*', rest)), _) => {
+            wildcard(rest, name) || (!name.is_empty() && wildcard(pattern, &name[1..]))
+        }
+        (Some(('?', rest)), Some((_, name))) => wildcard(rest, name),
+        (Some((p, rest)), Some((n, name))) => p == n && wildcard(rest, name),
+        _ => false,
+    }
+}
+
+// Files matching a path pattern like `builds/*/release/app`, with `*` and
+// `?` in any component
+fn glob(pattern: &str) -> Vec<PathBuf> {
+    let pattern = Path::new(pattern.trim());
+    let mut matches = vec![PathBuf::new()];
+    for component in pattern.components() {
+        let part = component.as_os_str().to_string_lossy();
+        if !part.contains(['*', '?']) {
+            for path in &mut matches {
+                path.push(component);
+            }
+            continue;
+        }
+        let part: Vec<char> = part.chars().collect();
+        matches = matches
+            .iter()
+            .flat_map(|dir| {
+                let dir = if dir.as_os_str().is_empty() {
+                    Path::new(".")
+                } else {
+                    dir
+                };
+                fs::read_dir(dir)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| {
+                        let name: Vec<char> = entry.file_name().to_string_lossy().chars().collect();
+                        wildcard(&part, &name)
+                    })
+                    .map(|entry| entry.path())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+    }
+    matches.retain(|path| path.is_file());
+    matches
+}
+
+// A single file stands for the artifact in every sibling build directory,
+// `<dir>/*/<name>`
+fn expand(paths: Vec<PathBuf>) -> Vec<PathBuf> {
+    let [path] = &paths[..] else {
+        return paths;
+    };
+    let (Some(name), Some(root)) = (path.file_name(), path.parent().and_then(Path::parent)) else {
+        return paths;
+    };
+    let mut series: Vec<PathBuf> = fs::read_dir(root)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| Some(entry.ok()?.path().join(name)))
+                .filter(|path| path.is_file())
+                .collect()
+        })
+        .unwrap_or_default();
+    if series.is_empty() {
+        series.push(path.clone());
+    }
+    series
+}
+
+// Labels builds by file name, or by directory if the file names are the same
+fn label(path: &Path, paths: &[PathBuf]) -> String {
+    let same_name = paths.iter().all(|p| p.file_name() == path.file_name());
+    let part = if same_name {
+        path.parent().and_then(Path::file_name)
+    } else {
+        path.file_name()
+    };
+    part.unwrap_or(path.as_os_str())
+        .to_string_lossy()
+        .into_owned()
+}
+
+// Loads a series of builds of the same artifact in the background. Picking a
+// single file loads it from each sibling build directory.
+pub fn open_series(series: RwSignal<Series>, objects: RwSignal<ObjectList>) {
+    dialog::pick_files(
+        AsyncFileDialog::new().set_title("Open builds of an artifact..."),
+        move |paths| load_series(series, objects, move || expand(paths)),
+    );
+}
+
+fn load_series(
+    series: RwSignal<Series>,
+    objects: RwSignal<ObjectList>,
+    paths: impl FnOnce() -> Vec<PathBuf> + Send + 'static,
+) {
+    let loaded = create_ext_action(
+        Scope::new(),
+        move |(builds, errors): (Vec<Build>, Vec<String>)| {
+            for error in errors {
+                report_error(objects, error);
+            }
+            series.set(Series { builds });
+        },
+    );
+    pool::spawn(pool::Priority::Interactive, move || {
+        let mut paths = paths();
+        paths.sort_by_cached_key(|path| natural_key(&path.to_string_lossy()));
+        let mut errors = Vec::new();
+        if paths.is_empty() {
+            errors.push("No builds matched".to_owned());
+        }
+        let builds = paths
+            .iter()
+            .filter_map(|path| {
+                let (objects, mut failed) = cli::load_objects(path);
+                errors.append(&mut failed);
+                if objects.is_empty() {
+                    return None;
+                }
+                Some(Build {
+                    label: label(path, &paths),
+                    objects,
+                })
+            })
+            .collect();
+        loaded((builds, errors));
+    });
+}
+
+// Loads the builds matching a typed path pattern
+fn pattern_input(series: RwSignal<Series>, objects: RwSignal<ObjectList>) -> impl View {
+    let pattern = create_rw_signal(String::new());
+    let load = move || {
+        let pattern = pattern.get_untracked();
+        if !pattern.trim().is_empty() {
+            load_series(series, objects, move || glob(&pattern));
+        }
+    };
+    stack((
+        text("Builds matching:").style(|s| s.padding(5.0)),
+        text_input(pattern)
+            .style(|s| {
+                s.width(300)
+                    .padding(5)
+                    .margin(4)
+                    .border(0.5)
+                    .border_radius(3)
+                    .border_color(theme().button_border)
+            })
+            .on_event(EventListener::KeyDown, move |e| {
+                if let Event::KeyDown(e) = e {
+                    if e.key.logical_key == Key::Named(NamedKey::Enter) {
+                        load();
+                        return true;
+                    }
+                }
+                false
+            }),
+        button("Load", move |_| {
+            load();
+            true
+        }),
+    ))
+    .style(|s| s.flex_row().items_center())
+}
+
+fn find(build: &Build, name: &str) -> Option<Symbol> {
+    build.objects.iter().find_map(|object| {
+        let data = object
+            .symbols_sorted
+            .iter()
+            .find(|data| data.name == name && data.section.is_some())?;
+        Some(Symbol {
+            object: object.clone(),
+            data: data.clone(),
+        })
+    })
+}
+
+pub fn timeline_view(
+    name: String,
+    series: RwSignal<Series>,
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+) -> Box<dyn View> {
+    let points: Vec<(String, Option<Arc<Point>>)> = series.with_untracked(|series| {
+        series
+            .builds
+            .iter()
+            .map(|build| {
+                let point = find(build, &name).map(|symbol| Arc::new(Point::compute(symbol)));
+                (build.label.clone(), point)
+            })
+            .collect()
+    });
+    if points.is_empty() {
+        return Box::new(
+            stack((
+                header(format!("Timeline: `{}`", name)),
+                text("No build series loaded").style(|s| s.padding(5.0)),
+                pattern_input(series, objects),
+            ))
+            .style(|s| s.flex_col().width_full()),
+        );
+    }
+
+    let max = points
+        .iter()
+        .filter_map(|(_, point)| Some(point.as_ref()?.instructions))
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let mut categories: Vec<&'static str> = points
+        .iter()
+        .filter_map(|(_, point)| point.as_ref())
+        .flat_map(|point| point.mix.keys().copied())
+        .collect();
+    categories.sort_unstable();
+    categories.dedup();
+    let color = {
+        let categories = categories.clone();
+        move |category: &str| {
+            let i = categories.iter().position(|c| *c == category).unwrap_or(0);
+            PALETTE[i % PALETTE.len()]
+        }
+    };
+
+    // The first point clicked is the base of the next diff
+    let base = create_rw_signal(None::<usize>);
+
+    let legend_color = color.clone();
+
+    let columns = list(
+        move || points.clone().into_iter().enumerate(),
+        |(i, _)| *i,
+        move |(i, (label, point))| {
+            let Some(point) = point else {
+                return stack((
+                    empty().style(|s| s.height(CHART_HEIGHT)),
+                    text(label).style(|s| s.padding_top(4)),
+                    text("missing").style(|s| s.color(theme().dim)),
+                ))
+                .style(|s| s.flex_col().items_center().width(90).padding(4));
+            };
+            // Stacked from the bottom in category order
+            let segments: Vec<(f64, Color)> = point
+                .mix
+                .iter()
+                .rev()
+                .map(|(category, count)| {
+                    (CHART_HEIGHT * *count as f64 / max as f64, color(category))
+                })
+                .collect();
+            let bar = list(
+                move || segments.clone().into_iter().enumerate(),
+                |(i, _)| *i,
+                |(_, (height, color))| {
+                    empty().style(move |s| s.width(40).height(height.max(1.0)).background(color))
+                },
+            )
+            .style(|s| s.flex_col());
+            let symbol = point.symbol.clone();
+            let (size, instructions) = (point.size, point.instructions);
+            stack((
+                empty().style(move |s| {
+                    s.height(CHART_HEIGHT * (1.0 - instructions as f64 / max as f64))
+                }),
+                bar,
+                text(label).style(|s| s.padding_top(4)),
+                units::size_text(size as u64),
+                text(format!("{} insts", units::count(instructions as u64))),
+            ))
+            .style(move |s| {
+                s.flex_col()
+                    .items_center()
+                    .width(90)
+                    .padding(4)
+                    .border_radius(3)
+                    .apply_if(base.get() == Some(i), |s| s.background(theme().button_on))
+            })
+            .hover_style(|s| s.background(theme().hover))
+            .on_click(move |_| {
+                match base.get_untracked() {
+                    Some(first) if first != i => {
+                        let first = find_point(series, first, &symbol.data.name);
+                        if let Some(first) = first {
+                            selection.set(Selection::Diff(first, symbol.clone()));
+                        }
+                    }
+                    Some(_) => base.set(None),
+                    None => base.set(Some(i)),
+                }
+                true
+            })
+        },
+    )
+    .style(|s| s.flex_row().items_end().padding(5));
+
+    let legend = list(
+        move || categories.clone().into_iter(),
+        |category| *category,
+        move |category| {
+            let swatch = legend_color(category);
+            stack((
+                empty().style(move |s| s.width(10).height(10).background(swatch)),
+                text(category).style(|s| s.padding_left(4).padding_right(12)),
+            ))
+            .style(|s| s.flex_row().items_center())
+        },
+    )
+    .style(|s| s.flex_row().padding(5));
+
+    Box::new(
+        stack((
+            header(format!("Timeline: `{}`", name)),
+            pattern_input(series, objects),
+            text("Click two builds to diff the function between them.").style(|s| s.padding(5.0)),
+            legend,
+            scroll(columns).style(|s| s.width_full()),
+        ))
+        .style(|s| s.flex_col().width_full().height_full()),
+    )
+}
+
+fn find_point(series: RwSignal<Series>, build: usize, name: &str) -> Option<Symbol> {
+    series.with_untracked(|series| find(series.builds.get(build)?, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorts_naturally() {
+        let mut builds = vec!["build-10", "build-9", "build-1", "build-9a", "alpha"];
+        builds.sort_by_cached_key(|build| natural_key(build));
+        assert_eq!(
+            builds,
+            ["alpha", "build-1", "build-9", "build-9a", "build-10"]
+        );
+        assert!(natural_key("v1.10") > natural_key("v1.9"));
+        assert_eq!(natural_key("a01"), natural_key("a1"));
+    }
+}
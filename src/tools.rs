@@ -0,0 +1,177 @@
+use std::{
+    env,
+    ffi::OsString,
+    fs,
+    path::PathBuf,
+    process::Command,
+    sync::{Arc, OnceLock},
+};
+
+use floem::menu::{Menu, MenuItem};
+
+use crate::{names, report_problem, settings::config_dir, Object, SymbolData};
+
+// An external command from the tools file. Each line of the file is
+// `Name = program arguments...`, where the arguments may contain `{path}`,
+// `{object}`, `{symbol}` and `{address}` placeholders. Symbols are passed with
+// the exact bytes of their names.
+#[derive(Debug)]
+pub struct Tool {
+    name: String,
+    command: Vec<String>,
+}
+
+// What the context menu was opened on
+pub struct ToolContext {
+    path: PathBuf,
+    object: String,
+    symbol: Option<Vec<u8>>,
+    address: Option<u64>,
+}
+
+impl ToolContext {
+    pub fn new(object: &Object, symbol: Option<&SymbolData>, address: Option<u64>) -> ToolContext {
+        ToolContext {
+            path: object.path.clone(),
+            object: object.name.clone(),
+            symbol: symbol.map(|symbol| symbol.name_bytes().to_vec()),
+            address,
+        }
+    }
+
+    // Context for an instruction, naming the symbol containing it
+    pub fn at_address(object: &Object, address: u64) -> ToolContext {
+        let symbol = object.symbols_sorted.iter().find(|symbol| {
+            symbol.section.is_some()
+                && (symbol.address..symbol.address + symbol.extent().unwrap_or(0))
+                    .contains(&address)
+        });
+        ToolContext::new(object, symbol.map(|symbol| &**symbol), Some(address))
+    }
+}
+
+fn tools_file() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("ASM_VIEWER_TOOLS") {
+        return Some(PathBuf::from(path));
+    }
+    Some(config_dir()?.join("tools.txt"))
+}
+
+// Splits a command line on whitespace, keeping double quoted arguments intact
+fn split_command(line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut started = false;
+    for c in line.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                started = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if started {
+                    args.push(std::mem::take(&mut current));
+                    started = false;
+                }
+            }
+            c => {
+                current.push(c);
+                started = true;
+            }
+        }
+    }
+    if started {
+        args.push(current);
+    }
+    args
+}
+
+fn parse(text: &str) -> Vec<Tool> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (name, command) = line.split_once('=')?;
+            let command = split_command(command);
+            (!command.is_empty()).then(|| Tool {
+                name: name.trim().to_owned(),
+                command,
+            })
+        })
+        .collect()
+}
+
+pub fn tools() -> &'static [Tool] {
+    static TOOLS: OnceLock<Vec<Tool>> = OnceLock::new();
+    TOOLS.get_or_init(|| {
+        tools_file()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|text| parse(&text))
+            .unwrap_or_default()
+    })
+}
+
+impl Tool {
+    // Tools referring to a symbol or address are only usable when there is one
+    fn applies(&self, context: &ToolContext) -> bool {
+        self.command.iter().all(|arg| {
+            (context.symbol.is_some() || !arg.contains("{symbol}"))
+                && (context.address.is_some() || !arg.contains("{address}"))
+        })
+    }
+
+    fn run(&self, context: &ToolContext) {
+        let args: Vec<OsString> = self
+            .command
+            .iter()
+            .map(|arg| {
+                let arg = arg
+                    .replace("{path}", &context.path.to_string_lossy())
+                    .replace("{object}", &context.object)
+                    .replace(
+                        "{address}",
+                        &context
+                            .address
+                            .map(|address| format!("{:#x}", address))
+                            .unwrap_or_default(),
+                    );
+                let symbol = names::os_string(context.symbol.as_deref().unwrap_or_default());
+                let mut parts = arg.split("{symbol}");
+                let mut out = OsString::from(parts.next().unwrap_or_default());
+                for part in parts {
+                    out.push(&symbol);
+                    out.push(part);
+                }
+                out
+            })
+            .collect();
+
+        match Command::new(&args[0]).args(&args[1..]).spawn() {
+            // Wait in the background so the process gets reaped
+            Ok(mut child) => {
+                std::thread::spawn(move || child.wait());
+            }
+            Err(error) => report_problem(format!("Running `{}`: {}", self.name, error)),
+        }
+    }
+}
+
+// Context menu with the external tools applicable to `context`
+pub fn menu(context: ToolContext) -> Menu {
+    let context = Arc::new(context);
+    let tools = tools();
+    let mut menu = Menu::new("External Tools");
+    for tool in tools {
+        let context = context.clone();
+        menu = menu.entry(
+            MenuItem::new(tool.name.clone())
+                .enabled(tool.applies(&context))
+                .action(move || tool.run(&context)),
+        );
+    }
+    if tools.is_empty() {
+        menu = menu.entry(MenuItem::new("No external tools configured").enabled(false));
+    }
+    menu
+}
@@ -0,0 +1,180 @@
+use std::{fs, path::PathBuf};
+
+use floem::{
+    reactive::RwSignal,
+    style::Style,
+    view::View,
+    views::{label, stack, text, Decorators},
+};
+
+use crate::{
+    button, open_file, report_problem, sample, settings::config_dir, theme::theme, ObjectList,
+    Selection,
+};
+
+// The parts of the window a step of the tour points at
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Area {
+    Open,
+    Symbols,
+    Assembly,
+    Navigation,
+    Search,
+}
+
+const STEPS: &[(Area, &str, &str)] = &[
+    (
+        Area::Open,
+        "Opening files",
+        "Open loads executables, object files, archives and PDBs. The sample opened \
+         for this tour was generated on the spot.",
+    ),
+    (
+        Area::Symbols,
+        "Symbols",
+        "The sidebar lists the loaded objects and the symbols of the selected one. \
+         Click a symbol to view its assembly.",
+    ),
+    (
+        Area::Assembly,
+        "Assembly",
+        "This is `main`. Hover an instruction for its documentation, or a register \
+         to see its other uses. Arrows in the margin show where jumps go.",
+    ),
+    (
+        Area::Navigation,
+        "Navigation",
+        "Click the target of a `call` to follow it. Back and Forward, or Alt+Left and \
+         Alt+Right, retrace your steps.",
+    ),
+    (
+        Area::Search,
+        "Search",
+        "Search finds symbols by name in every loaded object.",
+    ),
+];
+
+// Written when the tour is done or skipped, so it's only offered once
+fn done_file() -> Option<PathBuf> {
+    Some(config_dir()?.join("tour-done"))
+}
+
+pub fn first_run() -> bool {
+    done_file().is_some_and(|path| !path.exists())
+}
+
+// Opens the sample and shows the first step
+pub fn start(
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+    tour: RwSignal<Option<usize>>,
+) {
+    sample::open(objects, selection);
+    tour.set(Some(0));
+}
+
+fn finish(tour: RwSignal<Option<usize>>) {
+    tour.set(None);
+    let Some(path) = done_file() else {
+        return;
+    };
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    if let Err(error) = fs::write(&path, "") {
+        report_problem(format!("Saving `{}`: {}", path.display(), error));
+    }
+}
+
+// Outlines `area` while the tour is at it
+pub fn highlight(s: Style, tour: RwSignal<Option<usize>>, area: Area) -> Style {
+    let current = tour.with(|tour| tour.and_then(|step| STEPS.get(step)).map(|step| step.0));
+    s.apply_if(current == Some(area), |s| {
+        s.border(2.0).border_color(theme().link)
+    })
+}
+
+// The banner with the current step of the tour
+pub fn tour_card(tour: RwSignal<Option<usize>>) -> impl View {
+    let last = STEPS.len() - 1;
+    let step = move || tour.get().unwrap_or_default();
+    stack((
+        label(move || {
+            tour.with(|tour| {
+                tour.and_then(|step| STEPS.get(step))
+                    .map(|(_, title, text)| {
+                        let step = tour.unwrap_or_default() + 1;
+                        format!("{}/{}  {}: {}", step, STEPS.len(), title, text)
+                    })
+                    .unwrap_or_default()
+            })
+        })
+        .style(|s| s.padding(5.0).flex_grow(1.0).flex_basis(0.0).min_width(0.0)),
+        button("Back", move |_| {
+            tour.update(|tour| *tour = tour.map(|step| step.saturating_sub(1)));
+            true
+        })
+        .style(move |s| s.apply_if(step() == 0, |s| s.hide())),
+        button("Next", move |_| {
+            tour.update(|tour| *tour = tour.map(|step| step + 1));
+            true
+        })
+        .style(move |s| s.apply_if(step() == last, |s| s.hide())),
+        button("Done", move |_| {
+            finish(tour);
+            true
+        })
+        .style(move |s| s.apply_if(step() != last, |s| s.hide())),
+        button("End Tour", move |_| {
+            finish(tour);
+            true
+        })
+        .style(move |s| s.apply_if(step() == last, |s| s.hide())),
+    ))
+    .style(move |s| {
+        s.flex_row()
+            .items_center()
+            .width_full()
+            .background(theme().prompt)
+            .border_bottom(0.5)
+            .border_color(theme().border)
+            .apply_if(tour.with(|tour| tour.is_none()), |s| s.hide())
+    })
+}
+
+// Shown in place of the assembly until something is loaded
+pub fn welcome(
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+    tour: RwSignal<Option<usize>>,
+) -> impl View {
+    let empty = move || objects.with(|list| list.objects.is_empty() && list.loading.is_empty());
+    stack((
+        text("Nothing selected").style(move |s| s.apply_if(empty(), |s| s.hide())),
+        stack((
+            text("Assembly Viewer").style(|s| s.font_size(18.0).padding_bottom(5.0)),
+            text(
+                "Open an executable, object file, archive or PDB to browse the assembly of \
+                 its symbols, or look around a small sample first.",
+            )
+            .style(|s| s.color(theme().dim)),
+            stack((
+                button("Open", move |_| {
+                    open_file(objects, selection);
+                    true
+                }),
+                button("Open Sample", move |_| {
+                    sample::open(objects, selection);
+                    true
+                }),
+                button("Take the Tour", move |_| {
+                    start(objects, selection, tour);
+                    true
+                }),
+            ))
+            .style(|s| s.flex_row().margin_top(10.0)),
+        ))
+        .style(move |s| s.flex_col().apply_if(!empty(), |s| s.hide())),
+    ))
+    .style(|s| s.flex_col().padding(5.0))
+}
@@ -0,0 +1,236 @@
+use std::sync::Arc;
+
+use floem::{
+    reactive::RwSignal,
+    view::View,
+    views::{list, scroll, stack, text, Decorators},
+};
+
+use crate::{
+    diff, header, project::Project, theme::theme, units, xrefs, Object, Selection, Settings, Symbol,
+};
+
+const COLUMN_WIDTH: f64 = 180.0;
+
+// Matches `name` against a pattern where `*` stands for any text
+fn matches(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+// The tracked patterns of the projects of all loaded objects
+fn patterns(objects: &[Arc<Object>]) -> Vec<String> {
+    let mut patterns: Vec<String> = Vec::new();
+    for object in objects {
+        for pattern in Project::load(&object.path).tracked {
+            if !patterns.contains(&pattern) {
+                patterns.push(pattern);
+            }
+        }
+    }
+    patterns
+}
+
+// A tracked symbol in one object, compared against the previous object
+// which has it
+#[derive(Clone)]
+struct Cell {
+    symbol: Symbol,
+    size: u64,
+    previous: Option<(Symbol, u64)>,
+    changed: usize,
+}
+
+impl Cell {
+    fn label(&self) -> String {
+        match &self.previous {
+            None => units::size(self.size),
+            Some((_, previous)) => {
+                let delta = self.size as i64 - *previous as i64;
+                format!(
+                    "{} ({}, {} changed)",
+                    units::size(self.size),
+                    units::size_delta(delta),
+                    units::count(self.changed as u64)
+                )
+            }
+        }
+    }
+}
+
+// Sizes of every tracked symbol in each object, in load order
+fn rows(objects: &[Arc<Object>], settings: &Settings) -> Vec<(String, Vec<Option<Cell>>)> {
+    let patterns = patterns(objects);
+    let mut names: Vec<String> = Vec::new();
+    for object in objects {
+        for symbol in &object.symbols_sorted {
+            let demangled = symbol.demangled.as_deref().unwrap_or(&symbol.name);
+            let tracked = patterns
+                .iter()
+                .any(|pattern| matches(pattern, &symbol.name) || matches(pattern, demangled));
+            if tracked && !names.contains(&symbol.name) {
+                names.push(symbol.name.clone());
+            }
+        }
+    }
+    names.sort();
+
+    names
+        .into_iter()
+        .map(|name| {
+            let mut previous: Option<(Symbol, u64)> = None;
+            let mut label = name.clone();
+            let cells = objects
+                .iter()
+                .map(|object| {
+                    let data = xrefs::find(object, &name)?;
+                    if let Some(demangled) = &data.demangled {
+                        label = demangled.clone();
+                    }
+                    let symbol = Symbol {
+                        object: object.clone(),
+                        data,
+                    };
+                    let size = symbol.data.extent().unwrap_or_default();
+                    let changed = match &previous {
+                        Some((before, _)) => changed(before, &symbol, settings),
+                        None => 0,
+                    };
+                    let cell = Cell {
+                        symbol: symbol.clone(),
+                        size,
+                        previous: previous.replace((symbol, size)),
+                        changed,
+                    };
+                    Some(cell)
+                })
+                .collect();
+            (label, cells)
+        })
+        .collect()
+}
+
+// The number of rows of the diff between the symbols which aren't equal
+fn changed(left: &Symbol, right: &Symbol, settings: &Settings) -> usize {
+    let (Some(left_instructions), Some(right_instructions)) = (
+        diff::instructions(left, settings),
+        diff::instructions(right, settings),
+    ) else {
+        return 0;
+    };
+    diff::diff_instructions(
+        left,
+        &left_instructions,
+        right,
+        &right_instructions,
+        settings,
+    )
+    .iter()
+    .filter(|row| row.change != diff::Change::Equal)
+    .count()
+}
+
+pub fn dashboard(
+    objects: &[Arc<Object>],
+    settings: &Settings,
+    selection: RwSignal<Selection>,
+) -> Box<dyn View> {
+    let rows = rows(objects, settings);
+    if rows.is_empty() {
+        return Box::new(
+            stack((
+                header("Tracked Symbols"),
+                text(
+                    "No tracked symbols. Add lines like `track<TAB>name*` to the \
+                     `.asmv` project file next to an object.",
+                )
+                .style(|s| s.padding(5.0)),
+            ))
+            .style(|s| s.flex_col().width_full()),
+        );
+    }
+
+    let titles: Vec<String> = objects.iter().map(|object| object.title()).collect();
+    let columns = list(
+        move || titles.clone().into_iter().enumerate(),
+        |(i, _)| *i,
+        |(_, title)| text(title).style(|s| s.width(COLUMN_WIDTH).color(theme().dim)),
+    )
+    .style(|s| s.flex_row());
+    let heading = stack((text("Symbol").style(|s| s.width(300)), columns)).style(|s| {
+        s.flex_row()
+            .padding(5)
+            .border_bottom(0.5)
+            .border_color(theme().border)
+    });
+
+    let rows = list(
+        move || rows.clone().into_iter().enumerate(),
+        |(i, _)| *i,
+        move |(_, (name, cells))| {
+            let cells = list(
+                move || cells.clone().into_iter().enumerate(),
+                |(i, _)| *i,
+                move |(_, cell)| {
+                    let label = cell.as_ref().map_or_else(|| "-".to_owned(), Cell::label);
+                    let differs = cell.as_ref().is_some_and(|cell| {
+                        let resized = cell
+                            .previous
+                            .as_ref()
+                            .is_some_and(|(_, size)| *size != cell.size);
+                        cell.changed > 0 || resized
+                    });
+                    text(label)
+                        .style(move |s| {
+                            s.width(COLUMN_WIDTH)
+                                .apply_if(differs, |s| s.color(theme().error))
+                        })
+                        .hover_style(|s| s.background(theme().hover))
+                        .on_click(move |_| {
+                            // Opens the diff against the previous build when
+                            // there is one
+                            match &cell {
+                                Some(Cell {
+                                    symbol,
+                                    previous: Some((previous, _)),
+                                    ..
+                                }) => {
+                                    selection.set(Selection::Diff(previous.clone(), symbol.clone()))
+                                }
+                                Some(cell) => selection.set(Selection::Symbol(cell.symbol.clone())),
+                                None => {}
+                            }
+                            true
+                        })
+                },
+            )
+            .style(|s| s.flex_row());
+            stack((text(name).style(|s| s.width(300).min_width(0.0)), cells))
+                .style(|s| s.flex_row().padding(5).width_full())
+        },
+    )
+    .style(|s| s.flex_col().width_full());
+
+    Box::new(
+        stack((
+            header("Tracked Symbols"),
+            heading,
+            scroll(rows).style(|s| s.width_full().flex_grow(1.0).flex_basis(0.0)),
+        ))
+        .style(|s| s.flex_col().width_full().height_full()),
+    )
+}
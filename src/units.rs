@@ -0,0 +1,49 @@
+use floem::views::{text, tooltip, Decorators, Tooltip};
+
+use crate::theme::theme;
+
+const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+// A size in binary units with one decimal, like `12.4 KiB`. Sizes below a KiB
+// are given in bytes.
+pub fn size(bytes: u64) -> String {
+    if bytes < 1024 {
+        return format!("{} bytes", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+// A change in size with its sign, like `+1.5 KiB`
+pub fn size_delta(delta: i64) -> String {
+    let sign = if delta < 0 { '-' } else { '+' };
+    format!("{}{}", sign, size(delta.unsigned_abs()))
+}
+
+// A count with thousands separators, like `12,345`
+pub fn count(n: u64) -> String {
+    let digits: Vec<char> = n.to_string().chars().collect();
+    let groups: Vec<String> = digits
+        .rchunks(3)
+        .rev()
+        .map(|group| group.iter().collect())
+        .collect();
+    groups.join(",")
+}
+
+// A readable size which shows the exact number of bytes on hover
+pub fn size_text(bytes: u64) -> Tooltip {
+    tooltip(text(size(bytes)), move || {
+        text(format!("{} bytes", count(bytes))).style(|s| {
+            s.padding(5.0)
+                .background(theme().panel)
+                .border(0.5)
+                .border_color(theme().border)
+        })
+    })
+}
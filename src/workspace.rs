@@ -0,0 +1,274 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::Read,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use floem::{
+    ext_event::create_ext_action,
+    reactive::{RwSignal, Scope},
+    style::TextOverflow,
+    view::View,
+    views::{list, stack, text, Decorators},
+};
+
+use rfd::AsyncFileDialog;
+
+use crate::{
+    button, dialog, header, open_paths, reload_object, theme::theme, ObjectList, Selection,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+// Build directories can be deep, but artifacts are near the top
+const MAX_DEPTH: usize = 4;
+
+// A folder which is scanned for artifacts. Artifacts are only loaded when
+// clicked.
+#[derive(Clone)]
+pub struct Workspace {
+    pub root: PathBuf,
+    pub artifacts: Vec<PathBuf>,
+    // Cleared to stop the scanning thread
+    watching: Arc<AtomicBool>,
+    // Owns the actions delivering changes
+    scope: Scope,
+}
+
+// Artifacts with their modification times
+type Artifacts = Vec<(PathBuf, SystemTime)>;
+
+// Hands the artifacts to the UI thread once they change
+type Notify = Box<dyn FnOnce(Artifacts) + Send>;
+
+// Whether files are artifacts by their path and modification time, so files
+// are only read again once they change
+type Magic = HashMap<PathBuf, (SystemTime, bool)>;
+
+pub fn is_artifact(path: &Path) -> bool {
+    let mut magic = [0; 8];
+    let Ok(read) = File::open(path).and_then(|mut file| file.read(&mut magic)) else {
+        return false;
+    };
+    let magic = &magic[..read];
+    magic.starts_with(b"\x7fELF")
+        || magic.starts_with(b"MZ")
+        || magic.starts_with(b"!<arch>\n")
+        || magic.starts_with(&[0xcf, 0xfa, 0xed, 0xfe])
+        || magic.starts_with(&[0xce, 0xfa, 0xed, 0xfe])
+        || magic.starts_with(&[0xca, 0xfe, 0xba, 0xbe])
+        // COFF objects have no magic, so go by the extension
+        || path.extension().is_some_and(|extension| extension == "obj")
+}
+
+fn scan_dir(dir: &Path, depth: usize, known: &Magic, found: &mut Magic) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        let Ok(kind) = entry.file_type() else {
+            continue;
+        };
+        if kind.is_dir() {
+            // Skip `incremental` which only has compiler caches
+            if depth < MAX_DEPTH && !hidden && entry.file_name() != "incremental" {
+                scan_dir(&path, depth + 1, known, found);
+            }
+        } else if kind.is_file() {
+            let Ok(modified) = entry.metadata().and_then(|metadata| metadata.modified()) else {
+                continue;
+            };
+            let artifact = match known.get(&path) {
+                Some(&(time, artifact)) if time == modified => artifact,
+                _ => is_artifact(&path),
+            };
+            found.insert(path, (modified, artifact));
+        }
+    }
+}
+
+fn scan(root: &Path, magic: &mut Magic) -> Artifacts {
+    let mut found = HashMap::new();
+    scan_dir(root, 0, magic, &mut found);
+    *magic = found;
+    let mut artifacts: Artifacts = magic
+        .iter()
+        .filter(|(_, &(_, artifact))| artifact)
+        .map(|(path, &(modified, _))| (path.clone(), modified))
+        .collect();
+    artifacts.sort();
+    artifacts
+}
+
+pub fn add_folder(objects: RwSignal<ObjectList>, selection: RwSignal<Selection>) {
+    dialog::pick_folder(
+        AsyncFileDialog::new().set_title("Add a workspace folder..."),
+        move |root| add_root(objects, selection, root),
+    );
+}
+
+fn add_root(objects: RwSignal<ObjectList>, selection: RwSignal<Selection>, root: PathBuf) {
+    if objects.with_untracked(|list| list.workspaces.iter().any(|w| w.root == root)) {
+        return;
+    }
+    let watching = Arc::new(AtomicBool::new(true));
+    let scope = Scope::new();
+    objects.update(|list| {
+        list.workspaces.push(Workspace {
+            root: root.clone(),
+            artifacts: Vec::new(),
+            watching: watching.clone(),
+            scope,
+        })
+    });
+    let (notify, notifications) = mpsc::channel();
+    arm(
+        objects,
+        selection,
+        root.clone(),
+        scope,
+        notify,
+        HashMap::new(),
+    );
+    thread::spawn(move || watch(&root, &watching, notifications));
+}
+
+fn remove_root(objects: RwSignal<ObjectList>, root: &Path) {
+    objects.update(|list| {
+        if let Some(i) = list.workspaces.iter().position(|w| w.root == root) {
+            let workspace = list.workspaces.remove(i);
+            workspace.watching.store(false, Ordering::Relaxed);
+            workspace.scope.dispose();
+        }
+    });
+}
+
+// Hands the scanning thread the action applying the next change of the
+// artifacts of `root`. Loaded artifacts which were rebuilt are reloaded.
+fn arm(
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+    root: PathBuf,
+    scope: Scope,
+    notify: mpsc::Sender<Notify>,
+    known: HashMap<PathBuf, SystemTime>,
+) {
+    let sender = notify.clone();
+    let changed = create_ext_action(scope, move |artifacts: Artifacts| {
+        let rebuilt: Vec<_> = objects.with_untracked(|list| {
+            list.objects
+                .iter()
+                .filter(|object| {
+                    artifacts.iter().any(|(path, modified)| {
+                        object.path == *path
+                            && known.get(path).is_some_and(|known| known != modified)
+                    })
+                })
+                .cloned()
+                .collect()
+        });
+        objects.update(|list| {
+            if let Some(workspace) = list.workspaces.iter_mut().find(|w| w.root == root) {
+                workspace.artifacts = artifacts.iter().map(|(path, _)| path.clone()).collect();
+            }
+        });
+        for object in rebuilt {
+            reload_object(objects, selection, &object);
+        }
+        arm(
+            objects,
+            selection,
+            root,
+            scope,
+            notify,
+            artifacts.into_iter().collect(),
+        );
+    });
+    let _ = sender.send(Box::new(changed));
+}
+
+// Rescans the folder until the workspace is removed, and passes its artifacts
+// on when they differ from the last scan
+fn watch(root: &Path, watching: &AtomicBool, notifications: mpsc::Receiver<Notify>) {
+    let mut magic = HashMap::new();
+    let mut known = None;
+    while watching.load(Ordering::Relaxed) {
+        let artifacts = scan(root, &mut magic);
+        if known.as_ref() != Some(&artifacts) {
+            let Ok(notify) = notifications.recv() else {
+                return;
+            };
+            notify(artifacts.clone());
+            known = Some(artifacts);
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+// Artifacts of the workspaces which aren't loaded yet
+pub fn workspace_list(objects: RwSignal<ObjectList>, selection: RwSignal<Selection>) -> impl View {
+    list(
+        move || objects.with(|list| list.workspaces.clone().into_iter().enumerate()),
+        |(i, workspace)| (*i, workspace.artifacts.clone()),
+        move |(_, workspace)| {
+            let root = workspace.root.clone();
+            let artifacts: Vec<PathBuf> = workspace.artifacts;
+            let unloaded = move || {
+                let artifacts = artifacts.clone();
+                objects.with(|list| {
+                    artifacts
+                        .into_iter()
+                        .filter(|path| !list.objects.iter().any(|o| o.path == *path))
+                        .collect::<Vec<_>>()
+                })
+            };
+            let entries = list(
+                unloaded,
+                |path| path.clone(),
+                move |path| {
+                    let name = path
+                        .strip_prefix(&root)
+                        .unwrap_or(&path)
+                        .to_string_lossy()
+                        .into_owned();
+                    text(name)
+                        .style(|s| {
+                            s.padding(5)
+                                .width_full()
+                                .color(theme().dim)
+                                .text_overflow(TextOverflow::Clip)
+                        })
+                        .hover_style(|s| s.background(theme().button_hover))
+                        .on_click(move |_| {
+                            open_paths(objects, selection, vec![path.clone()], None);
+                            true
+                        })
+                },
+            )
+            .style(|s| s.flex_col().width_full());
+            let removed = workspace.root.clone();
+            stack((
+                stack((
+                    header(format!("Workspace: `{}`", workspace.root.display())),
+                    button("Remove", move |_| {
+                        remove_root(objects, &removed);
+                        true
+                    }),
+                ))
+                .style(|s| s.flex_row().items_center()),
+                entries,
+            ))
+            .style(|s| s.flex_col().width_full())
+        },
+    )
+    .style(|s| s.flex_col().width_full())
+}
@@ -0,0 +1,86 @@
+use floem::{
+    reactive::RwSignal,
+    view::View,
+    views::{
+        scroll, stack, text, virtual_list, Decorators, VirtualListDirection, VirtualListItemSize,
+    },
+};
+
+pub use asm_viewer_core::xrefs::{counts, find};
+
+use crate::{
+    header, history, history::History, theme::theme, ObjectList, Selection, Symbol, ViewPosition,
+};
+
+// Functions with at least this many callers are highlighted as hot
+pub const HOT_CALLERS: usize = 20;
+
+// Every instruction in the loaded objects referring to `symbol`
+pub fn references_view(
+    symbol: &Symbol,
+    objects: RwSignal<ObjectList>,
+    selection: RwSignal<Selection>,
+    history: RwSignal<History>,
+    position: RwSignal<ViewPosition>,
+) -> impl View {
+    let references: im::Vector<(Symbol, u64)> = objects.with_untracked(|list| {
+        list.objects
+            .iter()
+            .flat_map(|object| {
+                object
+                    .callers
+                    .get(&symbol.data.name)
+                    .iter()
+                    .filter_map(|xref| {
+                        let data = find(object, &xref.from)?;
+                        let symbol = Symbol {
+                            object: object.clone(),
+                            data,
+                        };
+                        Some((symbol, xref.address))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    });
+    let count = references.len();
+
+    let list = virtual_list(
+        VirtualListDirection::Vertical,
+        VirtualListItemSize::Fixed(Box::new(|| 26.0)),
+        move || references.clone(),
+        |(symbol, address)| (symbol.key(), *address),
+        move |(symbol, address)| {
+            let offset = address - symbol.data.address;
+            let name = symbol
+                .data
+                .demangled
+                .as_ref()
+                .unwrap_or(&symbol.data.name)
+                .clone();
+            stack((
+                text(format!("{}+{:#x}", name, offset)).style(|s| {
+                    s.flex_grow(1.0)
+                        .flex_basis(0.0)
+                        .min_width(0.0)
+                        .color(theme().text)
+                }),
+                text(symbol.object.title()).style(|s| s.color(theme().dim)),
+            ))
+            .style(|s| s.flex_row().padding(5).width_full().height(26.0))
+            .hover_style(|s| s.background(theme().hover))
+            .on_click(move |_| {
+                position.set(Some((symbol.data.name.clone(), offset)));
+                history::jump(selection, history, Selection::Symbol(symbol.clone()));
+                true
+            })
+        },
+    )
+    .style(|s| s.flex_col().width_full());
+
+    stack((
+        header(format!("References ({})", count)),
+        scroll(list).style(|s| s.width_full().max_height(160)),
+    ))
+    .style(|s| s.flex_col().width_full())
+}